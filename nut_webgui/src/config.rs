@@ -1,6 +1,12 @@
 use crate::uri_path::UriPath;
 use core::net::{IpAddr, Ipv4Addr};
-use std::{num::NonZeroUsize, path::PathBuf};
+use nut_webgui_upsmc::{
+  VarName,
+  clients::{FailbackPolicy, UNIX_ADDR_PREFIX},
+  errors::{Error, TlsError},
+  tls::TlsConfig,
+};
+use std::{num::NonZeroUsize, path::PathBuf, time::Duration};
 use tracing::Level;
 
 pub mod cfg_args;
@@ -21,6 +27,8 @@ pub struct ServerConfig {
 
   pub http_server: HttpServerConfig,
   pub upsd: UpsdConfig,
+  pub history: HistoryConfig,
+  pub storage: StorageConfig,
 }
 
 #[derive(Debug)]
@@ -37,13 +45,23 @@ pub struct UpsdConfig {
   /// Poll interval in seconds for ups status
   pub poll_interval: u64,
 
-  /// UPSD TCP address
-  /// It can be hostname, IPv4, or IPv6
+  /// Refresh interval in seconds for writable variable details and command lists, 0 disables
+  /// periodic refresh
+  pub rw_refresh_interval: u64,
+
+  /// UPSD address
+  /// It can be hostname, IPv4, IPv6, or a unix domain socket path with `unix:` prefix
   pub addr: Box<str>,
 
   /// UPSD TCP port
   pub port: u16,
 
+  /// Fallback UPSD endpoints in `host:port` form, tried in order when the active endpoint fails
+  pub fallback_endpoints: Vec<Box<str>>,
+
+  /// Delay in seconds before returning to a higher priority endpoint, 0 disables failback
+  pub failback_delay: u64,
+
   /// UPSD username
   pub user: Option<Box<str>>,
 
@@ -52,11 +70,177 @@ pub struct UpsdConfig {
 
   /// Maximum allowed connection limit aka pool size
   pub max_conn: NonZeroUsize,
+
+  /// Connection timeout in seconds, including the TLS upgrade
+  pub connect_timeout: u64,
+
+  /// Request timeout in seconds
+  pub read_timeout: u64,
+
+  /// Idle pooled connections are closed after this many seconds, 0 keeps them open
+  pub pool_max_idle: u64,
+
+  /// Pooled connections are closed after this many seconds, 0 disables the limit
+  pub pool_max_lifetime: u64,
+
+  /// Pooled connections idle longer than this many seconds are checked before use
+  pub pool_validate_after: u64,
+
+  /// STARTTLS settings
+  pub tls: UpsdTlsConfig,
+}
+
+#[derive(Debug, Default)]
+pub struct UpsdTlsConfig {
+  /// Requires STARTTLS for all upsd connections
+  pub enabled: bool,
+
+  /// Server name for certificate verification of the primary upsd address, defaults to upsd
+  /// address. Fallback endpoints are verified against their own hostnames.
+  pub server_name: Option<Box<str>>,
+
+  /// PEM encoded CA certificate file
+  pub ca_file: Option<PathBuf>,
+
+  /// SHA-256 fingerprints of trusted upsd certificates
+  pub pinned_certs: Vec<Box<str>>,
+
+  /// PEM encoded client certificate file
+  pub client_cert: Option<PathBuf>,
+
+  /// PEM encoded client private key file
+  pub client_key: Option<PathBuf>,
+}
+
+#[derive(Debug)]
+pub struct HistoryConfig {
+  /// Numeric variables recorded for each device, empty list disables metric history
+  pub variables: Vec<VarName>,
+
+  /// Retention in days of the hourly tier, 0 disables metric history
+  pub retention_days: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct StorageConfig {
+  /// Data directory for persistent metric history and events, both are kept in memory only when not set
+  pub path: Option<PathBuf>,
+}
+
+impl HistoryConfig {
+  /// Returns [None] when metric history is disabled.
+  pub fn get_retention(&self) -> Option<Duration> {
+    if self.retention_days == 0 || self.variables.is_empty() {
+      None
+    } else {
+      Some(Duration::from_secs(self.retention_days * 24 * 60 * 60))
+    }
+  }
 }
 
 impl UpsdConfig {
+  /// Returns upsd address in `host:port` form. Unix domain socket addresses are returned as is,
+  /// since port is not applicable.
   pub fn get_socket_addr(&self) -> String {
-    format!("{address}:{port}", address = self.addr, port = self.port)
+    if self.addr.starts_with(UNIX_ADDR_PREFIX) {
+      self.addr.to_string()
+    } else {
+      format!("{address}:{port}", address = self.addr, port = self.port)
+    }
+  }
+
+  pub fn get_failback_policy(&self) -> FailbackPolicy {
+    if self.failback_delay == 0 {
+      FailbackPolicy::Never
+    } else {
+      FailbackPolicy::After(Duration::from_secs(self.failback_delay))
+    }
+  }
+
+  /// Interval for probing higher priority endpoints after a failover. Returns [None] when failback
+  /// is disabled or there is no fallback endpoint.
+  pub fn get_failback_probe_interval(&self) -> Option<Duration> {
+    if self.failback_delay == 0 || self.fallback_endpoints.is_empty() {
+      None
+    } else {
+      Some(Duration::from_secs(self.failback_delay))
+    }
+  }
+
+  /// Interval for closing expired pool connections, half of the shortest pool limit bounded to
+  /// 1-60 seconds. Returns [None] when neither idle time nor lifetime is limited.
+  pub fn get_pool_reap_interval(&self) -> Option<Duration> {
+    let shortest = [self.pool_max_idle, self.pool_max_lifetime]
+      .into_iter()
+      .filter(|limit| *limit > 0)
+      .min()?;
+
+    Some(Duration::from_secs((shortest / 2).clamp(1, 60)))
+  }
+
+  /// Returns [None] when periodic writable variable refresh is disabled.
+  pub fn get_rw_refresh_interval(&self) -> Option<Duration> {
+    if self.rw_refresh_interval == 0 {
+      None
+    } else {
+      Some(Duration::from_secs(self.rw_refresh_interval))
+    }
+  }
+
+  /// Loads TLS certificates and keys. Returns [None] when TLS is not enabled.
+  pub fn get_tls_config(&self) -> Result<Option<TlsConfig>, Error> {
+    let tls = &self.tls;
+
+    if !tls.enabled {
+      return Ok(None);
+    }
+
+    let server_name = tls.server_name.as_deref().unwrap_or(&self.addr);
+    let mut builder = TlsConfig::builder(server_name);
+
+    if let Some(ca_file) = &tls.ca_file {
+      builder = builder.with_ca_file(ca_file);
+    }
+
+    for fingerprint in tls.pinned_certs.iter() {
+      builder = builder.with_pinned_cert(fingerprint.as_ref());
+    }
+
+    builder = match (&tls.client_cert, &tls.client_key) {
+      (Some(cert), Some(key)) => builder.with_client_cert(cert, key),
+      (None, None) => builder,
+      _ => {
+        return Err(
+          TlsError::Config("client certificate and client key must be configured together".into())
+            .into(),
+        );
+      }
+    };
+
+    builder.build().map(Some)
+  }
+
+  /// Derives TLS config of a fallback endpoint from the primary config, the endpoint's hostname is
+  /// used for certificate verification.
+  pub fn get_endpoint_tls_config(
+    &self,
+    tls: &TlsConfig,
+    endpoint: &str,
+  ) -> Result<TlsConfig, Error> {
+    tls.with_server_name(endpoint_host(endpoint))
+  }
+}
+
+/// Strips the port from `host:port` form. IPv6 addresses in `[addr]:port` form are returned
+/// without brackets.
+fn endpoint_host(endpoint: &str) -> &str {
+  if let Some(rest) = endpoint.strip_prefix('[') {
+    return rest.split_once(']').map_or(rest, |(host, _)| host);
+  }
+
+  match endpoint.rsplit_once(':') {
+    Some((host, port)) if !host.contains(':') && port.parse::<u16>().is_ok() => host,
+    _ => endpoint,
   }
 }
 
@@ -83,9 +267,38 @@ impl Default for UpsdConfig {
       user: None,
       addr: "127.0.0.1".into(),
       port: 3493,
+      fallback_endpoints: Vec::new(),
+      failback_delay: 30,
       poll_freq: 30,
       poll_interval: 2,
+      rw_refresh_interval: 300,
       max_conn: unsafe { NonZeroUsize::new_unchecked(4) },
+      connect_timeout: 5,
+      read_timeout: 10,
+      pool_max_idle: 300,
+      pool_max_lifetime: 0,
+      pool_validate_after: 30,
+      tls: UpsdTlsConfig::default(),
+    }
+  }
+}
+
+impl Default for HistoryConfig {
+  fn default() -> Self {
+    Self {
+      variables: vec![
+        VarName::BATTERY_CHARGE,
+        VarName::BATTERY_RUNTIME,
+        VarName::BATTERY_VOLTAGE,
+        VarName::INPUT_FREQUENCY,
+        VarName::INPUT_VOLTAGE,
+        VarName::OUTPUT_VOLTAGE,
+        VarName::UPS_LOAD,
+        VarName::UPS_POWER,
+        VarName::UPS_REALPOWER,
+        VarName::UPS_TEMPERATURE,
+      ],
+      retention_days: 30,
     }
   }
 }
@@ -98,6 +311,8 @@ impl Default for ServerConfig {
       log_level: Level::INFO,
       upsd: Default::default(),
       http_server: Default::default(),
+      history: Default::default(),
+      storage: Default::default(),
     }
   }
 }
@@ -123,18 +338,30 @@ impl core::fmt::Debug for UpsdConfig {
     struct _Filtered<'a> {
       poll_freq: u64,
       poll_interval: u64,
+      rw_refresh_interval: u64,
       addr: &'a str,
       port: u16,
+      fallback_endpoints: &'a [Box<str>],
+      failback_delay: u64,
       user: Option<&'static str>,
       pass: Option<&'static str>,
       max_conn: NonZeroUsize,
+      connect_timeout: u64,
+      read_timeout: u64,
+      pool_max_idle: u64,
+      pool_max_lifetime: u64,
+      pool_validate_after: u64,
+      tls: &'a UpsdTlsConfig,
     }
 
     let filtered = _Filtered {
       poll_freq: self.poll_freq,
       poll_interval: self.poll_interval,
+      rw_refresh_interval: self.rw_refresh_interval,
       addr: self.addr.as_ref(),
       port: self.port,
+      fallback_endpoints: &self.fallback_endpoints,
+      failback_delay: self.failback_delay,
       user: if self.user.is_some() {
         Some("******")
       } else {
@@ -146,8 +373,53 @@ impl core::fmt::Debug for UpsdConfig {
         None
       },
       max_conn: self.max_conn,
+      connect_timeout: self.connect_timeout,
+      read_timeout: self.read_timeout,
+      pool_max_idle: self.pool_max_idle,
+      pool_max_lifetime: self.pool_max_lifetime,
+      pool_validate_after: self.pool_validate_after,
+      tls: &self.tls,
     };
 
     core::fmt::Debug::fmt(&filtered, f)
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{UpsdConfig, endpoint_host};
+  use core::time::Duration;
+
+  #[test]
+  fn endpoint_host_strips_port() {
+    assert_eq!(endpoint_host("upsd-backup.local:3493"), "upsd-backup.local");
+    assert_eq!(endpoint_host("192.168.1.20:3493"), "192.168.1.20");
+    assert_eq!(endpoint_host("upsd-backup.local"), "upsd-backup.local");
+  }
+
+  #[test]
+  fn endpoint_host_ipv6() {
+    assert_eq!(endpoint_host("[fd00::20]:3493"), "fd00::20");
+    assert_eq!(endpoint_host("[fd00::20]"), "fd00::20");
+    assert_eq!(endpoint_host("fd00::20"), "fd00::20");
+  }
+
+  #[test]
+  fn failback_probe_interval() {
+    let mut config = UpsdConfig::default();
+
+    assert_eq!(config.get_failback_probe_interval(), None);
+
+    config.fallback_endpoints = vec!["upsd-backup.local:3493".into()];
+    config.failback_delay = 30;
+
+    assert_eq!(
+      config.get_failback_probe_interval(),
+      Some(Duration::from_secs(30))
+    );
+
+    config.failback_delay = 0;
+
+    assert_eq!(config.get_failback_probe_interval(), None);
+  }
+}
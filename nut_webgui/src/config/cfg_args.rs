@@ -2,6 +2,7 @@ use super::{ConfigLayer, ServerConfig, macros::override_opt_field};
 use crate::uri_path::{InvalidPathError, UriPath};
 use clap::Parser;
 use core::net::IpAddr;
+use nut_webgui_upsmc::VarName;
 use std::{num::NonZeroUsize, path::PathBuf};
 
 #[derive(Parser, Debug)]
@@ -15,11 +16,35 @@ pub struct ServerCliArgs {
   #[arg(long)]
   pub poll_interval: Option<u64>,
 
+  /// Writable variable details and command lists refresh interval in seconds, 0 disables it.
+  #[arg(long)]
+  pub rw_refresh_interval: Option<u64>,
+
   /// Allowed maximum connection for UPSD client.
   #[arg(long)]
   pub upsd_max_connection: Option<NonZeroUsize>,
 
-  /// NUT server address
+  /// Connection timeout in seconds for NUT server connections
+  #[arg(long)]
+  pub upsd_connect_timeout: Option<u64>,
+
+  /// Request timeout in seconds for NUT server connections
+  #[arg(long)]
+  pub upsd_read_timeout: Option<u64>,
+
+  /// Closes pooled connections idle longer than given seconds, 0 keeps them open
+  #[arg(long)]
+  pub upsd_pool_max_idle: Option<u64>,
+
+  /// Closes pooled connections older than given seconds, 0 disables the limit
+  #[arg(long)]
+  pub upsd_pool_max_lifetime: Option<u64>,
+
+  /// Checks pooled connections idle longer than given seconds before use
+  #[arg(long)]
+  pub upsd_pool_validate_after: Option<u64>,
+
+  /// NUT server address, or a unix domain socket path with `unix:` prefix
   #[arg(long)]
   pub upsd_addr: Option<Box<str>>,
 
@@ -27,6 +52,14 @@ pub struct ServerCliArgs {
   #[arg(short, long)]
   pub upsd_port: Option<u16>,
 
+  /// Fallback NUT server endpoint in host:port or unix:path form, can be used multiple times
+  #[arg(long)]
+  pub upsd_fallback_endpoint: Option<Vec<Box<str>>>,
+
+  /// Delay in seconds before returning to a higher priority NUT server, 0 disables failback
+  #[arg(long)]
+  pub upsd_failback_delay: Option<u64>,
+
   /// NUT username
   #[arg(long)]
   pub upsd_user: Option<Box<str>>,
@@ -35,6 +68,42 @@ pub struct ServerCliArgs {
   #[arg(long)]
   pub upsd_pass: Option<Box<str>>,
 
+  /// Requires STARTTLS for NUT server connections
+  #[arg(long, default_value_t = false)]
+  pub upsd_tls: bool,
+
+  /// NUT server name used for TLS certificate verification of the primary address
+  #[arg(long)]
+  pub upsd_tls_server_name: Option<Box<str>>,
+
+  /// PEM encoded CA certificate file for NUT server certificate verification
+  #[arg(long)]
+  pub upsd_tls_ca_file: Option<PathBuf>,
+
+  /// SHA-256 fingerprint of trusted NUT server certificate, can be used multiple times
+  #[arg(long)]
+  pub upsd_tls_pinned_cert: Option<Vec<Box<str>>>,
+
+  /// PEM encoded client certificate file for NUT server
+  #[arg(long)]
+  pub upsd_tls_client_cert: Option<PathBuf>,
+
+  /// PEM encoded client private key file for NUT server
+  #[arg(long)]
+  pub upsd_tls_client_key: Option<PathBuf>,
+
+  /// Numeric variable recorded for metric history, can be used multiple times
+  #[arg(long)]
+  pub history_variable: Option<Vec<VarName>>,
+
+  /// Metric history retention in days, 0 disables it
+  #[arg(long)]
+  pub history_retention_days: Option<u64>,
+
+  /// Data directory for persistent metric history and events
+  #[arg(long)]
+  pub storage_path: Option<PathBuf>,
+
   /// Listen address for HTTP server
   #[arg(short, long)]
   pub listen: Option<IpAddr>,
@@ -83,13 +152,36 @@ impl ConfigLayer for ServerCliArgs {
     override_opt_field!(config.log_level, inner_value: self.log_level);
 
     override_opt_field!(config.upsd.addr, inner_value: self.upsd_addr);
+    override_opt_field!(config.upsd.connect_timeout, inner_value: self.upsd_connect_timeout);
+    override_opt_field!(config.upsd.failback_delay, inner_value: self.upsd_failback_delay);
+    override_opt_field!(config.upsd.fallback_endpoints, inner_value: self.upsd_fallback_endpoint);
     override_opt_field!(config.upsd.pass, self.upsd_pass);
+    override_opt_field!(config.upsd.pool_max_idle, inner_value: self.upsd_pool_max_idle);
+    override_opt_field!(config.upsd.pool_max_lifetime, inner_value: self.upsd_pool_max_lifetime);
+    override_opt_field!(config.upsd.pool_validate_after, inner_value: self.upsd_pool_validate_after);
     override_opt_field!(config.upsd.poll_freq, inner_value: self.poll_freq);
     override_opt_field!(config.upsd.poll_interval, inner_value: self.poll_interval);
+    override_opt_field!(config.upsd.rw_refresh_interval, inner_value: self.rw_refresh_interval);
     override_opt_field!(config.upsd.port, inner_value: self.upsd_port);
+    override_opt_field!(config.upsd.read_timeout, inner_value: self.upsd_read_timeout);
     override_opt_field!(config.upsd.max_conn, inner_value: self.upsd_max_connection);
     override_opt_field!(config.upsd.user, self.upsd_user);
 
+    if self.upsd_tls {
+      config.upsd.tls.enabled = true;
+    }
+
+    override_opt_field!(config.upsd.tls.ca_file, self.upsd_tls_ca_file);
+    override_opt_field!(config.upsd.tls.client_cert, self.upsd_tls_client_cert);
+    override_opt_field!(config.upsd.tls.client_key, self.upsd_tls_client_key);
+    override_opt_field!(config.upsd.tls.pinned_certs, inner_value: self.upsd_tls_pinned_cert);
+    override_opt_field!(config.upsd.tls.server_name, self.upsd_tls_server_name);
+
+    override_opt_field!(config.history.retention_days, inner_value: self.history_retention_days);
+    override_opt_field!(config.history.variables, inner_value: self.history_variable);
+
+    override_opt_field!(config.storage.path, self.storage_path);
+
     override_opt_field!(config.http_server.base_path, inner_value:  self.base_path);
     override_opt_field!(config.http_server.listen, inner_value: self.listen);
     override_opt_field!(config.http_server.port, inner_value: self.port);
@@ -1,6 +1,7 @@
 use super::{ConfigLayer, ServerConfig, error::EnvConfigError};
 use crate::{config::macros::override_opt_field, uri_path::UriPath};
 use core::net::IpAddr;
+use nut_webgui_upsmc::VarName;
 use std::{
   env,
   fs::File,
@@ -14,16 +15,33 @@ use tracing::Level;
 pub struct ServerEnvArgs {
   pub config_file: Option<PathBuf>,
   pub default_theme: Option<Box<str>>,
+  pub history_retention_days: Option<u64>,
+  pub history_variables: Option<Vec<VarName>>,
   pub listen: Option<IpAddr>,
   pub log_level: Option<tracing::Level>,
   pub poll_freq: Option<u64>,
   pub poll_interval: Option<u64>,
+  pub rw_refresh_interval: Option<u64>,
   pub port: Option<u16>,
+  pub storage_path: Option<PathBuf>,
   pub upsd_addr: Option<Box<str>>,
+  pub upsd_connect_timeout: Option<u64>,
+  pub upsd_failback_delay: Option<u64>,
+  pub upsd_fallback_endpoints: Option<Vec<Box<str>>>,
   pub upsd_pass: Option<Box<str>>,
+  pub upsd_pool_max_idle: Option<u64>,
+  pub upsd_pool_max_lifetime: Option<u64>,
+  pub upsd_pool_validate_after: Option<u64>,
   pub upsd_port: Option<u16>,
+  pub upsd_read_timeout: Option<u64>,
   pub upsd_user: Option<Box<str>>,
   pub upsd_max_conn: Option<NonZeroUsize>,
+  pub upsd_tls_ca_file: Option<PathBuf>,
+  pub upsd_tls_client_cert: Option<PathBuf>,
+  pub upsd_tls_client_key: Option<PathBuf>,
+  pub upsd_tls_enabled: Option<bool>,
+  pub upsd_tls_pinned_certs: Option<Vec<Box<str>>>,
+  pub upsd_tls_server_name: Option<Box<str>>,
   pub base_path: Option<UriPath>,
 }
 
@@ -63,6 +81,29 @@ macro_rules! load_var {
       $target_field = Some(Box::from(value.trim()));
     }
   };
+  (@rule $env_name:literal, $target_field:expr, boxed_str_list) => {
+    if let Some(value) = $crate::config::cfg_env::load_from_env($env_name)? {
+      $target_field = Some(
+        value
+          .split(',')
+          .map(|v| v.trim())
+          .filter(|v| !v.is_empty())
+          .map(Box::from)
+          .collect(),
+      );
+    }
+  };
+  (@rule $env_name:literal, $target_field:expr, [$item_type:ty]) => {
+    if let Some(value) = $crate::config::cfg_env::load_from_env($env_name)? {
+      let mut items = Vec::new();
+
+      for item in value.split(',').map(|v| v.trim()).filter(|v| !v.is_empty()) {
+        items.push(item.parse::<$item_type>()?);
+      }
+
+      $target_field = Some(items);
+    }
+  };
   (@rule $env_name:literal, $target_field:expr, path_buf) => {
     if let Ok(value) = std::env::var($env_name) {
       $target_field = Some(std::path::PathBuf::from(value.trim()));
@@ -84,17 +125,37 @@ impl ServerEnvArgs {
       ("NUTWG__DEFAULT_THEME",          env_config.default_theme, boxed_str);
       ("NUTWG__LOG_LEVEL",              env_config.log_level,     Level);
 
+      ("NUTWG__HISTORY__RETENTION_DAYS", env_config.history_retention_days, u64);
+      ("NUTWG__HISTORY__VARIABLES",     env_config.history_variables, [VarName]);
+
+      ("NUTWG__STORAGE__PATH",          env_config.storage_path,  path_buf);
+
       ("NUTWG__HTTP_SERVER__BASE_PATH", env_config.base_path,     UriPath);
       ("NUTWG__HTTP_SERVER__LISTEN",    env_config.listen,        IpAddr);
       ("NUTWG__HTTP_SERVER__PORT",      env_config.port,          u16);
 
       ("NUTWG__UPSD__ADDRESS",          env_config.upsd_addr,     boxed_str);
+      ("NUTWG__UPSD__CONNECT_TIMEOUT",  env_config.upsd_connect_timeout, u64);
+      ("NUTWG__UPSD__FAILBACK_DELAY",   env_config.upsd_failback_delay, u64);
+      ("NUTWG__UPSD__FALLBACK_ENDPOINTS", env_config.upsd_fallback_endpoints, boxed_str_list);
       ("NUTWG__UPSD__MAX_CONNECTION",   env_config.upsd_max_conn, NonZeroUsize);
       ("NUTWG__UPSD__PASSWORD",         env_config.upsd_pass,     boxed_str);
       ("NUTWG__UPSD__POLL_FREQ",        env_config.poll_freq,     u64);
       ("NUTWG__UPSD__POLL_INTERVAL",    env_config.poll_interval, u64);
+      ("NUTWG__UPSD__POOL_MAX_IDLE",    env_config.upsd_pool_max_idle, u64);
+      ("NUTWG__UPSD__POOL_MAX_LIFETIME", env_config.upsd_pool_max_lifetime, u64);
+      ("NUTWG__UPSD__POOL_VALIDATE_AFTER", env_config.upsd_pool_validate_after, u64);
       ("NUTWG__UPSD__PORT",             env_config.upsd_port,     u16);
+      ("NUTWG__UPSD__READ_TIMEOUT",     env_config.upsd_read_timeout, u64);
+      ("NUTWG__UPSD__RW_REFRESH_INTERVAL", env_config.rw_refresh_interval, u64);
       ("NUTWG__UPSD__USERNAME",         env_config.upsd_user,     boxed_str);
+
+      ("NUTWG__UPSD__TLS__CA_FILE",       env_config.upsd_tls_ca_file,      path_buf);
+      ("NUTWG__UPSD__TLS__CLIENT_CERT",   env_config.upsd_tls_client_cert,  path_buf);
+      ("NUTWG__UPSD__TLS__CLIENT_KEY",    env_config.upsd_tls_client_key,   path_buf);
+      ("NUTWG__UPSD__TLS__ENABLED",       env_config.upsd_tls_enabled,      bool);
+      ("NUTWG__UPSD__TLS__PINNED_CERTS",  env_config.upsd_tls_pinned_certs, boxed_str_list);
+      ("NUTWG__UPSD__TLS__SERVER_NAME",   env_config.upsd_tls_server_name,  boxed_str);
     );
 
     Ok(env_config)
@@ -108,13 +169,33 @@ impl ConfigLayer for ServerEnvArgs {
     override_opt_field!(config.log_level, inner_value: self.log_level);
 
     override_opt_field!(config.upsd.addr, inner_value: self.upsd_addr);
+    override_opt_field!(config.upsd.connect_timeout, inner_value: self.upsd_connect_timeout);
+    override_opt_field!(config.upsd.failback_delay, inner_value: self.upsd_failback_delay);
+    override_opt_field!(config.upsd.fallback_endpoints, inner_value: self.upsd_fallback_endpoints);
     override_opt_field!(config.upsd.max_conn, inner_value: self.upsd_max_conn);
     override_opt_field!(config.upsd.pass, self.upsd_pass);
+    override_opt_field!(config.upsd.pool_max_idle, inner_value: self.upsd_pool_max_idle);
+    override_opt_field!(config.upsd.pool_max_lifetime, inner_value: self.upsd_pool_max_lifetime);
+    override_opt_field!(config.upsd.pool_validate_after, inner_value: self.upsd_pool_validate_after);
     override_opt_field!(config.upsd.poll_freq, inner_value: self.poll_freq);
     override_opt_field!(config.upsd.poll_interval, inner_value: self.poll_interval);
+    override_opt_field!(config.upsd.rw_refresh_interval, inner_value: self.rw_refresh_interval);
     override_opt_field!(config.upsd.port, inner_value: self.upsd_port);
+    override_opt_field!(config.upsd.read_timeout, inner_value: self.upsd_read_timeout);
     override_opt_field!(config.upsd.user, self.upsd_user);
 
+    override_opt_field!(config.upsd.tls.ca_file, self.upsd_tls_ca_file);
+    override_opt_field!(config.upsd.tls.client_cert, self.upsd_tls_client_cert);
+    override_opt_field!(config.upsd.tls.client_key, self.upsd_tls_client_key);
+    override_opt_field!(config.upsd.tls.enabled, inner_value: self.upsd_tls_enabled);
+    override_opt_field!(config.upsd.tls.pinned_certs, inner_value: self.upsd_tls_pinned_certs);
+    override_opt_field!(config.upsd.tls.server_name, self.upsd_tls_server_name);
+
+    override_opt_field!(config.history.retention_days, inner_value: self.history_retention_days);
+    override_opt_field!(config.history.variables, inner_value: self.history_variables);
+
+    override_opt_field!(config.storage.path, self.storage_path);
+
     override_opt_field!(config.http_server.base_path, inner_value: self.base_path);
     override_opt_field!(config.http_server.listen, inner_value: self.listen);
     override_opt_field!(config.http_server.port, inner_value: self.port);
@@ -1,8 +1,14 @@
 use super::{ConfigLayer, ServerConfig, error::TomlConfigError};
 use crate::{config::macros::override_opt_field, uri_path::UriPath};
 use core::{net::IpAddr, str};
+use nut_webgui_upsmc::VarName;
 use serde::{Deserialize, de::Visitor};
-use std::{fs::File, io::Read, num::NonZeroUsize, path::Path};
+use std::{
+  fs::File,
+  io::Read,
+  num::NonZeroUsize,
+  path::{Path, PathBuf},
+};
 use tracing::Level;
 
 #[derive(Debug)]
@@ -53,6 +59,8 @@ pub struct ServerTomlArgs {
   pub log_level: Option<LogLevel>,
   pub http_server: HttpServerConfigSection,
   pub upsd: UpsdConfigSection,
+  pub history: Option<HistoryConfigSection>,
+  pub storage: Option<StorageConfigSection>,
 }
 
 #[derive(Deserialize, Default, Debug)]
@@ -68,9 +76,39 @@ pub struct UpsdConfigSection {
   pub password: Option<Box<str>>,
   pub poll_freq: Option<u64>,
   pub poll_interval: Option<u64>,
+  pub rw_refresh_interval: Option<u64>,
   pub port: Option<u16>,
+  pub fallback_endpoints: Option<Vec<Box<str>>>,
+  pub failback_delay: Option<u64>,
   pub username: Option<Box<str>>,
   pub max_connection: Option<NonZeroUsize>,
+  pub connect_timeout: Option<u64>,
+  pub read_timeout: Option<u64>,
+  pub pool_max_idle: Option<u64>,
+  pub pool_max_lifetime: Option<u64>,
+  pub pool_validate_after: Option<u64>,
+  pub tls: Option<UpsdTlsConfigSection>,
+}
+
+#[derive(Deserialize, Default, Debug)]
+pub struct UpsdTlsConfigSection {
+  pub enabled: Option<bool>,
+  pub server_name: Option<Box<str>>,
+  pub ca_file: Option<PathBuf>,
+  pub pinned_certs: Option<Vec<Box<str>>>,
+  pub client_cert: Option<PathBuf>,
+  pub client_key: Option<PathBuf>,
+}
+
+#[derive(Deserialize, Default, Debug)]
+pub struct HistoryConfigSection {
+  pub variables: Option<Vec<VarName>>,
+  pub retention_days: Option<u64>,
+}
+
+#[derive(Deserialize, Default, Debug)]
+pub struct StorageConfigSection {
+  pub path: Option<PathBuf>,
 }
 
 impl ServerTomlArgs {
@@ -95,13 +133,39 @@ impl ConfigLayer for ServerTomlArgs {
     override_opt_field!(config.log_level, inner_value: self.log_level.map(|val| val.0));
 
     override_opt_field!(config.upsd.addr, inner_value: self.upsd.address);
+    override_opt_field!(config.upsd.connect_timeout, inner_value: self.upsd.connect_timeout);
+    override_opt_field!(config.upsd.failback_delay, inner_value: self.upsd.failback_delay);
+    override_opt_field!(config.upsd.fallback_endpoints, inner_value: self.upsd.fallback_endpoints);
     override_opt_field!(config.upsd.max_conn, inner_value: self.upsd.max_connection);
     override_opt_field!(config.upsd.pass, self.upsd.password);
+    override_opt_field!(config.upsd.pool_max_idle, inner_value: self.upsd.pool_max_idle);
+    override_opt_field!(config.upsd.pool_max_lifetime, inner_value: self.upsd.pool_max_lifetime);
+    override_opt_field!(config.upsd.pool_validate_after, inner_value: self.upsd.pool_validate_after);
     override_opt_field!(config.upsd.poll_freq, inner_value: self.upsd.poll_freq);
     override_opt_field!(config.upsd.poll_interval, inner_value: self.upsd.poll_interval);
+    override_opt_field!(config.upsd.rw_refresh_interval, inner_value: self.upsd.rw_refresh_interval);
     override_opt_field!(config.upsd.port, inner_value: self.upsd.port);
+    override_opt_field!(config.upsd.read_timeout, inner_value: self.upsd.read_timeout);
     override_opt_field!(config.upsd.user, self.upsd.username);
 
+    if let Some(tls) = self.upsd.tls {
+      override_opt_field!(config.upsd.tls.ca_file, tls.ca_file);
+      override_opt_field!(config.upsd.tls.client_cert, tls.client_cert);
+      override_opt_field!(config.upsd.tls.client_key, tls.client_key);
+      override_opt_field!(config.upsd.tls.enabled, inner_value: tls.enabled);
+      override_opt_field!(config.upsd.tls.pinned_certs, inner_value: tls.pinned_certs);
+      override_opt_field!(config.upsd.tls.server_name, tls.server_name);
+    }
+
+    if let Some(history) = self.history {
+      override_opt_field!(config.history.retention_days, inner_value: history.retention_days);
+      override_opt_field!(config.history.variables, inner_value: history.variables);
+    }
+
+    if let Some(storage) = self.storage {
+      override_opt_field!(config.storage.path, storage.path);
+    }
+
     override_opt_field!(config.http_server.base_path, inner_value: self.http_server.base_path);
     override_opt_field!(config.http_server.listen, inner_value: self.http_server.listen);
     override_opt_field!(config.http_server.port, inner_value: self.http_server.port);
@@ -1,5 +1,6 @@
 use crate::uri_path::InvalidPathError;
-use core::{net::AddrParseError, num::ParseIntError};
+use core::{net::AddrParseError, num::ParseIntError, str::ParseBoolError};
+use nut_webgui_upsmc::errors::VarNameParseError;
 use std::ffi::OsString;
 
 #[derive(Debug)]
@@ -43,9 +44,11 @@ pub enum EnvConfigError {
   IOError { inner: std::io::Error },
   NonUnicodeVar { variable: OsString },
   InvalidNumericFormat { inner: ParseIntError },
+  InvalidBoolFormat,
   InvalidLogLevelFormat,
   InvalidAddrFormat { inner: core::net::AddrParseError },
   InvalidUriPath,
+  InvalidVarName { inner: VarNameParseError },
 }
 
 impl core::error::Error for EnvConfigError {}
@@ -63,6 +66,9 @@ impl std::fmt::Display for EnvConfigError {
       EnvConfigError::InvalidNumericFormat { .. } => {
         f.write_fmt(format_args!("invalid numeric format"))
       }
+      EnvConfigError::InvalidBoolFormat => {
+        f.write_fmt(format_args!("invalid boolean format, expected true or false"))
+      }
       EnvConfigError::InvalidLogLevelFormat => {
         f.write_fmt(format_args!("invalid log level"))
       }
@@ -70,6 +76,9 @@ impl std::fmt::Display for EnvConfigError {
         f.write_fmt(format_args!("invalid ip address format, {}", inner))
       }
       EnvConfigError::InvalidUriPath => f.write_fmt(format_args!("invalid uri path format")),
+      EnvConfigError::InvalidVarName { inner } => {
+        f.write_fmt(format_args!("invalid variable name, {}", inner))
+      }
     }
   }
 }
@@ -101,12 +110,24 @@ impl From<ParseIntError> for EnvConfigError {
   }
 }
 
+impl From<ParseBoolError> for EnvConfigError {
+  fn from(_: ParseBoolError) -> Self {
+    Self::InvalidBoolFormat
+  }
+}
+
 impl From<InvalidPathError> for EnvConfigError {
   fn from(_: InvalidPathError) -> Self {
     Self::InvalidUriPath
   }
 }
 
+impl From<VarNameParseError> for EnvConfigError {
+  fn from(value: VarNameParseError) -> Self {
+    Self::InvalidVarName { inner: value }
+  }
+}
+
 impl From<AddrParseError> for EnvConfigError {
   fn from(value: AddrParseError) -> Self {
     Self::InvalidAddrFormat { inner: value }
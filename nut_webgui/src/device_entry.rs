@@ -1,59 +1,177 @@
 use chrono::{DateTime, Utc};
 use core::net::IpAddr;
-use nut_webgui_upsmc::{UpsName, Value, VarName, ups_status::UpsStatus, variables::UpsVariables};
-use serde::{Serialize, ser::SerializeStruct};
+use nut_webgui_upsmc::{
+  CmdName, RwDetail, UpsName, Value, VarCategory, VarKind, VarName, VarUnit,
+  errors::ParseDiagnostic, ups_status::UpsStatusInfo, variables::UpsVariables,
+};
+use serde::{
+  Serialize,
+  ser::{SerializeMap, SerializeStruct},
+};
 use std::collections::HashMap;
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Clone)]
 pub struct DeviceEntry {
   pub attached: Vec<IpAddr>,
   pub commands: Vec<String>,
   pub desc: Box<str>,
   pub last_modified: DateTime<Utc>,
   pub name: UpsName,
-  pub rw_variables: HashMap<VarName, VarDetail>,
-  pub status: UpsStatus,
+  pub parse_warnings: ParseWarnings,
+  pub rw_variables: HashMap<VarName, RwDetail>,
+  pub status: UpsStatusInfo,
   pub variables: UpsVariables,
 }
 
-#[derive(Debug, Clone)]
-pub enum VarDetail {
-  String { max_len: usize },
-  Number,
-  Enum { options: Vec<Value> },
-  Range { min: Value, max: Value },
+/// Malformed lines skipped while parsing device lists. Each group is replaced when its list is
+/// reloaded, so warnings disappear once the driver reports valid data.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ParseWarnings {
+  pub commands: Vec<ParseWarning>,
+  /// Includes `LIST ENUM` lines of writable variables.
+  pub rw_variables: Vec<ParseWarning>,
+  pub variables: Vec<ParseWarning>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParseWarning {
+  pub line: usize,
+  pub col: usize,
+  pub text: Box<str>,
+  pub reason: Box<str>,
+}
+
+/// Variable value formatted with its standard metadata, so variables are displayed the same way in
+/// UI and JSON API.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VarFormat {
+  pub category: VarCategory,
+  pub kind: VarKind,
+  /// Only set when the value is numeric.
+  pub unit: Option<VarUnit>,
+  /// Value with its unit symbol, e.g. `230 V`.
+  pub display: String,
+}
+
+/// Serializes catalog metadata of standard commands as a map. Non-standard commands are skipped.
+struct CmdMetadataMap<'a>(&'a [String]);
+
+/// Serializes [VarFormat] of all variables as a map.
+struct VarFormats<'a>(&'a UpsVariables);
+
+impl ParseWarnings {
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  #[inline]
+  pub fn len(&self) -> usize {
+    self.commands.len() + self.rw_variables.len() + self.variables.len()
+  }
+
+  /// Iterates over all warnings with the list name they belong to.
+  pub fn iter(&self) -> impl Iterator<Item = (&'static str, &ParseWarning)> {
+    let variables = self.variables.iter().map(|w| ("LIST VAR", w));
+    let rw_variables = self.rw_variables.iter().map(|w| ("LIST RW", w));
+    let commands = self.commands.iter().map(|w| ("LIST CMD", w));
+
+    variables.chain(rw_variables).chain(commands)
+  }
 }
 
-impl Serialize for VarDetail {
+impl VarFormat {
+  /// Custom variables have no unit, their kind is guessed from the value.
+  pub fn new(name: &VarName, value: &Value) -> Self {
+    let (category, kind, unit) = match name.metadata() {
+      Some(metadata) => (metadata.category, metadata.kind, metadata.unit),
+      None if value.is_numeric() => (name.category(), VarKind::Number, None),
+      None => (name.category(), VarKind::Text, None),
+    };
+
+    // Drivers may report placeholders like "N/A" for numeric variables.
+    let unit = unit.filter(|_| kind.is_numeric() && value.is_numeric());
+
+    let display = match unit {
+      Some(unit) => format!("{} {}", value, unit.symbol()),
+      None => value.to_string(),
+    };
+
+    Self {
+      category,
+      kind,
+      unit,
+      display,
+    }
+  }
+
+  /// Returns `true` for durations in seconds, which are rendered as time display in UI.
+  #[inline]
+  pub fn is_seconds(&self) -> bool {
+    self.unit == Some(VarUnit::Second)
+  }
+}
+
+impl From<ParseDiagnostic> for ParseWarning {
+  fn from(value: ParseDiagnostic) -> Self {
+    Self {
+      line: value.position.line,
+      col: value.position.col,
+      reason: value.error.to_string().into_boxed_str(),
+      text: value.line,
+    }
+  }
+}
+
+impl Serialize for DeviceEntry {
   fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
   where
     S: serde::Serializer,
   {
-    match self {
-      VarDetail::String { max_len } => {
-        let mut ser = serializer.serialize_struct("VarDetail", 2)?;
-        ser.serialize_field("type", "string")?;
-        ser.serialize_field("max_len", max_len)?;
-        ser.end()
-      }
-      VarDetail::Number => {
-        let mut ser = serializer.serialize_struct("VarDetail", 1)?;
-        ser.serialize_field("type", "number")?;
-        ser.end()
-      }
-      VarDetail::Enum { options } => {
-        let mut ser = serializer.serialize_struct("VarDetail", 2)?;
-        ser.serialize_field("type", "enum")?;
-        ser.serialize_field("options", options)?;
-        ser.end()
-      }
-      VarDetail::Range { min, max } => {
-        let mut ser = serializer.serialize_struct("VarDetail", 3)?;
-        ser.serialize_field("type", "range")?;
-        ser.serialize_field("min", min)?;
-        ser.serialize_field("max", max)?;
-        ser.end()
+    let mut ser = serializer.serialize_struct("DeviceEntry", 11)?;
+    ser.serialize_field("attached", &self.attached)?;
+    ser.serialize_field("command_metadata", &CmdMetadataMap(&self.commands))?;
+    ser.serialize_field("commands", &self.commands)?;
+    ser.serialize_field("desc", &self.desc)?;
+    ser.serialize_field("last_modified", &self.last_modified)?;
+    ser.serialize_field("name", &self.name)?;
+    ser.serialize_field("parse_warnings", &self.parse_warnings)?;
+    ser.serialize_field("rw_variables", &self.rw_variables)?;
+    ser.serialize_field("status", &self.status)?;
+    ser.serialize_field("variable_formats", &VarFormats(&self.variables))?;
+    ser.serialize_field("variables", &self.variables)?;
+    ser.end()
+  }
+}
+
+impl Serialize for CmdMetadataMap<'_> {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer,
+  {
+    let mut ser = serializer.serialize_map(None)?;
+
+    for cmd in self.0.iter() {
+      if let Some(metadata) = CmdName::new_unchecked(cmd.as_str()).metadata() {
+        ser.serialize_entry(cmd, &metadata)?;
       }
     }
+
+    ser.end()
+  }
+}
+
+impl Serialize for VarFormats<'_> {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer,
+  {
+    let mut ser = serializer.serialize_map(Some(self.0.len()))?;
+
+    for (name, value) in self.0.iter() {
+      ser.serialize_entry(name, &VarFormat::new(name, value))?;
+    }
+
+    ser.end()
   }
 }
@@ -1,4 +1,4 @@
-use nut_webgui_upsmc::{UpsName, responses::UpsDevice};
+use nut_webgui_upsmc::{RwDetail, UpsName, VarName, responses::UpsDevice};
 use std::{collections::HashMap, net::IpAddr};
 
 pub trait Diff<T> {
@@ -81,3 +81,143 @@ where
     diff
   }
 }
+
+pub struct RwDiff {
+  pub added: Vec<VarName>,
+  pub removed: Vec<VarName>,
+  pub modified: Vec<VarName>,
+}
+
+impl RwDiff {
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+  }
+}
+
+impl<'a> Diff<&'a HashMap<VarName, RwDetail>> for &HashMap<VarName, RwDetail> {
+  type Result = RwDiff;
+
+  fn into_diff(self, target: &'a HashMap<VarName, RwDetail>) -> Self::Result {
+    let mut diff = RwDiff {
+      added: Vec::new(),
+      removed: Vec::new(),
+      modified: Vec::new(),
+    };
+
+    for (name, detail) in target.iter() {
+      match self.get(name) {
+        Some(old) if old != detail => diff.modified.push(name.clone()),
+        Some(_) => {}
+        None => diff.added.push(name.clone()),
+      }
+    }
+
+    for name in self.keys() {
+      if !target.contains_key(name) {
+        diff.removed.push(name.clone());
+      }
+    }
+
+    diff
+  }
+}
+
+pub struct CmdDiff {
+  pub added: Vec<String>,
+  pub removed: Vec<String>,
+}
+
+impl<'a> Diff<&'a [String]> for &[String] {
+  type Result = CmdDiff;
+
+  fn into_diff(self, target: &'a [String]) -> Self::Result {
+    CmdDiff {
+      added: target
+        .iter()
+        .filter(|v| !self.contains(v))
+        .cloned()
+        .collect(),
+      removed: self
+        .iter()
+        .filter(|v| !target.contains(v))
+        .cloned()
+        .collect(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Diff;
+  use nut_webgui_upsmc::{RwDetail, Value, VarName};
+  use std::collections::HashMap;
+
+  fn rw_vars(vars: &[(&str, RwDetail)]) -> HashMap<VarName, RwDetail> {
+    vars
+      .iter()
+      .map(|(name, detail)| (VarName::new(name).unwrap(), detail.clone()))
+      .collect()
+  }
+
+  fn sorted(mut names: Vec<VarName>) -> Vec<VarName> {
+    names.sort();
+    names
+  }
+
+  #[test]
+  fn rw_diff_reports_added_removed_modified() {
+    let old = rw_vars(&[
+      ("input.transfer.high", RwDetail::Number),
+      ("ups.delay.shutdown", RwDetail::Number),
+      ("ups.id", RwDetail::String { max_len: 16 }),
+    ]);
+    let new = rw_vars(&[
+      ("input.transfer.high", RwDetail::Number),
+      ("ups.id", RwDetail::String { max_len: 32 }),
+      (
+        "input.sensitivity",
+        RwDetail::Enum {
+          options: vec![Value::from("low"), Value::from("high")],
+        },
+      ),
+    ]);
+
+    let diff = (&old).into_diff(&new);
+
+    assert!(!diff.is_empty());
+    assert_eq!(diff.added, [VarName::new("input.sensitivity").unwrap()]);
+    assert_eq!(diff.removed, [VarName::new("ups.delay.shutdown").unwrap()]);
+    assert_eq!(diff.modified, [VarName::new("ups.id").unwrap()]);
+  }
+
+  #[test]
+  fn rw_diff_of_identical_maps_is_empty() {
+    let vars = rw_vars(&[
+      ("input.transfer.high", RwDetail::Number),
+      ("ups.id", RwDetail::String { max_len: 16 }),
+    ]);
+
+    assert!((&vars).into_diff(&vars.clone()).is_empty());
+  }
+
+  #[test]
+  fn rw_diff_from_empty_adds_everything() {
+    let new = rw_vars(&[
+      ("input.transfer.high", RwDetail::Number),
+      ("ups.id", RwDetail::String { max_len: 16 }),
+    ]);
+
+    let diff = (&HashMap::new()).into_diff(&new);
+
+    assert_eq!(
+      sorted(diff.added),
+      [
+        VarName::new("input.transfer.high").unwrap(),
+        VarName::new("ups.id").unwrap()
+      ]
+    );
+    assert!(diff.removed.is_empty());
+    assert!(diff.modified.is_empty());
+  }
+}
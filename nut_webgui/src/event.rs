@@ -1,7 +1,7 @@
 use std::net::IpAddr;
 
 use crate::state::DaemonStatus;
-use nut_webgui_upsmc::{UpsName, ups_status::UpsStatus};
+use nut_webgui_upsmc::{UpsName, VarName, ups_status::UpsStatus};
 use tokio::sync::broadcast::{Receiver, Sender, channel};
 
 #[derive(Debug, Clone)]
@@ -17,12 +17,24 @@ pub struct DeviceClientInfo {
   pub clients: Vec<IpAddr>,
 }
 
+/// Changes on writable variables and instant commands of a device.
+#[derive(Debug, Clone)]
+pub struct DeviceRwChange {
+  pub name: UpsName,
+  pub rw_added: Vec<VarName>,
+  pub rw_removed: Vec<VarName>,
+  pub rw_modified: Vec<VarName>,
+  pub cmds_added: Vec<String>,
+  pub cmds_removed: Vec<String>,
+}
+
 #[derive(Debug, Clone)]
 pub enum SystemEvent {
   DeviceAddition { devices: Vec<UpsName> },
   DeviceRemoval { devices: Vec<UpsName> },
   DeviceUpdate { devices: Vec<UpsName> },
   DeviceStatusChange { changes: Vec<DeviceStatusChange> },
+  DeviceRwChange { changes: Vec<DeviceRwChange> },
   DaemonStatusUpdate { status: DaemonStatus },
   ClientConnection { devices: Vec<DeviceClientInfo> },
   ClientDisconnection { devices: Vec<DeviceClientInfo> },
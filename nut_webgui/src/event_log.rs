@@ -0,0 +1,454 @@
+use crate::{event::SystemEvent, state::DaemonStatus};
+use chrono::{DateTime, Utc};
+use nut_webgui_upsmc::{
+  UpsName,
+  ups_event::{UpsEvent, UpsEvents},
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::VecDeque, net::IpAddr};
+
+/// Category of a journal entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+  DeviceAdded,
+  DeviceRemoved,
+  /// Device switched to battery power.
+  Outage,
+  /// Device is receiving power from wall again.
+  Recovery,
+  StatusChange,
+  RwChange,
+  ClientConnected,
+  ClientDisconnected,
+  DaemonStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventLevel {
+  Info,
+  Success,
+  Warning,
+  Error,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventEntry {
+  pub id: u64,
+  pub timestamp: DateTime<Utc>,
+  pub kind: EventKind,
+  pub level: EventLevel,
+  /// Related device, [None] for upsd events.
+  pub device: Option<UpsName>,
+  pub message: String,
+}
+
+/// Optional filters of [EventLog::query].
+#[derive(Debug, Default)]
+pub struct EventFilter {
+  pub device: Option<UpsName>,
+  pub kind: Option<EventKind>,
+  pub min_level: Option<EventLevel>,
+  pub from: Option<DateTime<Utc>>,
+  pub to: Option<DateTime<Utc>>,
+}
+
+impl EventFilter {
+  fn matches(&self, entry: &EventEntry) -> bool {
+    self
+      .device
+      .as_ref()
+      .is_none_or(|device| entry.device.as_ref() == Some(device))
+      && self.kind.is_none_or(|kind| entry.kind == kind)
+      && self.min_level.is_none_or(|level| entry.level >= level)
+      && self.from.is_none_or(|from| entry.timestamp >= from)
+      && self.to.is_none_or(|to| entry.timestamp <= to)
+  }
+}
+
+/// Bounded journal of [SystemEvent]s in human readable form. Oldest entries are dropped when
+/// capacity is reached.
+#[derive(Debug)]
+pub struct EventLog {
+  entries: VecDeque<EventEntry>,
+  capacity: usize,
+  next_id: u64,
+}
+
+impl EventLog {
+  pub fn new(capacity: usize) -> Self {
+    Self {
+      entries: VecDeque::new(),
+      capacity: capacity.max(1),
+      next_id: 1,
+    }
+  }
+
+  /// Restores entries loaded from storage. Entries are expected in insertion order.
+  pub fn with_entries<I>(mut self, entries: I) -> Self
+  where
+    I: IntoIterator<Item = EventEntry>,
+  {
+    for entry in entries {
+      self.next_id = self.next_id.max(entry.id + 1);
+      self.push_entry(entry);
+    }
+
+    self
+  }
+
+  #[inline]
+  pub fn capacity(&self) -> usize {
+    self.capacity
+  }
+
+  /// Iterates over entries from oldest to newest.
+  pub fn iter(&self) -> impl DoubleEndedIterator<Item = &EventEntry> {
+    self.entries.iter()
+  }
+
+  /// Returns matching entries from newest to oldest.
+  pub fn query<'a>(&'a self, filter: &'a EventFilter) -> impl Iterator<Item = &'a EventEntry> + 'a {
+    self
+      .entries
+      .iter()
+      .rev()
+      .filter(|entry| filter.matches(entry))
+  }
+
+  /// Records a system event, and returns the new entries. Periodic events like
+  /// [SystemEvent::DeviceUpdate] are not recorded.
+  pub fn record(&mut self, event: &SystemEvent, timestamp: DateTime<Utc>) -> Vec<EventEntry> {
+    let mut new_entries = Vec::new();
+    let mut push = |kind, level, device: Option<&UpsName>, message: String| {
+      new_entries.push(EventEntry {
+        id: 0,
+        timestamp,
+        kind,
+        level,
+        device: device.cloned(),
+        message,
+      });
+    };
+
+    match event {
+      SystemEvent::DeviceAddition { devices } => {
+        for device in devices {
+          push(
+            EventKind::DeviceAdded,
+            EventLevel::Info,
+            Some(device),
+            String::from("Device connected"),
+          );
+        }
+      }
+      SystemEvent::DeviceRemoval { devices } => {
+        for device in devices {
+          push(
+            EventKind::DeviceRemoved,
+            EventLevel::Warning,
+            Some(device),
+            String::from("Device disconnected"),
+          );
+        }
+      }
+      SystemEvent::DeviceStatusChange { changes } => {
+        for change in changes {
+          let mut events: Vec<UpsEvent> = UpsEvents::new(change.old_status, change.new_status)
+            .iter()
+            .copied()
+            .collect();
+
+          // Most severe events first, event set has no stable order.
+          events.sort_by_cached_key(|event| {
+            (core::cmp::Reverse(event_level(event)), event.to_string())
+          });
+
+          for event in events {
+            let kind = match event {
+              UpsEvent::OnBattery => EventKind::Outage,
+              UpsEvent::Online => EventKind::Recovery,
+              _ => EventKind::StatusChange,
+            };
+
+            push(
+              kind,
+              event_level(&event),
+              Some(&change.name),
+              event.to_string(),
+            );
+          }
+        }
+      }
+      SystemEvent::DeviceRwChange { changes } => {
+        for change in changes {
+          let parts: Vec<String> = [
+            (change.rw_added.len(), "writable variable", "added"),
+            (change.rw_removed.len(), "writable variable", "removed"),
+            (change.rw_modified.len(), "writable variable", "modified"),
+            (change.cmds_added.len(), "command", "added"),
+            (change.cmds_removed.len(), "command", "removed"),
+          ]
+          .into_iter()
+          .filter(|(count, _, _)| *count > 0)
+          .map(|(count, noun, verb)| match count {
+            1 => format!("1 {noun} {verb}"),
+            _ => format!("{count} {noun}s {verb}"),
+          })
+          .collect();
+
+          push(
+            EventKind::RwChange,
+            EventLevel::Info,
+            Some(&change.name),
+            parts.join(", "),
+          );
+        }
+      }
+      SystemEvent::ClientConnection { devices } => {
+        for info in devices {
+          push(
+            EventKind::ClientConnected,
+            EventLevel::Info,
+            Some(&info.name),
+            client_message("attached", &info.clients),
+          );
+        }
+      }
+      SystemEvent::ClientDisconnection { devices } => {
+        for info in devices {
+          push(
+            EventKind::ClientDisconnected,
+            EventLevel::Info,
+            Some(&info.name),
+            client_message("detached", &info.clients),
+          );
+        }
+      }
+      SystemEvent::DaemonStatusUpdate { status } => {
+        let (level, message) = match status {
+          DaemonStatus::Online => (EventLevel::Success, "Ups daemon is online"),
+          DaemonStatus::Dead => (EventLevel::Error, "Ups daemon is unreachable"),
+          DaemonStatus::NotReady => (EventLevel::Warning, "Ups daemon is not ready"),
+        };
+
+        push(EventKind::DaemonStatus, level, None, String::from(message));
+      }
+      SystemEvent::DeviceUpdate { .. } => {}
+    }
+
+    for entry in new_entries.iter_mut() {
+      entry.id = self.next_id;
+      self.next_id += 1;
+      self.push_entry(entry.clone());
+    }
+
+    new_entries
+  }
+
+  fn push_entry(&mut self, entry: EventEntry) {
+    self.entries.push_back(entry);
+
+    while self.entries.len() > self.capacity {
+      _ = self.entries.pop_front();
+    }
+  }
+}
+
+fn client_message(action: &str, clients: &[IpAddr]) -> String {
+  let addresses: Vec<String> = clients.iter().map(|addr| addr.to_string()).collect();
+
+  match addresses.len() {
+    1 => format!("Client {action}: {}", addresses[0]),
+    _ => format!("Clients {action}: {}", addresses.join(", ")),
+  }
+}
+
+const fn event_level(event: &UpsEvent) -> EventLevel {
+  match event {
+    UpsEvent::OnBattery
+    | UpsEvent::LowBattery
+    | UpsEvent::FSD
+    | UpsEvent::NoCOMM
+    | UpsEvent::NoBattery
+    | UpsEvent::Overloaded
+    | UpsEvent::Overheat
+    | UpsEvent::FanFailure
+    | UpsEvent::DeviceOff => EventLevel::Error,
+    UpsEvent::AlarmOn
+    | UpsEvent::ReplaceBattery
+    | UpsEvent::HighBattery
+    | UpsEvent::BypassOn
+    | UpsEvent::Boosting
+    | UpsEvent::Trimming => EventLevel::Warning,
+    UpsEvent::Online
+    | UpsEvent::COMM
+    | UpsEvent::LowBatteryEnded
+    | UpsEvent::NoBatteryEnded
+    | UpsEvent::OverloadEnded
+    | UpsEvent::OverheatEnded
+    | UpsEvent::FanFailureEnded
+    | UpsEvent::DeviceOn
+    | UpsEvent::ReplaceBatteryEnded
+    | UpsEvent::AlarmOff => EventLevel::Success,
+    _ => EventLevel::Info,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{EventFilter, EventKind, EventLevel, EventLog};
+  use crate::{
+    event::{DeviceStatusChange, SystemEvent},
+    state::DaemonStatus,
+  };
+  use chrono::{DateTime, TimeDelta, Utc};
+  use nut_webgui_upsmc::{UpsName, ups_status::UpsStatus};
+
+  fn timestamp() -> DateTime<Utc> {
+    "2024-01-10T10:00:00Z".parse().unwrap()
+  }
+
+  fn status_change(name: &UpsName, old_status: &str, new_status: &str) -> SystemEvent {
+    SystemEvent::DeviceStatusChange {
+      changes: vec![DeviceStatusChange {
+        name: name.clone(),
+        old_status: UpsStatus::from(old_status),
+        new_status: UpsStatus::from(new_status),
+      }],
+    }
+  }
+
+  #[test]
+  fn record_outage_and_recovery() {
+    let device = UpsName::new_unchecked("bx1600mi");
+    let mut log = EventLog::new(16);
+
+    let outage = log.record(&status_change(&device, "OL", "OB"), timestamp());
+    let recovery = log.record(
+      &status_change(&device, "OB", "OL"),
+      timestamp() + TimeDelta::minutes(5),
+    );
+
+    assert_eq!(outage[0].kind, EventKind::Outage);
+    assert_eq!(outage[0].level, EventLevel::Error);
+    assert_eq!(outage[0].device.as_ref(), Some(&device));
+    assert!(
+      recovery
+        .iter()
+        .any(|entry| entry.kind == EventKind::Recovery && entry.level == EventLevel::Success)
+    );
+
+    // Ids are assigned in insertion order.
+    let ids: Vec<u64> = log.iter().map(|entry| entry.id).collect();
+    let expected: Vec<u64> = (1..=ids.len() as u64).collect();
+
+    assert_eq!(ids, expected);
+  }
+
+  #[test]
+  fn record_skips_periodic_updates() {
+    let mut log = EventLog::new(16);
+    let entries = log.record(
+      &SystemEvent::DeviceUpdate {
+        devices: vec![UpsName::new_unchecked("bx1600mi")],
+      },
+      timestamp(),
+    );
+
+    assert!(entries.is_empty());
+    assert_eq!(log.iter().count(), 0);
+  }
+
+  #[test]
+  fn record_drops_oldest_entries() {
+    let mut log = EventLog::new(2);
+
+    for status in [
+      DaemonStatus::Online,
+      DaemonStatus::Dead,
+      DaemonStatus::NotReady,
+    ] {
+      _ = log.record(&SystemEvent::DaemonStatusUpdate { status }, timestamp());
+    }
+
+    let ids: Vec<u64> = log.iter().map(|entry| entry.id).collect();
+
+    assert_eq!(ids, [2, 3]);
+  }
+
+  #[test]
+  fn query_filters_newest_first() {
+    let ups1 = UpsName::new_unchecked("ups1");
+    let ups2 = UpsName::new_unchecked("ups2");
+    let mut log = EventLog::new(16);
+
+    _ = log.record(
+      &SystemEvent::DeviceAddition {
+        devices: vec![ups1.clone(), ups2.clone()],
+      },
+      timestamp(),
+    );
+    _ = log.record(
+      &SystemEvent::DeviceRemoval {
+        devices: vec![ups1.clone()],
+      },
+      timestamp() + TimeDelta::minutes(1),
+    );
+    _ = log.record(
+      &SystemEvent::DaemonStatusUpdate {
+        status: DaemonStatus::Dead,
+      },
+      timestamp() + TimeDelta::minutes(2),
+    );
+
+    let query = |filter: EventFilter| -> Vec<u64> { log.query(&filter).map(|e| e.id).collect() };
+
+    assert_eq!(query(EventFilter::default()), [4, 3, 2, 1]);
+    assert_eq!(
+      query(EventFilter {
+        device: Some(ups1.clone()),
+        ..Default::default()
+      }),
+      [3, 1]
+    );
+    assert_eq!(
+      query(EventFilter {
+        min_level: Some(EventLevel::Warning),
+        ..Default::default()
+      }),
+      [4, 3]
+    );
+    assert_eq!(
+      query(EventFilter {
+        kind: Some(EventKind::DeviceAdded),
+        from: Some(timestamp()),
+        to: Some(timestamp()),
+        ..Default::default()
+      }),
+      [2, 1]
+    );
+  }
+
+  #[test]
+  fn restored_entries_continue_ids() {
+    let mut log = EventLog::new(16);
+    let entries = log.record(
+      &SystemEvent::DaemonStatusUpdate {
+        status: DaemonStatus::Online,
+      },
+      timestamp(),
+    );
+
+    let mut restored = EventLog::new(16).with_entries(entries);
+    let next = restored.record(
+      &SystemEvent::DaemonStatusUpdate {
+        status: DaemonStatus::Dead,
+      },
+      timestamp(),
+    );
+
+    assert_eq!(next[0].id, 2);
+  }
+}
@@ -0,0 +1,959 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use nut_webgui_upsmc::{UpsName, VarName, variables::UpsVariables};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+/// Downsampling tier of recorded metrics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Resolution {
+  #[serde(rename = "1m")]
+  Minute,
+  #[serde(rename = "15m")]
+  QuarterHour,
+  #[serde(rename = "1h")]
+  Hour,
+}
+
+impl Resolution {
+  /// All tiers, from finest to coarsest.
+  pub const ALL: [Resolution; 3] = [
+    Resolution::Minute,
+    Resolution::QuarterHour,
+    Resolution::Hour,
+  ];
+
+  #[inline]
+  pub const fn as_secs(&self) -> i64 {
+    match self {
+      Resolution::Minute => 60,
+      Resolution::QuarterHour => 15 * 60,
+      Resolution::Hour => 60 * 60,
+    }
+  }
+
+  /// Retention limit of the tier. Hourly tier is only limited by the configured retention.
+  const fn max_retention(&self) -> Option<TimeDelta> {
+    match self {
+      Resolution::Minute => Some(TimeDelta::hours(6)),
+      Resolution::QuarterHour => Some(TimeDelta::days(7)),
+      Resolution::Hour => None,
+    }
+  }
+
+  #[inline]
+  const fn align(&self, timestamp: i64) -> i64 {
+    timestamp - timestamp.rem_euclid(self.as_secs())
+  }
+
+  const fn from_u8(value: u8) -> Option<Self> {
+    match value {
+      0 => Some(Resolution::Minute),
+      1 => Some(Resolution::QuarterHour),
+      2 => Some(Resolution::Hour),
+      _ => None,
+    }
+  }
+
+  const fn as_u8(&self) -> u8 {
+    match self {
+      Resolution::Minute => 0,
+      Resolution::QuarterHour => 1,
+      Resolution::Hour => 2,
+    }
+  }
+}
+
+/// Aggregated samples of a single time bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct HistoryPoint {
+  /// Start of the bucket.
+  pub timestamp: DateTime<Utc>,
+  pub avg: f64,
+  pub min: f64,
+  pub max: f64,
+}
+
+/// Points of a single variable within the requested time range.
+#[derive(Debug, Serialize)]
+pub struct HistorySeries {
+  pub resolution: Resolution,
+  pub points: Vec<HistoryPoint>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+  start: i64,
+  sum: f64,
+  count: u32,
+  min: f64,
+  max: f64,
+}
+
+impl Bucket {
+  fn new(start: i64, value: f64) -> Self {
+    Self {
+      start,
+      sum: value,
+      count: 1,
+      min: value,
+      max: value,
+    }
+  }
+
+  fn merge(&mut self, other: &Bucket) {
+    self.sum += other.sum;
+    self.count += other.count;
+    self.min = self.min.min(other.min);
+    self.max = self.max.max(other.max);
+  }
+
+  fn to_point(self) -> HistoryPoint {
+    HistoryPoint {
+      timestamp: DateTime::from_timestamp(self.start, 0).unwrap_or_default(),
+      avg: self.sum / f64::from(self.count),
+      min: self.min,
+      max: self.max,
+    }
+  }
+}
+
+#[derive(Debug)]
+struct Tier {
+  resolution: Resolution,
+  capacity: usize,
+  points: VecDeque<HistoryPoint>,
+  current: Option<Bucket>,
+}
+
+impl Tier {
+  fn new(resolution: Resolution, retention: TimeDelta) -> Self {
+    let retention = match resolution.max_retention() {
+      Some(max_retention) => retention.min(max_retention),
+      None => retention,
+    };
+
+    Self {
+      resolution,
+      capacity: (retention.num_seconds() / resolution.as_secs()).max(1) as usize,
+      points: VecDeque::new(),
+      current: None,
+    }
+  }
+
+  #[inline]
+  fn retention(&self) -> TimeDelta {
+    TimeDelta::seconds(self.capacity as i64 * self.resolution.as_secs())
+  }
+
+  /// Merges a sample or an aggregate of a finer tier, returns the bucket completed by it.
+  fn push(&mut self, sample: Bucket) -> Option<Bucket> {
+    let start = self.resolution.align(sample.start);
+
+    match &mut self.current {
+      Some(bucket) if bucket.start == start => {
+        bucket.merge(&sample);
+        None
+      }
+      // Samples older than the current bucket are dropped, e.g. after a system clock change.
+      Some(bucket) if bucket.start > start => None,
+      current => {
+        let completed = current.replace(Bucket { start, ..sample })?;
+        self.points.push_back(completed.to_point());
+
+        while self.points.len() > self.capacity {
+          _ = self.points.pop_front();
+        }
+
+        Some(completed)
+      }
+    }
+  }
+
+  /// Iterates over completed points, followed by the bucket still in progress.
+  fn iter(&self) -> impl Iterator<Item = HistoryPoint> + '_ {
+    self
+      .points
+      .iter()
+      .copied()
+      .chain(self.current.map(Bucket::to_point))
+  }
+}
+
+#[derive(Debug)]
+struct VarHistory {
+  tiers: Vec<Tier>,
+
+  /// Start of the latest bucket already in storage.
+  persisted_until: i64,
+}
+
+impl VarHistory {
+  fn new(retention: TimeDelta) -> Self {
+    Self {
+      tiers: Resolution::ALL
+        .iter()
+        .map(|resolution| Tier::new(*resolution, retention))
+        .collect(),
+      persisted_until: i64::MIN,
+    }
+  }
+
+  /// Pushes a sample to all tiers, returns the completed minute bucket that is not persisted yet.
+  fn push(&mut self, sample: Bucket) -> Option<Bucket> {
+    let mut completed = None;
+
+    for tier in self.tiers.iter_mut() {
+      let bucket = tier.push(sample);
+
+      if tier.resolution == Resolution::Minute {
+        completed = bucket.filter(|bucket| bucket.start > self.persisted_until);
+      }
+    }
+
+    completed
+  }
+
+  /// Pushes a stored aggregate to the tiers it can be merged into.
+  fn restore(&mut self, resolution: Resolution, bucket: Bucket) {
+    for tier in self.tiers.iter_mut() {
+      if tier.resolution >= resolution {
+        _ = tier.push(bucket);
+      }
+    }
+
+    self.persisted_until = self.persisted_until.max(bucket.start);
+  }
+
+  /// Start of the latest bucket, including the ones still in progress.
+  fn latest(&self) -> Option<i64> {
+    self
+      .tiers
+      .iter()
+      .filter_map(|tier| tier.current.map(|bucket| bucket.start))
+      .max()
+  }
+
+  /// Returns the finest tier whose retention still covers `from`, or the coarsest one.
+  fn select_tier(&self, from: DateTime<Utc>, now: DateTime<Utc>) -> &Tier {
+    self
+      .tiers
+      .iter()
+      .find(|tier| now - tier.retention() <= from)
+      .or(self.tiers.last())
+      .expect("history has at least one tier")
+  }
+}
+
+/// Aggregated bucket of a single variable as kept in persistent storage.
+#[derive(Debug, Clone)]
+pub struct HistoryRecord {
+  device: UpsName,
+  var: VarName,
+  resolution: Resolution,
+  bucket: Bucket,
+}
+
+/// Encoded size of the fixed fields: resolution, start, count, sum, min and max.
+const RECORD_FIXED_LEN: usize = 1 + 8 + 4 + 8 + 8 + 8;
+
+impl HistoryRecord {
+  pub fn encode(&self, buffer: &mut Vec<u8>) {
+    let device: &str = self.device.as_ref();
+    let var: &str = self.var.as_str();
+
+    buffer.reserve(RECORD_FIXED_LEN + 4 + device.len() + var.len());
+    buffer.push(self.resolution.as_u8());
+    buffer.extend_from_slice(&self.bucket.start.to_le_bytes());
+    buffer.extend_from_slice(&self.bucket.count.to_le_bytes());
+    buffer.extend_from_slice(&self.bucket.sum.to_le_bytes());
+    buffer.extend_from_slice(&self.bucket.min.to_le_bytes());
+    buffer.extend_from_slice(&self.bucket.max.to_le_bytes());
+
+    for name in [device, var] {
+      buffer.extend_from_slice(&(name.len() as u16).to_le_bytes());
+      buffer.extend_from_slice(name.as_bytes());
+    }
+  }
+
+  /// Returns [None] when the record is malformed.
+  pub fn decode(bytes: &[u8]) -> Option<Self> {
+    let (fixed, mut names) = bytes.split_at_checked(RECORD_FIXED_LEN)?;
+    let resolution = Resolution::from_u8(fixed[0])?;
+    let start = i64::from_le_bytes(fixed[1..9].try_into().ok()?);
+    let count = u32::from_le_bytes(fixed[9..13].try_into().ok()?);
+    let sum = f64::from_le_bytes(fixed[13..21].try_into().ok()?);
+    let min = f64::from_le_bytes(fixed[21..29].try_into().ok()?);
+    let max = f64::from_le_bytes(fixed[29..37].try_into().ok()?);
+
+    let mut next_name = || {
+      let (len, rest) = names.split_at_checked(2)?;
+      let len = u16::from_le_bytes(len.try_into().ok()?) as usize;
+      let (name, rest) = rest.split_at_checked(len)?;
+      names = rest;
+
+      core::str::from_utf8(name).ok()
+    };
+
+    let device: UpsName = next_name()?.parse().ok()?;
+    let var: VarName = next_name()?.parse().ok()?;
+
+    if count == 0 {
+      return None;
+    }
+
+    Some(Self {
+      device,
+      var,
+      resolution,
+      bucket: Bucket {
+        start,
+        sum,
+        count,
+        min,
+        max,
+      },
+    })
+  }
+}
+
+/// Drops expired records and merges old minute records into coarser tiers, the same way the
+/// in-memory tiers age out. Returned records are ordered by time for each variable.
+pub fn compact_records(
+  records: Vec<HistoryRecord>,
+  variables: &[VarName],
+  retention: TimeDelta,
+  now: DateTime<Utc>,
+) -> Vec<HistoryRecord> {
+  let now = now.timestamp();
+  let expire_before = now - retention.num_seconds();
+
+  // Cut-off points are aligned to hours, so merged buckets never span two resolutions.
+  let cut_offs: Vec<(Resolution, Option<i64>)> = Resolution::ALL
+    .iter()
+    .map(|resolution| {
+      let cut_off = resolution
+        .max_retention()
+        .map(|max_retention| Resolution::Hour.align(now - max_retention.num_seconds()));
+
+      (*resolution, cut_off)
+    })
+    .collect();
+
+  let mut series: HashMap<(UpsName, VarName), Vec<HistoryRecord>> = HashMap::new();
+
+  for record in records {
+    if record.bucket.start >= expire_before && variables.contains(&record.var) {
+      series
+        .entry((record.device.clone(), record.var.clone()))
+        .or_default()
+        .push(record);
+    }
+  }
+
+  let mut compacted = Vec::new();
+
+  for mut records in series.into_values() {
+    records.sort_by_key(|record| record.bucket.start);
+    let offset = compacted.len();
+
+    for record in records {
+      let target = cut_offs
+        .iter()
+        .find(|(_, cut_off)| cut_off.is_none_or(|cut_off| record.bucket.start >= cut_off))
+        .map_or(Resolution::Hour, |(resolution, _)| *resolution);
+
+      let resolution = record.resolution.max(target);
+      let start = resolution.align(record.bucket.start);
+
+      match compacted[offset..].last_mut() {
+        Some(HistoryRecord {
+          resolution: last_resolution,
+          bucket,
+          ..
+        }) if *last_resolution == resolution && bucket.start == start => {
+          bucket.merge(&record.bucket)
+        }
+        _ => compacted.push(HistoryRecord {
+          resolution,
+          bucket: Bucket {
+            start,
+            ..record.bucket
+          },
+          ..record
+        }),
+      }
+    }
+  }
+
+  compacted
+}
+
+/// Upper limit of pending records kept while storage is failing, the oldest ones are dropped
+/// beyond it.
+const PENDING_LIMIT: usize = 64 * 1024;
+
+/// Bounded in-memory time series of numeric device variables, downsampled into
+/// [Resolution] tiers.
+#[derive(Debug)]
+pub struct HistoryStore {
+  variables: Vec<VarName>,
+  retention: Option<TimeDelta>,
+  devices: HashMap<UpsName, HashMap<VarName, VarHistory>>,
+
+  /// Completed minute buckets waiting to be written, only collected when persistence is enabled.
+  pending: Option<Vec<HistoryRecord>>,
+}
+
+impl HistoryStore {
+  /// Creates a store for the given variables. Nothing is recorded when `retention` is [None].
+  pub fn new(variables: Vec<VarName>, retention: Option<core::time::Duration>) -> Self {
+    Self {
+      variables,
+      retention: retention.and_then(|v| TimeDelta::from_std(v).ok()),
+      devices: HashMap::new(),
+      pending: None,
+    }
+  }
+
+  /// Restores records loaded from storage and starts collecting completed buckets for
+  /// [HistoryStore::take_pending]. Records of variables that are no longer configured are skipped.
+  pub fn with_persistence<I>(mut self, records: I) -> Self
+  where
+    I: IntoIterator<Item = HistoryRecord>,
+  {
+    if let Some(retention) = self.retention {
+      for record in records {
+        if !self.variables.contains(&record.var) {
+          continue;
+        }
+
+        self
+          .devices
+          .entry(record.device)
+          .or_default()
+          .entry(record.var)
+          .or_insert_with(|| VarHistory::new(retention))
+          .restore(record.resolution, record.bucket);
+      }
+
+      self.pending = Some(Vec::new());
+    }
+
+    self
+  }
+
+  /// Takes completed buckets recorded since the last call.
+  pub fn take_pending(&mut self) -> Vec<HistoryRecord> {
+    self
+      .pending
+      .as_mut()
+      .map(core::mem::take)
+      .unwrap_or_default()
+  }
+
+  /// Puts records back in front of the pending queue, e.g. after a failed write, so they are taken
+  /// again with the next [HistoryStore::take_pending] call.
+  pub fn restore_pending(&mut self, mut records: Vec<HistoryRecord>) {
+    let Some(pending) = self.pending.as_mut() else {
+      return;
+    };
+
+    records.append(pending);
+
+    if records.len() > PENDING_LIMIT {
+      records.drain(..records.len() - PENDING_LIMIT);
+    }
+
+    *pending = records;
+  }
+
+  #[inline]
+  pub fn is_enabled(&self) -> bool {
+    self.retention.is_some()
+  }
+
+  /// Returns true when the variable is configured for recording.
+  #[inline]
+  pub fn is_recorded(&self, var: &VarName) -> bool {
+    self.is_enabled() && self.variables.contains(var)
+  }
+
+  /// Returns true when the store holds any history of the device, including removed devices whose
+  /// history is not expired yet.
+  #[inline]
+  pub fn contains_device(&self, device: &UpsName) -> bool {
+    self.devices.contains_key(device)
+  }
+
+  /// Records configured variables of a device. Non-numeric values are skipped.
+  pub fn record(&mut self, device: &UpsName, variables: &UpsVariables, timestamp: DateTime<Utc>) {
+    let Some(retention) = self.retention else {
+      return;
+    };
+
+    let timestamp = timestamp.timestamp();
+    let series = self.devices.entry(device.clone()).or_default();
+
+    for var in self.variables.iter() {
+      let Some(value) = variables.get(var).and_then(|v| v.as_lossly_f64()) else {
+        continue;
+      };
+
+      let completed = series
+        .entry(var.clone())
+        .or_insert_with(|| VarHistory::new(retention))
+        .push(Bucket::new(timestamp, value));
+
+      if let (Some(pending), Some(bucket)) = (&mut self.pending, completed) {
+        pending.push(HistoryRecord {
+          device: device.clone(),
+          var: var.clone(),
+          resolution: Resolution::Minute,
+          bucket,
+        });
+      }
+    }
+  }
+
+  /// Drops variables without any sample within the retention, and devices left without any
+  /// variable. History of a removed device is kept until then, in case it reconnects.
+  pub fn prune_expired(&mut self, now: DateTime<Utc>) {
+    let Some(retention) = self.retention else {
+      return;
+    };
+
+    let expire_before = (now - retention).timestamp();
+
+    self.devices.retain(|_, series| {
+      series.retain(|_, history| history.latest().is_some_and(|start| start >= expire_before));
+      !series.is_empty()
+    });
+  }
+
+  /// Returns points between `from` and `to`. When resolution is not specified, the finest tier
+  /// covering `from` is used.
+  pub fn query(
+    &self,
+    device: &UpsName,
+    var: &VarName,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    resolution: Option<Resolution>,
+  ) -> Option<HistorySeries> {
+    let history = self.devices.get(device)?.get(var)?;
+    let tier = match resolution {
+      Some(resolution) => history
+        .tiers
+        .iter()
+        .find(|tier| tier.resolution == resolution)?,
+      None => history.select_tier(from, Utc::now()),
+    };
+
+    // Buckets starting before `from` are included when they overlap with the requested range.
+    let from = from - TimeDelta::seconds(tier.resolution.as_secs());
+
+    Some(HistorySeries {
+      resolution: tier.resolution,
+      points: tier
+        .iter()
+        .filter(|point| point.timestamp > from && point.timestamp <= to)
+        .collect(),
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{Bucket, HistoryRecord, HistoryStore, PENDING_LIMIT, Resolution, compact_records};
+  use chrono::{DateTime, TimeDelta, Utc};
+  use core::time::Duration;
+  use nut_webgui_upsmc::{UpsName, Value, VarName, variables::UpsVariables};
+
+  const RETENTION: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+  fn store() -> HistoryStore {
+    HistoryStore::new(vec![VarName::BATTERY_CHARGE], Some(RETENTION))
+  }
+
+  fn timestamp(value: &str) -> DateTime<Utc> {
+    value.parse().unwrap()
+  }
+
+  fn minute_record(var: VarName, timestamp: DateTime<Utc>, value: f64) -> HistoryRecord {
+    HistoryRecord {
+      device: UpsName::new_unchecked("bx1600mi"),
+      var,
+      resolution: Resolution::Minute,
+      bucket: Bucket::new(timestamp.timestamp(), value),
+    }
+  }
+
+  fn record(store: &mut HistoryStore, device: &UpsName, value: i64, timestamp: DateTime<Utc>) {
+    let mut variables = UpsVariables::new();
+    _ = variables.insert(VarName::BATTERY_CHARGE, Value::from(value));
+
+    store.record(device, &variables, timestamp);
+  }
+
+  #[test]
+  fn query_selects_finest_tier_covering_range() {
+    let device = UpsName::new_unchecked("bx1600mi");
+    let now = Utc::now();
+    let mut store = store();
+
+    for minute in (0..120).rev() {
+      record(
+        &mut store,
+        &device,
+        minute,
+        now - TimeDelta::minutes(minute),
+      );
+    }
+
+    let resolution_of = |from: TimeDelta| {
+      store
+        .query(&device, &VarName::BATTERY_CHARGE, now - from, now, None)
+        .map(|series| series.resolution)
+    };
+
+    assert_eq!(resolution_of(TimeDelta::hours(1)), Some(Resolution::Minute));
+    assert_eq!(
+      resolution_of(TimeDelta::hours(7)),
+      Some(Resolution::QuarterHour)
+    );
+    assert_eq!(
+      resolution_of(TimeDelta::days(2)),
+      Some(Resolution::QuarterHour)
+    );
+    assert_eq!(resolution_of(TimeDelta::days(10)), Some(Resolution::Hour));
+
+    // Ranges beyond the retention fall back to the coarsest tier.
+    assert_eq!(resolution_of(TimeDelta::days(60)), Some(Resolution::Hour));
+  }
+
+  #[test]
+  fn query_with_explicit_resolution() {
+    let device = UpsName::new_unchecked("bx1600mi");
+    let now = Utc::now();
+    let mut store = store();
+
+    for minute in (0..120).rev() {
+      record(
+        &mut store,
+        &device,
+        minute,
+        now - TimeDelta::minutes(minute),
+      );
+    }
+
+    let from = now - TimeDelta::hours(1);
+    let minutes = store
+      .query(
+        &device,
+        &VarName::BATTERY_CHARGE,
+        from,
+        now,
+        Some(Resolution::Minute),
+      )
+      .unwrap();
+    let hours = store
+      .query(
+        &device,
+        &VarName::BATTERY_CHARGE,
+        from,
+        now,
+        Some(Resolution::Hour),
+      )
+      .unwrap();
+
+    assert_eq!(hours.resolution, Resolution::Hour);
+    assert!(hours.points.len() <= 2);
+    assert_eq!(minutes.resolution, Resolution::Minute);
+
+    // Bucket overlapping with `from` is included.
+    assert!((60..=62).contains(&minutes.points.len()));
+    assert!(
+      minutes
+        .points
+        .iter()
+        .all(|point| point.timestamp > from - TimeDelta::minutes(1) && point.timestamp <= now)
+    );
+  }
+
+  #[test]
+  fn query_unknown_series() {
+    let device = UpsName::new_unchecked("bx1600mi");
+    let now = Utc::now();
+    let mut store = store();
+
+    record(&mut store, &device, 100, now);
+
+    assert!(
+      store
+        .query(
+          &UpsName::new_unchecked("other"),
+          &VarName::BATTERY_CHARGE,
+          now - TimeDelta::hours(1),
+          now,
+          None
+        )
+        .is_none()
+    );
+    assert!(
+      store
+        .query(
+          &device,
+          &VarName::UPS_LOAD,
+          now - TimeDelta::hours(1),
+          now,
+          None
+        )
+        .is_none()
+    );
+  }
+
+  #[test]
+  fn prune_keeps_history_within_retention() {
+    let removed = UpsName::new_unchecked("removed");
+    let expired = UpsName::new_unchecked("expired");
+    let now = Utc::now();
+    let mut store = store();
+
+    record(&mut store, &removed, 100, now - TimeDelta::days(29));
+    record(&mut store, &expired, 100, now - TimeDelta::days(31));
+    store.prune_expired(now);
+
+    let from = now - TimeDelta::days(30);
+
+    assert!(
+      store
+        .query(&removed, &VarName::BATTERY_CHARGE, from, now, None)
+        .is_some()
+    );
+    assert!(
+      store
+        .query(&expired, &VarName::BATTERY_CHARGE, from, now, None)
+        .is_none()
+    );
+  }
+
+  #[test]
+  fn record_encode_decode_round_trip() {
+    let record = HistoryRecord {
+      device: UpsName::new_unchecked("bx1600mi@localhost"),
+      var: VarName::BATTERY_CHARGE,
+      resolution: Resolution::QuarterHour,
+      bucket: Bucket {
+        start: 1_704_845_700,
+        sum: 301.5,
+        count: 3,
+        min: 99.5,
+        max: 101.0,
+      },
+    };
+
+    let mut buffer = Vec::new();
+    record.encode(&mut buffer);
+
+    let decoded = HistoryRecord::decode(&buffer).unwrap();
+
+    assert_eq!(decoded.device, record.device);
+    assert_eq!(decoded.var, record.var);
+    assert_eq!(decoded.resolution, record.resolution);
+    assert_eq!(decoded.bucket.start, record.bucket.start);
+    assert_eq!(decoded.bucket.sum, record.bucket.sum);
+    assert_eq!(decoded.bucket.count, record.bucket.count);
+    assert_eq!(decoded.bucket.min, record.bucket.min);
+    assert_eq!(decoded.bucket.max, record.bucket.max);
+  }
+
+  #[test]
+  fn record_decode_rejects_malformed() {
+    let record = minute_record(
+      VarName::BATTERY_CHARGE,
+      timestamp("2024-01-10T00:00:00Z"),
+      100.0,
+    );
+
+    let mut buffer = Vec::new();
+    record.encode(&mut buffer);
+
+    assert!(HistoryRecord::decode(&buffer[..buffer.len() - 1]).is_none());
+    assert!(HistoryRecord::decode(&buffer[..10]).is_none());
+
+    let mut invalid_resolution = buffer.clone();
+    invalid_resolution[0] = 9;
+
+    assert!(HistoryRecord::decode(&invalid_resolution).is_none());
+
+    let mut empty_bucket = buffer.clone();
+    empty_bucket[9..13].copy_from_slice(&0u32.to_le_bytes());
+
+    assert!(HistoryRecord::decode(&empty_bucket).is_none());
+  }
+
+  #[test]
+  fn compact_downsamples_at_cut_offs() {
+    let now = timestamp("2024-01-10T00:30:00Z");
+    let retention = TimeDelta::days(30);
+    let var = VarName::BATTERY_CHARGE;
+
+    // Minute cut-off is 2024-01-09T18:00, quarter hour cut-off is 2024-01-03T00:00.
+    let records = vec![
+      minute_record(var.clone(), timestamp("2024-01-09T18:00:00Z"), 10.0),
+      minute_record(var.clone(), timestamp("2024-01-09T17:59:00Z"), 20.0),
+      minute_record(var.clone(), timestamp("2024-01-02T23:59:00Z"), 60.0),
+      minute_record(var.clone(), timestamp("2024-01-09T17:46:00Z"), 40.0),
+      minute_record(var.clone(), timestamp("2024-01-09T17:44:00Z"), 50.0),
+      minute_record(var.clone(), timestamp("2024-01-02T23:01:00Z"), 70.0),
+      minute_record(var.clone(), timestamp("2023-12-01T00:00:00Z"), 80.0),
+      minute_record(VarName::UPS_LOAD, timestamp("2024-01-09T20:00:00Z"), 90.0),
+    ];
+
+    let compacted: Vec<(Resolution, DateTime<Utc>, u32, f64)> =
+      compact_records(records, &[var], retention, now)
+        .into_iter()
+        .map(|record| {
+          (
+            record.resolution,
+            DateTime::from_timestamp(record.bucket.start, 0).unwrap(),
+            record.bucket.count,
+            record.bucket.sum,
+          )
+        })
+        .collect();
+
+    assert_eq!(
+      compacted,
+      [
+        (
+          Resolution::Hour,
+          timestamp("2024-01-02T23:00:00Z"),
+          2,
+          130.0
+        ),
+        (
+          Resolution::QuarterHour,
+          timestamp("2024-01-09T17:30:00Z"),
+          1,
+          50.0
+        ),
+        (
+          Resolution::QuarterHour,
+          timestamp("2024-01-09T17:45:00Z"),
+          2,
+          60.0
+        ),
+        (
+          Resolution::Minute,
+          timestamp("2024-01-09T18:00:00Z"),
+          1,
+          10.0
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn compact_never_refines_resolution() {
+    let now = timestamp("2024-01-10T00:30:00Z");
+    let record = HistoryRecord {
+      resolution: Resolution::Hour,
+      ..minute_record(
+        VarName::BATTERY_CHARGE,
+        timestamp("2024-01-09T23:00:00Z"),
+        100.0,
+      )
+    };
+
+    let compacted = compact_records(
+      vec![record],
+      &[VarName::BATTERY_CHARGE],
+      TimeDelta::days(30),
+      now,
+    );
+
+    assert_eq!(compacted.len(), 1);
+    assert_eq!(compacted[0].resolution, Resolution::Hour);
+  }
+
+  #[test]
+  fn restore_skips_persisted_buckets() {
+    let device = UpsName::new_unchecked("bx1600mi");
+    let persisted = Utc::now() - TimeDelta::minutes(10);
+    let persisted =
+      DateTime::from_timestamp(Resolution::Minute.align(persisted.timestamp()), 0).unwrap();
+
+    let mut store =
+      store().with_persistence([minute_record(VarName::BATTERY_CHARGE, persisted, 100.0)]);
+
+    // Completes the restored bucket, which is already in storage.
+    record(&mut store, &device, 90, persisted + TimeDelta::seconds(30));
+    record(&mut store, &device, 80, persisted + TimeDelta::minutes(1));
+
+    assert!(store.take_pending().is_empty());
+
+    // Completes the first bucket recorded after the restart.
+    record(&mut store, &device, 70, persisted + TimeDelta::minutes(2));
+
+    let pending = store.take_pending();
+
+    assert_eq!(pending.len(), 1);
+    assert_eq!(
+      pending[0].bucket.start,
+      (persisted + TimeDelta::minutes(1)).timestamp()
+    );
+    assert!(store.take_pending().is_empty());
+
+    let series = store
+      .query(
+        &device,
+        &VarName::BATTERY_CHARGE,
+        persisted,
+        persisted + TimeDelta::minutes(2),
+        Some(Resolution::Minute),
+      )
+      .unwrap();
+    let restored = series.points.first().unwrap();
+
+    assert_eq!(restored.timestamp, persisted);
+    assert_eq!(restored.avg, 95.0);
+  }
+
+  #[test]
+  fn restore_pending_keeps_order() {
+    let device = UpsName::new_unchecked("bx1600mi");
+    let start = DateTime::from_timestamp(Resolution::Minute.align(Utc::now().timestamp()), 0)
+      .unwrap()
+      - TimeDelta::minutes(10);
+
+    let mut store = store().with_persistence([]);
+    record(&mut store, &device, 90, start);
+    record(&mut store, &device, 80, start + TimeDelta::minutes(1));
+
+    let failed = store.take_pending();
+    assert_eq!(failed.len(), 1);
+
+    record(&mut store, &device, 70, start + TimeDelta::minutes(2));
+    store.restore_pending(failed);
+
+    let pending = store.take_pending();
+    let starts: Vec<i64> = pending.iter().map(|r| r.bucket.start).collect();
+
+    assert_eq!(
+      starts,
+      vec![
+        start.timestamp(),
+        (start + TimeDelta::minutes(1)).timestamp()
+      ]
+    );
+
+    let overflow = vec![pending[0].clone(); PENDING_LIMIT];
+    store.restore_pending(overflow);
+    store.restore_pending(vec![pending[1].clone()]);
+
+    let pending = store.take_pending();
+    assert_eq!(pending.len(), PENDING_LIMIT);
+    assert_eq!(pending[0].bucket.start, start.timestamp());
+  }
+}
@@ -4,16 +4,25 @@ mod middlewares;
 mod probes;
 mod problem_detail;
 
-use crate::{config::ServerConfig, state::ServerState};
+use crate::{
+  config::ServerConfig,
+  state::{ServerState, TrackedAction, TrackingEntry},
+};
 use axum::{
   Router, ServiceExt,
   http::{HeaderValue, StatusCode, header},
   routing::{get, patch, post},
 };
+use chrono::Utc;
 use hypermedia::routes;
 use middlewares::{
   daemon_status::DaemonStateLayer, validate_content_length::ValidateEmptyContentLength,
 };
+use nut_webgui_upsmc::{
+  CmdName, UpsName, Value, VarName,
+  clients::{AsyncNutClient, NutAuthPoolClient, NutPoolClient},
+  errors::Error,
+};
 use problem_detail::ProblemDetail;
 use std::{sync::Arc, time::Duration};
 use tokio::{net::TcpListener, sync::RwLock};
@@ -24,22 +33,119 @@ use tower_http::{
   trace::TraceLayer, validate_request::ValidateRequestHeaderLayer,
 };
 
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 struct RouterState {
   config: Arc<ServerConfig>,
   state: Arc<RwLock<ServerState>>,
+  client_pool: NutPoolClient<String>,
+
+  /// Authenticated connection pool for write operations. Only available when upsd username and
+  /// password are configured.
+  auth_pool: Option<NutAuthPoolClient<String>>,
+}
+
+impl RouterState {
+  /// Sends INSTCMD with an optional command argument and registers its tracking id. Returns
+  /// [None] when upsd does not support tracking, and the command is sent untracked.
+  async fn instcmd(
+    &self,
+    auth_pool: &NutAuthPoolClient<String>,
+    ups_name: &UpsName,
+    cmd: &CmdName,
+    value: Option<&Value>,
+  ) -> Result<Option<TrackingEntry>, Error> {
+    let tracking_id = match value {
+      Some(value) => {
+        auth_pool
+          .instcmd_with_value_tracked(ups_name, cmd, value)
+          .await?
+      }
+      None => auth_pool.instcmd_tracked(ups_name, cmd).await?,
+    };
+
+    match tracking_id {
+      Some(id) => {
+        let action = TrackedAction::Instcmd {
+          instcmd: cmd.clone(),
+          value: value.cloned(),
+        };
+
+        Ok(Some(self.track(id, ups_name, action).await))
+      }
+      None => Ok(None),
+    }
+  }
+
+  /// Sends SET VAR and registers its tracking id. Returns [None] when upsd does not support
+  /// tracking, and the request is sent untracked.
+  async fn set_var(
+    &self,
+    auth_pool: &NutAuthPoolClient<String>,
+    ups_name: &UpsName,
+    var: &VarName,
+    value: &Value,
+  ) -> Result<Option<TrackingEntry>, Error> {
+    match auth_pool.set_var_tracked(ups_name, var, value).await? {
+      Some(id) => {
+        let action = TrackedAction::SetVar {
+          variable: var.clone(),
+          value: value.clone(),
+        };
+
+        Ok(Some(self.track(id, ups_name, action).await))
+      }
+      None => Ok(None),
+    }
+  }
+
+  async fn track(&self, id: Box<str>, ups_name: &UpsName, action: TrackedAction) -> TrackingEntry {
+    let entry = TrackingEntry::new(id, ups_name.clone(), action);
+    self.state.write().await.insert_tracking(entry.clone());
+
+    entry
+  }
+
+  /// Returns the tracked request. Status is refreshed from upsd while the request is pending.
+  async fn get_tracking(&self, id: &str) -> Result<Option<TrackingEntry>, Error> {
+    match self.state.read().await.tracking.get(id) {
+      Some(entry) if entry.status.is_pending() => {}
+      Some(entry) => return Ok(Some(entry.clone())),
+      None => return Ok(None),
+    }
+
+    let status = self.client_pool.get_tracking(id).await?;
+    let mut state = self.state.write().await;
+
+    Ok(state.tracking.get_mut(id).map(|entry| {
+      if entry.status != status {
+        entry.status = status;
+        entry.last_modified = Utc::now();
+      }
+
+      entry.clone()
+    }))
+  }
 }
 
 pub struct HttpServer {
   config: ServerConfig,
   server_state: Arc<RwLock<ServerState>>,
+  client_pool: NutPoolClient<String>,
+  auth_pool: Option<NutAuthPoolClient<String>>,
 }
 
 impl HttpServer {
-  pub fn new(config: ServerConfig, server_state: Arc<RwLock<ServerState>>) -> Self {
+  pub fn new(
+    config: ServerConfig,
+    server_state: Arc<RwLock<ServerState>>,
+    client_pool: NutPoolClient<String>,
+    auth_pool: Option<NutAuthPoolClient<String>>,
+  ) -> Self {
     Self {
       config,
       server_state,
+      client_pool,
+      auth_pool,
     }
   }
 
@@ -50,6 +156,8 @@ impl HttpServer {
     let Self {
       server_state,
       config,
+      client_pool,
+      auth_pool,
     } = self;
 
     let middleware = ServiceBuilder::new()
@@ -72,10 +180,12 @@ impl HttpServer {
       .route("/ups", get(json::get_ups_list))
       .route("/ups/{ups_name}", get(json::get_ups_by_name))
       .route("/ups/{ups_name}", patch(json::patch_var))
+      .route("/ups/{ups_name}/history", get(json::get_ups_history))
       .route(
         "/ups/{ups_name}/instcmd",
         post(json::post_command),
       )
+      .route("/tracking/{id}", get(json::get_tracking))
       .route(
         "/ups/{ups_name}/fsd",
         post(json::post_fsd).layer(ValidateRequestHeaderLayer::custom(
@@ -84,6 +194,10 @@ impl HttpServer {
       )
       .fallback(|| async { ProblemDetail::new("Target resource not found", StatusCode::NOT_FOUND) })
       .layer(DaemonStateLayer::new(server_state.clone()))
+      // Pool stats and events stay available while upsd is unreachable, added after the daemon
+      // state layer.
+      .route("/pool", get(json::get_pool_stats))
+      .route("/events", get(json::get_events))
       .layer(ValidateRequestHeaderLayer::accept("application/json"))
       .layer(CorsLayer::permissive());
 
@@ -116,15 +230,19 @@ impl HttpServer {
         patch(hypermedia::routes::ups::patch_rw),
       )
       .route("/", get(hypermedia::routes::home::get))
+      .route("/events", get(hypermedia::routes::events::get))
       .route("/not-found", get(hypermedia::routes::not_found::get))
       .route("/server", get(hypermedia::routes::server_info::get))
       .route("/ups/{ups_name}", get(hypermedia::routes::ups::get))
+      .route("/tracking/{id}", get(hypermedia::routes::tracking::get))
       .fallback(hypermedia::routes::not_found::get);
 
     let shared_config = Arc::new(config);
     let router_state = RouterState {
       config: shared_config.clone(),
       state: server_state,
+      client_pool,
+      auth_pool,
     };
 
     let router = Router::new()
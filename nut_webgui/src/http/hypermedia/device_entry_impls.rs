@@ -13,6 +13,21 @@ pub struct ValueDetail<'a> {
   pub approx: bool,
 }
 
+/// Unit symbol from standard variable metadata.
+#[inline]
+fn unit_symbol(name: &VarName) -> Option<&'static str> {
+  name
+    .metadata()
+    .and_then(|metadata| metadata.unit)
+    .map(|unit| unit.symbol())
+}
+
+/// Same as [unit_symbol], but text values are displayed without unit.
+#[inline]
+fn unit_sign(name: &VarName, value: &Value) -> Option<&'static str> {
+  unit_symbol(name).filter(|_| value.is_numeric())
+}
+
 // Provides hypermedia specific impls for DeviceEntry struct
 impl DeviceEntry {
   #[inline]
@@ -23,6 +38,14 @@ impl DeviceEntry {
     }
   }
 
+  #[inline]
+  pub fn get_alarm(&self) -> Option<&str> {
+    match self.variables.get(VarName::UPS_ALARM) {
+      Some(Value::String(v)) => Some(v.as_ref()),
+      _ => None,
+    }
+  }
+
   #[inline]
   pub fn get_beeper_status(&self) -> Option<bool> {
     match self.variables.get(VarName::UPS_BEEPER_STATUS) {
@@ -63,11 +86,7 @@ impl DeviceEntry {
 
   pub fn get_ups_temperature(&self) -> Option<ValueDetail<'_>> {
     let temperature = self.variables.get(VarName::UPS_TEMPERATURE)?;
-    let unit_sign = if temperature.is_text() {
-      None
-    } else {
-      Some("℃")
-    };
+    let unit_sign = unit_sign(&VarName::UPS_TEMPERATURE, temperature);
 
     let semantic_class = {
       match temperature.as_lossly_f64() {
@@ -104,7 +123,7 @@ impl DeviceEntry {
 
   pub fn get_ups_load(&self) -> Option<ValueDetail<'_>> {
     let load = self.variables.get(VarName::UPS_LOAD)?;
-    let unit_sign = if load.is_text() { None } else { Some("%") };
+    let unit_sign = unit_sign(&VarName::UPS_LOAD, load);
 
     let semantic_class = {
       match load.as_lossly_i64() {
@@ -123,7 +142,7 @@ impl DeviceEntry {
 
   pub fn get_battery_charge(&self) -> Option<ValueDetail<'_>> {
     let charge = self.variables.get(VarName::BATTERY_CHARGE)?;
-    let unit_sign = if charge.is_text() { None } else { Some("%") };
+    let unit_sign = unit_sign(&VarName::BATTERY_CHARGE, charge);
 
     let semantic_class = {
       let warn_level = self
@@ -191,7 +210,7 @@ impl DeviceEntry {
       Some(ValueDetail {
         value: Cow::Owned(Value::from(v)),
         class: SemanticType::Info,
-        unit_sign: Some("W"),
+        unit_sign: unit_symbol(&VarName::UPS_REALPOWER),
         approx: false,
       })
     } else {
@@ -209,7 +228,7 @@ impl DeviceEntry {
           Some(ValueDetail {
             value: Cow::Owned(Value::from(calc)),
             class: SemanticType::Info,
-            unit_sign: Some("W"),
+            unit_sign: unit_symbol(&VarName::UPS_REALPOWER),
             approx: true,
           })
         }
@@ -227,7 +246,7 @@ impl DeviceEntry {
       Some(ValueDetail {
         value: Cow::Owned(Value::from(v)),
         class: SemanticType::Info,
-        unit_sign: Some("VA"),
+        unit_sign: unit_symbol(&VarName::UPS_POWER),
         approx: false,
       })
     } else {
@@ -245,7 +264,7 @@ impl DeviceEntry {
           Some(ValueDetail {
             value: Cow::Owned(Value::from(calc)),
             class: SemanticType::Info,
-            unit_sign: Some("VA"),
+            unit_sign: unit_symbol(&VarName::UPS_POWER),
             approx: true,
           })
         }
@@ -9,6 +9,7 @@ pub struct NotificationTemplate<'a> {
   pub message: Cow<'a, str>,
   pub ttl: u128,
   pub semantic_type: SemanticType,
+  pub poll_url: Option<String>,
 }
 
 const DEFAULT_DURATION_MS: u128 = 3000;
@@ -20,6 +21,13 @@ impl<'a> NotificationTemplate<'a> {
     self
   }
 
+  /// Notification replaces itself with the response of `url` after a short delay.
+  #[inline]
+  pub fn set_poll_url(mut self, url: String) -> Self {
+    self.poll_url = Some(url);
+    self
+  }
+
   #[inline]
   pub fn set_ttl(mut self, ttl: Duration) -> Self {
     self.ttl = ttl.as_millis();
@@ -34,6 +42,7 @@ impl<'a> From<&'a str> for NotificationTemplate<'a> {
       message: Cow::Borrowed(value),
       ttl: DEFAULT_DURATION_MS,
       semantic_type: SemanticType::Info,
+      poll_url: None,
     }
   }
 }
@@ -45,6 +54,7 @@ impl From<String> for NotificationTemplate<'_> {
       message: Cow::Owned(value),
       ttl: DEFAULT_DURATION_MS,
       semantic_type: SemanticType::Info,
+      poll_url: None,
     }
   }
 }
@@ -1,6 +1,8 @@
+pub mod events;
 pub mod home;
 pub mod layout;
 pub mod not_found;
 pub mod server_info;
 pub mod static_content;
+pub mod tracking;
 pub mod ups;
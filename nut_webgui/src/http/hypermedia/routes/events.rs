@@ -0,0 +1,311 @@
+use crate::{
+  event_log::{EventEntry, EventKind, EventLevel},
+  http::{
+    RouterState,
+    hypermedia::{error::ErrorPage, semantic_classes::SemanticType, utils::RenderWithConfig},
+  },
+};
+use askama::Template;
+use axum::{
+  extract::{Query, State},
+  response::{Html, IntoResponse, Response},
+};
+use chrono::{DateTime, TimeDelta, Utc};
+use nut_webgui_upsmc::UpsName;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Maximum number of timeline entries rendered for each device.
+const TIMELINE_LIMIT: usize = 50;
+
+/// Maximum number of outages rendered for each device.
+const OUTAGE_LIMIT: usize = 10;
+
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+#[derive(Deserialize)]
+pub struct EventsFragmentQuery {
+  section: Option<String>,
+  device: Option<String>,
+  level: Option<String>,
+}
+
+impl EventsFragmentQuery {
+  /// Minimum level to display. Empty value of the "All levels" option and unknown levels are
+  /// treated as no filter.
+  fn min_level(&self) -> Option<EventLevel> {
+    match self.level.as_deref()? {
+      "success" => Some(EventLevel::Success),
+      "warning" => Some(EventLevel::Warning),
+      "error" => Some(EventLevel::Error),
+      _ => None,
+    }
+  }
+}
+
+struct TimelineRow<'a> {
+  timestamp: String,
+  message: &'a str,
+  class: SemanticType,
+  icon_name: &'static str,
+}
+
+impl<'a> From<&'a EventEntry> for TimelineRow<'a> {
+  fn from(entry: &'a EventEntry) -> Self {
+    let icon_name = match entry.kind {
+      EventKind::DeviceAdded => "plus-circle",
+      EventKind::DeviceRemoved => "minus-circle",
+      EventKind::Outage => "zap-off",
+      EventKind::Recovery => "zap",
+      EventKind::StatusChange => "activity",
+      EventKind::RwChange => "edit",
+      EventKind::ClientConnected => "link",
+      EventKind::ClientDisconnected => "link-2",
+      EventKind::DaemonStatus => "server",
+    };
+
+    Self {
+      timestamp: entry.timestamp.format(TIMESTAMP_FORMAT).to_string(),
+      message: entry.message.as_str(),
+      class: level_class(entry.level),
+      icon_name,
+    }
+  }
+}
+
+/// Time on battery, from an [EventKind::Outage] to the following [EventKind::Recovery].
+struct Outage {
+  start: String,
+  end: Option<String>,
+  duration: String,
+}
+
+impl Outage {
+  fn new(start: DateTime<Utc>, end: Option<DateTime<Utc>>) -> Self {
+    Self {
+      start: start.format(TIMESTAMP_FORMAT).to_string(),
+      end: end.map(|end| end.format(TIMESTAMP_FORMAT).to_string()),
+      duration: format_duration(end.unwrap_or_else(Utc::now) - start),
+    }
+  }
+}
+
+struct DeviceTimeline<'a> {
+  /// [None] for upsd events.
+  name: Option<&'a UpsName>,
+  outages: Vec<Outage>,
+  rows: Vec<TimelineRow<'a>>,
+}
+
+#[derive(Template)]
+#[template(path = "events/+page.html", blocks = ["timeline"])]
+struct EventsTemplate<'a> {
+  devices: Vec<&'a UpsName>,
+  selected_device: &'a str,
+  selected_level: &'static str,
+  timelines: Vec<DeviceTimeline<'a>>,
+}
+
+const fn level_class(level: EventLevel) -> SemanticType {
+  match level {
+    EventLevel::Info => SemanticType::Info,
+    EventLevel::Success => SemanticType::Success,
+    EventLevel::Warning => SemanticType::Warning,
+    EventLevel::Error => SemanticType::Error,
+  }
+}
+
+fn format_duration(duration: TimeDelta) -> String {
+  let secs = duration.num_seconds().max(0);
+  let (hours, minutes, seconds) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+
+  match (hours, minutes) {
+    (0, 0) => format!("{seconds}s"),
+    (0, _) => format!("{minutes}m {seconds}s"),
+    _ => format!("{hours}h {minutes}m"),
+  }
+}
+
+/// Pairs outage and recovery entries of a single device. Entries are expected from oldest to
+/// newest, and outages are returned from newest to oldest.
+fn collect_outages<'a, I>(entries: I) -> Vec<Outage>
+where
+  I: Iterator<Item = &'a EventEntry>,
+{
+  let mut outages = Vec::new();
+  let mut outage_start: Option<DateTime<Utc>> = None;
+
+  for entry in entries {
+    match entry.kind {
+      EventKind::Outage if outage_start.is_none() => outage_start = Some(entry.timestamp),
+      EventKind::Recovery => {
+        if let Some(start) = outage_start.take() {
+          outages.push(Outage::new(start, Some(entry.timestamp)));
+        }
+      }
+      _ => {}
+    }
+  }
+
+  if let Some(start) = outage_start {
+    outages.push(Outage::new(start, None));
+  }
+
+  outages.reverse();
+  outages.truncate(OUTAGE_LIMIT);
+  outages
+}
+
+pub async fn get(
+  query: Query<EventsFragmentQuery>,
+  State(rs): State<RouterState>,
+) -> Result<Response, ErrorPage<askama::Error>> {
+  let state = &rs.state.read().await;
+  let selected_device = query.device.as_deref().filter(|v| !v.is_empty());
+  let min_level = query.min_level();
+  let mut groups: BTreeMap<Option<&UpsName>, Vec<&EventEntry>> = BTreeMap::new();
+
+  for entry in state.events.iter() {
+    let name = entry.device.as_ref();
+
+    if selected_device.is_none_or(|selected| name.is_some_and(|name| name.as_str() == selected)) {
+      groups.entry(name).or_default().push(entry);
+    }
+  }
+
+  let mut timelines: Vec<DeviceTimeline> = groups
+    .into_iter()
+    .map(|(name, entries)| DeviceTimeline {
+      name,
+      outages: collect_outages(entries.iter().copied()),
+      rows: entries
+        .iter()
+        .rev()
+        .filter(|entry| min_level.is_none_or(|level| entry.level >= level))
+        .take(TIMELINE_LIMIT)
+        .map(|entry| TimelineRow::from(*entry))
+        .collect(),
+    })
+    .filter(|timeline| !timeline.rows.is_empty() || !timeline.outages.is_empty())
+    .collect();
+
+  // Upsd events are listed after the devices.
+  if timelines
+    .first()
+    .is_some_and(|timeline| timeline.name.is_none())
+  {
+    let daemon_timeline = timelines.remove(0);
+    timelines.push(daemon_timeline);
+  }
+
+  let mut devices: Vec<&UpsName> = state.devices.keys().collect();
+  devices.sort_unstable();
+
+  let template = EventsTemplate {
+    devices,
+    selected_device: selected_device.unwrap_or_default(),
+    selected_level: match min_level {
+      Some(EventLevel::Info) | None => "",
+      Some(EventLevel::Success) => "success",
+      Some(EventLevel::Warning) => "warning",
+      Some(EventLevel::Error) => "error",
+    },
+    timelines,
+  };
+
+  let response = match query.section.as_deref() {
+    Some("timeline") => {
+      Html(template.as_timeline().render_with_config(&rs.config)?).into_response()
+    }
+    _ => Html(template.render_with_config(&rs.config)?).into_response(),
+  };
+
+  Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{EventsFragmentQuery, collect_outages};
+  use crate::event_log::{EventEntry, EventKind, EventLevel};
+  use axum::{extract::Query, http::Uri};
+  use chrono::{DateTime, Utc};
+
+  fn query(uri: &'static str) -> EventsFragmentQuery {
+    let Query(query) = Query::try_from_uri(&Uri::from_static(uri)).unwrap();
+    query
+  }
+
+  fn entry(kind: EventKind, timestamp: &str) -> EventEntry {
+    EventEntry {
+      id: 0,
+      timestamp: timestamp.parse::<DateTime<Utc>>().unwrap(),
+      kind,
+      level: EventLevel::Info,
+      device: None,
+      message: String::new(),
+    }
+  }
+
+  #[test]
+  fn query_with_all_levels() {
+    let query = query("/events?section=timeline&device=&level=");
+
+    assert_eq!(query.section.as_deref(), Some("timeline"));
+    assert_eq!(query.min_level(), None);
+  }
+
+  #[test]
+  fn query_with_level() {
+    assert_eq!(
+      query("/events?device=bx1600mi&level=warning").min_level(),
+      Some(EventLevel::Warning)
+    );
+    assert_eq!(
+      query("/events?section=timeline&device=&level=error").min_level(),
+      Some(EventLevel::Error)
+    );
+    assert_eq!(query("/events").min_level(), None);
+    assert_eq!(query("/events?level=unknown").min_level(), None);
+  }
+
+  #[test]
+  fn outages_are_paired_with_recoveries() {
+    let entries = [
+      entry(EventKind::Recovery, "2024-01-10T09:00:00Z"),
+      entry(EventKind::Outage, "2024-01-10T10:00:00Z"),
+      entry(EventKind::StatusChange, "2024-01-10T10:01:00Z"),
+      entry(EventKind::Outage, "2024-01-10T10:02:00Z"),
+      entry(EventKind::Recovery, "2024-01-10T10:05:30Z"),
+      entry(EventKind::Outage, "2024-01-10T11:00:00Z"),
+    ];
+
+    let outages = collect_outages(entries.iter());
+
+    assert_eq!(outages.len(), 2);
+
+    // Ongoing outage is listed first.
+    assert_eq!(outages[0].start, "2024-01-10 11:00:00");
+    assert_eq!(outages[0].end, None);
+
+    assert_eq!(outages[1].start, "2024-01-10 10:00:00");
+    assert_eq!(outages[1].end.as_deref(), Some("2024-01-10 10:05:30"));
+    assert_eq!(outages[1].duration, "5m 30s");
+  }
+
+  #[test]
+  fn outages_are_limited() {
+    let entries: Vec<EventEntry> = (0..15)
+      .flat_map(|hour| {
+        [
+          entry(EventKind::Outage, &format!("2024-01-10T{hour:02}:00:00Z")),
+          entry(EventKind::Recovery, &format!("2024-01-10T{hour:02}:30:00Z")),
+        ]
+      })
+      .collect();
+
+    let outages = collect_outages(entries.iter());
+
+    assert_eq!(outages.len(), super::OUTAGE_LIMIT);
+    assert_eq!(outages[0].start, "2024-01-10 14:00:00");
+  }
+}
@@ -11,6 +11,8 @@ use axum::{
   extract::{Query, State},
   response::{Html, IntoResponse, Response},
 };
+use core::time::Duration;
+use nut_webgui_upsmc::clients::{CommandStats, EndpointHealth, PoolStats};
 use serde::Deserialize;
 
 #[derive(Deserialize)]
@@ -24,6 +26,53 @@ struct ServerInfoTemplate<'a> {
   device_count: usize,
   config: &'a ServerConfig,
   state: &'a DaemonState,
+  endpoints: Vec<(String, String)>,
+  pool: PoolStats,
+  commands: Vec<CommandRow>,
+}
+
+struct CommandRow {
+  command: &'static str,
+  calls: u64,
+  errors: u64,
+  mean: String,
+  p95: String,
+  max: String,
+}
+
+impl From<&CommandStats> for CommandRow {
+  fn from(value: &CommandStats) -> Self {
+    Self {
+      command: value.command,
+      calls: value.calls,
+      errors: value.errors,
+      mean: format_latency(value.latency.mean()),
+      p95: value
+        .latency
+        .quantile(0.95)
+        .map_or_else(|| String::from("-"), format_latency),
+      max: format_latency(value.latency.max()),
+    }
+  }
+}
+
+fn format_latency(value: Duration) -> String {
+  format!("{:.1} ms", value.as_secs_f64() * 1000.0)
+}
+
+fn endpoint_status(endpoint: &EndpointHealth<String>) -> String {
+  match (&endpoint.last_error, endpoint.is_healthy()) {
+    (_, true) if endpoint.is_active => String::from("active"),
+    (_, true) => String::from("standby"),
+    (Some(err), false) => format!(
+      "failing ({count} attempts), {err}",
+      count = endpoint.consecutive_failures
+    ),
+    (None, false) => format!(
+      "failing ({count} attempts)",
+      count = endpoint.consecutive_failures
+    ),
+  }
 }
 
 pub async fn get(
@@ -31,11 +80,20 @@ pub async fn get(
   State(rs): State<RouterState>,
 ) -> Result<Response, ErrorPage<askama::Error>> {
   let state = &rs.state.read().await;
+  let pool = rs.client_pool.stats();
 
   let template = ServerInfoTemplate {
     config: &rs.config,
     state: &state.remote_state,
     device_count: state.devices.len(),
+    endpoints: rs
+      .client_pool
+      .endpoints()
+      .iter()
+      .map(|endpoint| (endpoint.addr.clone(), endpoint_status(endpoint)))
+      .collect(),
+    commands: pool.commands.iter().map(CommandRow::from).collect(),
+    pool,
   };
 
   let response = match query.section.as_deref() {
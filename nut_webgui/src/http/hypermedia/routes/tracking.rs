@@ -0,0 +1,81 @@
+use crate::{
+  http::{
+    RouterState,
+    hypermedia::{
+      error::ErrorPage, notifications::NotificationTemplate, semantic_classes::SemanticType,
+      utils::RenderWithConfig,
+    },
+  },
+  state::{TrackedAction, TrackingEntry},
+  uri_path::UriPath,
+};
+use axum::{
+  extract::{Path, State},
+  response::{Html, IntoResponse, Response},
+};
+use core::time::Duration;
+use nut_webgui_upsmc::responses::TrackingStatus;
+use tracing::{error, info};
+
+/// Creates notification for the current state of a tracked request. Pending requests are polled
+/// until driver reports the result.
+pub fn tracking_notification(
+  entry: &TrackingEntry,
+  base_path: &UriPath,
+) -> NotificationTemplate<'static> {
+  let action = match &entry.action {
+    TrackedAction::Instcmd { .. } => "INSTCMD",
+    TrackedAction::SetVar { .. } => "Set var request",
+  };
+
+  match &entry.status {
+    TrackingStatus::Pending => NotificationTemplate::from(format!(
+      "{action} {0} is waiting for {1}.",
+      &entry.action, &entry.ups_name
+    ))
+    .set_ttl(Duration::from_secs(15))
+    .set_poll_url(format!("{base_path}/tracking/{id}", id = &entry.id)),
+    TrackingStatus::Success => NotificationTemplate::from(format!(
+      "{action} {0} successfully executed on {1}.",
+      &entry.action, &entry.ups_name
+    ))
+    .set_level(SemanticType::Success),
+    TrackingStatus::Failed { reason } => NotificationTemplate::from(format!(
+      "{action} {0} failed on {1}, {reason}",
+      &entry.action, &entry.ups_name
+    ))
+    .set_level(SemanticType::Error)
+    .set_ttl(Duration::from_secs(15)),
+  }
+}
+
+pub async fn get(
+  State(rs): State<RouterState>,
+  Path(id): Path<Box<str>>,
+) -> Result<Response, ErrorPage<askama::Error>> {
+  let template = match rs.get_tracking(&id).await {
+    Ok(Some(entry)) => {
+      match &entry.status {
+        TrackingStatus::Pending => {}
+        TrackingStatus::Success => {
+          info!(message = "tracked request completed", device = %entry.ups_name, tracking_id = %entry.id, action = %entry.action);
+        }
+        TrackingStatus::Failed { reason } => {
+          error!(message = "tracked request failed", device = %entry.ups_name, tracking_id = %entry.id, action = %entry.action, reason = %reason);
+        }
+      }
+
+      tracking_notification(&entry, &rs.config.http_server.base_path)
+    }
+    Ok(None) => NotificationTemplate::from("Request status is no longer available.")
+      .set_level(SemanticType::Warning),
+    Err(err) => {
+      error!(message = "tracking status request failed", tracking_id = %id, reason = %err);
+
+      NotificationTemplate::from(format!("Unable to get request status, {}", err))
+        .set_level(SemanticType::Error)
+    }
+  };
+
+  Ok(Html(template.render_with_config(&rs.config)?).into_response())
+}
@@ -1,11 +1,12 @@
 use crate::{
-  config::{ServerConfig, UpsdConfig},
-  device_entry::{DeviceEntry, VarDetail},
+  config::ServerConfig,
+  device_entry::{DeviceEntry, VarFormat},
   htmx_redirect, htmx_swap,
   http::{
     RouterState,
     hypermedia::{
-      error::ErrorPage, notifications::NotificationTemplate, semantic_classes::SemanticType,
+      error::ErrorPage, notifications::NotificationTemplate,
+      routes::tracking::tracking_notification, semantic_classes::SemanticType,
       utils::RenderWithConfig,
     },
   },
@@ -18,7 +19,9 @@ use axum::{
   http::{HeaderValue, StatusCode},
   response::{Html, IntoResponse, Redirect, Response},
 };
-use nut_webgui_upsmc::{CmdName, InferValueFrom, UpsName, Value, VarName, clients::NutAuthClient};
+use nut_webgui_upsmc::{
+  CmdCategory, CmdName, CmdRisk, RwDetail, UpsName, Value, VarName, errors::RwValueError,
+};
 use serde::{Deserialize, de::Visitor};
 use std::{
   collections::{BTreeMap, HashMap},
@@ -36,7 +39,7 @@ struct UpsPageTemplate<'a> {
 #[derive(Template, Debug)]
 #[template(path = "ups/form_rw.html")]
 pub struct RwFormTemplate<'a> {
-  pub detail: &'a VarDetail,
+  pub detail: &'a RwDetail,
   pub message: Option<&'static str>,
   pub semantic: SemanticType,
   pub value: Option<&'a Value>,
@@ -49,6 +52,29 @@ pub struct RwFormTemplate<'a> {
 struct CmdTemplate<'a> {
   id: &'a str,
   desc: Option<&'a str>,
+  risk: Option<CmdRisk>,
+  accepts_param: bool,
+}
+
+impl CmdTemplate<'_> {
+  /// Power cutting commands require typing the command name before running.
+  #[inline]
+  fn is_power_cutting(&self) -> bool {
+    self.risk == Some(CmdRisk::PowerCutting)
+  }
+
+  #[inline]
+  fn is_disruptive(&self) -> bool {
+    self.risk == Some(CmdRisk::Disruptive)
+  }
+}
+
+/// Commands of the same category, non-standard commands are grouped under
+/// [CmdCategory::Other].
+#[derive(Debug)]
+struct CmdGroup<'a> {
+  category: CmdCategory,
+  commands: Vec<CmdTemplate<'a>>,
 }
 
 #[derive(Template, Debug)]
@@ -59,12 +85,12 @@ enum UpsPageTabTemplate<'a> {
   #[template(path = "ups/tab_commands.html")]
   Commands {
     device: &'a DeviceEntry,
-    commands: Vec<CmdTemplate<'a>>,
+    groups: Vec<CmdGroup<'a>>,
   },
 
   #[template(path = "ups/tab_variables.html")]
   Variables {
-    variables: Vec<(&'a VarName, &'a Value)>,
+    variables: Vec<(&'a VarName, &'a Value, VarFormat)>,
     descriptions: &'a HashMap<DescriptionKey, Box<str>>,
     name: &'a UpsName,
   },
@@ -91,8 +117,12 @@ fn get_tab_template<'a>(
 ) -> UpsPageTabTemplate<'a> {
   match tab_name {
     TabName::Variables => {
-      let mut variables: Vec<_> = device.variables.iter().collect();
-      variables.sort_unstable_by_key(|(k, _)| *k);
+      let mut variables: Vec<_> = device
+        .variables
+        .iter()
+        .map(|(name, value)| (name, value, VarFormat::new(name, value)))
+        .collect();
+      variables.sort_unstable_by_key(|(k, _, _)| *k);
 
       UpsPageTabTemplate::Variables {
         variables,
@@ -101,17 +131,26 @@ fn get_tab_template<'a>(
       }
     }
     TabName::Commands => {
-      let cmds = device
-        .commands
-        .iter()
-        .map(|c| {
-          let desc = state.shared_desc.get(c.as_str()).map(|v| v.as_ref());
-          CmdTemplate { id: c.as_str(), desc }
-        })
-        .collect();
+      let mut groups: BTreeMap<CmdCategory, Vec<CmdTemplate>> = BTreeMap::new();
+
+      for cmd in device.commands.iter() {
+        let metadata = CmdName::new_unchecked(cmd).metadata();
+        let category = metadata.map_or(CmdCategory::Other, |m| m.category);
+
+        groups.entry(category).or_default().push(CmdTemplate {
+          id: cmd.as_str(),
+          desc: state.shared_desc.get(cmd.as_str()).map(|v| v.as_ref()),
+          risk: metadata.map(|m| m.risk),
+          accepts_param: metadata.is_some_and(|m| m.accepts_param),
+        });
+      }
+
       UpsPageTabTemplate::Commands {
         device,
-        commands: cmds,
+        groups: groups
+          .into_iter()
+          .map(|(category, commands)| CmdGroup { category, commands })
+          .collect(),
       }
     }
     TabName::Clients => UpsPageTabTemplate::Clients { device },
@@ -242,6 +281,8 @@ pub async fn get(
 #[derive(Deserialize, Debug)]
 pub struct CommandRequest {
   command: CmdName,
+  /// Command argument from the commands tab. Empty inputs are sent without an argument.
+  value: Option<String>,
 }
 
 pub async fn post_instcmd(
@@ -249,13 +290,9 @@ pub async fn post_instcmd(
   Path(ups_name): Path<UpsName>,
   Form(request): Form<CommandRequest>,
 ) -> Result<Response, ErrorPage<askama::Error>> {
-  let (addr, username, password) = match &rs.config.upsd {
-    upsd @ UpsdConfig {
-      pass: Some(pass),
-      user: Some(user),
-      ..
-    } => (upsd.get_socket_addr(), user.as_ref(), pass.as_ref()),
-    _ => {
+  let auth_pool = match &rs.auth_pool {
+    Some(auth_pool) => auth_pool,
+    None => {
       return Ok(
         Html(
           NotificationTemplate::from(
@@ -268,19 +305,35 @@ pub async fn post_instcmd(
     }
   };
 
-  let connection = NutAuthClient::connect(addr, username, password).await;
+  let value = request
+    .value
+    .as_deref()
+    .map(str::trim)
+    .filter(|v| !v.is_empty())
+    .map(Value::from);
+
+  if value.is_some() && request.command.metadata().is_some_and(|m| !m.accepts_param) {
+    return Ok(
+      Html(
+        NotificationTemplate::from(format!("'{}' does not accept a value.", &request.command))
+          .set_level(SemanticType::Error)
+          .render_with_config(&rs.config)?,
+      )
+      .into_response(),
+    );
+  }
 
-  let cmd_result = match connection {
-    Ok(mut client) => {
-      let result = client.instcmd(&ups_name, &request.command).await;
-      _ = client.close().await;
-      result
-    }
-    Err(err) => Err(err),
-  };
+  let cmd_result = rs
+    .instcmd(auth_pool, &ups_name, &request.command, value.as_ref())
+    .await;
 
   let template = match cmd_result {
-    Ok(_) => {
+    Ok(Some(entry)) => {
+      info!(message = "instcmd is tracked", device_name = %ups_name, cmd = %request.command, tracking_id = %entry.id);
+
+      tracking_notification(&entry, &rs.config.http_server.base_path)
+    }
+    Ok(None) => {
       info!(message = "instcmd called successfully", device_name = %ups_name, cmd = %request.command);
 
       NotificationTemplate::from(format!(
@@ -304,13 +357,9 @@ pub async fn post_fsd(
   State(rs): State<RouterState>,
   Path(ups_name): Path<UpsName>,
 ) -> Result<Response, ErrorPage<askama::Error>> {
-  let (addr, username, password) = match &rs.config.upsd {
-    upsd @ UpsdConfig {
-      pass: Some(pass),
-      user: Some(user),
-      ..
-    } => (upsd.get_socket_addr(), user.as_ref(), pass.as_ref()),
-    _ => {
+  let auth_pool = match &rs.auth_pool {
+    Some(auth_pool) => auth_pool,
+    None => {
       return Ok(
         Html(
           NotificationTemplate::from(
@@ -323,16 +372,7 @@ pub async fn post_fsd(
     }
   };
 
-  let connection = NutAuthClient::connect(addr, username, password).await;
-
-  let fsd_result = match connection {
-    Ok(mut client) => {
-      let result = client.fsd(&ups_name).await;
-      _ = client.close().await;
-      result
-    }
-    Err(err) => Err(err),
-  };
+  let fsd_result = auth_pool.fsd(&ups_name).await;
 
   let template = match fsd_result {
     Ok(_) => {
@@ -362,13 +402,9 @@ pub async fn patch_rw(
   Path(ups_name): Path<UpsName>,
   Form(request): Form<RwRequest>,
 ) -> Result<Response, ErrorPage<askama::Error>> {
-  let (addr, username, password) = match &rs.config.upsd {
-    upsd @ UpsdConfig {
-      pass: Some(pass),
-      user: Some(user),
-      ..
-    } => (upsd.get_socket_addr(), user.as_ref(), pass.as_ref()),
-    _ => {
+  let auth_pool = match &rs.auth_pool {
+    Some(auth_pool) => auth_pool,
+    None => {
       return Ok(htmx_swap!(
         Html(
           NotificationTemplate::from(
@@ -410,139 +446,74 @@ pub async fn patch_rw(
     }
   };
 
-  let (value, message, semantic, is_valid) = match detail {
-    VarDetail::String { max_len } => {
-      if request.value.as_ref().trim().is_empty() {
-        (
-          Value::from(request.value),
-          Some("input is empty"),
-          SemanticType::Error,
-          false,
-        )
-      } else if request.value.as_ref().len() > *max_len {
-        (
-          Value::from(request.value),
-          Some("input is too long"),
-          SemanticType::Error,
-          false,
-        )
-      } else {
-        (Value::from(request.value), None, SemanticType::None, true)
-      }
-    }
-    VarDetail::Number => match Value::infer_number_from(request.value.as_ref()) {
-      Ok(v) => (v, None, SemanticType::None, true),
-      Err(_) => (
-        Value::from(request.value),
-        Some("input is not a number"),
-        SemanticType::Error,
-        false,
-      ),
-    },
-    VarDetail::Enum { options } => {
-      let value = Value::from(request.value);
-
-      if options.contains(&value) {
-        (value, None, SemanticType::None, true)
-      } else {
-        (value, Some("invalid option"), SemanticType::Error, false)
-      }
-    }
-    VarDetail::Range { min, max } => match Value::infer_number_from(request.value.as_ref()) {
-      Ok(value) => match (min.as_lossly_f64(), max.as_lossly_f64()) {
-        (Some(min), Some(max)) => {
-          let valuef64 = value.as_lossly_f64().unwrap_or(0.0);
-
-          if min <= valuef64 && valuef64 <= max {
-            (value, None, SemanticType::None, true)
-          } else {
-            (
-              value,
-              Some("value is not in range"),
-              SemanticType::Error,
-              false,
-            )
-          }
-        }
-        _ => (
-          value,
-          Some("driver reported min-max values are not numeric values"),
-          SemanticType::Error,
-          false,
-        ),
-      },
-      Err(_) => (
-        Value::from(request.value),
-        Some("input is not a number"),
-        SemanticType::Error,
-        false,
-      ),
-    },
+  let (value, message, semantic, is_valid) = match detail.parse(request.value.as_ref()) {
+    Ok(value) => (value, None, SemanticType::None, true),
+    Err(err) => (
+      Value::from(request.value),
+      Some(rw_value_message(err)),
+      SemanticType::Error,
+      false,
+    ),
   };
 
   let response = if is_valid {
-    let connection = NutAuthClient::connect(addr, username, password).await;
-
-    match connection {
-      Ok(mut client) => {
-        let result = client.set_var(&ups_name, &request.name, &value).await;
-        _ = client.close().await;
-
-        let (semantic, message, notification) = match result {
-          Ok(_) => {
-            info!(message = "set var request accepted", device = %ups_name, value = %value, name = %request.name);
-
-            (
-              semantic,
-              message,
-              Some(
-                NotificationTemplate::from("Set var request is accepted")
-                  .set_level(SemanticType::Success),
-              ),
-            )
-          }
-          Err(err) => {
-            error!(message = "set var request failed", device = %ups_name,  value = %value, name = %request.name, reason = %err);
-
-            (
-              SemanticType::Error,
-              Some("value is rejected by device driver"),
-              Some(
-                NotificationTemplate::from(format!("Set var request failed, {}", err))
-                  .set_level(SemanticType::Error)
-                  .set_ttl(Duration::from_secs(15)),
-              ),
-            )
-          }
-        };
+    let result = rs
+      .set_var(auth_pool, &ups_name, &request.name, &value)
+      .await;
 
-        Html(
-          RwFormTemplate {
-            value: Some(&value),
-            semantic,
-            message,
-            detail,
-            device_name: &ups_name,
-            var_name: &request.name,
-            notification,
-          }
-          .render_with_config(&rs.config)?,
+    let (semantic, message, notification) = match result {
+      Ok(Some(entry)) => {
+        info!(message = "set var request is tracked", device = %ups_name, value = %value, name = %request.name, tracking_id = %entry.id);
+
+        (
+          semantic,
+          message,
+          Some(tracking_notification(
+            &entry,
+            &rs.config.http_server.base_path,
+          )),
+        )
+      }
+      Ok(None) => {
+        info!(message = "set var request accepted", device = %ups_name, value = %value, name = %request.name);
+
+        (
+          semantic,
+          message,
+          Some(
+            NotificationTemplate::from("Set var request is accepted")
+              .set_level(SemanticType::Success),
+          ),
         )
-        .into_response()
       }
       Err(err) => {
-        error!(message = "auth connection failed for set var request", value = %value, name = %request.name, reason = %err);
+        error!(message = "set var request failed", device = %ups_name,  value = %value, name = %request.name, reason = %err);
 
-        htmx_swap!(
-          Html(
-            NotificationTemplate::from(format!("client authentication failed, {}", err))
+        (
+          SemanticType::Error,
+          Some("value is rejected by device driver"),
+          Some(
+            NotificationTemplate::from(format!("Set var request failed, {}", err))
               .set_level(SemanticType::Error)
-              .render_with_config(&rs.config)?,
+              .set_ttl(Duration::from_secs(15)),
           ),
-          "none"
         )
       }
-    }
+    };
+
+    Html(
+      RwFormTemplate {
+        value: Some(&value),
+        semantic,
+        message,
+        detail,
+        device_name: &ups_name,
+        var_name: &request.name,
+        notification,
+      }
+      .render_with_config(&rs.config)?,
+    )
+    .into_response()
   } else {
     Html(
       RwFormTemplate {
@@ -564,6 +535,19 @@ pub async fn patch_rw(
   Ok(response)
 }
 
+/// Short form message for a rejected writable variable input.
+fn rw_value_message(err: RwValueError) -> &'static str {
+  match err {
+    RwValueError::Empty => "input is empty",
+    RwValueError::TooLong { .. } => "input is too long",
+    RwValueError::NotNumber => "input is not a number",
+    RwValueError::NotText => "input is not a text",
+    RwValueError::InvalidOption => "invalid option",
+    RwValueError::OutOfRange => "value is not in range",
+    RwValueError::MalformedRange => "driver reported min-max values are not numeric values",
+  }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum TabName {
   Commands,
@@ -11,14 +11,22 @@ pub struct StatusDetail<'a> {
 
 pub struct StatusDetailIter<'a> {
   inner: SplitAsciiWhitespace<'a>,
+  alarm: Option<&'a str>,
 }
 
 impl<'a> StatusDetailIter<'a> {
   pub fn new(status_text: &'a str) -> Self {
     Self {
       inner: status_text.split_ascii_whitespace(),
+      alarm: None,
     }
   }
+
+  /// Uses `ups.alarm` text as the description of the `ALARM` status.
+  pub fn with_alarm(mut self, alarm: Option<&'a str>) -> Self {
+    self.alarm = alarm;
+    self
+  }
 }
 
 impl<'a> Iterator for StatusDetailIter<'a> {
@@ -61,7 +69,7 @@ impl<'a> Iterator for StatusDetailIter<'a> {
       "ALARM" => Some(StatusDetail {
         icon_name: "alert-circle",
         class: SemanticType::Error,
-        desc: "UPS requires intervention",
+        desc: self.alarm.unwrap_or("UPS requires intervention"),
         name: token,
       }),
       "FSD" => Some(StatusDetail {
@@ -118,6 +126,48 @@ impl<'a> Iterator for StatusDetailIter<'a> {
         desc: "Battery is disconnected for maintenance",
         name: token,
       }),
+      "ECO" => Some(StatusDetail {
+        icon_name: "feather",
+        class: SemanticType::Info,
+        desc: "UPS is in ECO mode",
+        name: token,
+      }),
+      "HE" => Some(StatusDetail {
+        icon_name: "feather",
+        class: SemanticType::Info,
+        desc: "UPS is in high efficiency mode",
+        name: "HIGH-EFFICIENCY",
+      }),
+      "HB" => Some(StatusDetail {
+        icon_name: "battery",
+        class: SemanticType::Warning,
+        desc: "High battery",
+        name: "HIGH-BATTERY",
+      }),
+      "FANFAIL" => Some(StatusDetail {
+        icon_name: "wind",
+        class: SemanticType::Error,
+        desc: "Fan failure",
+        name: "FAN-FAILURE",
+      }),
+      "OVERHEAT" => Some(StatusDetail {
+        icon_name: "thermometer",
+        class: SemanticType::Error,
+        desc: "UPS is overheated",
+        name: token,
+      }),
+      "NOBATTERY" => Some(StatusDetail {
+        icon_name: "battery",
+        class: SemanticType::Error,
+        desc: "Battery is missing or disconnected",
+        name: "NO-BATTERY",
+      }),
+      "OFF" => Some(StatusDetail {
+        icon_name: "power",
+        class: SemanticType::Warning,
+        desc: "UPS is offline",
+        name: "OFFLINE",
+      }),
       name => Some(StatusDetail {
         icon_name: "",
         class: SemanticType::Info,
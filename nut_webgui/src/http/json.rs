@@ -1,31 +1,35 @@
 use super::{RouterState, problem_detail::ProblemDetail};
 
 use crate::{
-  config::UpsdConfig,
-  device_entry::{DeviceEntry, VarDetail},
+  device_entry::DeviceEntry,
+  event_log::{EventEntry, EventFilter, EventKind, EventLevel},
+  history::{HistorySeries, Resolution},
+  state::TrackingEntry,
 };
 use axum::{
   Json,
   extract::{
-    Path, State,
-    rejection::{JsonRejection, PathRejection},
+    Path, Query, State,
+    rejection::{JsonRejection, PathRejection, QueryRejection},
   },
-  http::StatusCode,
+  http::{HeaderValue, StatusCode, header},
   response::{IntoResponse, Response},
 };
-use nut_webgui_upsmc::{CmdName, UpsName, Value, VarName, clients::NutAuthClient};
-use serde::Deserialize;
+use chrono::{DateTime, TimeDelta, Utc};
+use core::time::Duration;
+use nut_webgui_upsmc::{
+  CmdName, RwDetail, UpsName, Value, VarName,
+  clients::{CommandStats, LatencyHistogram, PoolStats},
+  errors::RwValueError,
+};
+use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
 
-macro_rules! require_auth_config {
-  ($config:expr) => {
-    match $config {
-      upsd @ UpsdConfig {
-        pass: Some(pass),
-        user: Some(user),
-        ..
-      } => Ok((upsd.get_socket_addr(), user.as_ref(), pass.as_ref())),
-      _ => Err(
+macro_rules! require_auth_pool {
+  ($rs:expr) => {
+    match &$rs.auth_pool {
+      Some(auth_pool) => Ok(auth_pool),
+      None => Err(
         ProblemDetail::new("Insufficient upsd configuration", StatusCode::UNAUTHORIZED)
           .with_detail("Operation requires valid username and password to be configured.".into()),
       ),
@@ -33,9 +37,200 @@ macro_rules! require_auth_config {
   };
 }
 
+/// Creates `202 Accepted` response. When upsd tracks the request, response body contains the
+/// tracking resource and `Location` points to its poll address.
+fn accepted_response(base_path: &str, tracking: Option<TrackingEntry>) -> Response {
+  match tracking {
+    Some(entry) => {
+      let location = format!("{}/api/tracking/{}", base_path, entry.id);
+      let mut response = (StatusCode::ACCEPTED, Json(entry)).into_response();
+
+      if let Ok(location) = HeaderValue::from_str(&location) {
+        response.headers_mut().insert(header::LOCATION, location);
+      }
+
+      response
+    }
+    None => StatusCode::ACCEPTED.into_response(),
+  }
+}
+
+/// Maps a rejected [patch_var] value into a problem detail.
+fn rw_value_problem(var_name: &VarName, detail: &RwDetail, err: RwValueError) -> ProblemDetail {
+  match (err, detail) {
+    (RwValueError::NotNumber, RwDetail::Range { ranges }) => {
+      ProblemDetail::new("Invalid value type", StatusCode::BAD_REQUEST).with_detail(format!(
+        "'{var_name}' expects a numeric value within {ranges}, but the provided value is not a number."
+      ))
+    }
+    (RwValueError::NotNumber, _) => ProblemDetail::new("Invalid value type", StatusCode::BAD_REQUEST)
+      .with_detail(format!(
+        "'{var_name}' expects a numeric type, but the provided value is not a number."
+      )),
+    (RwValueError::NotText, _) => ProblemDetail::new("Invalid value type", StatusCode::BAD_REQUEST)
+      .with_detail(format!(
+        "'{var_name}' expects a string type, but the provided value is not a string."
+      )),
+    (RwValueError::Empty, _) => ProblemDetail::new("Empty value", StatusCode::BAD_REQUEST)
+      .with_detail("Value cannot be empty or consist of only whitespaces.".to_owned()),
+    (RwValueError::TooLong { max_len }, _) => {
+      ProblemDetail::new("Out of range", StatusCode::BAD_REQUEST)
+        .with_detail(format!("Maximum allowed string length is {}.", max_len))
+    }
+    (RwValueError::InvalidOption, RwDetail::Enum { options }) => {
+      ProblemDetail::new("Invalid option", StatusCode::BAD_REQUEST).with_detail(format!(
+        "'{var_name}' is an enum type, allowed options: {opts:?}",
+        opts = options
+          .iter()
+          .map(|v| v.as_str())
+          .collect::<Vec<std::borrow::Cow<'_, str>>>()
+      ))
+    }
+    (RwValueError::OutOfRange, RwDetail::Range { ranges }) => {
+      ProblemDetail::new("Out of range", StatusCode::BAD_REQUEST).with_detail(format!(
+        "'{var_name}' is not within the acceptable ranges {ranges}"
+      ))
+    }
+    (RwValueError::MalformedRange, _) => {
+      ProblemDetail::new("Malformed driver response", StatusCode::INTERNAL_SERVER_ERROR).with_detail(
+        "Cannot process request since the reported min-max values by ups device are not number."
+          .to_owned(),
+      )
+    }
+    (err, _) => {
+      ProblemDetail::new("Invalid value", StatusCode::BAD_REQUEST).with_detail(err.to_string())
+    }
+  }
+}
+
+/// Checks [post_command] value before it's written into the `INSTCMD` request line.
+fn validate_command_value(cmd_name: &CmdName, value: &Value) -> Result<(), ProblemDetail> {
+  if cmd_name.metadata().is_some_and(|m| !m.accepts_param) {
+    return Err(
+      ProblemDetail::new("Invalid command value", StatusCode::BAD_REQUEST)
+        .with_detail(format!("'{cmd_name}' does not accept a value.")),
+    );
+  }
+
+  value.validate_argument().map_err(|err| {
+    ProblemDetail::new("Invalid command value", StatusCode::BAD_REQUEST)
+      .with_detail(format!("'{cmd_name}' value is rejected, {err}."))
+  })
+}
+
+#[derive(Debug, Serialize)]
+pub struct PoolStatsResponse {
+  max_connections: usize,
+  idle: usize,
+  in_use: usize,
+  waiting: usize,
+  connecting: usize,
+  connections_opened: u64,
+  connection_failures: u64,
+  connections_discarded: u64,
+  connections_evicted: u64,
+  permit_wait: PermitWaitResponse,
+  commands: Vec<CommandStatsResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PermitWaitResponse {
+  count: u64,
+  avg_ms: f64,
+  max_ms: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CommandStatsResponse {
+  command: &'static str,
+  calls: u64,
+  errors: u64,
+  latency: LatencyResponse,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LatencyResponse {
+  mean_ms: f64,
+  p50_ms: Option<f64>,
+  p95_ms: Option<f64>,
+  p99_ms: Option<f64>,
+  max_ms: f64,
+  buckets: Vec<LatencyBucketResponse>,
+}
+
+/// Histogram bucket, `le_ms` is `null` for the last unbounded bucket.
+#[derive(Debug, Serialize)]
+pub struct LatencyBucketResponse {
+  le_ms: Option<f64>,
+  count: u64,
+}
+
+#[inline]
+fn as_millis(value: Duration) -> f64 {
+  value.as_secs_f64() * 1000.0
+}
+
+impl From<&LatencyHistogram> for LatencyResponse {
+  fn from(value: &LatencyHistogram) -> Self {
+    Self {
+      mean_ms: as_millis(value.mean()),
+      p50_ms: value.quantile(0.5).map(as_millis),
+      p95_ms: value.quantile(0.95).map(as_millis),
+      p99_ms: value.quantile(0.99).map(as_millis),
+      max_ms: as_millis(value.max()),
+      buckets: value
+        .buckets()
+        .map(|(bound, count)| LatencyBucketResponse {
+          le_ms: bound.map(as_millis),
+          count,
+        })
+        .collect(),
+    }
+  }
+}
+
+impl From<CommandStats> for CommandStatsResponse {
+  fn from(value: CommandStats) -> Self {
+    Self {
+      command: value.command,
+      calls: value.calls,
+      errors: value.errors,
+      latency: LatencyResponse::from(&value.latency),
+    }
+  }
+}
+
+impl From<PoolStats> for PoolStatsResponse {
+  fn from(value: PoolStats) -> Self {
+    Self {
+      max_connections: value.max_connections,
+      idle: value.idle,
+      in_use: value.in_use,
+      waiting: value.waiting,
+      connecting: value.connecting,
+      connections_opened: value.connections_opened,
+      connection_failures: value.connection_failures,
+      connections_discarded: value.connections_discarded,
+      connections_evicted: value.connections_evicted,
+      permit_wait: PermitWaitResponse {
+        count: value.permit_waits,
+        avg_ms: as_millis(value.permit_wait_avg),
+        max_ms: as_millis(value.permit_wait_max),
+      },
+      commands: value
+        .commands
+        .into_iter()
+        .map(CommandStatsResponse::from)
+        .collect(),
+    }
+  }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct CommandRequest {
   instcmd: CmdName,
+  #[serde(default)]
+  value: Option<Value>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -44,6 +239,40 @@ pub struct RwRequest {
   value: Value,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+  var: VarName,
+  from: Option<DateTime<Utc>>,
+  to: Option<DateTime<Utc>>,
+  resolution: Option<Resolution>,
+}
+
+#[derive(Debug, Serialize)]
+struct HistoryResponse<'a> {
+  name: &'a UpsName,
+  variable: &'a VarName,
+  from: DateTime<Utc>,
+  to: DateTime<Utc>,
+  #[serde(flatten)]
+  series: HistorySeries,
+}
+
+/// Default time range of history requests without `from` parameter.
+const HISTORY_DEFAULT_RANGE: TimeDelta = TimeDelta::hours(1);
+
+#[derive(Debug, Deserialize)]
+pub struct EventQuery {
+  device: Option<UpsName>,
+  kind: Option<EventKind>,
+  level: Option<EventLevel>,
+  from: Option<DateTime<Utc>>,
+  to: Option<DateTime<Utc>>,
+  limit: Option<usize>,
+}
+
+/// Default number of returned events without `limit` parameter.
+const EVENTS_DEFAULT_LIMIT: usize = 100;
+
 pub async fn get_ups_by_name(
   State(rs): State<RouterState>,
   ups_name: Result<Path<UpsName>, PathRejection>,
@@ -60,6 +289,64 @@ pub async fn get_ups_by_name(
   }
 }
 
+pub async fn get_ups_history(
+  State(rs): State<RouterState>,
+  ups_name: Result<Path<UpsName>, PathRejection>,
+  query: Result<Query<HistoryQuery>, QueryRejection>,
+) -> Result<Response, ProblemDetail> {
+  let Path(ups_name) = ups_name?;
+  let Query(query) = query?;
+  let server_state = rs.state.read().await;
+
+  // History of removed devices is kept until it expires, so it stays readable after removal.
+  if !server_state.devices.contains_key(&ups_name)
+    && !server_state.history.contains_device(&ups_name)
+  {
+    return Err(ProblemDetail::new(
+      "Device not found",
+      StatusCode::NOT_FOUND,
+    ));
+  }
+
+  if !server_state.history.is_recorded(&query.var) {
+    return Err(
+      ProblemDetail::new("Variable is not recorded", StatusCode::BAD_REQUEST).with_detail(format!(
+        "'{var_name}' is not configured for metric history.",
+        var_name = &query.var
+      )),
+    );
+  }
+
+  let to = query.to.unwrap_or_else(Utc::now);
+  let from = query.from.unwrap_or(to - HISTORY_DEFAULT_RANGE);
+
+  if from > to {
+    return Err(
+      ProblemDetail::new("Invalid time range", StatusCode::BAD_REQUEST)
+        .with_detail("'from' must be earlier than 'to'.".into()),
+    );
+  }
+
+  let series = server_state
+    .history
+    .query(&ups_name, &query.var, from, to, query.resolution)
+    .unwrap_or_else(|| HistorySeries {
+      resolution: query.resolution.unwrap_or(Resolution::Minute),
+      points: Vec::new(),
+    });
+
+  Ok(
+    Json(HistoryResponse {
+      name: &ups_name,
+      variable: &query.var,
+      from,
+      to,
+      series,
+    })
+    .into_response(),
+  )
+}
+
 pub async fn get_ups_list(State(rs): State<RouterState>) -> Response {
   let server_state = rs.state.read().await;
   let mut device_refs: Vec<&DeviceEntry> = server_state.devices.values().collect();
@@ -72,10 +359,10 @@ pub async fn post_command(
   State(rs): State<RouterState>,
   ups_name: Result<Path<UpsName>, PathRejection>,
   body: Result<Json<CommandRequest>, JsonRejection>,
-) -> Result<StatusCode, ProblemDetail> {
+) -> Result<Response, ProblemDetail> {
   let Path(ups_name) = ups_name?;
   let Json(body) = body?;
-  let (addr, user, password) = require_auth_config!(&rs.config.upsd)?;
+  let auth_pool = require_auth_pool!(rs)?;
 
   {
     let server_state = rs.state.read().await;
@@ -100,22 +387,26 @@ pub async fn post_command(
     }
   }?;
 
-  let mut client = NutAuthClient::connect(addr, user, password).await?;
+  if let Some(value) = &body.value {
+    validate_command_value(&body.instcmd, value)?;
+  }
 
-  {
-    let response = client.instcmd(&ups_name, &body.instcmd).await;
-    _ = client.close().await;
-
-    response
-  }?;
+  let tracking = rs
+    .instcmd(auth_pool, &ups_name, &body.instcmd, body.value.as_ref())
+    .await?;
 
   info!(
     message = "instcmd called",
     device = %ups_name,
-    instcmd = %&body.instcmd
+    instcmd = %&body.instcmd,
+    value = body.value.as_ref().map(tracing::field::display),
+    tracking_id = tracking.as_ref().map(|v| v.id.as_ref()),
   );
 
-  Ok(StatusCode::ACCEPTED)
+  Ok(accepted_response(
+    rs.config.http_server.base_path.as_str(),
+    tracking,
+  ))
 }
 
 pub async fn post_fsd(
@@ -123,7 +414,7 @@ pub async fn post_fsd(
   ups_name: Result<Path<UpsName>, PathRejection>,
 ) -> Result<StatusCode, ProblemDetail> {
   let Path(ups_name) = ups_name?;
-  let (addr, user, password) = require_auth_config!(&rs.config.upsd)?;
+  let auth_pool = require_auth_pool!(rs)?;
 
   {
     let server_state = rs.state.read().await;
@@ -137,14 +428,7 @@ pub async fn post_fsd(
     }
   }?;
 
-  let mut client = NutAuthClient::connect(addr, user, password).await?;
-
-  {
-    let response = client.fsd(&ups_name).await;
-    _ = client.close().await;
-
-    response
-  }?;
+  auth_pool.fsd(&ups_name).await?;
 
   warn!(
     message = "force shutdown (fsd) called",
@@ -158,114 +442,19 @@ pub async fn patch_var(
   State(rs): State<RouterState>,
   ups_name: Result<Path<UpsName>, PathRejection>,
   body: Result<Json<RwRequest>, JsonRejection>,
-) -> Result<StatusCode, ProblemDetail> {
+) -> Result<Response, ProblemDetail> {
   let Path(ups_name) = ups_name?;
   let Json(body) = body?;
-  let (addr, user, password) = require_auth_config!(&rs.config.upsd)?;
+  let auth_pool = require_auth_pool!(rs)?;
 
   {
     let server_state = rs.state.read().await;
 
     match server_state.devices.get(&ups_name) {
       Some(device) => match device.rw_variables.get(&body.variable) {
-        Some(VarDetail::Number) => {
-          if body.value.is_numeric() {
-            Ok(())
-          } else {
-            Err(
-              ProblemDetail::new("Invalid value type", StatusCode::BAD_REQUEST).with_detail(
-                format!(
-                  "'{var_name}' expects a numeric type, but the provided value is not a number.",
-                  var_name = &body.variable
-                ),
-              ),
-            )
-          }
-        }
-        Some(VarDetail::String { max_len }) => {
-          if body.value.is_text() {
-            let value_str = body.value.as_str();
-            let trimmed = value_str.trim();
-
-            if trimmed.is_empty() {
-              Err(
-                ProblemDetail::new("Empty value", StatusCode::BAD_REQUEST)
-                  .with_detail("Value cannot be empty or consist of only whitespaces.".to_owned()),
-              )
-            } else if trimmed.len() > *max_len {
-              Err(
-                ProblemDetail::new("Out of range", StatusCode::BAD_REQUEST)
-                  .with_detail(format!("Maximum allowed string length is {}.", max_len)),
-              )
-            } else {
-              Ok(())
-            }
-          } else {
-            Err(
-              ProblemDetail::new("Invalid value type", StatusCode::BAD_REQUEST).with_detail(
-                format!(
-                  "'{var_name}' expects a string type, but the provided value is not a string.",
-                  var_name = &body.variable
-                ),
-              ),
-            )
-          }
-        }
-        Some(VarDetail::Enum { options }) => {
-          if options.contains(&body.value) {
-            Ok(())
-          } else {
-            Err(
-              ProblemDetail::new("Invalid option", StatusCode::BAD_REQUEST).with_detail(format!(
-                "'{var_name}' is an enum type, allowed options: {opts:?}",
-                var_name = &body.variable,
-                opts = options
-                  .iter()
-                  .map(|v| v.as_str())
-                  .collect::<Vec<std::borrow::Cow<'_, str>>>()
-              )),
-            )
-          }
-        }
-        Some(VarDetail::Range { min, max }) => {
-          if body.value.is_numeric() {
-            match (min.as_lossly_f64(), max.as_lossly_f64()) {
-              (Some(min), Some(max)) => {
-                let valuef64 = body.value.as_lossly_f64().unwrap_or(0.0);
-
-                if min <= valuef64 && valuef64 <= max {
-                  Ok(())
-                } else {
-                  Err(
-                    ProblemDetail::new("Out of range", StatusCode::BAD_REQUEST).with_detail(
-                      format!(
-                        "'{var_name}' is not within the acceptable range [{min}, {max}]",
-                        var_name = &body.variable,
-                        min = min,
-                        max = max,
-                      ),
-                    ),
-                  )
-                }
-              }
-              _ => Err(ProblemDetail::new("Malformed driver response", StatusCode::INTERNAL_SERVER_ERROR).with_detail(
-                "Cannot process request since the reported min-max values by ups device are not number.".to_owned(),
-              ),
-            ),
-            }
-          } else {
-            Err(
-              ProblemDetail::new("Invalid value type", StatusCode::BAD_REQUEST).with_detail(
-                format!(
-                  "'{var_name}' expects a numeric value between {min} and {max}, but the provided value is not a number.",
-                  var_name = &body.variable,
-                  min = min,
-                  max = max,
-                ),
-              ),
-            )
-          }
-        }
+        Some(detail) => detail
+          .validate(&body.value)
+          .map_err(|err| rw_value_problem(&body.variable, detail, err)),
         None => Err(
           ProblemDetail::new("Invalid RW variable", StatusCode::BAD_REQUEST).with_detail(format!(
             "'{var_name}' is not a valid writeable variable.",
@@ -280,21 +469,174 @@ pub async fn patch_var(
     }
   }?;
 
-  let mut client = NutAuthClient::connect(addr, user, password).await?;
-
-  {
-    let response = client.set_var(&ups_name, &body.variable, &body.value).await;
-    _ = client.close().await;
-
-    response
-  }?;
+  let tracking = rs
+    .set_var(auth_pool, &ups_name, &body.variable, &body.value)
+    .await?;
 
   info!(
     message = "set var request accepted",
     device = %ups_name,
     variable = %body.variable,
     value = %body.value,
+    tracking_id = tracking.as_ref().map(|v| v.id.as_ref()),
   );
 
-  Ok(StatusCode::ACCEPTED)
+  Ok(accepted_response(
+    rs.config.http_server.base_path.as_str(),
+    tracking,
+  ))
+}
+
+pub async fn get_tracking(
+  State(rs): State<RouterState>,
+  id: Result<Path<Box<str>>, PathRejection>,
+) -> Result<Response, ProblemDetail> {
+  let Path(id) = id?;
+
+  match rs.get_tracking(&id).await? {
+    Some(entry) => Ok(Json(entry).into_response()),
+    None => Err(ProblemDetail::new(
+      "Tracking id not found",
+      StatusCode::NOT_FOUND,
+    )),
+  }
+}
+
+pub async fn get_events(
+  State(rs): State<RouterState>,
+  query: Result<Query<EventQuery>, QueryRejection>,
+) -> Result<Response, ProblemDetail> {
+  let Query(query) = query?;
+  let filter = EventFilter {
+    device: query.device,
+    kind: query.kind,
+    min_level: query.level,
+    from: query.from,
+    to: query.to,
+  };
+
+  let server_state = rs.state.read().await;
+  let events: Vec<&EventEntry> = server_state
+    .events
+    .query(&filter)
+    .take(query.limit.unwrap_or(EVENTS_DEFAULT_LIMIT))
+    .collect();
+
+  Ok(Json(events).into_response())
+}
+
+pub async fn get_pool_stats(State(rs): State<RouterState>) -> Response {
+  Json(PoolStatsResponse::from(rs.client_pool.stats())).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{HistoryQuery, RouterState, get_ups_history, validate_command_value};
+  use crate::{
+    config::ServerConfig,
+    event_log::EventLog,
+    history::HistoryStore,
+    state::{DaemonState, ServerState},
+  };
+  use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+  };
+  use chrono::Utc;
+  use core::{num::NonZeroUsize, time::Duration};
+  use nut_webgui_upsmc::{
+    CmdName, MAX_ARGUMENT_LEN, UpsName, Value, VarName, clients::NutPoolClient,
+    variables::UpsVariables,
+  };
+  use std::{collections::HashMap, sync::Arc};
+  use tokio::sync::RwLock;
+
+  fn router_state(history: HistoryStore) -> RouterState {
+    RouterState {
+      config: Arc::new(ServerConfig::default()),
+      state: Arc::new(RwLock::new(ServerState {
+        devices: HashMap::new(),
+        remote_state: DaemonState::new(),
+        shared_desc: HashMap::new(),
+        tracking: HashMap::new(),
+        history,
+        events: EventLog::new(16),
+      })),
+      client_pool: NutPoolClient::new("127.0.0.1:3493".to_owned(), NonZeroUsize::MIN),
+      auth_pool: None,
+    }
+  }
+
+  fn history_query() -> HistoryQuery {
+    HistoryQuery {
+      var: VarName::BATTERY_CHARGE,
+      from: None,
+      to: None,
+      resolution: None,
+    }
+  }
+
+  #[test]
+  fn command_value_accepted() {
+    let cmd = CmdName::new_unchecked("load.off.delay");
+
+    assert!(validate_command_value(&cmd, &Value::from(120)).is_ok());
+    assert!(validate_command_value(&cmd, &Value::from("120")).is_ok());
+
+    // Non-standard commands have no metadata, their values are passed through.
+    let custom = CmdName::new_unchecked("custom.cmd");
+
+    assert!(validate_command_value(&custom, &Value::from("on")).is_ok());
+  }
+
+  #[test]
+  fn command_value_rejected() {
+    let cmd = CmdName::new_unchecked("load.off.delay");
+    let injected = Value::from("120\nINSTCMD bx1600mi load.off");
+    let oversized = Value::from("1".repeat(MAX_ARGUMENT_LEN + 1));
+
+    for value in [injected, oversized] {
+      let problem = validate_command_value(&cmd, &value).unwrap_err();
+      assert_eq!(problem.status, StatusCode::BAD_REQUEST);
+    }
+
+    let parameterless = CmdName::new_unchecked("load.off");
+    let problem = validate_command_value(&parameterless, &Value::from(120)).unwrap_err();
+
+    assert_eq!(problem.status, StatusCode::BAD_REQUEST);
+  }
+
+  #[tokio::test]
+  async fn history_of_removed_device() {
+    let mut history = HistoryStore::new(
+      vec![VarName::BATTERY_CHARGE],
+      Some(Duration::from_secs(24 * 60 * 60)),
+    );
+    let mut variables = UpsVariables::new();
+    _ = variables.insert(VarName::BATTERY_CHARGE, Value::from(100));
+
+    // The device is not in `devices` anymore, only its history is left.
+    let removed = UpsName::new_unchecked("removed");
+    history.record(&removed, &variables, Utc::now());
+
+    let rs = router_state(history);
+
+    let response = get_ups_history(
+      State(rs.clone()),
+      Ok(Path(removed)),
+      Ok(Query(history_query())),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let problem = get_ups_history(
+      State(rs),
+      Ok(Path(UpsName::new_unchecked("unknown"))),
+      Ok(Query(history_query())),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(problem.status, StatusCode::NOT_FOUND);
+  }
 }
@@ -1,6 +1,6 @@
 use axum::{
   Json,
-  extract::rejection::{JsonRejection, PathRejection},
+  extract::rejection::{JsonRejection, PathRejection, QueryRejection},
   http::StatusCode,
   response::{IntoResponse, Response},
 };
@@ -83,6 +83,16 @@ impl From<Error> for ProblemDetail {
         status: StatusCode::INTERNAL_SERVER_ERROR,
         detail: Some(err.to_string()),
       },
+      ErrorKind::TlsError { .. } => ProblemDetail {
+        title: "UPS daemon TLS error",
+        status: StatusCode::INTERNAL_SERVER_ERROR,
+        detail: Some(err.to_string()),
+      },
+      ErrorKind::ArgumentError { .. } => ProblemDetail {
+        title: "Invalid command argument",
+        status: StatusCode::BAD_REQUEST,
+        detail: Some(err.to_string()),
+      },
       ErrorKind::ConnectionPoolClosed => ProblemDetail {
         title: "Server connection pool error",
         status: StatusCode::INTERNAL_SERVER_ERROR,
@@ -150,3 +160,20 @@ impl From<JsonRejection> for ProblemDetail {
     }
   }
 }
+
+impl From<QueryRejection> for ProblemDetail {
+  fn from(value: QueryRejection) -> Self {
+    match value {
+      QueryRejection::FailedToDeserializeQueryString(err) => ProblemDetail {
+        title: "Unable to deserialize query string",
+        detail: Some(err.body_text()),
+        status: err.status(),
+      },
+      c => ProblemDetail {
+        title: "Invalid query string",
+        detail: Some(c.body_text()),
+        status: c.status(),
+      },
+    }
+  }
+}
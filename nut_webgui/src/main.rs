@@ -2,9 +2,12 @@ mod config;
 mod device_entry;
 mod diff_utils;
 mod event;
+mod event_log;
+mod history;
 mod http;
 mod service;
 mod state;
+mod storage;
 mod uri_path;
 
 use self::config::{
@@ -12,14 +15,18 @@ use self::config::{
 };
 use crate::config::error::ConfigError;
 use event::EventChannel;
+use event_log::{EventEntry, EventLog};
+use history::{HistoryRecord, HistoryStore};
 use http::HttpServer;
 use nut_webgui_upsmc::clients::NutPoolClient;
 use service::{
-  BackgroundServiceRunner, sync_desc::DescriptionSyncService, sync_device::DeviceSyncService,
-  sync_status::StatusSyncService,
+  BackgroundServiceRunner, event_journal::EventJournalService, persistence::PersistenceService,
+  pool_reaper::PoolReaperService, sync_desc::DescriptionSyncService,
+  sync_device::DeviceSyncService, sync_rw::RwSyncService, sync_status::StatusSyncService,
 };
 use state::{DaemonState, ServerState};
-use std::{collections::HashMap, panic, sync::Arc, time::Duration};
+use std::{collections::HashMap, num::NonZeroUsize, panic, sync::Arc, time::Duration};
+use storage::{EVENT_JOURNAL, HISTORY_JOURNAL, Journal};
 use tokio::{
   net::TcpListener,
   select,
@@ -28,6 +35,12 @@ use tokio::{
 };
 use tracing::{debug, error, info, warn};
 
+/// Connection limit of the authenticated pool used for INSTCMD, SET VAR and FSD requests.
+const AUTH_POOL_MAX_CONN: NonZeroUsize = NonZeroUsize::new(2).unwrap();
+
+/// Maximum number of entries kept in the event log.
+const EVENT_LOG_CAPACITY: usize = 5000;
+
 fn load_configs() -> Result<ServerConfig, ConfigError> {
   let cli_args = ServerCliArgs::load()?;
 
@@ -97,16 +110,136 @@ async fn main() -> Result<(), Box<dyn core::error::Error>> {
     .await
     .inspect_err(|err| error!(message = "cannot bind tcp socket to listen", reason = %err, listen_port = config.http_server.port))?;
 
-  let client_pool = NutPoolClient::new_with_timeout(
-    config.upsd.get_socket_addr(),
-    config.upsd.max_conn,
-    Duration::from_secs(config.upsd.poll_freq),
+  let upsd_tls = config
+    .upsd
+    .get_tls_config()
+    .inspect_err(|err| error!(message = "cannot load upsd tls config", reason = %err))?;
+
+  let (client_pool, auth_pool) = {
+    let mut builder = NutPoolClient::builder(config.upsd.get_socket_addr(), config.upsd.max_conn)
+      .with_connect_timeout(Duration::from_secs(config.upsd.connect_timeout))
+      .with_timeout(Duration::from_secs(config.upsd.read_timeout))
+      .with_validate_after(Duration::from_secs(config.upsd.pool_validate_after))
+      .with_failback(config.upsd.get_failback_policy());
+
+    for endpoint in config.upsd.fallback_endpoints.iter() {
+      builder = match &upsd_tls {
+        Some(tls) => {
+          let endpoint_tls = config
+            .upsd
+            .get_endpoint_tls_config(tls, endpoint)
+            .inspect_err(|err| error!(message = "cannot load upsd tls config", reason = %err, endpoint = %endpoint))?;
+
+          builder.with_endpoint_tls(endpoint.to_string(), endpoint_tls)
+        }
+        None => builder.with_endpoint(endpoint.to_string()),
+      };
+    }
+
+    if config.upsd.pool_max_idle > 0 {
+      builder = builder.with_max_idle(Duration::from_secs(config.upsd.pool_max_idle));
+    }
+
+    if config.upsd.pool_max_lifetime > 0 {
+      builder = builder.with_max_lifetime(Duration::from_secs(config.upsd.pool_max_lifetime));
+    }
+
+    if let Some(tls) = upsd_tls {
+      builder = builder.with_tls(tls);
+    }
+
+    let client_pool = builder.build();
+
+    // Write operations are rare, a couple of authenticated sessions are enough to serve them.
+    let auth_pool = match (&config.upsd.user, &config.upsd.pass) {
+      (Some(user), Some(pass)) => {
+        Some(client_pool.auth_pool(user.as_ref(), pass.as_ref(), AUTH_POOL_MAX_CONN))
+      }
+      _ => None,
+    };
+
+    (client_pool, auth_pool)
+  };
+  let mut history = HistoryStore::new(
+    config.history.variables.clone(),
+    config.history.get_retention(),
   );
-  let event_channel = EventChannel::new(64);
+
+  if let Some(path) = &config.storage.path {
+    std::fs::create_dir_all(path)
+      .inspect_err(|err| error!(message = "cannot create storage directory", reason = %err, path = %path.display()))?;
+  }
+
+  let history_journal = match (&config.storage.path, config.history.get_retention()) {
+    (Some(path), Some(retention)) => {
+      let (journal, records) = Journal::open(path.join(HISTORY_JOURNAL))
+        .inspect_err(|err| error!(message = "cannot open metric history journal", reason = %err, path = %path.display()))?;
+
+      let total = records.len();
+      let records: Vec<HistoryRecord> = records
+        .iter()
+        .filter_map(|record| HistoryRecord::decode(record))
+        .collect();
+
+      if records.len() < total {
+        warn!(
+          message = "skipped malformed metric history records",
+          count = total - records.len()
+        );
+      }
+
+      info!(
+        message = "metric history loaded from storage",
+        record_count = records.len()
+      );
+
+      history = history.with_persistence(records);
+      Some((journal, retention))
+    }
+    _ => None,
+  };
+
+  let mut event_log = EventLog::new(EVENT_LOG_CAPACITY);
+
+  let event_journal = match &config.storage.path {
+    Some(path) => {
+      let (journal, records) = Journal::open(path.join(EVENT_JOURNAL)).inspect_err(
+        |err| error!(message = "cannot open event journal", reason = %err, path = %path.display()),
+      )?;
+
+      let total = records.len();
+      let entries: Vec<EventEntry> = records
+        .iter()
+        .filter_map(|record| serde_json::from_slice(record).ok())
+        .collect();
+
+      if entries.len() < total {
+        warn!(
+          message = "skipped malformed event journal records",
+          count = total - entries.len()
+        );
+      }
+
+      info!(
+        message = "event log loaded from storage",
+        record_count = entries.len()
+      );
+
+      event_log = event_log.with_entries(entries);
+      Some((journal, total))
+    }
+    None => None,
+  };
+
+  // Event journal records outages from this channel, a lagging receiver would lose them.
+  let event_channel = EventChannel::new(1024);
   let server_state = Arc::new(RwLock::new(ServerState {
     remote_state: DaemonState::new(),
     devices: HashMap::new(),
     shared_desc: HashMap::new(),
+    tracking: HashMap::new(),
+    history,
+    events: event_log,
   }));
 
   let device_sync = DeviceSyncService::new(
@@ -130,12 +263,62 @@ async fn main() -> Result<(), Box<dyn core::error::Error>> {
     Duration::from_secs(config.upsd.poll_freq),
   );
 
-  let bg_services = BackgroundServiceRunner::new()
+  let rw_sync = RwSyncService::new(
+    client_pool.clone(),
+    event_channel.clone(),
+    server_state.clone(),
+    config.upsd.get_rw_refresh_interval(),
+  );
+
+  let mut event_journal_service =
+    EventJournalService::new(event_channel.clone(), server_state.clone());
+
+  if let Some((journal, journal_len)) = event_journal {
+    event_journal_service = event_journal_service.with_journal(journal, journal_len);
+  }
+
+  let mut bg_services = BackgroundServiceRunner::new()
     .with_max_timeout(Duration::from_secs(10))
+    // Subscribes to the event channel before sync services start emitting events.
+    .add_service(event_journal_service)
     .add_service(device_sync)
     .add_service(desc_sync)
     .add_service(status_sync)
-    .start();
+    .add_service(rw_sync);
+
+  let reap_interval = config.upsd.get_pool_reap_interval();
+  let failback_interval = config.upsd.get_failback_probe_interval();
+
+  if reap_interval.is_some() || failback_interval.is_some() {
+    let mut reaper = PoolReaperService::new(client_pool.clone());
+
+    if let Some(reap_interval) = reap_interval {
+      reaper = reaper.with_reap_interval(reap_interval);
+    }
+
+    if let Some(failback_interval) = failback_interval {
+      reaper = reaper.with_failback_interval(failback_interval);
+    }
+
+    if let Some(auth_pool) = &auth_pool {
+      reaper = reaper.with_auth_pool(auth_pool.clone());
+    }
+
+    bg_services = bg_services.add_service(reaper);
+  }
+
+  if let Some((journal, retention)) = history_journal {
+    bg_services = bg_services.add_service(PersistenceService::new(
+      server_state.clone(),
+      journal,
+      config.history.variables.clone(),
+      retention,
+    ));
+  }
+
+  let bg_services = bg_services.start();
+
+  let http_server = HttpServer::new(config, server_state, client_pool.clone(), auth_pool.clone());
 
   let close_signal = async move {
     select! {
@@ -152,9 +335,13 @@ async fn main() -> Result<(), Box<dyn core::error::Error>> {
 
     info!("closing open upsd connections");
     _ = client_pool.close().await;
+
+    if let Some(auth_pool) = auth_pool {
+      auth_pool.close().await;
+    }
   };
 
-  HttpServer::new(config, server_state)
+  http_server
     .serve(listener, close_signal)
     .await
     .inspect_err(|err| {
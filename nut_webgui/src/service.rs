@@ -8,8 +8,12 @@ use tokio::{
 use tokio_util::sync::CancellationToken;
 
 pub mod error;
+pub mod event_journal;
+pub mod persistence;
+pub mod pool_reaper;
 pub mod sync_desc;
 pub mod sync_device;
+pub mod sync_rw;
 pub mod sync_status;
 
 /// Trait for services that can be run in the background.
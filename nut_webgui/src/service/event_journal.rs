@@ -0,0 +1,151 @@
+use super::BackgroundService;
+use crate::{event::EventChannel, event_log::EventEntry, state::ServerState, storage::Journal};
+use chrono::Utc;
+use std::{
+  io,
+  sync::{Arc, Mutex},
+};
+use tokio::{
+  select,
+  sync::{RwLock, broadcast::error::RecvError},
+  task::spawn_blocking,
+};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+
+/// Records system events to the event log, and appends them to the journal when persistent
+/// storage is enabled.
+pub struct EventJournalService {
+  event_channel: EventChannel,
+  state: Arc<RwLock<ServerState>>,
+  journal: Option<Arc<Mutex<Journal>>>,
+  journal_len: usize,
+}
+
+impl EventJournalService {
+  pub fn new(event_channel: EventChannel, state: Arc<RwLock<ServerState>>) -> Self {
+    Self {
+      event_channel,
+      state,
+      journal: None,
+      journal_len: 0,
+    }
+  }
+
+  /// Persists recorded events. `journal_len` is the number of records already in the journal.
+  pub fn with_journal(mut self, journal: Journal, journal_len: usize) -> Self {
+    self.journal = Some(Arc::new(Mutex::new(journal)));
+    self.journal_len = journal_len;
+    self
+  }
+}
+
+impl BackgroundService for EventJournalService {
+  fn run(
+    &self,
+    token: CancellationToken,
+  ) -> core::pin::Pin<Box<dyn core::future::Future<Output = ()> + Send + Sync + 'static>> {
+    let mut events = self.event_channel.subscribe();
+    let mut task = EventJournalTask {
+      state: self.state.clone(),
+      journal: self.journal.clone(),
+      journal_len: self.journal_len,
+    };
+
+    Box::pin(async move {
+      'MAIN: loop {
+        select! {
+          event = events.recv() => {
+            match event {
+              Ok(event) => {
+                let entries = {
+                  let mut write_lock = task.state.write().await;
+                  write_lock.events.record(&event, Utc::now())
+                };
+
+                if let Err(err) = task.persist(entries).await {
+                  warn!(message = "cannot write events to journal", reason = %err);
+                }
+              }
+              Err(RecvError::Closed) => break 'MAIN,
+              Err(RecvError::Lagged(lagged)) => {
+                warn!(message = "event journal service can't keep up with system events", lagged_event_count=lagged)
+              }
+            }
+          }
+          _ = token.cancelled() => { break 'MAIN; }
+        }
+      }
+
+      debug!(message = "event journal service stopped");
+    })
+  }
+}
+
+struct EventJournalTask {
+  state: Arc<RwLock<ServerState>>,
+  journal: Option<Arc<Mutex<Journal>>>,
+  journal_len: usize,
+}
+
+impl EventJournalTask {
+  async fn persist(&mut self, entries: Vec<EventEntry>) -> io::Result<()> {
+    let Some(journal) = &self.journal else {
+      return Ok(());
+    };
+
+    if entries.is_empty() {
+      return Ok(());
+    }
+
+    let mut records = Vec::with_capacity(entries.len());
+
+    for entry in entries.iter() {
+      records.push(serde_json::to_vec(entry)?);
+    }
+
+    // Journal is rewritten with the retained entries once it grows twice the log capacity.
+    let (records, compacted) = {
+      let read_lock = self.state.read().await;
+      let capacity = read_lock.events.capacity();
+
+      if self.journal_len + records.len() > capacity * 2 {
+        let mut retained = Vec::with_capacity(capacity);
+
+        for entry in read_lock.events.iter() {
+          retained.push(serde_json::to_vec(entry)?);
+        }
+
+        (retained, true)
+      } else {
+        (records, false)
+      }
+    };
+
+    let journal = journal.clone();
+    let count = records.len();
+
+    spawn_blocking(move || {
+      let mut journal = journal
+        .lock()
+        .map_err(|_| io::Error::other("journal lock poisoned"))?;
+
+      if compacted {
+        journal.rewrite(records.iter())
+      } else {
+        journal.append(records.iter())
+      }
+    })
+    .await
+    .map_err(io::Error::other)??;
+
+    if compacted {
+      debug!(message = "event journal compacted", record_count = count);
+      self.journal_len = count;
+    } else {
+      self.journal_len += count;
+    }
+
+    Ok(())
+  }
+}
@@ -0,0 +1,270 @@
+use super::BackgroundService;
+use crate::{
+  history::{HistoryRecord, compact_records},
+  state::ServerState,
+  storage::Journal,
+};
+use chrono::{TimeDelta, Utc};
+use nut_webgui_upsmc::VarName;
+use std::{
+  io,
+  sync::{Arc, Mutex},
+  time::Duration,
+};
+use tokio::{
+  select,
+  sync::RwLock,
+  task::spawn_blocking,
+  time::{MissedTickBehavior, interval},
+};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+
+/// Completed minute buckets are written to disk in this interval.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Expired records are dropped and old records are downsampled in this interval.
+const COMPACTION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Writes completed history buckets to the journal and periodically compacts it.
+pub struct PersistenceService {
+  state: Arc<RwLock<ServerState>>,
+  journal: Arc<Mutex<Journal>>,
+  variables: Arc<[VarName]>,
+  retention: TimeDelta,
+}
+
+impl PersistenceService {
+  pub fn new(
+    state: Arc<RwLock<ServerState>>,
+    journal: Journal,
+    variables: Vec<VarName>,
+    retention: Duration,
+  ) -> Self {
+    Self {
+      state,
+      journal: Arc::new(Mutex::new(journal)),
+      variables: variables.into(),
+      retention: TimeDelta::from_std(retention).unwrap_or(TimeDelta::MAX),
+    }
+  }
+}
+
+impl BackgroundService for PersistenceService {
+  fn run(
+    &self,
+    token: CancellationToken,
+  ) -> core::pin::Pin<Box<dyn core::future::Future<Output = ()> + Send + Sync + 'static>> {
+    let task = PersistenceTask {
+      state: self.state.clone(),
+      journal: self.journal.clone(),
+      variables: self.variables.clone(),
+      retention: self.retention,
+    };
+
+    Box::pin(async move {
+      let mut flush_interval = interval(FLUSH_INTERVAL);
+      flush_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+      // First tick completes immediately, nothing is recorded yet.
+      flush_interval.tick().await;
+
+      // Journal is compacted on startup, since it may be left uncompacted for a long time.
+      let mut compaction_interval = interval(COMPACTION_INTERVAL);
+      compaction_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+      'MAIN: loop {
+        select! {
+          _ = flush_interval.tick() => {
+            if let Err(err) = task.flush().await {
+              warn!(message = "cannot write metric history to journal", reason = %err);
+            }
+          }
+          _ = compaction_interval.tick() => {
+            if let Err(err) = task.compact().await {
+              warn!(message = "cannot compact metric history journal", reason = %err);
+            }
+          }
+          _ = token.cancelled() => {
+            if let Err(err) = task.flush().await {
+              warn!(message = "cannot write metric history to journal", reason = %err);
+            }
+
+            break 'MAIN;
+          }
+        }
+      }
+
+      debug!(message = "persistence service stopped");
+    })
+  }
+}
+
+struct PersistenceTask {
+  state: Arc<RwLock<ServerState>>,
+  journal: Arc<Mutex<Journal>>,
+  variables: Arc<[VarName]>,
+  retention: TimeDelta,
+}
+
+impl PersistenceTask {
+  async fn flush(&self) -> io::Result<()> {
+    let records = {
+      let mut write_lock = self.state.write().await;
+      write_lock.history.take_pending()
+    };
+
+    if records.is_empty() {
+      return Ok(());
+    }
+
+    let frames: Vec<Vec<u8>> = records.iter().map(encode_record).collect();
+    let journal = self.journal.clone();
+    let count = records.len();
+
+    let result = spawn_blocking(move || {
+      let mut journal = journal
+        .lock()
+        .map_err(|_| io::Error::other("journal lock poisoned"))?;
+      journal.append(frames)
+    })
+    .await
+    .map_err(io::Error::other)
+    .and_then(|result| result);
+
+    if let Err(err) = result {
+      // Records are written with the next flush instead.
+      let mut write_lock = self.state.write().await;
+      write_lock.history.restore_pending(records);
+
+      return Err(err);
+    }
+
+    debug!(
+      message = "metric history written to journal",
+      record_count = count
+    );
+
+    Ok(())
+  }
+
+  async fn compact(&self) -> io::Result<()> {
+    let journal = self.journal.clone();
+    let variables = self.variables.clone();
+    let retention = self.retention;
+
+    let (before, after) = spawn_blocking(move || {
+      let mut journal = journal
+        .lock()
+        .map_err(|_| io::Error::other("journal lock poisoned"))?;
+      let records: Vec<HistoryRecord> = journal
+        .read_all()?
+        .iter()
+        .filter_map(|record| HistoryRecord::decode(record))
+        .collect();
+
+      let before = records.len();
+      let records = compact_records(records, &variables, retention, Utc::now());
+      let after = records.len();
+
+      journal.rewrite(records.iter().map(encode_record))?;
+
+      Ok::<_, io::Error>((before, after))
+    })
+    .await
+    .map_err(io::Error::other)??;
+
+    debug!(
+      message = "metric history journal compacted",
+      records_before = before,
+      records_after = after
+    );
+
+    Ok(())
+  }
+}
+
+fn encode_record(record: &HistoryRecord) -> Vec<u8> {
+  let mut buffer = Vec::new();
+  record.encode(&mut buffer);
+  buffer
+}
+
+#[cfg(test)]
+mod tests {
+  use super::PersistenceTask;
+  use crate::{
+    event_log::EventLog,
+    history::HistoryStore,
+    state::{DaemonState, ServerState},
+    storage::Journal,
+  };
+  use chrono::{DateTime, TimeDelta, Utc};
+  use core::time::Duration;
+  use nut_webgui_upsmc::{UpsName, Value, VarName, variables::UpsVariables};
+  use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+  };
+  use tokio::sync::RwLock;
+
+  const RETENTION: Duration = Duration::from_secs(24 * 60 * 60);
+
+  /// Returns a history store with a single completed minute bucket waiting to be written.
+  fn history() -> HistoryStore {
+    let mut history =
+      HistoryStore::new(vec![VarName::BATTERY_CHARGE], Some(RETENTION)).with_persistence([]);
+    let device = UpsName::new_unchecked("bx1600mi");
+    let start = DateTime::from_timestamp(Utc::now().timestamp() / 60 * 60, 0).unwrap()
+      - TimeDelta::minutes(5);
+
+    for (value, timestamp) in [(90, start), (80, start + TimeDelta::minutes(1))] {
+      let mut variables = UpsVariables::new();
+      _ = variables.insert(VarName::BATTERY_CHARGE, Value::from(value));
+      history.record(&device, &variables, timestamp);
+    }
+
+    history
+  }
+
+  #[tokio::test]
+  async fn failed_flush_keeps_pending_records() {
+    let path =
+      std::env::temp_dir().join(format!("nut_webgui_{}_flush.journal", std::process::id()));
+    let (journal, _) = Journal::open(path.clone()).unwrap();
+    let journal = Arc::new(Mutex::new(journal));
+
+    // Poisons the journal lock, so the next write fails.
+    let poisoned = journal.clone();
+    _ = std::thread::spawn(move || {
+      let _guard = poisoned.lock().unwrap();
+      panic!("poisoning journal lock");
+    })
+    .join();
+
+    let task = PersistenceTask {
+      state: Arc::new(RwLock::new(ServerState {
+        devices: HashMap::new(),
+        remote_state: DaemonState::new(),
+        shared_desc: HashMap::new(),
+        tracking: HashMap::new(),
+        history: history(),
+        events: EventLog::new(16),
+      })),
+      journal: journal.clone(),
+      variables: vec![VarName::BATTERY_CHARGE].into(),
+      retention: TimeDelta::from_std(RETENTION).unwrap(),
+    };
+
+    assert!(task.flush().await.is_err());
+
+    journal.clear_poison();
+    task.flush().await.unwrap();
+
+    let written = journal.lock().unwrap().read_all().unwrap();
+    _ = std::fs::remove_file(&path);
+
+    assert_eq!(written.len(), 1);
+    assert!(task.state.write().await.history.take_pending().is_empty());
+  }
+}
@@ -0,0 +1,101 @@
+use super::BackgroundService;
+use nut_webgui_upsmc::clients::{NutAuthPoolClient, NutPoolClient, ToNutAddr};
+use std::{fmt::Debug, time::Duration};
+use tokio::{
+  select,
+  time::{MissedTickBehavior, interval},
+};
+use tokio_util::sync::CancellationToken;
+use tracing::debug;
+
+/// Periodically closes idle pool connections exceeding max idle time or max lifetime, and probes
+/// higher priority upsd endpoints for failback, so requests never wait on them.
+pub struct PoolReaperService<A>
+where
+  A: ToNutAddr + Debug + Send + Sync + 'static,
+{
+  client: NutPoolClient<A>,
+  auth_client: Option<NutAuthPoolClient<A>>,
+  reap_interval: Option<Duration>,
+  failback_interval: Option<Duration>,
+}
+
+impl<A> PoolReaperService<A>
+where
+  A: ToNutAddr + Debug + Send + Sync + 'static,
+{
+  pub fn new(client: NutPoolClient<A>) -> Self {
+    Self {
+      client,
+      auth_client: None,
+      reap_interval: None,
+      failback_interval: None,
+    }
+  }
+
+  /// Closes expired connections on the given interval.
+  pub fn with_reap_interval(mut self, reap_interval: Duration) -> Self {
+    self.reap_interval = Some(reap_interval);
+    self
+  }
+
+  /// Probes higher priority endpoints on the given interval.
+  pub fn with_failback_interval(mut self, failback_interval: Duration) -> Self {
+    self.failback_interval = Some(failback_interval);
+    self
+  }
+
+  /// Also closes expired connections of the authenticated pool.
+  pub fn with_auth_pool(mut self, auth_client: NutAuthPoolClient<A>) -> Self {
+    self.auth_client = Some(auth_client);
+    self
+  }
+}
+
+impl<A> BackgroundService for PoolReaperService<A>
+where
+  A: ToNutAddr + Debug + Send + Sync + 'static,
+{
+  fn run(
+    &self,
+    token: CancellationToken,
+  ) -> core::pin::Pin<Box<dyn core::future::Future<Output = ()> + Send + Sync + 'static>> {
+    let client = self.client.clone();
+    let auth_client = self.auth_client.clone();
+    let reap_interval = self.reap_interval;
+    let failback_interval = self.failback_interval;
+
+    Box::pin(async move {
+      // Disabled tasks keep a placeholder period, their branches never run.
+      let mut reap_timer = interval(reap_interval.unwrap_or(Duration::from_secs(60)));
+      reap_timer.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+      let mut failback_timer = interval(failback_interval.unwrap_or(Duration::from_secs(60)));
+      failback_timer.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+      'MAIN: loop {
+        select! {
+          _ = failback_timer.tick(), if failback_interval.is_some() => {
+            // Endpoint switches are logged by the pool. Authenticated pool shares the same
+            // endpoint state.
+            _ = client.try_failback().await;
+          }
+          _ = reap_timer.tick(), if reap_interval.is_some() => {
+            let mut evicted = client.evict_expired().await;
+
+            if let Some(auth_client) = &auth_client {
+              evicted += auth_client.evict_expired().await;
+            }
+
+            if evicted > 0 {
+              debug!(message = "expired pool connections closed", count = evicted);
+            }
+          }
+          _ = token.cancelled() => { break 'MAIN; }
+        }
+      }
+
+      debug!(message = "pool reaper stopped");
+    })
+  }
+}
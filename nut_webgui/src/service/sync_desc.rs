@@ -5,10 +5,10 @@ use crate::{
 };
 use nut_webgui_upsmc::{
   CmdName, UpsName, VarName,
-  clients::{AsyncNutClient, NutPoolClient},
+  clients::{AsyncNutClient, NutPoolClient, ToNutAddr},
   responses::{CmdDesc, UpsVarDesc},
 };
-use std::{collections::HashSet, net::ToSocketAddrs, sync::Arc};
+use std::{collections::HashSet, fmt::Debug, sync::Arc};
 use tokio::{
   join, select,
   sync::{RwLock, broadcast::error::RecvError},
@@ -19,7 +19,7 @@ use tracing::{debug, warn};
 
 pub struct DescriptionSyncService<A>
 where
-  A: ToSocketAddrs + Send + Sync + 'static,
+  A: ToNutAddr + Debug + Send + Sync + 'static,
 {
   event_channel: EventChannel,
   client: NutPoolClient<A>,
@@ -28,7 +28,7 @@ where
 
 impl<A> DescriptionSyncService<A>
 where
-  A: ToSocketAddrs + Send + Sync + 'static,
+  A: ToNutAddr + Debug + Send + Sync + 'static,
 {
   pub fn new(
     client: NutPoolClient<A>,
@@ -45,7 +45,7 @@ where
 
 impl<A> BackgroundService for DescriptionSyncService<A>
 where
-  A: ToSocketAddrs + Send + Sync + 'static,
+  A: ToNutAddr + Debug + Send + Sync + 'static,
 {
   fn run(
     &self,
@@ -85,7 +85,7 @@ where
 
 struct DescriptionTask<A>
 where
-  A: ToSocketAddrs + Send + Sync + 'static,
+  A: ToNutAddr + Debug + Send + Sync + 'static,
 {
   client: NutPoolClient<A>,
   state: Arc<RwLock<ServerState>>,
@@ -99,7 +99,7 @@ struct TaskContext {
 
 impl<A> DescriptionTask<A>
 where
-  A: ToSocketAddrs + Send + Sync + 'static,
+  A: ToNutAddr + Debug + Send + Sync + 'static,
 {
   pub async fn next(&self, devices: Vec<UpsName>) {
     let task_ctx: Vec<TaskContext> = {
@@ -163,10 +163,15 @@ where
   }
 
   /// **concurrently** loads requested command and variable descriptions for target ups.
+  /// Command descriptions are skipped on daemons without `GET CMDDESC` support.
   async fn load_descs(client: NutPoolClient<A>, ctx: TaskContext) -> Vec<(Box<str>, Box<str>)> {
+    let cmds: &[String] = match client.cached_capabilities() {
+      Some(capabilities) if !capabilities.cmd_desc.is_available() => &[],
+      _ => &ctx.cmds,
+    };
+
     let cmd_future = futures::future::join_all(
-      ctx
-        .cmds
+      cmds
         .iter()
         .cloned()
         .map(|v| client.get_cmd_desc(&ctx.name, CmdName::new_unchecked(v))),
@@ -3,7 +3,7 @@ use super::{
   error::{DeviceLoadError, IntoLoadError, SyncTaskError},
 };
 use crate::{
-  device_entry::{DeviceEntry, VarDetail},
+  device_entry::{DeviceEntry, ParseWarning, ParseWarnings},
   diff_utils::Diff,
   event::{EventBatch, EventChannel, SystemEvent},
   state::{DaemonStatus, ServerState},
@@ -11,20 +11,26 @@ use crate::{
 use chrono::Utc;
 use futures::future::join_all;
 use nut_webgui_upsmc::{
-  UpsName, Value, VarName, VarType,
-  clients::{AsyncNutClient, NutPoolClient},
-  responses::UpsDevice,
-  ups_status::UpsStatus,
+  RwDetail, UpsName, VarName,
+  clients::{AsyncNutClient, Batch, NutPoolClient, ToNutAddr},
+  errors::ParseDiagnostic,
+  responses::{RwList, UpsDevice},
+  ups_status::UpsStatusInfo,
 };
-use std::{collections::HashMap, net::ToSocketAddrs, sync::Arc, time::Duration};
-use tokio::{join, select, sync::RwLock, task::JoinSet, time::interval, try_join};
+use std::{
+  collections::HashMap,
+  fmt::{Debug, Display},
+  sync::Arc,
+  time::Duration,
+};
+use tokio::{select, sync::RwLock, task::JoinSet, time::interval, try_join};
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
 /// Synchronizes device list from UPSD.
 pub struct DeviceSyncService<A>
 where
-  A: ToSocketAddrs + Send + Sync + 'static,
+  A: ToNutAddr + Debug + Display + Send + Sync + 'static,
 {
   client: NutPoolClient<A>,
   event_channel: EventChannel,
@@ -34,7 +40,7 @@ where
 
 impl<A> DeviceSyncService<A>
 where
-  A: ToSocketAddrs + Send + Sync + 'static,
+  A: ToNutAddr + Debug + Display + Send + Sync + 'static,
 {
   pub fn new(
     client: NutPoolClient<A>,
@@ -53,7 +59,7 @@ where
 
 impl<A> BackgroundService for DeviceSyncService<A>
 where
-  A: ToSocketAddrs + Send + Sync + 'static,
+  A: ToNutAddr + Debug + Display + Send + Sync + 'static,
 {
   fn run(
     &self,
@@ -97,7 +103,7 @@ where
 
 struct DeviceSyncTask<A>
 where
-  A: ToSocketAddrs + Send + Sync + 'static,
+  A: ToNutAddr + Debug + Display + Send + Sync + 'static,
 {
   client: NutPoolClient<A>,
   state: Arc<RwLock<ServerState>>,
@@ -106,7 +112,7 @@ where
 
 impl<A> DeviceSyncTask<A>
 where
-  A: ToSocketAddrs + Send + Sync + 'static,
+  A: ToNutAddr + Debug + Display + Send + Sync + 'static,
 {
   pub async fn next(&self) -> Result<(), SyncTaskError> {
     let remote_details = try_join!(
@@ -124,6 +130,9 @@ where
           write_lock.remote_state.status = DaemonStatus::Dead;
           write_lock.remote_state.prot_ver = None;
           write_lock.remote_state.ver = None;
+          write_lock.remote_state.endpoint = None;
+          write_lock.remote_state.capabilities = None;
+          self.client.reset_capabilities();
 
           error!(message = "ups daemon is disconnected", reason = %err);
 
@@ -138,6 +147,16 @@ where
       }
     }?;
 
+    // Probed once per endpoint, unknown capabilities are treated as supported.
+    let capabilities = match self.client.capabilities().await {
+      Ok(capabilities) => Some(capabilities),
+      Err(err) => {
+        debug!(message = "unable to detect upsd capabilities", reason = %err);
+        None
+      }
+    };
+    let list_client = capabilities.is_none_or(|c| c.list_client.is_available());
+
     let local_devices: HashMap<_, _> = {
       let state_lock = self.state.read().await;
       state_lock
@@ -163,7 +182,7 @@ where
 
     for device in diff.new.into_iter() {
       let client = self.client.clone();
-      task_set.spawn(Self::load_device_entry(client, device));
+      task_set.spawn(Self::load_device_entry(client, device, list_client));
     }
 
     let mut new_devices: Vec<DeviceEntry> = Vec::new();
@@ -194,6 +213,9 @@ where
         write_lock.remote_state.status = DaemonStatus::Dead;
         write_lock.remote_state.prot_ver = None;
         write_lock.remote_state.ver = None;
+        write_lock.remote_state.endpoint = None;
+        write_lock.remote_state.capabilities = None;
+        self.client.reset_capabilities();
 
         if let Err(err) = self.event_channel.send(SystemEvent::DaemonStatusUpdate {
           status: DaemonStatus::Dead,
@@ -232,6 +254,8 @@ where
         events.removed_device(device_name);
       }
 
+      write_lock.history.prune_expired(Utc::now());
+
       if write_lock.remote_state.status != DaemonStatus::Online {
         info!(message = "ups daemon is online");
 
@@ -242,6 +266,13 @@ where
       write_lock.remote_state.last_device_sync = Some(Utc::now());
       write_lock.remote_state.prot_ver = Some(prot_ver.value.into_boxed_str());
       write_lock.remote_state.ver = Some(ver.value.into_boxed_str());
+      write_lock.remote_state.endpoint = Some(self.client.active_endpoint().to_string().into());
+
+      if capabilities.is_some() && write_lock.remote_state.capabilities != capabilities {
+        info!(message = "upsd capabilities detected", capabilities = ?capabilities);
+      }
+
+      write_lock.remote_state.capabilities = capabilities;
 
       if let Err(err) = events.send(&self.event_channel) {
         warn!(message = "unable to send events", reason= %err);
@@ -254,44 +285,59 @@ where
   async fn load_device_entry(
     client: NutPoolClient<A>,
     device: UpsDevice,
+    list_client: bool,
   ) -> Result<DeviceEntry, DeviceLoadError> {
     let UpsDevice { ups_name, desc } = device;
 
-    let (clients, commands, rw_vars, vars) = join!(
-      client.list_client(&ups_name),
-      client.list_cmd(&ups_name),
-      client.list_rw(&ups_name),
-      client.list_var(&ups_name),
-    );
+    // Lists are parsed leniently, so a malformed line from a buggy driver is reported as a
+    // warning instead of dropping the whole device.
+    let mut batch = Batch::new();
+    let clients = list_client.then(|| batch.list_client(&ups_name));
+    let commands = batch.list_cmd_lenient(&ups_name);
+    let rw_vars = batch.list_rw_lenient(&ups_name);
+    let vars = batch.list_var_lenient(&ups_name);
+
+    let mut responses = client.send_batch(&batch).await.map_load_err(&ups_name)?;
+    let mut parse_warnings = ParseWarnings::default();
+
+    let vars = responses.take(vars).map_load_err(&ups_name)?;
+    update_warnings(&ups_name, &mut parse_warnings.variables, vars.diagnostics);
 
-    let variables = vars.map_load_err(&ups_name)?.variables;
+    let variables = vars.response.variables;
     let status = match variables.get(VarName::UPS_STATUS) {
-      Some(value) => UpsStatus::from(value),
-      _ => UpsStatus::default(),
+      Some(value) => UpsStatusInfo::from(value),
+      _ => UpsStatusInfo::default(),
+    };
+    let attached = match clients {
+      Some(clients) => responses.take(clients).map_load_err(&ups_name)?.ips,
+      None => Vec::new(),
     };
-    let attached = clients.map_load_err(&ups_name)?.ips;
-    let commands = commands.map_load_err(&ups_name)?;
-
-    let rw_vars = join_all(
-      rw_vars
-        .map_load_err(&ups_name)?
-        .variables
-        .into_iter()
-        .map(|(var_name, _)| Self::load_var_detail(client.clone(), &ups_name, var_name)),
-    )
-    .await;
 
-    let mut rw_variables = HashMap::with_capacity(rw_vars.len());
+    let commands = responses.take(commands).map_load_err(&ups_name)?;
+    update_warnings(
+      &ups_name,
+      &mut parse_warnings.commands,
+      commands.diagnostics,
+    );
 
-    for result in rw_vars {
-      match result {
-        Ok((var_name, detail)) => {
-          _ = rw_variables.insert(var_name, detail);
-        }
-        Err(err) => {
-          warn!(message = "failed to get RW variable type details, variable will be displayed as read-only", device = %err.name, reason = %err.inner );
-        }
-      };
+    let commands = commands
+      .response
+      .cmds
+      .into_iter()
+      .map(|cmd| cmd.into_boxed_str().into())
+      .collect();
+
+    let rw_vars = responses.take(rw_vars).map_load_err(&ups_name)?;
+    let VarDetails {
+      details: rw_variables,
+      diagnostics: mut rw_diagnostics,
+      failed,
+    } = load_var_details(&client, &ups_name, rw_vars.response).await;
+    rw_diagnostics.extend(rw_vars.diagnostics);
+    update_warnings(&ups_name, &mut parse_warnings.rw_variables, rw_diagnostics);
+
+    for (var_name, err) in failed {
+      warn!(message = "failed to get RW variable type details, variable will be displayed as read-only", device = %err.name, var_name = %var_name, reason = %err.inner);
     }
 
     let entry = DeviceEntry {
@@ -300,6 +346,7 @@ where
       desc,
       last_modified: Utc::now(),
       name: ups_name,
+      parse_warnings,
       rw_variables,
       status,
       variables,
@@ -307,66 +354,70 @@ where
 
     Ok(entry)
   }
+}
 
-  async fn load_var_detail(
-    client: NutPoolClient<A>,
-    ups_name: &UpsName,
-    var_name: VarName,
-  ) -> Result<(VarName, VarDetail), DeviceLoadError> {
-    let type_info = client
-      .get_var_type(ups_name, &var_name)
-      .await
-      .map_load_err(ups_name)?;
-
-    for var_type in type_info.var_types {
-      match var_type {
-        VarType::ReadWrite => continue,
-        VarType::Enum => {
-          let enum_list = client
-            .list_enum(ups_name, &var_name)
-            .await
-            .map_load_err(ups_name)?;
-
-          if enum_list.values.is_empty() {
-            warn!(message = "nut driver reports variable type as enum, but it does not provide any enum option", var_name = %var_name, device = %ups_name);
-          }
-
-          return Ok((
-            enum_list.name,
-            VarDetail::Enum {
-              options: enum_list.values,
-            },
-          ));
-        }
-        VarType::Range => {
-          let mut range_list = client
-            .list_range(ups_name, &var_name)
-            .await
-            .map_load_err(ups_name)?;
-
-          return match range_list.ranges.pop() {
-            Some((min, max)) => Ok((range_list.name, VarDetail::Range { min, max })),
-            None => {
-              warn!(message = "nut driver reports variable type as range, but it does not provide any range information", var_name = %var_name, device = %ups_name);
-              Ok((
-                range_list.name,
-                VarDetail::Range {
-                  min: Value::from(i64::MIN),
-                  max: Value::from(i64::MAX),
-                },
-              ))
-            }
-          };
-        }
-        VarType::String { max_len } => {
-          return Ok((var_name, VarDetail::String { max_len }));
-        }
-        VarType::Number => {
-          return Ok((var_name, VarDetail::Number));
-        }
-      }
+/// Replaces warnings of a device list, and logs the lines which were not reported before.
+pub(super) fn update_warnings(
+  ups_name: &UpsName,
+  warnings: &mut Vec<ParseWarning>,
+  diagnostics: Vec<ParseDiagnostic>,
+) {
+  let new_warnings: Vec<ParseWarning> = diagnostics.into_iter().map(ParseWarning::from).collect();
+
+  for warning in new_warnings.iter() {
+    if !warnings.contains(warning) {
+      warn!(message = "skipped malformed line in upsd response", device = %ups_name, line = %warning.text, reason = %warning.reason);
     }
+  }
+
+  *warnings = new_warnings;
+}
+
+/// Type details of the writable variables reported by `LIST RW`.
+pub(super) struct VarDetails {
+  pub details: HashMap<VarName, RwDetail>,
+
+  /// Malformed `LIST ENUM` lines.
+  pub diagnostics: Vec<ParseDiagnostic>,
 
-    Ok((var_name, VarDetail::String { max_len: 64 }))
+  /// Variables with failed type queries, which are not included in `details`.
+  pub failed: Vec<(VarName, DeviceLoadError)>,
+}
+
+/// Loads type details of the writable variables reported by `LIST RW`. Failed variables are
+/// reported separately, callers decide whether a partial result is usable.
+pub(super) async fn load_var_details<A>(
+  client: &NutPoolClient<A>,
+  ups_name: &UpsName,
+  rw_vars: RwList,
+) -> VarDetails
+where
+  A: ToNutAddr + Debug + Send + Sync + 'static,
+{
+  let rw_vars = join_all(rw_vars.variables.into_iter().map(|(var_name, _)| async {
+    let result = client
+      .get_rw_detail(ups_name, &var_name)
+      .await
+      .map_load_err(ups_name);
+    (var_name, result)
+  }))
+  .await;
+
+  let mut result = VarDetails {
+    details: HashMap::with_capacity(rw_vars.len()),
+    diagnostics: Vec::new(),
+    failed: Vec::new(),
+  };
+
+  for (var_name, detail) in rw_vars {
+    match detail {
+      Ok(detail) => {
+        _ = result.details.insert(var_name, detail.response);
+        result.diagnostics.extend(detail.diagnostics);
+      }
+      Err(err) => result.failed.push((var_name, err)),
+    };
   }
+
+  result
 }
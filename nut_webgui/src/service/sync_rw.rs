@@ -0,0 +1,361 @@
+use super::{
+  BackgroundService,
+  error::{DeviceLoadError, IntoLoadError},
+  sync_device::{VarDetails, load_var_details, update_warnings},
+};
+use crate::{
+  diff_utils::Diff,
+  event::{DeviceRwChange, EventChannel, SystemEvent},
+  state::ServerState,
+};
+use futures::future::join_all;
+use nut_webgui_upsmc::{
+  RwDetail, UpsName, VarName,
+  clients::{Batch, NutPoolClient, ToNutAddr},
+  errors::ParseDiagnostic,
+  variables::UpsVariables,
+};
+use std::{
+  collections::HashMap,
+  fmt::Debug,
+  hash::{DefaultHasher, Hash, Hasher},
+  sync::Arc,
+  time::Duration,
+};
+use tokio::{
+  select,
+  sync::{RwLock, broadcast::error::RecvError},
+  time::{MissedTickBehavior, interval},
+};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+/// Variables identifying the driver and the device firmware. A change on any of them triggers a
+/// refresh outside of the regular interval.
+const IDENTITY_VARS: [VarName; 7] = [
+  VarName::DRIVER_NAME,
+  VarName::DRIVER_VERSION,
+  VarName::DRIVER_VERSION_INTERNAL,
+  VarName::UPS_FIRMWARE,
+  VarName::UPS_FIRMWARE_AUX,
+  VarName::UPS_MODEL,
+  VarName::DEVICE_MODEL,
+];
+
+/// Refreshes writable variable details and instant command lists, which are otherwise loaded
+/// only once when a device first appears. Devices are refreshed on the configured interval, and
+/// when their driver or firmware identity changes.
+pub struct RwSyncService<A>
+where
+  A: ToNutAddr + Debug + Send + Sync + 'static,
+{
+  client: NutPoolClient<A>,
+  event_channel: EventChannel,
+  state: Arc<RwLock<ServerState>>,
+  refresh_interval: Option<Duration>,
+}
+
+impl<A> RwSyncService<A>
+where
+  A: ToNutAddr + Debug + Send + Sync + 'static,
+{
+  pub fn new(
+    client: NutPoolClient<A>,
+    event_channel: EventChannel,
+    state: Arc<RwLock<ServerState>>,
+    refresh_interval: Option<Duration>,
+  ) -> Self {
+    Self {
+      client,
+      event_channel,
+      state,
+      refresh_interval,
+    }
+  }
+}
+
+impl<A> BackgroundService for RwSyncService<A>
+where
+  A: ToNutAddr + Debug + Send + Sync + 'static,
+{
+  fn run(
+    &self,
+    token: CancellationToken,
+  ) -> core::pin::Pin<Box<dyn core::future::Future<Output = ()> + Send + Sync + 'static>> {
+    let mut events = self.event_channel.subscribe();
+    let client = self.client.clone();
+    let event_channel = self.event_channel.clone();
+    let state = self.state.clone();
+    let refresh_interval = self.refresh_interval;
+
+    Box::pin(async move {
+      let mut task = RwSyncTask {
+        client,
+        event_channel,
+        state,
+        fingerprints: HashMap::new(),
+      };
+
+      // Without a refresh interval, only identity changes trigger a refresh. The interval is
+      // still created with a placeholder period, but its branch is disabled.
+      let mut interval = interval(refresh_interval.unwrap_or(Duration::from_secs(60)));
+      interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+      // First tick completes immediately, device details are already fresh on startup.
+      interval.tick().await;
+
+      'MAIN: loop {
+        select! {
+          _ = interval.tick(), if refresh_interval.is_some() => {
+            debug!(message = "starting writable variable refresh");
+
+            let devices = task.snapshot_device_names().await;
+            task.next(devices).await;
+          }
+          event = events.recv() => {
+            match event {
+              Ok(SystemEvent::DeviceUpdate { devices }) => {
+                let devices = task.identity_changed(devices).await;
+
+                if !devices.is_empty() {
+                  task.next(devices).await;
+                }
+              }
+              Ok(SystemEvent::DeviceAddition { devices } | SystemEvent::DeviceRemoval { devices }) => {
+                // Added devices are loaded with fresh details, their fingerprint is taken on the
+                // next update.
+                for device in devices.iter() {
+                  _ = task.fingerprints.remove(device);
+                }
+              }
+              Ok(_) => continue,
+              Err(RecvError::Closed) => break 'MAIN,
+              Err(RecvError::Lagged(lagged)) => {
+                warn!(message = "writable variable refresh service can't keep up with system events", lagged_event_count=lagged)
+              }
+            }
+          }
+          _ = token.cancelled() => { break 'MAIN; }
+        }
+      }
+
+      debug!(message = "writable variable refresh task stopped");
+    })
+  }
+}
+
+struct RwSyncTask<A>
+where
+  A: ToNutAddr + Debug + Send + Sync + 'static,
+{
+  client: NutPoolClient<A>,
+  event_channel: EventChannel,
+  state: Arc<RwLock<ServerState>>,
+  fingerprints: HashMap<UpsName, u64>,
+}
+
+struct RwLoadResult {
+  device: UpsName,
+  rw_variables: HashMap<VarName, RwDetail>,
+  rw_diagnostics: Vec<ParseDiagnostic>,
+  commands: Vec<String>,
+  cmd_diagnostics: Vec<ParseDiagnostic>,
+}
+
+impl<A> RwSyncTask<A>
+where
+  A: ToNutAddr + Debug + Send + Sync + 'static,
+{
+  async fn snapshot_device_names(&self) -> Vec<UpsName> {
+    let read_lock = self.state.read().await;
+    read_lock.devices.keys().cloned().collect()
+  }
+
+  /// Returns devices whose identity fingerprint differs from the last known one. Devices seen for
+  /// the first time only record their fingerprint.
+  async fn identity_changed(&mut self, devices: Vec<UpsName>) -> Vec<UpsName> {
+    let read_lock = self.state.read().await;
+    let mut changed = Vec::new();
+
+    for device in devices {
+      let Some(entry) = read_lock.devices.get(&device) else {
+        continue;
+      };
+
+      let fingerprint = identity_fingerprint(&entry.variables);
+
+      match self.fingerprints.get(&device) {
+        Some(last) if *last != fingerprint => changed.push(device),
+        Some(_) => {}
+        None => _ = self.fingerprints.insert(device, fingerprint),
+      }
+    }
+
+    changed
+  }
+
+  async fn load(&self, device: UpsName) -> Result<RwLoadResult, DeviceLoadError> {
+    let mut batch = Batch::new();
+    let rw_vars = batch.list_rw_lenient(&device);
+    let commands = batch.list_cmd_lenient(&device);
+
+    let mut responses = self.client.send_batch(&batch).await.map_load_err(&device)?;
+
+    let rw_vars = responses.take(rw_vars).map_load_err(&device)?;
+    let commands = responses.take(commands).map_load_err(&device)?;
+    let VarDetails {
+      details: rw_variables,
+      diagnostics: mut rw_diagnostics,
+      failed,
+    } = load_var_details(&self.client, &device, rw_vars.response).await;
+
+    // A partial result would drop the failed variables and display them as read-only, so the
+    // whole refresh is abandoned and the previous details are kept.
+    if let Some((var_name, err)) = failed.into_iter().next() {
+      debug!(message = "failed to get RW variable type details", device = %device, var_name = %var_name, reason = %err.inner);
+      return Err(err);
+    }
+
+    rw_diagnostics.extend(rw_vars.diagnostics);
+
+    Ok(RwLoadResult {
+      device,
+      rw_variables,
+      rw_diagnostics,
+      commands: commands
+        .response
+        .cmds
+        .into_iter()
+        .map(|cmd| cmd.into_boxed_str().into())
+        .collect(),
+      cmd_diagnostics: commands.diagnostics,
+    })
+  }
+
+  pub async fn next(&mut self, devices: Vec<UpsName>) {
+    let results = join_all(devices.into_iter().map(|device| self.load(device))).await;
+    let mut changes: Vec<DeviceRwChange> = Vec::new();
+
+    {
+      let mut write_lock = self.state.write().await;
+
+      for result in results {
+        match result {
+          Ok(RwLoadResult {
+            device,
+            rw_variables,
+            rw_diagnostics,
+            commands,
+            cmd_diagnostics,
+          }) => {
+            if let Some(entry) = write_lock.devices.get_mut(&device) {
+              self
+                .fingerprints
+                .insert(device.clone(), identity_fingerprint(&entry.variables));
+
+              let warnings = &mut entry.parse_warnings;
+              update_warnings(&device, &mut warnings.rw_variables, rw_diagnostics);
+              update_warnings(&device, &mut warnings.commands, cmd_diagnostics);
+
+              let rw_diff = (&entry.rw_variables).into_diff(&rw_variables);
+              let cmd_diff = entry.commands.as_slice().into_diff(commands.as_slice());
+
+              if rw_diff.is_empty() && cmd_diff.added.is_empty() && cmd_diff.removed.is_empty() {
+                continue;
+              }
+
+              info!(message = "device writable variables or commands changed", device = %device);
+
+              entry.rw_variables = rw_variables;
+              entry.commands = commands;
+
+              changes.push(DeviceRwChange {
+                name: device,
+                rw_added: rw_diff.added,
+                rw_removed: rw_diff.removed,
+                rw_modified: rw_diff.modified,
+                cmds_added: cmd_diff.added,
+                cmds_removed: cmd_diff.removed,
+              });
+            }
+          }
+          Err(err) => {
+            debug!(message = "failed to refresh writable variables", device = %err.name, reason = %err.inner)
+          }
+        }
+      }
+    }
+
+    if !changes.is_empty() {
+      let send_result = self
+        .event_channel
+        .send(SystemEvent::DeviceRwChange { changes });
+
+      if let Err(err) = send_result {
+        warn!(message = "cannot write new system events to channel", reason = %err);
+      }
+    }
+  }
+}
+
+/// Hashes the identity variables of a device. Missing variables are hashed as absent, so a
+/// driver starting to report one also changes the fingerprint.
+fn identity_fingerprint(variables: &UpsVariables) -> u64 {
+  let mut hasher = DefaultHasher::new();
+
+  for name in IDENTITY_VARS.iter() {
+    variables.get(name).map(|v| v.as_str()).hash(&mut hasher);
+  }
+
+  hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::identity_fingerprint;
+  use nut_webgui_upsmc::{Value, VarName, variables::UpsVariables};
+
+  fn variables(vars: &[(VarName, &str)]) -> UpsVariables {
+    let mut variables = UpsVariables::new();
+
+    for (name, value) in vars {
+      _ = variables.insert(name.clone(), Value::from(*value));
+    }
+
+    variables
+  }
+
+  #[test]
+  fn fingerprint_ignores_non_identity_variables() {
+    let mut vars = variables(&[
+      (VarName::DRIVER_NAME, "usbhid-ups"),
+      (VarName::UPS_FIRMWARE, "1.0"),
+    ]);
+    let fingerprint = identity_fingerprint(&vars);
+
+    _ = vars.insert(VarName::BATTERY_CHARGE, Value::from(55));
+    _ = vars.insert(VarName::UPS_STATUS, Value::from("OB"));
+
+    assert_eq!(identity_fingerprint(&vars), fingerprint);
+  }
+
+  #[test]
+  fn fingerprint_changes_with_identity_variables() {
+    let vars = variables(&[
+      (VarName::DRIVER_NAME, "usbhid-ups"),
+      (VarName::UPS_FIRMWARE, "1.0"),
+    ]);
+    let upgraded = variables(&[
+      (VarName::DRIVER_NAME, "usbhid-ups"),
+      (VarName::UPS_FIRMWARE, "1.1"),
+    ]);
+    let extended = variables(&[
+      (VarName::DRIVER_NAME, "usbhid-ups"),
+      (VarName::UPS_FIRMWARE, "1.0"),
+      (VarName::DRIVER_VERSION, "2.8.2"),
+    ]);
+
+    assert_ne!(identity_fingerprint(&vars), identity_fingerprint(&upgraded));
+    assert_ne!(identity_fingerprint(&vars), identity_fingerprint(&extended));
+  }
+}
@@ -1,4 +1,4 @@
-use super::BackgroundService;
+use super::{BackgroundService, sync_device::update_warnings};
 use crate::{
   diff_utils::Diff,
   event::{DeviceStatusChange, EventBatch, EventChannel, SystemEvent},
@@ -8,10 +8,12 @@ use chrono::Utc;
 use futures::future::join_all;
 use nut_webgui_upsmc::{
   UpsName, VarName,
-  clients::{AsyncNutClient, NutPoolClient},
-  ups_status::UpsStatus,
+  clients::{AsyncNutClient, Batch, NutPoolClient, ToNutAddr},
+  errors::ParseDiagnostic,
+  ups_status::UpsStatusInfo,
+  variables::UpsVariables,
 };
-use std::{net::ToSocketAddrs, sync::Arc, time::Duration};
+use std::{collections::HashSet, fmt::Debug, sync::Arc, time::Duration};
 use tokio::{
   join, select,
   sync::RwLock,
@@ -22,7 +24,7 @@ use tracing::{debug, info, warn};
 
 pub struct StatusSyncService<A>
 where
-  A: ToSocketAddrs + Send + Sync + 'static,
+  A: ToNutAddr + Debug + Send + Sync + 'static,
 {
   client: NutPoolClient<A>,
   event_channel: EventChannel,
@@ -33,7 +35,7 @@ where
 
 impl<A> StatusSyncService<A>
 where
-  A: ToSocketAddrs + Send + Sync + 'static,
+  A: ToNutAddr + Debug + Send + Sync + 'static,
 {
   pub fn new(
     client: NutPoolClient<A>,
@@ -54,7 +56,7 @@ where
 
 impl<A> BackgroundService for StatusSyncService<A>
 where
-  A: ToSocketAddrs + Send + Sync + 'static,
+  A: ToNutAddr + Debug + Send + Sync + 'static,
 {
   fn run(
     &self,
@@ -73,10 +75,11 @@ where
     };
 
     Box::pin(async move {
-      let task = StatusSyncTask {
+      let mut task = StatusSyncTask {
         client,
         event_channel,
         state,
+        buffers: Vec::new(),
       };
 
       let mut interval = UpsPollInterval::new(poll_interval, poll_freq);
@@ -117,16 +120,19 @@ where
 
 struct StatusSyncTask<A>
 where
-  A: ToSocketAddrs + Send + Sync + 'static,
+  A: ToNutAddr + Debug + Send + Sync + 'static,
 {
   client: NutPoolClient<A>,
   state: Arc<RwLock<ServerState>>,
   event_channel: EventChannel,
+
+  /// Per device receive buffers of [StatusSyncTask::state_sync], kept across syncs.
+  buffers: Vec<RawVariables>,
 }
 
 impl<A> StatusSyncTask<A>
 where
-  A: ToSocketAddrs + Send + Sync + 'static,
+  A: ToNutAddr + Debug + Send + Sync + 'static,
 {
   async fn snapshot_device_names(&self) -> Vec<UpsName> {
     let read_lock = self.state.read().await;
@@ -157,10 +163,11 @@ where
         match result {
           Ok(variable) => {
             if let Some(entry) = write_lock.devices.get_mut(&variable.ups_name) {
-              let old_status = entry.status;
-              let new_status = UpsStatus::from(&variable.value);
+              let new_info = UpsStatusInfo::from(&variable.value);
+              let old_status = entry.status.flags();
+              let new_status = new_info.flags();
 
-              entry.status = new_status;
+              entry.status = new_info;
               entry.variables.insert(variable.name, variable.value);
               entry.last_modified = Utc::now();
 
@@ -191,7 +198,7 @@ where
     }
   }
 
-  pub async fn state_sync(&self) {
+  pub async fn state_sync(&mut self) {
     let devices = self.snapshot_device_names().await;
 
     if devices.is_empty() {
@@ -199,73 +206,140 @@ where
       return;
     }
 
-    let responses = join_all(devices.iter().map(|device| async move {
-      let (variables, clients, commands) = join!(
-        self.client.list_var(device),
-        self.client.list_client(device),
-        self.client.list_cmd(device)
-      );
+    // Attached clients are skipped on daemons without `LIST CLIENT` support.
+    let list_client = self
+      .client
+      .cached_capabilities()
+      .is_none_or(|c| c.list_client.is_available());
+
+    // Requests for all devices are pipelined, variables with a single streamed request and
+    // attached clients with a single batch.
+    let mut batch = Batch::new();
+    let client_requests: Vec<_> = devices
+      .iter()
+      .map(|device| list_client.then(|| batch.list_client(device)))
+      .collect();
+
+    self
+      .buffers
+      .resize_with(devices.len(), RawVariables::default);
+    self.buffers.iter_mut().for_each(RawVariables::clear);
+
+    let buffers = &mut self.buffers;
+    let (variables, mut batch_responses) = join!(
+      self.client.list_vars_streamed(&devices, |index, item| {
+        let buffer = &mut buffers[index];
+
+        match item {
+          Ok(var) => buffer.push(var.name, &var.value),
+          Err(diagnostic) => buffer.push_diagnostic(diagnostic),
+        }
+      }),
+      async {
+        if batch.is_empty() {
+          None
+        } else {
+          match self.client.send_batch(&batch).await {
+            Ok(batch_responses) => Some(batch_responses),
+            Err(err) => {
+              debug!(message = "failed to read ups attached clients", reason = %err);
+              None
+            }
+          }
+        }
+      }
+    );
 
-      (device, variables, clients, commands)
-    }))
-    .await;
+    let variables = match variables {
+      Ok(variables) => variables,
+      Err(err) => {
+        debug!(message = "failed to read ups variables", reason = %err);
+        return;
+      }
+    };
+
+    let responses: Vec<_> = devices
+      .iter()
+      .zip(variables)
+      .zip(client_requests)
+      .zip(self.buffers.iter_mut())
+      .map(|(((device, count), clients), buffer)| {
+        let clients = match (clients, batch_responses.as_mut()) {
+          (Some(clients), Some(batch_responses)) => batch_responses.take(clients).map(Some),
+          _ => Ok(None),
+        };
+
+        (device, count.map(|count| (count, buffer)), clients)
+      })
+      .collect();
 
     let mut events = EventBatch::new();
 
     {
       let mut write_lock = self.state.write().await;
+      let state = &mut *write_lock;
 
       for result in responses {
         match result {
-          (device, Ok(var_list), Ok(clients), Ok(commands)) => {
-            if let Some(entry) = write_lock.devices.get_mut(device) {
-              if let Some(status_value) = var_list.variables.get(VarName::UPS_STATUS) {
-                let new_status = UpsStatus::from(status_value);
-                let old_status = entry.status;
+          (device, Ok((count, buffer)), Ok(clients)) => {
+            if let Some(entry) = state.devices.get_mut(device) {
+              update_warnings(
+                device,
+                &mut entry.parse_warnings.variables,
+                std::mem::take(&mut buffer.diagnostics),
+              );
 
-                if old_status != new_status {
-                  entry.status = new_status;
+              buffer.apply(&mut entry.variables, count);
+
+              if let Some(status_value) = entry.variables.get(VarName::UPS_STATUS) {
+                let new_info = UpsStatusInfo::from(status_value);
+                let old_status = entry.status.flags();
+                let new_status = new_info.flags();
 
-                  events.status_change(var_list.ups_name, old_status, new_status);
+                entry.status = new_info;
+
+                if old_status != new_status {
+                  events.status_change(device.clone(), old_status, new_status);
                 }
               }
 
-              let client_diff = entry.attached.as_slice().into_diff(&clients.ips);
+              if let Some(clients) = clients {
+                let client_diff = entry.attached.as_slice().into_diff(&clients.ips);
+
+                if !client_diff.connected.is_empty() {
+                  for client in client_diff.connected.iter() {
+                    info!(message = "new client attached to ups", device = %device, client = %client)
+                  }
 
-              if !client_diff.connected.is_empty() {
-                for client in client_diff.connected.iter() {
-                  info!(message = "new client attached to ups", device = %device, client = %client)
+                  events.client_connection(device.clone(), client_diff.connected);
                 }
 
-                events.client_connection(device.clone(), client_diff.connected);
-              }
+                if !client_diff.disconnected.is_empty() {
+                  for client in client_diff.disconnected.iter() {
+                    info!(message = "client detached from ups", device = %device, client = %client)
+                  }
 
-              if !client_diff.disconnected.is_empty() {
-                for client in client_diff.disconnected.iter() {
-                  info!(message = "client detached from ups", device = %device, client = %client)
+                  events.client_disconnect(device.clone(), client_diff.disconnected);
                 }
 
-                events.client_disconnect(device.clone(), client_diff.disconnected);
+                entry.attached = clients.ips;
               }
 
-              entry.variables = var_list.variables;
-              entry.attached = clients.ips;
-              entry.commands = commands;
               entry.last_modified = Utc::now();
+              state
+                .history
+                .record(device, &entry.variables, entry.last_modified);
 
-              events.updated_device(clients.ups_name);
+              events.updated_device(device.clone());
             }
           }
-          (device, vars_results, clients_result, cmds_result) => {
+          (device, vars_results, clients_result) => {
             if let Err(err) = vars_results {
               debug!(message = "failed to read ups variables", device = %device, reason = %err)
             }
             if let Err(err) = clients_result {
               debug!(message = "failed to read ups attached clients", device = %device, reason = %err)
             }
-            if let Err(err) = cmds_result {
-              debug!(message = "failed to read ups commands", device = %device, reason = %err)
-            }
           }
         }
       }
@@ -277,6 +351,64 @@ where
   }
 }
 
+/// Variables of a device as received from upsd. Names and values are appended to a single text
+/// buffer, which is reused on every sync, so streaming a response does not allocate per variable.
+#[derive(Debug, Default)]
+struct RawVariables {
+  text: String,
+
+  /// Name start, value start and value end of each variable in `text`.
+  spans: Vec<(usize, usize, usize)>,
+  diagnostics: Vec<ParseDiagnostic>,
+}
+
+impl RawVariables {
+  fn clear(&mut self) {
+    self.text.clear();
+    self.spans.clear();
+    self.diagnostics.clear();
+  }
+
+  fn push(&mut self, name: &str, value: &str) {
+    let name_start = self.text.len();
+    self.text.push_str(name);
+
+    let value_start = self.text.len();
+    self.text.push_str(value);
+
+    self.spans.push((name_start, value_start, self.text.len()));
+  }
+
+  /// Pooled requests are retried after I/O errors, so the same line may be reported twice.
+  fn push_diagnostic(&mut self, diagnostic: ParseDiagnostic) {
+    if !self.diagnostics.iter().any(|d| d.line == diagnostic.line) {
+      self.diagnostics.push(diagnostic);
+    }
+  }
+
+  fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+    self.spans.iter().map(|(name_start, value_start, end)| {
+      (
+        &self.text[*name_start..*value_start],
+        &self.text[*value_start..*end],
+      )
+    })
+  }
+
+  /// Updates `variables` in place, only new and changed values are allocated. `count` is the
+  /// number of variables in the response, variables missing from it are removed.
+  fn apply(&self, variables: &mut UpsVariables, count: usize) {
+    for (name, value) in self.iter() {
+      _ = variables.update_raw(name, value);
+    }
+
+    if variables.len() != count {
+      let received: HashSet<&str> = self.iter().map(|(name, _)| name).collect();
+      variables.retain(|name, _| received.contains(name.as_str()));
+    }
+  }
+}
+
 struct UpsPollInterval {
   interval: Interval,
   last_full_sync: Option<Instant>,
@@ -330,3 +462,50 @@ impl UpsPollInterval {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::RawVariables;
+  use nut_webgui_upsmc::{Value, VarName, variables::UpsVariables};
+
+  #[test]
+  fn raw_variables_update_in_place() {
+    let mut variables = UpsVariables::new();
+    _ = variables.insert(VarName::UPS_STATUS, Value::from("OL"));
+    _ = variables.insert(VarName::BATTERY_CHARGE, Value::from(100));
+    _ = variables.insert(VarName::UPS_LOAD, Value::from(20));
+
+    let mut raw = RawVariables::default();
+    raw.push("ups.status", "OB");
+    raw.push("battery.charge", "100");
+    raw.apply(&mut variables, 2);
+
+    assert_eq!(variables.len(), 2);
+    assert_eq!(variables.get(VarName::UPS_STATUS), Some(&Value::from("OB")));
+    assert_eq!(
+      variables.get(VarName::BATTERY_CHARGE),
+      Some(&Value::from(100))
+    );
+    assert!(!variables.contains_key(VarName::UPS_LOAD));
+  }
+
+  #[test]
+  fn raw_variables_retried_response() {
+    let mut variables = UpsVariables::new();
+    _ = variables.insert(VarName::UPS_LOAD, Value::from(20));
+
+    // Items of a failed first attempt are followed by the complete retried response.
+    let mut raw = RawVariables::default();
+    raw.push("ups.status", "OL");
+    raw.push("ups.status", "OB");
+    raw.push("battery.charge", "90");
+    raw.apply(&mut variables, 2);
+
+    assert_eq!(variables.len(), 2);
+    assert_eq!(variables.get(VarName::UPS_STATUS), Some(&Value::from("OB")));
+    assert!(!variables.contains_key(VarName::UPS_LOAD));
+
+    raw.clear();
+    assert_eq!(raw.iter().count(), 0);
+  }
+}
@@ -1,9 +1,14 @@
-use crate::device_entry::DeviceEntry;
+use crate::{device_entry::DeviceEntry, event_log::EventLog, history::HistoryStore};
 use chrono::{DateTime, Utc};
-use nut_webgui_upsmc::{CmdName, UpsName, VarName};
+use nut_webgui_upsmc::{
+  CmdName, UpsName, Value, VarName, clients::Capabilities, responses::TrackingStatus,
+};
 use serde::Serialize;
 use std::{borrow::Borrow, collections::HashMap};
 
+/// Tracked requests older than this are dropped, same as upsd's default `TRACKINGDELAY`.
+const TRACKING_RETENTION: chrono::TimeDelta = chrono::TimeDelta::hours(1);
+
 #[derive(Debug)]
 pub struct ServerState {
   /// Ups devices
@@ -14,6 +19,85 @@ pub struct ServerState {
 
   /// Shared description table for ups variables
   pub shared_desc: HashMap<DescriptionKey, Box<str>>,
+
+  /// Tracked INSTCMD and SET VAR requests, keyed by upsd tracking id
+  pub tracking: HashMap<Box<str>, TrackingEntry>,
+
+  /// Downsampled history of numeric device variables
+  pub history: HistoryStore,
+
+  /// Recent system events in human readable form
+  pub events: EventLog,
+}
+
+impl ServerState {
+  /// Registers a new tracked request, and drops the expired ones.
+  pub fn insert_tracking(&mut self, entry: TrackingEntry) {
+    let now = Utc::now();
+
+    self
+      .tracking
+      .retain(|_, v| now - v.created < TRACKING_RETENTION);
+    self.tracking.insert(entry.id.clone(), entry);
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrackingEntry {
+  pub id: Box<str>,
+  pub ups_name: UpsName,
+  pub action: TrackedAction,
+  #[serde(flatten)]
+  pub status: TrackingStatus,
+  pub created: DateTime<Utc>,
+  pub last_modified: DateTime<Utc>,
+}
+
+impl TrackingEntry {
+  pub fn new(id: Box<str>, ups_name: UpsName, action: TrackedAction) -> Self {
+    let now = Utc::now();
+
+    Self {
+      id,
+      ups_name,
+      action,
+      status: TrackingStatus::Pending,
+      created: now,
+      last_modified: now,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TrackedAction {
+  Instcmd {
+    instcmd: CmdName,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<Value>,
+  },
+  SetVar {
+    variable: VarName,
+    value: Value,
+  },
+}
+
+impl std::fmt::Display for TrackedAction {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      TrackedAction::Instcmd {
+        instcmd,
+        value: None,
+      } => f.write_fmt(format_args!("'{}'", instcmd)),
+      TrackedAction::Instcmd {
+        instcmd,
+        value: Some(value),
+      } => f.write_fmt(format_args!("'{} {}'", instcmd, value)),
+      TrackedAction::SetVar { variable, value } => {
+        f.write_fmt(format_args!("'{}' = '{}'", variable, value))
+      }
+    }
+  }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
@@ -39,6 +123,10 @@ pub struct DaemonState {
   pub status: DaemonStatus,
   pub prot_ver: Option<Box<str>>,
   pub ver: Option<Box<str>>,
+  /// Active upsd endpoint during the last successful sync
+  pub endpoint: Option<Box<str>>,
+  /// Optional commands supported by the active upsd endpoint
+  pub capabilities: Option<Capabilities>,
 }
 
 impl DaemonState {
@@ -48,6 +136,8 @@ impl DaemonState {
       status: DaemonStatus::NotReady,
       ver: None,
       prot_ver: None,
+      endpoint: None,
+      capabilities: None,
     }
   }
 }
@@ -0,0 +1,317 @@
+use std::{
+  fs::{File, OpenOptions},
+  io::{self, Read, Write},
+  path::{Path, PathBuf},
+};
+use tracing::warn;
+
+/// Journal file name of the persistent metric history.
+pub const HISTORY_JOURNAL: &str = "history.journal";
+
+/// Journal file name of the persistent event log.
+pub const EVENT_JOURNAL: &str = "events.journal";
+
+/// Frame header: payload length and CRC-32 checksum of the payload, both little-endian.
+const FRAME_HEADER_LEN: usize = 8;
+
+/// Upper bound of a single record, larger lengths can only come from a corrupted header.
+const MAX_RECORD_LEN: usize = 64 * 1024;
+
+/// Append-only record file.
+///
+/// Each record is framed with its length and checksum. A torn or corrupted tail left by an
+/// interrupted write is detected and truncated on open, and rewrites go through a temporary file
+/// that atomically replaces the journal.
+#[derive(Debug)]
+pub struct Journal {
+  path: PathBuf,
+  file: File,
+
+  /// Length of the valid data in the file.
+  len: u64,
+}
+
+impl Journal {
+  /// Opens or creates the journal at the given path, and returns the records recovered from it.
+  pub fn open(path: PathBuf) -> io::Result<(Self, Vec<Vec<u8>>)> {
+    let mut file = OpenOptions::new()
+      .read(true)
+      .append(true)
+      .create(true)
+      .open(&path)?;
+
+    let mut buffer = Vec::new();
+    _ = file.read_to_end(&mut buffer)?;
+
+    let (records, len) = read_frames(&buffer);
+
+    if len < buffer.len() {
+      warn!(
+        message = "discarding corrupted journal tail",
+        path = %path.display(),
+        discarded_bytes = buffer.len() - len
+      );
+
+      file.set_len(len as u64)?;
+      file.sync_data()?;
+    }
+
+    let journal = Self {
+      path,
+      file,
+      len: len as u64,
+    };
+
+    Ok((journal, records))
+  }
+
+  /// Appends records and flushes them to disk.
+  pub fn append<I, R>(&mut self, records: I) -> io::Result<()>
+  where
+    I: IntoIterator<Item = R>,
+    R: AsRef<[u8]>,
+  {
+    let buffer = write_frames(records)?;
+
+    if buffer.is_empty() {
+      return Ok(());
+    }
+
+    let result = self
+      .file
+      .write_all(&buffer)
+      .and_then(|_| self.file.sync_data());
+
+    match result {
+      Ok(_) => {
+        self.len += buffer.len() as u64;
+        Ok(())
+      }
+      Err(err) => {
+        // Drops the partially written frames, so next appends are not hidden behind them.
+        _ = self.file.set_len(self.len);
+        Err(err)
+      }
+    }
+  }
+
+  /// Reads all records currently in the journal.
+  pub fn read_all(&self) -> io::Result<Vec<Vec<u8>>> {
+    let mut buffer = Vec::new();
+    _ = File::open(&self.path)?.read_to_end(&mut buffer)?;
+
+    let (records, _) = read_frames(&buffer[..buffer.len().min(self.len as usize)]);
+    Ok(records)
+  }
+
+  /// Atomically replaces the journal content with the given records.
+  pub fn rewrite<I, R>(&mut self, records: I) -> io::Result<()>
+  where
+    I: IntoIterator<Item = R>,
+    R: AsRef<[u8]>,
+  {
+    let buffer = write_frames(records)?;
+    let tmp_path = self.path.with_extension("tmp");
+
+    {
+      let mut tmp_file = File::create(&tmp_path)?;
+      tmp_file.write_all(&buffer)?;
+      tmp_file.sync_all()?;
+    }
+
+    std::fs::rename(&tmp_path, &self.path)?;
+    sync_parent_dir(&self.path)?;
+
+    self.file = OpenOptions::new()
+      .read(true)
+      .append(true)
+      .open(&self.path)?;
+    self.len = buffer.len() as u64;
+
+    Ok(())
+  }
+}
+
+fn write_frames<I, R>(records: I) -> io::Result<Vec<u8>>
+where
+  I: IntoIterator<Item = R>,
+  R: AsRef<[u8]>,
+{
+  let mut buffer = Vec::new();
+
+  for record in records {
+    let payload = record.as_ref();
+
+    if payload.len() > MAX_RECORD_LEN {
+      return Err(io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "journal record exceeds the size limit",
+      ));
+    }
+
+    buffer.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    buffer.extend_from_slice(&crc32fast::hash(payload).to_le_bytes());
+    buffer.extend_from_slice(payload);
+  }
+
+  Ok(buffer)
+}
+
+/// Returns valid records and the length of the data they occupy. Reading stops at the first
+/// incomplete or corrupted frame.
+fn read_frames(buffer: &[u8]) -> (Vec<Vec<u8>>, usize) {
+  let mut records = Vec::new();
+  let mut offset = 0;
+
+  while let Some(header) = buffer.get(offset..offset + FRAME_HEADER_LEN) {
+    let len = u32::from_le_bytes([header[0], header[1], header[2], header[3]]) as usize;
+    let checksum = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+
+    if len > MAX_RECORD_LEN {
+      break;
+    }
+
+    let start = offset + FRAME_HEADER_LEN;
+    let Some(payload) = buffer.get(start..start + len) else {
+      break;
+    };
+
+    if crc32fast::hash(payload) != checksum {
+      break;
+    }
+
+    records.push(payload.to_vec());
+    offset = start + len;
+  }
+
+  (records, offset)
+}
+
+fn sync_parent_dir(path: &Path) -> io::Result<()> {
+  match path.parent() {
+    Some(dir) if !dir.as_os_str().is_empty() => File::open(dir)?.sync_all(),
+    _ => Ok(()),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{FRAME_HEADER_LEN, Journal, MAX_RECORD_LEN, read_frames, write_frames};
+  use std::path::PathBuf;
+
+  const RECORDS: [&[u8]; 3] = [b"first", b"second", b"third"];
+
+  /// Removes the journal directory when dropped.
+  struct TempDir(PathBuf);
+
+  impl TempDir {
+    fn new(name: &str) -> Self {
+      let path = std::env::temp_dir().join(format!("nut_webgui_{}_{name}", std::process::id()));
+      std::fs::create_dir_all(&path).unwrap();
+
+      Self(path)
+    }
+  }
+
+  impl Drop for TempDir {
+    fn drop(&mut self) {
+      _ = std::fs::remove_dir_all(&self.0);
+    }
+  }
+
+  #[test]
+  fn frames_round_trip() {
+    let buffer = write_frames(RECORDS).unwrap();
+    let (records, len) = read_frames(&buffer);
+
+    assert_eq!(records, RECORDS);
+    assert_eq!(len, buffer.len());
+  }
+
+  #[test]
+  fn read_frames_stops_at_torn_tail() {
+    let buffer = write_frames(RECORDS).unwrap();
+    let valid_len = buffer.len() - FRAME_HEADER_LEN - RECORDS[2].len();
+
+    for torn_len in [valid_len + 3, buffer.len() - 1] {
+      let (records, len) = read_frames(&buffer[..torn_len]);
+
+      assert_eq!(records, &RECORDS[..2]);
+      assert_eq!(len, valid_len);
+    }
+  }
+
+  #[test]
+  fn read_frames_stops_at_checksum_mismatch() {
+    let mut buffer = write_frames(RECORDS).unwrap();
+    let second_payload = 2 * FRAME_HEADER_LEN + RECORDS[0].len();
+    buffer[second_payload] ^= 0xff;
+
+    let (records, len) = read_frames(&buffer);
+
+    assert_eq!(records, &RECORDS[..1]);
+    assert_eq!(len, FRAME_HEADER_LEN + RECORDS[0].len());
+  }
+
+  #[test]
+  fn oversized_length_is_rejected() {
+    let mut buffer = write_frames(&RECORDS[..1]).unwrap();
+    let valid_len = buffer.len();
+
+    // Corrupted header claiming a huge payload, followed by enough data to satisfy it.
+    buffer.extend_from_slice(&((MAX_RECORD_LEN + 1) as u32).to_le_bytes());
+    buffer.extend_from_slice(&0u32.to_le_bytes());
+    buffer.resize(buffer.len() + MAX_RECORD_LEN + 1, 0);
+
+    let (records, len) = read_frames(&buffer);
+
+    assert_eq!(records, &RECORDS[..1]);
+    assert_eq!(len, valid_len);
+    assert!(write_frames([vec![0u8; MAX_RECORD_LEN + 1]]).is_err());
+  }
+
+  #[test]
+  fn open_truncates_torn_tail() {
+    let dir = TempDir::new("open_truncates_torn_tail");
+    let path = dir.0.join("test.journal");
+
+    let buffer = write_frames(RECORDS).unwrap();
+    let valid_len = buffer.len() - FRAME_HEADER_LEN - RECORDS[2].len();
+    std::fs::write(&path, &buffer[..valid_len + 5]).unwrap();
+
+    let (mut journal, records) = Journal::open(path.clone()).unwrap();
+
+    assert_eq!(records, &RECORDS[..2]);
+    assert_eq!(std::fs::metadata(&path).unwrap().len(), valid_len as u64);
+
+    // Appended records follow the valid data, not the discarded tail.
+    journal.append([b"fourth"]).unwrap();
+
+    assert_eq!(
+      journal.read_all().unwrap(),
+      [RECORDS[0], RECORDS[1], b"fourth"]
+    );
+
+    drop(journal);
+
+    let (_, records) = Journal::open(path).unwrap();
+
+    assert_eq!(records, [RECORDS[0], RECORDS[1], b"fourth"]);
+  }
+
+  #[test]
+  fn rewrite_replaces_records() {
+    let dir = TempDir::new("rewrite_replaces_records");
+    let path = dir.0.join("test.journal");
+
+    let (mut journal, records) = Journal::open(path.clone()).unwrap();
+    assert!(records.is_empty());
+
+    journal.append(RECORDS).unwrap();
+    journal.rewrite([RECORDS[1]]).unwrap();
+    journal.append([RECORDS[2]]).unwrap();
+
+    assert_eq!(journal.read_all().unwrap(), &RECORDS[1..]);
+    assert!(!path.with_extension("tmp").exists());
+  }
+}
@@ -1,6 +1,6 @@
 import ConfirmationModal from "./confirmation_modal.js";
 
-/** @typedef {"cancel-text" | "confirm-text" | "message" | "target-event" | "title" | "value" | "name" } ConfirmationButtonAttributes */
+/** @typedef {"cancel-text" | "confirm-text" | "confirm-phrase" | "message" | "target-event" | "title" | "value" | "name" } ConfirmationButtonAttributes */
 
 export default class ConfirmationButton extends HTMLElement {
   /** @type {AbortController | undefined} */
@@ -15,6 +15,9 @@ export default class ConfirmationButton extends HTMLElement {
   /** @type {string | null | undefined} */
   #confirm_text;
 
+  /** @type {string | null | undefined} */
+  #confirm_phrase;
+
   /** @type {string | null | undefined} */
   #target_event;
 
@@ -28,6 +31,7 @@ export default class ConfirmationButton extends HTMLElement {
   static observedAttributes = [
     "cancel-text",
     "confirm-text",
+    "confirm-phrase",
     "message",
     "target-event",
     "title",
@@ -55,6 +59,7 @@ export default class ConfirmationButton extends HTMLElement {
     this.#abort_controller = new AbortController();
     this.#cancel_text = this.getAttribute("cancel-text");
     this.#confirm_text = this.getAttribute("confirm-text");
+    this.#confirm_phrase = this.getAttribute("confirm-phrase");
     this.#message = this.getAttribute("message");
     this.#target_event = this.getAttribute("target-event");
     this.#title = this.getAttribute("title");
@@ -68,6 +73,7 @@ export default class ConfirmationButton extends HTMLElement {
           title: this.#title,
           confirmText: this.#confirm_text,
           cancelText: this.#cancel_text,
+          phrase: this.#confirm_phrase,
         })
           .then((is_confirmed) => {
             if (
@@ -97,6 +103,9 @@ export default class ConfirmationButton extends HTMLElement {
       case "confirm-text":
         this.#confirm_text = new_value;
         break;
+      case "confirm-phrase":
+        this.#confirm_phrase = new_value;
+        break;
       case "message":
         this.#message = new_value;
         break;
@@ -10,6 +10,7 @@ TEMPLATE.innerHTML = `
       <p class="py-4">
         <slot></slot>
       </p>
+      <slot name="phrase"></slot>
       <div class="modal-action">
         <form class="flex flex-row gap-3" method="dialog">
           <button value="cancel" class="btn">
@@ -38,7 +39,9 @@ export default class ConfirmationModal extends HTMLElement {
 
   /**
    * Create a confirmation modal programmatically
-   * @param {{message?: string | null, title?:string | null, confirmText?:string | null, cancelText?:string | null}} options
+   * When `phrase` is set, confirm button stays disabled until the phrase is typed.
+   *
+   * @param {{message?: string | null, title?:string | null, confirmText?:string | null, cancelText?:string | null, phrase?: string | null}} options
    * @return {Promise<boolean>}
    */
   static create(options) {
@@ -62,6 +65,19 @@ export default class ConfirmationModal extends HTMLElement {
     message.textContent = options.message ?? null;
 
     modal.append(title, confirm, cancel, message);
+
+    if (options.phrase) {
+      const phrase_input = document.createElement("input");
+      phrase_input.slot = "phrase";
+      phrase_input.type = "text";
+      phrase_input.autocomplete = "off";
+      phrase_input.className = "input input-error w-full";
+      phrase_input.placeholder = `Type "${options.phrase}" to confirm`;
+
+      modal.append(phrase_input);
+      modal.requirePhrase(phrase_input, options.phrase);
+    }
+
     modal.showModal();
 
     return new Promise((resolve) => {
@@ -107,6 +123,28 @@ export default class ConfirmationModal extends HTMLElement {
   showModal() {
     this.#dialog?.showModal();
   }
+
+  /**
+   * Disables confirm button until the input value matches the phrase.
+   *
+   * @param {HTMLInputElement} input
+   * @param {string} phrase
+   */
+  requirePhrase(input, phrase) {
+    /** @type {HTMLButtonElement | null} */
+    const confirm_button = this.#shadow_root.querySelector(
+      "button[value=default]",
+    );
+
+    if (!confirm_button) {
+      return;
+    }
+
+    confirm_button.disabled = true;
+    input.addEventListener("input", () => {
+      confirm_button.disabled = input.value.trim() !== phrase;
+    });
+  }
 }
 
 customElements.define("nut-confirm", ConfirmationModal);
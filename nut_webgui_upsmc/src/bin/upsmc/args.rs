@@ -0,0 +1,106 @@
+use clap::{Parser, Subcommand, ValueEnum};
+use nut_webgui_upsmc::{CmdName, UpsName, VarName};
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Command-line client for Network UPS Tools servers", long_about = None)]
+pub struct CliArgs {
+  /// NUT server address in host:port form, or a unix domain socket path with `unix:` prefix
+  #[arg(short, long, env = "UPSMC_ADDR", default_value = "localhost:3493")]
+  pub addr: Box<str>,
+
+  /// NUT username, required for `instcmd` and `set`
+  #[arg(short, long, env = "UPSMC_USER")]
+  pub user: Option<Box<str>>,
+
+  /// NUT password, required for `instcmd` and `set`
+  #[arg(short, long, env = "UPSMC_PASS", hide_env_values = true)]
+  pub pass: Option<Box<str>>,
+
+  /// Request timeout in seconds
+  #[arg(short, long, default_value_t = 10)]
+  pub timeout: u64,
+
+  /// Records the protocol session to a file, passwords are redacted
+  #[arg(long)]
+  pub record: Option<PathBuf>,
+
+  #[command(subcommand)]
+  pub command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+  /// Lists devices and their descriptions
+  List {
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Plain)]
+    format: OutputFormat,
+  },
+
+  /// Dumps all variables of a device, or a single variable
+  Vars {
+    ups: UpsName,
+
+    var: Option<VarName>,
+
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Plain)]
+    format: OutputFormat,
+  },
+
+  /// Lists writable variables with their types and allowed values
+  Rw {
+    ups: UpsName,
+
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Plain)]
+    format: OutputFormat,
+  },
+
+  /// Lists instant commands with their descriptions, or describes a single command
+  Cmds {
+    ups: UpsName,
+
+    cmd: Option<CmdName>,
+
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Plain)]
+    format: OutputFormat,
+  },
+
+  /// Runs an instant command
+  Instcmd {
+    ups: UpsName,
+
+    cmd: CmdName,
+
+    /// Optional command argument, e.g. delay in seconds for `load.off.delay`
+    value: Option<Box<str>>,
+  },
+
+  /// Sets a writable variable after validating the value against its type
+  Set {
+    ups: UpsName,
+
+    var: VarName,
+
+    value: Box<str>,
+  },
+
+  /// Lists client addresses attached to a device
+  Clients {
+    ups: UpsName,
+
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Plain)]
+    format: OutputFormat,
+  },
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+  /// Aligned columns
+  Plain,
+
+  /// JSON document
+  Json,
+
+  /// `name: value` lines, same as `upsc` output
+  Upsc,
+}
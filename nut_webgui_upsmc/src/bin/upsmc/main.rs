@@ -0,0 +1,189 @@
+mod args;
+mod output;
+
+use self::{
+  args::{CliArgs, Command},
+  output::Output,
+};
+use clap::Parser;
+use core::time::Duration;
+use nut_webgui_upsmc::{
+  CmdName, Value,
+  clients::{AsyncNutClient, NutAddr, NutAuthClient, NutClient, NutStream, ToNutAddr},
+  errors::{Error, ErrorKind},
+  recording::{RecordingStream, SessionRecorder},
+};
+use std::process::ExitCode;
+use tokio::{
+  io::{AsyncRead, AsyncWrite},
+  time::timeout,
+};
+
+type CliResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
+  let args = CliArgs::parse();
+
+  match run(args).await {
+    Ok(_) => ExitCode::SUCCESS,
+    Err(err) => {
+      eprintln!("error: {err}");
+      ExitCode::FAILURE
+    }
+  }
+}
+
+async fn connect(addr: &str, request_timeout: Duration) -> Result<NutStream, Error> {
+  let connect = async {
+    match addr.to_nut_addr()? {
+      NutAddr::Tcp(addr) => NutStream::connect(addr.as_slice()).await,
+      #[cfg(unix)]
+      NutAddr::Unix(path) => NutStream::connect_unix(path).await,
+    }
+  };
+
+  match timeout(request_timeout, connect).await {
+    Ok(result) => result,
+    Err(_) => Err(ErrorKind::RequestTimeout.into()),
+  }
+}
+
+async fn authenticate<S>(client: NutClient<S>, args: &CliArgs) -> CliResult<NutAuthClient<S>>
+where
+  S: AsyncRead + AsyncWrite + Unpin,
+{
+  match (args.user.as_deref(), args.pass.as_deref()) {
+    (Some(user), Some(pass)) => Ok(client.authenticate(user, pass).await?),
+    _ => Err("this command requires --user and --pass, or UPSMC_USER and UPSMC_PASS".into()),
+  }
+}
+
+async fn run(args: CliArgs) -> CliResult<()> {
+  let request_timeout = Duration::from_secs(args.timeout);
+  let stream = connect(&args.addr, request_timeout).await?;
+
+  match args.record.as_ref() {
+    Some(path) => {
+      let recorder = SessionRecorder::create(path)?;
+      let stream = RecordingStream::new(stream, recorder);
+
+      exec(&args, NutClient::new(stream), request_timeout).await
+    }
+    None => exec(&args, NutClient::new(stream), request_timeout).await,
+  }
+}
+
+async fn exec<S>(
+  args: &CliArgs,
+  mut client: NutClient<S>,
+  request_timeout: Duration,
+) -> CliResult<()>
+where
+  S: AsyncRead + AsyncWrite + Unpin,
+{
+  client.set_timeout(request_timeout);
+
+  match &args.command {
+    Command::List { format } => {
+      let devices = client.list_ups().await?;
+      Output::new(*format).devices(&devices)?;
+    }
+    Command::Vars {
+      ups,
+      var: Some(var),
+      format,
+    } => {
+      let var = client.get_var(ups, var).await?;
+      Output::new(*format).variable(&var)?;
+    }
+    Command::Vars {
+      ups,
+      var: None,
+      format,
+    } => {
+      let vars = client.list_var(ups).await?;
+      Output::new(*format).variables(&vars)?;
+    }
+    Command::Rw { ups, format } => {
+      let rw_vars = client.list_rw(ups).await?;
+      let mut details = Vec::with_capacity(rw_vars.variables.len());
+
+      for (name, value) in rw_vars.variables {
+        let detail = client.get_rw_detail(ups, &name).await?.response;
+        let desc = client.get_var_desc(ups, &name).await.map(|v| v.desc).ok();
+
+        details.push(output::RwEntry {
+          name,
+          value,
+          desc,
+          detail,
+        });
+      }
+
+      details.sort_by(|a, b| a.name.as_ref().cmp(b.name.as_ref()));
+      Output::new(*format).rw_variables(ups, &details)?;
+    }
+    Command::Cmds {
+      ups,
+      cmd: Some(cmd),
+      format,
+    } => {
+      let desc = client.get_cmd_desc(ups, cmd).await?;
+      Output::new(*format).command(&desc)?;
+    }
+    Command::Cmds {
+      ups,
+      cmd: None,
+      format,
+    } => {
+      let cmds = client.list_cmd(ups).await?;
+      let mut entries = Vec::with_capacity(cmds.len());
+
+      for cmd in cmds {
+        let desc = match cmd.parse::<CmdName>() {
+          Ok(name) => client.get_cmd_desc(ups, &name).await.map(|v| v.desc).ok(),
+          Err(_) => None,
+        };
+
+        entries.push((cmd, desc));
+      }
+
+      Output::new(*format).commands(ups, &entries)?;
+    }
+    Command::Instcmd { ups, cmd, value } => {
+      let mut client = authenticate(client, args).await?;
+
+      match value {
+        Some(value) => {
+          client
+            .instcmd_with_value(ups, cmd, Value::from(value.as_ref()))
+            .await?
+        }
+        None => client.instcmd(ups, cmd).await?,
+      }
+
+      _ = client.close().await;
+
+      println!("OK");
+    }
+    Command::Set { ups, var, value } => {
+      let detail = client.get_rw_detail(ups, var).await?.response;
+      let value = detail
+        .parse(value)
+        .map_err(|err| format!("invalid value for '{var}', {err}"))?;
+
+      let mut client = authenticate(client, args).await?;
+      client.set_var(ups, var, &value).await?;
+      _ = client.close().await;
+
+      println!("OK");
+    }
+    Command::Clients { ups, format } => {
+      let clients = client.list_client(ups).await?;
+      Output::new(*format).clients(&clients)?;
+    }
+  }
+
+  Ok(())
+}
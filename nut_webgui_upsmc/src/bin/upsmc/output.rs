@@ -0,0 +1,231 @@
+use crate::args::OutputFormat;
+use nut_webgui_upsmc::{
+  RwDetail, UpsName, Value, VarName,
+  responses::{ClientList, CmdDesc, UpsList, UpsVar, UpsVarList},
+};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::BTreeMap;
+
+type OutputResult = Result<(), serde_json::Error>;
+
+pub struct RwEntry {
+  pub name: VarName,
+  pub value: Value,
+  pub desc: Option<Box<str>>,
+  pub detail: RwDetail,
+}
+
+pub struct Output {
+  format: OutputFormat,
+}
+
+impl Output {
+  #[inline]
+  pub fn new(format: OutputFormat) -> Self {
+    Self { format }
+  }
+
+  pub fn devices(&self, devices: &UpsList) -> OutputResult {
+    match self.format {
+      OutputFormat::Json => print_json(
+        &devices
+          .devices
+          .iter()
+          .map(|device| json!({ "name": device.ups_name, "desc": device.desc }))
+          .collect::<Vec<_>>(),
+      ),
+      OutputFormat::Plain => {
+        print_columns(
+          devices
+            .devices
+            .iter()
+            .map(|device| (device.ups_name.to_string(), device.desc.to_string())),
+        );
+        Ok(())
+      }
+      OutputFormat::Upsc => {
+        for device in devices.devices.iter() {
+          println!("{}: {}", device.ups_name, device.desc);
+        }
+        Ok(())
+      }
+    }
+  }
+
+  pub fn variable(&self, var: &UpsVar) -> OutputResult {
+    match self.format {
+      OutputFormat::Json => print_json(&json!({ var.name.as_ref(): var.value })),
+      OutputFormat::Plain | OutputFormat::Upsc => {
+        println!("{}", var.value);
+        Ok(())
+      }
+    }
+  }
+
+  pub fn variables(&self, vars: &UpsVarList) -> OutputResult {
+    let sorted: BTreeMap<&str, &Value> = vars
+      .variables
+      .iter()
+      .map(|(name, value)| (name.as_ref(), value))
+      .collect();
+
+    match self.format {
+      OutputFormat::Json => print_json(&sorted),
+      OutputFormat::Plain => {
+        print_columns(
+          sorted
+            .into_iter()
+            .map(|(name, value)| (name.to_owned(), value.to_string())),
+        );
+        Ok(())
+      }
+      OutputFormat::Upsc => {
+        for (name, value) in sorted {
+          println!("{name}: {value}");
+        }
+        Ok(())
+      }
+    }
+  }
+
+  pub fn rw_variables(&self, ups: &UpsName, entries: &[RwEntry]) -> OutputResult {
+    match self.format {
+      OutputFormat::Json => {
+        let map: BTreeMap<&str, _> = entries
+          .iter()
+          .map(|entry| {
+            (
+              entry.name.as_ref(),
+              json!({ "value": entry.value, "desc": entry.desc, "detail": entry.detail }),
+            )
+          })
+          .collect();
+
+        print_json(&map)
+      }
+      OutputFormat::Plain => {
+        print_columns(entries.iter().map(|entry| {
+          (
+            entry.name.to_string(),
+            format!("{}  {}", entry.value, describe_detail(&entry.detail)),
+          )
+        }));
+        Ok(())
+      }
+      OutputFormat::Upsc => {
+        for (idx, entry) in entries.iter().enumerate() {
+          if idx > 0 {
+            println!();
+          }
+
+          println!("[{}]", entry.name);
+          println!(
+            "{}",
+            entry.desc.as_deref().unwrap_or("Description unavailable")
+          );
+          println!("Type: {}", describe_detail(&entry.detail));
+          println!("Value: {}", entry.value);
+        }
+
+        if entries.is_empty() {
+          eprintln!("No writable variables on UPS [{ups}]");
+        }
+
+        Ok(())
+      }
+    }
+  }
+
+  pub fn command(&self, desc: &CmdDesc) -> OutputResult {
+    match self.format {
+      OutputFormat::Json => print_json(&json!({ "name": desc.cmd, "desc": desc.desc })),
+      OutputFormat::Plain | OutputFormat::Upsc => {
+        println!("{}", desc.desc);
+        Ok(())
+      }
+    }
+  }
+
+  pub fn commands(&self, ups: &UpsName, cmds: &[(String, Option<Box<str>>)]) -> OutputResult {
+    match self.format {
+      OutputFormat::Json => print_json(
+        &cmds
+          .iter()
+          .map(|(name, desc)| json!({ "name": name, "desc": desc }))
+          .collect::<Vec<_>>(),
+      ),
+      OutputFormat::Plain => {
+        print_columns(cmds.iter().map(|(name, desc)| {
+          (
+            name.to_owned(),
+            desc.as_deref().unwrap_or_default().to_owned(),
+          )
+        }));
+        Ok(())
+      }
+      OutputFormat::Upsc => {
+        println!("Instant commands supported on UPS [{ups}]:\n");
+
+        for (name, desc) in cmds {
+          println!(
+            "{name} - {}",
+            desc.as_deref().unwrap_or("Description unavailable")
+          );
+        }
+
+        Ok(())
+      }
+    }
+  }
+
+  pub fn clients(&self, clients: &ClientList) -> OutputResult {
+    match self.format {
+      OutputFormat::Json => print_json(&clients.ips),
+      OutputFormat::Plain | OutputFormat::Upsc => {
+        for ip in clients.ips.iter() {
+          println!("{ip}");
+        }
+        Ok(())
+      }
+    }
+  }
+}
+
+fn describe_detail(detail: &RwDetail) -> String {
+  match detail {
+    RwDetail::Number => "NUMBER".to_owned(),
+    RwDetail::String { max_len } => format!("STRING:{max_len}"),
+    RwDetail::Enum { options } => format!(
+      "ENUM {}",
+      options
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+    ),
+    RwDetail::Range { ranges } => format!("RANGE {}", ranges),
+  }
+}
+
+fn print_json<T>(value: &T) -> OutputResult
+where
+  T: Serialize + ?Sized,
+{
+  let text = serde_json::to_string_pretty(value)?;
+  println!("{text}");
+
+  Ok(())
+}
+
+fn print_columns<I>(rows: I)
+where
+  I: Iterator<Item = (String, String)>,
+{
+  let rows: Vec<(String, String)> = rows.collect();
+  let width = rows.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+
+  for (key, value) in rows {
+    println!("{key:<width$}  {value}");
+  }
+}
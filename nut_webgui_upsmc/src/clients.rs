@@ -2,18 +2,35 @@ use crate::{
   CmdName, UpsName, VarName,
   errors::Error,
   responses::{
-    ClientList, CmdDesc, DaemonVer, EnumList, ProtVer, RangeList, RwList, UpsDesc, UpsList, UpsVar,
-    UpsVarDesc, UpsVarList, UpsVarType,
+    ClientList, CmdDesc, DaemonVer, EnumList, ProtVer, RangeList, RwList, TrackingStatus, UpsDesc,
+    UpsList, UpsVar, UpsVarDesc, UpsVarList, UpsVarType,
   },
 };
 use core::{borrow::Borrow, future::Future};
+mod client_addr;
 mod client_auth;
+mod client_auth_pool;
 mod client_base;
+mod client_batch;
+mod client_caps;
+mod client_endpoints;
 mod client_pool;
+mod client_stats;
+mod client_stream;
 
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
+pub use client_addr::{NutAddr, ToNutAddr, UNIX_ADDR_PREFIX};
 pub use client_auth::NutAuthClient;
+pub use client_auth_pool::NutAuthPoolClient;
 pub use client_base::NutClient;
-pub use client_pool::NutPoolClient;
+pub use client_batch::{Batch, BatchItem, BatchResponses};
+pub use client_caps::{Capabilities, Support};
+pub use client_endpoints::{EndpointHealth, FailbackPolicy};
+pub use client_pool::{NutPoolClient, NutPoolClientBuilder};
+pub use client_stats::{CommandStats, LATENCY_BUCKETS_MS, LatencyHistogram, PoolStats};
+pub use client_stream::NutStream;
 
 pub trait AsyncNutClient {
   fn get_cmd_desc<N, C>(self, ups: N, cmd: C) -> impl Future<Output = Result<CmdDesc, Error>>
@@ -23,6 +40,11 @@ pub trait AsyncNutClient {
 
   fn get_protver(self) -> impl Future<Output = Result<ProtVer, Error>>;
 
+  /// Queries execution state of a tracked `INSTCMD` or `SET VAR` request.
+  fn get_tracking<I>(self, id: I) -> impl Future<Output = Result<TrackingStatus, Error>>
+  where
+    I: AsRef<str>;
+
   fn get_ups_desc<N>(self, ups: N) -> impl Future<Output = Result<UpsDesc, Error>>
   where
     N: Borrow<UpsName>;
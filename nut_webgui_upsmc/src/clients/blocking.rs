@@ -0,0 +1,874 @@
+//! Synchronous clients built on [std::net::TcpStream], for tools that do not run an async
+//! runtime. Commands and response parsers are shared with the async clients.
+
+use crate::{
+  CmdName, UpsName, Value, VarName, commands,
+  errors::{Error, ErrorKind, ProtocolError},
+  internal::{Deserialize, Serialize, lexer::Lexer},
+  protocol::{ClientProtocol, Frame},
+  responses::{
+    self, ClientList, CmdDesc, DaemonVer, EnumList, ProtVer, RangeList, RwList, TrackingStatus,
+    UpsDesc, UpsList, UpsVar, UpsVarDesc, UpsVarList, UpsVarType,
+  },
+};
+use core::{borrow::Borrow, time::Duration};
+use std::{
+  io::{BufRead, BufReader, Read, Write},
+  net::{Shutdown, TcpStream, ToSocketAddrs},
+};
+use tracing::{error, trace};
+
+#[cfg(unix)]
+use std::{os::unix::net::UnixStream, path::Path};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Blocking counterpart of [AsyncNutClient](super::AsyncNutClient).
+pub trait BlockingNutClient {
+  fn get_cmd_desc<N, C>(self, ups: N, cmd: C) -> Result<CmdDesc, Error>
+  where
+    N: Borrow<UpsName>,
+    C: Borrow<CmdName>;
+
+  fn get_protver(self) -> Result<ProtVer, Error>;
+
+  /// Queries execution state of a tracked `INSTCMD` or `SET VAR` request.
+  fn get_tracking<I>(self, id: I) -> Result<TrackingStatus, Error>
+  where
+    I: AsRef<str>;
+
+  fn get_ups_desc<N>(self, ups: N) -> Result<UpsDesc, Error>
+  where
+    N: Borrow<UpsName>;
+
+  fn get_var<N, V>(self, ups: N, var: V) -> Result<UpsVar, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>;
+
+  fn get_var_type<N, V>(self, ups: N, var: V) -> Result<UpsVarType, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>;
+
+  fn get_var_desc<N, V>(self, ups: N, var: V) -> Result<UpsVarDesc, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>;
+
+  fn get_ver(self) -> Result<DaemonVer, Error>;
+
+  fn list_client<N>(self, ups: N) -> Result<ClientList, Error>
+  where
+    N: Borrow<UpsName>;
+
+  fn list_cmd<N>(self, ups: N) -> Result<Vec<String>, Error>
+  where
+    N: Borrow<UpsName>;
+
+  fn list_enum<N, V>(self, ups: N, var: V) -> Result<EnumList, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>;
+
+  fn list_range<N, V>(self, ups: N, var: V) -> Result<RangeList, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>;
+
+  fn list_rw<N>(self, ups: N) -> Result<RwList, Error>
+  where
+    N: Borrow<UpsName>;
+
+  fn list_ups(self) -> Result<UpsList, Error>;
+
+  fn list_var<N>(self, ups: N) -> Result<UpsVarList, Error>
+  where
+    N: Borrow<UpsName>;
+}
+
+/// Streams with read and write timeouts, request timeouts are applied through them.
+pub trait TimeoutStream: Read + Write {
+  fn set_timeout(&self, timeout: Option<Duration>) -> std::io::Result<()>;
+
+  fn shutdown(&self) -> std::io::Result<()>;
+}
+
+impl TimeoutStream for TcpStream {
+  fn set_timeout(&self, timeout: Option<Duration>) -> std::io::Result<()> {
+    self.set_read_timeout(timeout)?;
+    self.set_write_timeout(timeout)
+  }
+
+  #[inline]
+  fn shutdown(&self) -> std::io::Result<()> {
+    TcpStream::shutdown(self, Shutdown::Both)
+  }
+}
+
+#[cfg(unix)]
+impl TimeoutStream for UnixStream {
+  fn set_timeout(&self, timeout: Option<Duration>) -> std::io::Result<()> {
+    self.set_read_timeout(timeout)?;
+    self.set_write_timeout(timeout)
+  }
+
+  #[inline]
+  fn shutdown(&self) -> std::io::Result<()> {
+    UnixStream::shutdown(self, Shutdown::Both)
+  }
+}
+
+pub struct NutClient<S>
+where
+  S: TimeoutStream,
+{
+  stream: BufReader<S>,
+  protocol: ClientProtocol,
+}
+
+impl NutClient<TcpStream> {
+  pub fn connect<A>(addr: A) -> Result<Self, Error>
+  where
+    A: ToSocketAddrs,
+  {
+    let connection = TcpStream::connect(addr)?;
+    connection.set_nodelay(true)?;
+
+    Self::new(connection)
+  }
+
+  /// Same as [NutClient::connect], but fails when the connection cannot be established within
+  /// `timeout` for any of the resolved addresses.
+  pub fn connect_timeout<A>(addr: A, timeout: Duration) -> Result<Self, Error>
+  where
+    A: ToSocketAddrs,
+  {
+    let mut last_err: Error = ErrorKind::IOError {
+      kind: std::io::ErrorKind::AddrNotAvailable,
+    }
+    .into();
+
+    for socket_addr in addr.to_socket_addrs()? {
+      match TcpStream::connect_timeout(&socket_addr, timeout) {
+        Ok(connection) => {
+          connection.set_nodelay(true)?;
+          return Self::new(connection);
+        }
+        Err(err) => last_err = map_io_err(err),
+      }
+    }
+
+    Err(last_err)
+  }
+}
+
+#[cfg(unix)]
+impl NutClient<UnixStream> {
+  /// Connects to a upsd compatible unix domain socket, e.g. a local socat proxy.
+  pub fn connect_unix<P>(path: P) -> Result<Self, Error>
+  where
+    P: AsRef<Path>,
+  {
+    let connection = UnixStream::connect(path)?;
+
+    Self::new(connection)
+  }
+}
+
+impl<S> NutClient<S>
+where
+  S: TimeoutStream,
+{
+  pub fn new(stream: S) -> Result<Self, Error> {
+    stream.set_timeout(Some(DEFAULT_TIMEOUT))?;
+
+    Ok(Self {
+      stream: BufReader::new(stream),
+      protocol: ClientProtocol::new(),
+    })
+  }
+
+  /// Sets read and write timeout of the underlying stream.
+  #[inline]
+  pub fn set_timeout(&mut self, timeout: Duration) -> Result<(), Error> {
+    self.stream.get_ref().set_timeout(Some(timeout))?;
+    Ok(())
+  }
+
+  pub fn is_open(&mut self) -> bool {
+    matches!(
+      self.send_raw(commands::GetProtVer.serialize()),
+      Ok(response) if !response.is_empty()
+    )
+  }
+
+  pub fn close(self) -> Result<(), Error> {
+    self.stream.get_ref().shutdown()?;
+    Ok(())
+  }
+
+  pub fn send_raw(&mut self, send: &str) -> Result<String, Error> {
+    trace!(message = "tcp message", send = %commands::redact(send));
+
+    self.protocol.send(send);
+
+    match self.flush_outgoing().and_then(|_| self.read_response(send)) {
+      Ok(response) => Ok(response),
+      Err(err) => {
+        if matches!(err.kind(), ErrorKind::RequestTimeout) {
+          self.protocol.reset();
+        }
+
+        Err(err)
+      }
+    }
+  }
+
+  fn flush_outgoing(&mut self) -> Result<(), Error> {
+    let outgoing = self.protocol.outgoing();
+    let len = outgoing.len();
+    let writer = self.stream.get_mut();
+
+    writer.write_all(outgoing).map_err(map_io_err)?;
+    writer.flush().map_err(map_io_err)?;
+    self.protocol.consume_outgoing(len);
+
+    Ok(())
+  }
+
+  /// Reads bytes into the protocol state until the next response is framed.
+  fn read_frame(&mut self) -> Result<Frame, Error> {
+    loop {
+      if let Some(frame) = self.protocol.poll_response()? {
+        return Ok(frame);
+      }
+
+      let received = self.stream.fill_buf().map_err(map_io_err)?;
+      let len = received.len();
+
+      if len == 0 {
+        return self.protocol.receive_eof();
+      }
+
+      self.protocol.receive(received);
+      self.stream.consume(len);
+    }
+  }
+
+  /// Reads a single response line, or a complete `BEGIN LIST` .. `END LIST` block. `ERR` lines are
+  /// returned as protocol errors.
+  fn read_response(&mut self, command: &str) -> Result<String, Error> {
+    let frame = self.read_frame()?;
+
+    match &frame {
+      Frame::List(response) => trace!(
+        message = "nut tcp list message received",
+        response = response,
+        command = %commands::redact(command)
+      ),
+      Frame::Line(response) => trace!(
+        message = "nut tcp line message received",
+        response = response,
+        command = %commands::redact(command)
+      ),
+      Frame::Error(_) => error!(
+        message = "upsd tcp protocol error received",
+        response = %frame,
+        command = %commands::redact(command)
+      ),
+    }
+
+    frame.into_raw()
+  }
+
+  fn send<C, R>(&mut self, command: C) -> Result<R, Error>
+  where
+    R: Deserialize<Error = Error>,
+    C: AsRef<str>,
+  {
+    let response = self.send_raw(command.as_ref())?;
+
+    if response.is_empty() {
+      Err(ErrorKind::EmptyResponse.into())
+    } else {
+      let mut lexer = Lexer::new(&response);
+
+      R::deserialize(&mut lexer)
+    }
+  }
+
+  pub fn authenticate(self, username: &str, password: &str) -> Result<NutAuthClient<S>, Error> {
+    let mut client = NutAuthClient {
+      inner: self,
+      tracking: false,
+    };
+
+    _ = client
+      .inner
+      .send::<_, responses::ProtOk>(commands::Username { username }.serialize())?;
+
+    _ = client
+      .inner
+      .send::<_, responses::ProtOk>(commands::Password { password }.serialize())?;
+
+    Ok(client)
+  }
+}
+
+/// Read and write timeouts are reported as [ErrorKind::RequestTimeout], same as the async
+/// clients.
+fn map_io_err(err: std::io::Error) -> Error {
+  match err.kind() {
+    std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock => {
+      ErrorKind::RequestTimeout.into()
+    }
+    _ => err.into(),
+  }
+}
+
+impl<S> BlockingNutClient for &mut NutClient<S>
+where
+  S: TimeoutStream,
+{
+  fn get_cmd_desc<N, C>(self, ups: N, cmd: C) -> Result<CmdDesc, Error>
+  where
+    N: Borrow<UpsName>,
+    C: Borrow<CmdName>,
+  {
+    let command = commands::GetCmdDesc {
+      ups: ups.borrow(),
+      cmd: cmd.borrow(),
+    }
+    .serialize();
+
+    self.send::<_, CmdDesc>(command)
+  }
+
+  fn get_protver(self) -> Result<ProtVer, Error> {
+    let response = self.send_raw(commands::GetProtVer.serialize())?;
+
+    if response.is_empty() {
+      Err(ErrorKind::EmptyResponse.into())
+    } else {
+      Ok(ProtVer {
+        value: response.trim().to_owned(),
+      })
+    }
+  }
+
+  fn get_tracking<I>(self, id: I) -> Result<TrackingStatus, Error>
+  where
+    I: AsRef<str>,
+  {
+    let command = commands::GetTracking { id: id.as_ref() }.serialize();
+
+    match self.send::<_, TrackingStatus>(command) {
+      Ok(status) => Ok(status),
+      Err(err) => match err.kind() {
+        ErrorKind::ProtocolError {
+          inner:
+            reason @ (ProtocolError::InvalidArgument
+            | ProtocolError::InstcmdFailed
+            | ProtocolError::SetFailed
+            | ProtocolError::Unknown(_)),
+        } => Ok(TrackingStatus::Failed {
+          reason: reason.clone(),
+        }),
+        _ => Err(err),
+      },
+    }
+  }
+
+  fn get_ups_desc<N>(self, ups: N) -> Result<UpsDesc, Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    let command = commands::GetUpsDesc { ups: ups.borrow() }.serialize();
+    self.send::<_, UpsDesc>(command)
+  }
+
+  fn get_var<N, V>(self, ups: N, var: V) -> Result<UpsVar, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+  {
+    let command = commands::GetVar {
+      ups: ups.borrow(),
+      var: var.borrow(),
+    }
+    .serialize();
+
+    self.send::<_, UpsVar>(command)
+  }
+
+  fn get_var_type<N, V>(self, ups: N, var: V) -> Result<UpsVarType, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+  {
+    let command = commands::GetVarType {
+      ups: ups.borrow(),
+      var: var.borrow(),
+    }
+    .serialize();
+
+    self.send::<_, UpsVarType>(command)
+  }
+
+  fn get_var_desc<N, V>(self, ups: N, var: V) -> Result<UpsVarDesc, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+  {
+    let command = commands::GetVarDesc {
+      ups: ups.borrow(),
+      var: var.borrow(),
+    }
+    .serialize();
+
+    self.send::<_, UpsVarDesc>(command)
+  }
+
+  fn get_ver(self) -> Result<DaemonVer, Error> {
+    let response = self.send_raw(commands::GetDaemonVer.serialize())?;
+
+    if response.is_empty() {
+      Err(ErrorKind::EmptyResponse.into())
+    } else {
+      Ok(DaemonVer {
+        value: response.trim().to_owned(),
+      })
+    }
+  }
+
+  fn list_client<N>(self, ups: N) -> Result<ClientList, Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    let command = commands::ListClient { ups: ups.borrow() }.serialize();
+    self.send::<_, ClientList>(command)
+  }
+
+  fn list_cmd<N>(self, ups: N) -> Result<Vec<String>, Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    let command = commands::ListCmd { ups: ups.borrow() }.serialize();
+    let result = self.send::<_, responses::CmdList>(command)?;
+
+    Ok(
+      result
+        .cmds
+        .into_iter()
+        .map(|c| c.into_boxed_str().into())
+        .collect(),
+    )
+  }
+
+  fn list_enum<N, V>(self, ups: N, var: V) -> Result<EnumList, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+  {
+    let command = commands::ListEnum {
+      ups: ups.borrow(),
+      var: var.borrow(),
+    }
+    .serialize();
+
+    self.send::<_, EnumList>(command)
+  }
+
+  fn list_range<N, V>(self, ups: N, var: V) -> Result<RangeList, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+  {
+    let command = commands::ListRange {
+      ups: ups.borrow(),
+      var: var.borrow(),
+    }
+    .serialize();
+
+    self.send::<_, RangeList>(command)
+  }
+
+  fn list_rw<N>(self, ups: N) -> Result<RwList, Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    let command = commands::ListRw { ups: ups.borrow() }.serialize();
+    self.send::<_, RwList>(command)
+  }
+
+  fn list_ups(self) -> Result<UpsList, Error> {
+    self.send::<_, UpsList>(commands::ListUps.serialize())
+  }
+
+  fn list_var<N>(self, ups: N) -> Result<UpsVarList, Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    let command = commands::ListVar { ups: ups.borrow() }.serialize();
+    self.send::<_, UpsVarList>(command)
+  }
+}
+
+pub struct NutAuthClient<S>
+where
+  S: TimeoutStream,
+{
+  inner: NutClient<S>,
+  tracking: bool,
+}
+
+impl NutAuthClient<TcpStream> {
+  pub fn connect<A>(addr: A, username: &str, password: &str) -> Result<Self, Error>
+  where
+    A: ToSocketAddrs,
+  {
+    NutClient::connect(addr)?.authenticate(username, password)
+  }
+}
+
+#[cfg(unix)]
+impl NutAuthClient<UnixStream> {
+  pub fn connect_unix<P>(path: P, username: &str, password: &str) -> Result<Self, Error>
+  where
+    P: AsRef<Path>,
+  {
+    NutClient::connect_unix(path)?.authenticate(username, password)
+  }
+}
+
+impl<S> NutAuthClient<S>
+where
+  S: TimeoutStream,
+{
+  pub fn attach<N>(&mut self, ups: N) -> Result<(), Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    let command = commands::AttachCommand { ups: ups.borrow() }.serialize();
+    self.inner.send::<_, responses::ProtOk>(command)?;
+
+    Ok(())
+  }
+
+  #[inline]
+  pub fn detach(self) -> Result<(), Error> {
+    self.close()
+  }
+
+  pub fn fsd<N>(&mut self, ups: N) -> Result<(), Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    let command = commands::FsdCommand { ups: ups.borrow() }.serialize();
+    _ = self.inner.send::<_, responses::ProtOkFsd>(command)?;
+
+    Ok(())
+  }
+
+  pub fn set_var<N, V, D>(&mut self, ups: N, var: V, value: D) -> Result<(), Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+    D: Borrow<Value>,
+  {
+    let value = value.borrow();
+    value.validate_argument()?;
+
+    let command = commands::SetVariable {
+      ups: ups.borrow(),
+      var: var.borrow(),
+      value,
+    }
+    .serialize();
+
+    _ = self.inner.send::<_, responses::ProtOk>(command)?;
+
+    Ok(())
+  }
+
+  pub fn instcmd<N, C>(&mut self, ups: N, cmd: C) -> Result<(), Error>
+  where
+    N: Borrow<UpsName>,
+    C: Borrow<CmdName>,
+  {
+    let command = commands::InstCmd {
+      ups: ups.borrow(),
+      cmd: cmd.borrow(),
+      value: None,
+    }
+    .serialize();
+
+    _ = self.inner.send::<_, responses::ProtOk>(command)?;
+
+    Ok(())
+  }
+
+  /// Same as [NutAuthClient::instcmd], but sends a command argument, e.g. delay in seconds for
+  /// `load.off.delay`.
+  pub fn instcmd_with_value<N, C, D>(&mut self, ups: N, cmd: C, value: D) -> Result<(), Error>
+  where
+    N: Borrow<UpsName>,
+    C: Borrow<CmdName>,
+    D: Borrow<Value>,
+  {
+    let value = value.borrow();
+    value.validate_argument()?;
+
+    let command = commands::InstCmd {
+      ups: ups.borrow(),
+      cmd: cmd.borrow(),
+      value: Some(value),
+    }
+    .serialize();
+
+    _ = self.inner.send::<_, responses::ProtOk>(command)?;
+
+    Ok(())
+  }
+
+  /// Same as [NutAuthClient::set_var], but returns upsd tracking id which can be polled with
+  /// [BlockingNutClient::get_tracking]. Tracking is enabled on the connection when it's not
+  /// already.
+  pub fn set_var_tracked<N, V, D>(&mut self, ups: N, var: V, value: D) -> Result<Box<str>, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+    D: Borrow<Value>,
+  {
+    let value = value.borrow();
+    value.validate_argument()?;
+
+    if !self.tracking {
+      self.set_tracking(true)?;
+    }
+
+    let command = commands::SetVariable {
+      ups: ups.borrow(),
+      var: var.borrow(),
+      value,
+    }
+    .serialize();
+
+    let response = self.inner.send::<_, responses::ProtOkTracking>(command)?;
+
+    Ok(response.id)
+  }
+
+  /// Same as [NutAuthClient::instcmd], but returns upsd tracking id which can be polled with
+  /// [BlockingNutClient::get_tracking]. Tracking is enabled on the connection when it's not
+  /// already.
+  pub fn instcmd_tracked<N, C>(&mut self, ups: N, cmd: C) -> Result<Box<str>, Error>
+  where
+    N: Borrow<UpsName>,
+    C: Borrow<CmdName>,
+  {
+    if !self.tracking {
+      self.set_tracking(true)?;
+    }
+
+    let command = commands::InstCmd {
+      ups: ups.borrow(),
+      cmd: cmd.borrow(),
+      value: None,
+    }
+    .serialize();
+
+    let response = self.inner.send::<_, responses::ProtOkTracking>(command)?;
+
+    Ok(response.id)
+  }
+
+  /// Same as [NutAuthClient::instcmd_with_value], but returns upsd tracking id which can be polled
+  /// with [BlockingNutClient::get_tracking].
+  pub fn instcmd_with_value_tracked<N, C, D>(
+    &mut self,
+    ups: N,
+    cmd: C,
+    value: D,
+  ) -> Result<Box<str>, Error>
+  where
+    N: Borrow<UpsName>,
+    C: Borrow<CmdName>,
+    D: Borrow<Value>,
+  {
+    let value = value.borrow();
+    value.validate_argument()?;
+
+    if !self.tracking {
+      self.set_tracking(true)?;
+    }
+
+    let command = commands::InstCmd {
+      ups: ups.borrow(),
+      cmd: cmd.borrow(),
+      value: Some(value),
+    }
+    .serialize();
+
+    let response = self.inner.send::<_, responses::ProtOkTracking>(command)?;
+
+    Ok(response.id)
+  }
+
+  /// Enables or disables `INSTCMD` and `SET VAR` tracking for the connection. Requires upsd 2.8.0
+  /// or later.
+  pub fn set_tracking(&mut self, enabled: bool) -> Result<(), Error> {
+    let command = commands::SetTracking { enabled }.serialize();
+    _ = self.inner.send::<_, responses::ProtOk>(command)?;
+    self.tracking = enabled;
+
+    Ok(())
+  }
+
+  #[inline]
+  pub fn set_timeout(&mut self, timeout: Duration) -> Result<(), Error> {
+    self.inner.set_timeout(timeout)
+  }
+
+  #[inline]
+  pub fn is_open(&mut self) -> bool {
+    self.inner.is_open()
+  }
+
+  /// Returns true when `INSTCMD` and `SET VAR` tracking is enabled on the connection.
+  #[inline]
+  pub fn is_tracking(&self) -> bool {
+    self.tracking
+  }
+
+  pub fn close(mut self) -> Result<(), Error> {
+    _ = self
+      .inner
+      .send::<_, responses::ProtOkDetach>(commands::DetachCommand.serialize())?;
+
+    self.inner.close()
+  }
+}
+
+impl<S> BlockingNutClient for &mut NutAuthClient<S>
+where
+  S: TimeoutStream,
+{
+  #[inline]
+  fn get_cmd_desc<N, C>(self, ups: N, cmd: C) -> Result<CmdDesc, Error>
+  where
+    N: Borrow<UpsName>,
+    C: Borrow<CmdName>,
+  {
+    self.inner.get_cmd_desc(ups, cmd)
+  }
+
+  #[inline]
+  fn get_protver(self) -> Result<ProtVer, Error> {
+    self.inner.get_protver()
+  }
+
+  #[inline]
+  fn get_tracking<I>(self, id: I) -> Result<TrackingStatus, Error>
+  where
+    I: AsRef<str>,
+  {
+    self.inner.get_tracking(id)
+  }
+
+  #[inline]
+  fn get_ups_desc<N>(self, ups: N) -> Result<UpsDesc, Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    self.inner.get_ups_desc(ups)
+  }
+
+  #[inline]
+  fn get_var<N, V>(self, ups: N, var: V) -> Result<UpsVar, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+  {
+    self.inner.get_var(ups, var)
+  }
+
+  #[inline]
+  fn get_var_type<N, V>(self, ups: N, var: V) -> Result<UpsVarType, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+  {
+    self.inner.get_var_type(ups, var)
+  }
+
+  #[inline]
+  fn get_var_desc<N, V>(self, ups: N, var: V) -> Result<UpsVarDesc, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+  {
+    self.inner.get_var_desc(ups, var)
+  }
+
+  #[inline]
+  fn get_ver(self) -> Result<DaemonVer, Error> {
+    self.inner.get_ver()
+  }
+
+  #[inline]
+  fn list_client<N>(self, ups: N) -> Result<ClientList, Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    self.inner.list_client(ups)
+  }
+
+  #[inline]
+  fn list_cmd<N>(self, ups: N) -> Result<Vec<String>, Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    self.inner.list_cmd(ups)
+  }
+
+  #[inline]
+  fn list_enum<N, V>(self, ups: N, var: V) -> Result<EnumList, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+  {
+    self.inner.list_enum(ups, var)
+  }
+
+  #[inline]
+  fn list_range<N, V>(self, ups: N, var: V) -> Result<RangeList, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+  {
+    self.inner.list_range(ups, var)
+  }
+
+  #[inline]
+  fn list_rw<N>(self, ups: N) -> Result<RwList, Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    self.inner.list_rw(ups)
+  }
+
+  #[inline]
+  fn list_ups(self) -> Result<UpsList, Error> {
+    self.inner.list_ups()
+  }
+
+  #[inline]
+  fn list_var<N>(self, ups: N) -> Result<UpsVarList, Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    self.inner.list_var(ups)
+  }
+}
@@ -0,0 +1,98 @@
+use crate::errors::Error;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6, ToSocketAddrs};
+
+#[cfg(unix)]
+use std::path::{Path, PathBuf};
+
+/// Prefix of unix domain socket addresses in string form, e.g. `unix:/run/nut/upsd.sock`.
+pub const UNIX_ADDR_PREFIX: &str = "unix:";
+
+/// Resolved upsd address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NutAddr {
+  Tcp(Vec<SocketAddr>),
+  #[cfg(unix)]
+  Unix(PathBuf),
+}
+
+/// Conversion into upsd addresses, similar to [ToSocketAddrs]. Strings starting with
+/// [UNIX_ADDR_PREFIX] are unix domain socket paths, everything else is resolved as a TCP address.
+pub trait ToNutAddr {
+  fn to_nut_addr(&self) -> Result<NutAddr, Error>;
+}
+
+macro_rules! impl_to_nut_addr_tcp {
+  ($($addr:ty),+) => {
+    $(
+      impl ToNutAddr for $addr {
+        #[inline]
+        fn to_nut_addr(&self) -> Result<NutAddr, Error> {
+          Ok(NutAddr::Tcp(self.to_socket_addrs()?.collect()))
+        }
+      }
+    )+
+  };
+}
+
+impl_to_nut_addr_tcp!(
+  SocketAddr,
+  SocketAddrV4,
+  SocketAddrV6,
+  (IpAddr, u16),
+  (Ipv4Addr, u16),
+  (Ipv6Addr, u16),
+  (&str, u16),
+  (String, u16)
+);
+
+impl ToNutAddr for str {
+  fn to_nut_addr(&self) -> Result<NutAddr, Error> {
+    match self.strip_prefix(UNIX_ADDR_PREFIX) {
+      #[cfg(unix)]
+      Some(path) => Ok(NutAddr::Unix(PathBuf::from(path))),
+      #[cfg(not(unix))]
+      Some(_) => Err(std::io::Error::from(std::io::ErrorKind::Unsupported).into()),
+      None => Ok(NutAddr::Tcp(self.to_socket_addrs()?.collect())),
+    }
+  }
+}
+
+impl ToNutAddr for String {
+  #[inline]
+  fn to_nut_addr(&self) -> Result<NutAddr, Error> {
+    self.as_str().to_nut_addr()
+  }
+}
+
+impl ToNutAddr for Box<str> {
+  #[inline]
+  fn to_nut_addr(&self) -> Result<NutAddr, Error> {
+    self.as_ref().to_nut_addr()
+  }
+}
+
+#[cfg(unix)]
+impl ToNutAddr for Path {
+  #[inline]
+  fn to_nut_addr(&self) -> Result<NutAddr, Error> {
+    Ok(NutAddr::Unix(self.to_path_buf()))
+  }
+}
+
+#[cfg(unix)]
+impl ToNutAddr for PathBuf {
+  #[inline]
+  fn to_nut_addr(&self) -> Result<NutAddr, Error> {
+    Ok(NutAddr::Unix(self.clone()))
+  }
+}
+
+impl<T> ToNutAddr for &T
+where
+  T: ToNutAddr + ?Sized,
+{
+  #[inline]
+  fn to_nut_addr(&self) -> Result<NutAddr, Error> {
+    (**self).to_nut_addr()
+  }
+}
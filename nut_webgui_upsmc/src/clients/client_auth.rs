@@ -9,11 +9,15 @@ use tokio::{
   net::{TcpStream, ToSocketAddrs},
 };
 
+#[cfg(unix)]
+use {std::path::Path, tokio::net::UnixStream};
+
 pub struct NutAuthClient<T>
 where
   T: AsyncRead + AsyncWrite + Unpin,
 {
   inner: NutClient<T>,
+  tracking: bool,
 }
 
 impl<T> AsyncNutClient for &mut NutAuthClient<T>
@@ -36,6 +40,13 @@ where
     self.inner.get_protver()
   }
 
+  fn get_tracking<I>(self, id: I) -> impl Future<Output = Result<responses::TrackingStatus, Error>>
+  where
+    I: AsRef<str>,
+  {
+    self.inner.get_tracking(id)
+  }
+
   fn get_ups_desc<N>(self, ups: N) -> impl Future<Output = Result<responses::UpsDesc, Error>>
   where
     N: std::borrow::Borrow<UpsName>,
@@ -171,10 +182,13 @@ where
     V: Borrow<VarName>,
     D: Borrow<Value>,
   {
+    let value = value.borrow();
+    value.validate_argument()?;
+
     let command = commands::SetVariable {
       ups: ups.borrow(),
       var: var.borrow(),
-      value: value.borrow(),
+      value,
     }
     .serialize();
 
@@ -191,10 +205,140 @@ where
     let command = commands::InstCmd {
       ups: ups.borrow(),
       cmd: cmd.borrow(),
+      value: None,
+    }
+    .serialize();
+
+    _ = self.inner.send::<_, responses::ProtOk>(command).await?;
+
+    Ok(())
+  }
+
+  /// Same as [NutAuthClient::instcmd], but sends a command argument, e.g. delay in seconds for
+  /// `load.off.delay`.
+  pub async fn instcmd_with_value<N, C, D>(&mut self, ups: N, cmd: C, value: D) -> Result<(), Error>
+  where
+    N: Borrow<UpsName>,
+    C: Borrow<CmdName>,
+    D: Borrow<Value>,
+  {
+    let value = value.borrow();
+    value.validate_argument()?;
+
+    let command = commands::InstCmd {
+      ups: ups.borrow(),
+      cmd: cmd.borrow(),
+      value: Some(value),
+    }
+    .serialize();
+
+    _ = self.inner.send::<_, responses::ProtOk>(command).await?;
+
+    Ok(())
+  }
+
+  /// Same as [NutAuthClient::set_var], but returns upsd tracking id which can be polled with
+  /// [AsyncNutClient::get_tracking]. Tracking is enabled on the connection when it's not already.
+  pub async fn set_var_tracked<N, V, D>(
+    &mut self,
+    ups: N,
+    var: V,
+    value: D,
+  ) -> Result<Box<str>, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+    D: Borrow<Value>,
+  {
+    let value = value.borrow();
+    value.validate_argument()?;
+
+    if !self.tracking {
+      self.set_tracking(true).await?;
+    }
+
+    let command = commands::SetVariable {
+      ups: ups.borrow(),
+      var: var.borrow(),
+      value,
+    }
+    .serialize();
+
+    let response = self
+      .inner
+      .send::<_, responses::ProtOkTracking>(command)
+      .await?;
+
+    Ok(response.id)
+  }
+
+  /// Same as [NutAuthClient::instcmd], but returns upsd tracking id which can be polled with
+  /// [AsyncNutClient::get_tracking]. Tracking is enabled on the connection when it's not already.
+  pub async fn instcmd_tracked<N, C>(&mut self, ups: N, cmd: C) -> Result<Box<str>, Error>
+  where
+    N: Borrow<UpsName>,
+    C: Borrow<CmdName>,
+  {
+    if !self.tracking {
+      self.set_tracking(true).await?;
+    }
+
+    let command = commands::InstCmd {
+      ups: ups.borrow(),
+      cmd: cmd.borrow(),
+      value: None,
+    }
+    .serialize();
+
+    let response = self
+      .inner
+      .send::<_, responses::ProtOkTracking>(command)
+      .await?;
+
+    Ok(response.id)
+  }
+
+  /// Same as [NutAuthClient::instcmd_with_value], but returns upsd tracking id which can be polled
+  /// with [AsyncNutClient::get_tracking].
+  pub async fn instcmd_with_value_tracked<N, C, D>(
+    &mut self,
+    ups: N,
+    cmd: C,
+    value: D,
+  ) -> Result<Box<str>, Error>
+  where
+    N: Borrow<UpsName>,
+    C: Borrow<CmdName>,
+    D: Borrow<Value>,
+  {
+    let value = value.borrow();
+    value.validate_argument()?;
+
+    if !self.tracking {
+      self.set_tracking(true).await?;
+    }
+
+    let command = commands::InstCmd {
+      ups: ups.borrow(),
+      cmd: cmd.borrow(),
+      value: Some(value),
     }
     .serialize();
 
+    let response = self
+      .inner
+      .send::<_, responses::ProtOkTracking>(command)
+      .await?;
+
+    Ok(response.id)
+  }
+
+  /// Enables or disables `INSTCMD` and `SET VAR` tracking for the connection. Requires upsd 2.8.0
+  /// or later.
+  pub async fn set_tracking(&mut self, enabled: bool) -> Result<(), Error> {
+    let command = commands::SetTracking { enabled }.serialize();
     _ = self.inner.send::<_, responses::ProtOk>(command).await?;
+    self.tracking = enabled;
 
     Ok(())
   }
@@ -204,6 +348,12 @@ where
     self.inner.is_open()
   }
 
+  /// Returns true when `INSTCMD` and `SET VAR` tracking is enabled on the connection.
+  #[inline]
+  pub fn is_tracking(&self) -> bool {
+    self.tracking
+  }
+
   pub async fn close(mut self) -> Result<(), Error> {
     _ = self
       .inner
@@ -230,6 +380,21 @@ impl NutAuthClient<TcpStream> {
   }
 }
 
+#[cfg(unix)]
+impl NutAuthClient<UnixStream> {
+  pub async fn connect_unix<P>(path: P, username: &str, password: &str) -> Result<Self, Error>
+  where
+    P: AsRef<Path>,
+  {
+    let client = NutClient::connect_unix(path)
+      .await?
+      .authenticate(username, password)
+      .await?;
+
+    Ok(client)
+  }
+}
+
 impl<T> NutClient<T>
 where
   T: AsyncRead + AsyncWrite + Unpin,
@@ -239,7 +404,10 @@ where
     username: &str,
     password: &str,
   ) -> Result<NutAuthClient<T>, Error> {
-    let mut client = NutAuthClient { inner: self };
+    let mut client = NutAuthClient {
+      inner: self,
+      tracking: false,
+    };
     _ = client
       .inner
       .send::<_, responses::ProtOk>(commands::Username { username }.serialize())
@@ -0,0 +1,397 @@
+use super::{
+  NutAuthClient, NutStream, ToNutAddr,
+  client_endpoints::EndpointHealth,
+  client_pool::{Connector, is_connection_error},
+  client_stats::{CommandMetrics, PoolStats},
+};
+use crate::{
+  CmdName, UpsName, Value, VarName,
+  errors::{Error, ErrorKind, ProtocolError},
+  internal::item_pool::{ItemAllocator, ItemPool, PoolGuard},
+};
+use core::{
+  borrow::Borrow,
+  fmt::Debug,
+  ops::{Deref, DerefMut},
+  sync::atomic::{AtomicBool, Ordering},
+};
+use std::{sync::Arc, time::Instant};
+use tracing::{debug, warn};
+
+pub struct AuthClientAllocator<A>
+where
+  A: ToNutAddr + Debug + Send + Sync + 'static,
+{
+  connector: Arc<Connector<A>>,
+  metrics: CommandMetrics,
+  username: Box<str>,
+  password: Box<str>,
+
+  /// Set after upsd rejects `SET TRACKING`, so it's not requested on every new connection.
+  tracking_unsupported: AtomicBool,
+}
+
+/// Pooled authenticated connection with the index of the endpoint it's connected to.
+pub struct PooledAuthClient {
+  client: NutAuthClient<NutStream>,
+  endpoint: usize,
+}
+
+impl Deref for PooledAuthClient {
+  type Target = NutAuthClient<NutStream>;
+
+  #[inline]
+  fn deref(&self) -> &Self::Target {
+    &self.client
+  }
+}
+
+impl DerefMut for PooledAuthClient {
+  #[inline]
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    &mut self.client
+  }
+}
+
+impl<A> AuthClientAllocator<A>
+where
+  A: ToNutAddr + Debug + Send + Sync + 'static,
+{
+  pub(super) fn new<U, P>(connector: Arc<Connector<A>>, username: U, password: P) -> Self
+  where
+    U: Into<Box<str>>,
+    P: Into<Box<str>>,
+  {
+    Self {
+      connector,
+      metrics: CommandMetrics::default(),
+      username: username.into(),
+      password: password.into(),
+      tracking_unsupported: AtomicBool::new(false),
+    }
+  }
+
+  /// Enables request tracking on the connection. Older upsd versions reject `SET TRACKING` with a
+  /// protocol error, which is reported as `false`.
+  async fn enable_tracking(&self, client: &mut NutAuthClient<NutStream>) -> Result<bool, Error> {
+    if client.is_tracking() {
+      return Ok(true);
+    }
+
+    if self.tracking_unsupported.load(Ordering::Relaxed) {
+      return Ok(false);
+    }
+
+    match client.set_tracking(true).await {
+      Ok(()) => Ok(true),
+      Err(err) => match err.kind() {
+        ErrorKind::ProtocolError { inner } => {
+          debug!(message = "upsd does not support request tracking", reason = %inner);
+          self.tracking_unsupported.store(true, Ordering::Relaxed);
+          Ok(false)
+        }
+        _ => Err(err),
+      },
+    }
+  }
+}
+
+impl<A> ItemAllocator for AuthClientAllocator<A>
+where
+  A: ToNutAddr + Debug + Send + Sync + 'static,
+{
+  type Output = PooledAuthClient;
+  type Error = Error;
+
+  async fn init(&self) -> Result<Self::Output, Self::Error> {
+    let (client, endpoint) = self
+      .connector
+      .connect_active(|client| client.authenticate(&self.username, &self.password))
+      .await?;
+
+    Ok(PooledAuthClient { client, endpoint })
+  }
+
+  async fn dealloc(&self, item: Self::Output) {
+    if let Err(err) = item.client.close().await {
+      warn!(message = "unable to close an authenticated connection in pool", error = %err);
+    }
+  }
+
+  /// Connections to an inactive endpoint are discarded after a failover or failback.
+  async fn is_valid_state(&self, item: &mut Self::Output) -> bool {
+    item.endpoint == self.connector.endpoints.active() && item.client.is_open().await
+  }
+}
+
+/// Errors proving that upsd rejected the request without executing it, retried once with a newly
+/// authenticated connection. Connection errors and timeouts are never retried, since the request
+/// might have already reached upsd, and commands like `load.off` must not run twice.
+#[inline]
+fn is_reauth_error(err: &Error) -> bool {
+  matches!(
+    err.kind(),
+    ErrorKind::ProtocolError {
+      inner: ProtocolError::UsernameRequired | ProtocolError::PasswordRequired
+    }
+  )
+}
+
+/// Connection pool for privileged requests. Connections are authenticated once and reused, failed
+/// sessions are replaced with a newly authenticated connection.
+///
+/// Created with [super::NutPoolClient::auth_pool] or [super::NutPoolClientBuilder::build_auth].
+pub struct NutAuthPoolClient<A>
+where
+  A: ToNutAddr + Debug + Send + Sync + 'static,
+{
+  pool: ItemPool<PooledAuthClient, AuthClientAllocator<A>>,
+}
+
+impl<A> Clone for NutAuthPoolClient<A>
+where
+  A: ToNutAddr + Debug + Send + Sync + 'static,
+{
+  #[inline]
+  fn clone(&self) -> Self {
+    Self {
+      pool: self.pool.clone(),
+    }
+  }
+}
+
+unsafe impl<A> Send for NutAuthPoolClient<A> where A: ToNutAddr + Debug + Send + Sync + 'static {}
+unsafe impl<A> Sync for NutAuthPoolClient<A> where A: ToNutAddr + Debug + Send + Sync + 'static {}
+
+macro_rules! impl_auth_call {
+  ($client_pool:expr, $name:literal, |$client:ident| $action:expr) => {{
+    let started = Instant::now();
+    let result = async {
+      let mut guard = $client_pool.get_client().await?;
+      let result = {
+        let $client: &mut NutAuthClient<NutStream> = &mut guard;
+        $action.await
+      };
+
+      match result {
+        Err(err) if is_reauth_error(&err) => {
+          guard.discard().await;
+
+          let mut guard = $client_pool.pool.get_checked().await?;
+          let result = {
+            let $client: &mut NutAuthClient<NutStream> = &mut guard;
+            $action.await
+          };
+
+          $client_pool.finish(guard, result).await
+        }
+        result => $client_pool.finish(guard, result).await,
+      }
+    }
+    .await;
+
+    $client_pool
+      .pool
+      .allocator()
+      .metrics
+      .record($name, started.elapsed(), result.is_err());
+
+    result
+  }};
+}
+
+impl<A> NutAuthPoolClient<A>
+where
+  A: ToNutAddr + Debug + Send + Sync + 'static,
+{
+  #[inline]
+  pub(super) fn new(pool: ItemPool<PooledAuthClient, AuthClientAllocator<A>>) -> Self {
+    Self { pool }
+  }
+
+  pub async fn instcmd<N, C>(&self, ups: N, cmd: C) -> Result<(), Error>
+  where
+    N: Borrow<UpsName>,
+    C: Borrow<CmdName>,
+  {
+    let (ups, cmd) = (ups.borrow(), cmd.borrow());
+    impl_auth_call!(self, "instcmd", |client| client.instcmd(ups, cmd))
+  }
+
+  /// Sends `INSTCMD` with tracking enabled, and returns upsd tracking id. Returns [None] when upsd
+  /// does not support tracking, and the command is sent untracked.
+  pub async fn instcmd_tracked<N, C>(&self, ups: N, cmd: C) -> Result<Option<Box<str>>, Error>
+  where
+    N: Borrow<UpsName>,
+    C: Borrow<CmdName>,
+  {
+    let (ups, cmd) = (ups.borrow(), cmd.borrow());
+    let allocator = self.pool.allocator();
+
+    impl_auth_call!(self, "instcmd_tracked", |client| async {
+      if allocator.enable_tracking(client).await? {
+        client.instcmd_tracked(ups, cmd).await.map(Some)
+      } else {
+        client.instcmd(ups, cmd).await.map(|_| None)
+      }
+    })
+  }
+
+  /// Same as [NutAuthPoolClient::instcmd], but sends a command argument.
+  pub async fn instcmd_with_value<N, C, D>(&self, ups: N, cmd: C, value: D) -> Result<(), Error>
+  where
+    N: Borrow<UpsName>,
+    C: Borrow<CmdName>,
+    D: Borrow<Value>,
+  {
+    let (ups, cmd, value) = (ups.borrow(), cmd.borrow(), value.borrow());
+    impl_auth_call!(self, "instcmd_with_value", |client| client.instcmd_with_value(ups, cmd, value))
+  }
+
+  /// Same as [NutAuthPoolClient::instcmd_tracked], but sends a command argument.
+  pub async fn instcmd_with_value_tracked<N, C, D>(
+    &self,
+    ups: N,
+    cmd: C,
+    value: D,
+  ) -> Result<Option<Box<str>>, Error>
+  where
+    N: Borrow<UpsName>,
+    C: Borrow<CmdName>,
+    D: Borrow<Value>,
+  {
+    let (ups, cmd, value) = (ups.borrow(), cmd.borrow(), value.borrow());
+    let allocator = self.pool.allocator();
+
+    impl_auth_call!(self, "instcmd_with_value_tracked", |client| async {
+      if allocator.enable_tracking(client).await? {
+        client
+          .instcmd_with_value_tracked(ups, cmd, value)
+          .await
+          .map(Some)
+      } else {
+        client
+          .instcmd_with_value(ups, cmd, value)
+          .await
+          .map(|_| None)
+      }
+    })
+  }
+
+  pub async fn set_var<N, V, D>(&self, ups: N, var: V, value: D) -> Result<(), Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+    D: Borrow<Value>,
+  {
+    let (ups, var, value) = (ups.borrow(), var.borrow(), value.borrow());
+    impl_auth_call!(self, "set_var", |client| client.set_var(ups, var, value))
+  }
+
+  /// Sends `SET VAR` with tracking enabled, and returns upsd tracking id. Returns [None] when upsd
+  /// does not support tracking, and the request is sent untracked.
+  pub async fn set_var_tracked<N, V, D>(
+    &self,
+    ups: N,
+    var: V,
+    value: D,
+  ) -> Result<Option<Box<str>>, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+    D: Borrow<Value>,
+  {
+    let (ups, var, value) = (ups.borrow(), var.borrow(), value.borrow());
+    let allocator = self.pool.allocator();
+
+    impl_auth_call!(self, "set_var_tracked", |client| async {
+      if allocator.enable_tracking(client).await? {
+        client.set_var_tracked(ups, var, value).await.map(Some)
+      } else {
+        client.set_var(ups, var, value).await.map(|_| None)
+      }
+    })
+  }
+
+  pub async fn fsd<N>(&self, ups: N) -> Result<(), Error>
+  where
+    N: Borrow<UpsName>,
+  {
+    let ups = ups.borrow();
+    impl_auth_call!(self, "fsd", |client| client.fsd(ups))
+  }
+
+  /// Returns the endpoint used for new connections.
+  #[inline]
+  pub fn active_endpoint(&self) -> &A {
+    let endpoints = &self.pool.allocator().connector.endpoints;
+    endpoints.addr(endpoints.active())
+  }
+
+  /// Returns health snapshots of all endpoints, in priority order.
+  #[inline]
+  pub fn endpoints(&self) -> Vec<EndpointHealth<A>>
+  where
+    A: Clone,
+  {
+    self.pool.allocator().connector.endpoints.health()
+  }
+
+  /// Returns a snapshot of connection pool and per-command statistics.
+  #[inline]
+  pub fn stats(&self) -> PoolStats {
+    PoolStats::new(self.pool.stats(), &self.pool.allocator().metrics)
+  }
+
+  /// Closes idle connections exceeding the configured max idle time or max lifetime, and returns
+  /// the number of closed connections.
+  #[inline]
+  pub fn evict_expired(&self) -> impl Future<Output = usize> {
+    self.pool.evict_expired()
+  }
+
+  /// Probes higher priority endpoints when failback is due, see [super::NutPoolClient::try_failback].
+  /// Only needed for standalone pools, pools created with [super::NutPoolClient::auth_pool] share
+  /// the endpoint state.
+  #[inline]
+  pub fn try_failback(&self) -> impl Future<Output = bool> {
+    self.pool.allocator().connector.try_failback()
+  }
+
+  /// Logs out all idle connections and closes the pool.
+  #[inline]
+  pub fn close(self) -> impl Future<Output = ()> {
+    self.pool.close()
+  }
+
+  /// Returns a connection for the active endpoint. Pooled connections are always validated, broken
+  /// sessions must be detected before sending the request since failed requests are not retried.
+  async fn get_client(
+    &self,
+  ) -> Result<PoolGuard<'_, PooledAuthClient, AuthClientAllocator<A>>, Error> {
+    Ok(self.pool.get_checked().await?)
+  }
+
+  /// Returns the connection back to the pool, or discards it when the connection is broken.
+  async fn finish<T>(
+    &self,
+    guard: PoolGuard<'_, PooledAuthClient, AuthClientAllocator<A>>,
+    result: Result<T, Error>,
+  ) -> Result<T, Error> {
+    match &result {
+      Err(err) if is_connection_error(err) => {
+        self
+          .pool
+          .allocator()
+          .connector
+          .endpoints
+          .report_failure(guard.endpoint, err);
+
+        guard.discard().await;
+      }
+      _ => guard.release().await,
+    }
+
+    result
+  }
+}
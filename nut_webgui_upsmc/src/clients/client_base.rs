@@ -1,8 +1,13 @@
-use super::AsyncNutClient;
+use super::{
+  AsyncNutClient,
+  client_batch::{Batch, BatchResponses},
+};
 use crate::{
-  CmdName, UpsName, VarName, commands,
-  errors::{Error, ErrorKind, ProtocolError},
+  CmdName, DEFAULT_RW_STRING_LEN, RwDetail, UpsName, Value, ValueRanges, VarName, VarType,
+  commands,
+  errors::{Error, ErrorKind, ParseDiagnostic, ParseError, Position, ProtocolError},
   internal::{Deserialize, Serialize, lexer::Lexer},
+  protocol::{ClientProtocol, Frame, ListEvent, ListVar},
   responses,
 };
 use core::{borrow::Borrow, time::Duration};
@@ -14,7 +19,13 @@ use tokio::{
   net::{TcpStream, ToSocketAddrs},
   time::timeout,
 };
-use tracing::{error, trace};
+use tracing::{error, trace, warn};
+
+#[cfg(feature = "tls")]
+use crate::tls::{TlsConfig, TlsStream};
+
+#[cfg(unix)]
+use {std::path::Path, tokio::net::UnixStream};
 
 pub struct NutClient<S>
 where
@@ -22,6 +33,7 @@ where
 {
   reader: BufReader<ReadHalf<S>>,
   writer: WriteHalf<S>,
+  protocol: ClientProtocol,
   timeout: Duration,
 }
 
@@ -30,17 +42,39 @@ impl NutClient<TcpStream> {
   where
     A: ToSocketAddrs,
   {
-    let connection = TcpStream::connect(addr).await?;
-    connection.set_nodelay(true)?;
-    connection.set_linger(None)?;
-    connection
-      .ready(Interest::READABLE | Interest::WRITABLE)
-      .await?;
+    let connection = connect_tcp(addr).await?;
+
+    Ok(Self::new(connection))
+  }
+}
+
+#[cfg(unix)]
+impl NutClient<UnixStream> {
+  /// Connects to a upsd compatible unix domain socket, e.g. a local socat proxy.
+  pub async fn connect_unix<P>(path: P) -> Result<Self, Error>
+  where
+    P: AsRef<Path>,
+  {
+    let connection = UnixStream::connect(path).await?;
 
     Ok(Self::new(connection))
   }
 }
 
+pub(super) async fn connect_tcp<A>(addr: A) -> Result<TcpStream, Error>
+where
+  A: ToSocketAddrs,
+{
+  let connection = TcpStream::connect(addr).await?;
+  connection.set_nodelay(true)?;
+  connection.set_linger(None)?;
+  connection
+    .ready(Interest::READABLE | Interest::WRITABLE)
+    .await?;
+
+  Ok(connection)
+}
+
 impl<S> From<S> for NutClient<S>
 where
   S: AsyncWrite + AsyncRead + Unpin + 'static,
@@ -61,6 +95,7 @@ where
     Self {
       writer,
       reader,
+      protocol: ClientProtocol::new(),
       timeout: Duration::from_secs(60),
     }
   }
@@ -82,65 +117,421 @@ where
     Ok(())
   }
 
+  /// Upgrades the connection to TLS by sending `STARTTLS` and performing the client handshake.
+  ///
+  /// Must be called before any authentication command, otherwise credentials are already sent in
+  /// cleartext.
+  #[cfg(feature = "tls")]
+  pub async fn start_tls(mut self, config: &TlsConfig) -> Result<NutClient<TlsStream<S>>, Error> {
+    _ = self
+      .send::<_, responses::ProtOkTls>(commands::StartTls.serialize())
+      .await?;
+
+    let request_timeout = self.timeout;
+    let stream = self.into_inner();
+    let tls_stream = match timeout(request_timeout, config.handshake(stream)).await {
+      Ok(result) => result?,
+      Err(_) => return Err(ErrorKind::RequestTimeout.into()),
+    };
+
+    let mut client = NutClient::new(tls_stream);
+    client.set_timeout(request_timeout);
+
+    Ok(client)
+  }
+
+  /// Reunites read and write halves. Any unread buffered data is discarded.
+  #[cfg(feature = "tls")]
+  pub(crate) fn into_inner(self) -> S {
+    self.reader.into_inner().unsplit(self.writer)
+  }
+
   pub async fn send_raw(&mut self, send: &str) -> Result<String, Error> {
     match timeout(self.timeout, self.inner_send_raw(send)).await {
       Ok(r) => r,
-      Err(_) => Err(ErrorKind::RequestTimeout.into()),
+      Err(_) => {
+        self.protocol.reset();
+        Err(ErrorKind::RequestTimeout.into())
+      }
     }
   }
 
   async fn inner_send_raw(&mut self, send: &str) -> Result<String, Error> {
-    trace!(message = "tcp message", send = send);
-    const LIST_START: &str = "BEGIN LIST";
-    const LIST_END: &str = "END LIST";
-    const PROT_ERR: &str = "ERR";
+    trace!(message = "tcp message", send = %commands::redact(send));
 
-    self.writer.write_all(send.as_bytes()).await?;
+    self.protocol.send(send);
+    self.flush_outgoing().await?;
+
+    self.read_response(send).await
+  }
+
+  async fn flush_outgoing(&mut self) -> Result<(), Error> {
+    let outgoing = self.protocol.outgoing();
+    let len = outgoing.len();
+
+    self.writer.write_all(outgoing).await?;
     self.writer.flush().await?;
+    self.protocol.consume_outgoing(len);
+
+    Ok(())
+  }
 
-    let mut response_buf = String::new();
-    let mut start_pos = self.reader.read_line(&mut response_buf).await?;
+  /// Reads bytes into the protocol state until the next response is framed.
+  async fn read_frame(&mut self) -> Result<Frame, Error> {
+    loop {
+      if let Some(frame) = self.protocol.poll_response()? {
+        return Ok(frame);
+      }
 
-    if response_buf.starts_with(LIST_START) {
-      loop {
-        let read = self.reader.read_line(&mut response_buf).await?;
-        let line = &response_buf[start_pos..];
+      let received = self.reader.fill_buf().await?;
+      let len = received.len();
 
-        if line.starts_with(LIST_END) {
-          break;
-        } else {
-          start_pos += read;
-        }
+      if len == 0 {
+        return self.protocol.receive_eof();
       }
 
-      trace!(
-        message = "nut tcp list message received",
-        response = &response_buf,
-        command = send
-      );
+      self.protocol.receive(received);
+      self.reader.consume(len);
+    }
+  }
 
-      Ok(response_buf)
-    } else if let Some(prot_err) = response_buf.strip_prefix(PROT_ERR) {
-      let prot_err = ProtocolError::from(prot_err.trim());
+  /// Reads a single response line, or a complete `BEGIN LIST` .. `END LIST` block. `ERR` lines are
+  /// returned as protocol errors.
+  async fn read_response(&mut self, command: &str) -> Result<String, Error> {
+    let frame = self.read_frame().await?;
 
-      error!(
+    match &frame {
+      Frame::List(response) => trace!(
+        message = "nut tcp list message received",
+        response = response,
+        command = %commands::redact(command)
+      ),
+      Frame::Line(response) => trace!(
+        message = "nut tcp line message received",
+        response = response,
+        command = %commands::redact(command)
+      ),
+      Frame::Error(_) => error!(
         message = "upsd tcp protocol error received",
-        response = &response_buf,
-        command = send
-      );
+        response = %frame,
+        command = %commands::redact(command)
+      ),
+    }
 
-      Err(prot_err.into())
-    } else {
-      trace!(
-        message = "nut tcp line message received",
-        response = &response_buf,
-        command = send
-      );
+    frame.into_raw()
+  }
+
+  /// Writes all commands in the batch at once, then reads their responses in order. Protocol
+  /// errors are kept per command, I/O errors and timeouts fail the whole batch since remaining
+  /// responses cannot be matched to their commands anymore.
+  pub async fn send_batch(&mut self, batch: &Batch) -> Result<BatchResponses, Error> {
+    match timeout(self.timeout, self.inner_send_batch(batch)).await {
+      Ok(r) => r,
+      Err(_) => {
+        self.protocol.reset();
+        Err(ErrorKind::RequestTimeout.into())
+      }
+    }
+  }
+
+  async fn inner_send_batch(&mut self, batch: &Batch) -> Result<BatchResponses, Error> {
+    trace!(
+      message = "tcp batch message",
+      send = %commands::redact(batch.as_str()),
+      count = batch.len()
+    );
+
+    for command in batch.commands() {
+      self.protocol.send(command);
+    }
+
+    self.flush_outgoing().await?;
+
+    let mut responses = Vec::with_capacity(batch.len());
+
+    for command in batch.commands() {
+      match self.read_response(command).await {
+        Ok(response) if response.is_empty() => return Err(ErrorKind::EmptyResponse.into()),
+        Ok(response) => responses.push(Ok(response)),
+        Err(err) if matches!(err.kind(), ErrorKind::ProtocolError { .. }) => {
+          responses.push(Err(err))
+        }
+        Err(err) => return Err(err),
+      }
+    }
+
+    Ok(BatchResponses::new(responses))
+  }
+
+  /// Loads type details of a writable variable with `GET TYPE`, followed by `LIST ENUM` or
+  /// `LIST RANGE` depending on the reported type. Malformed `ENUM` lines are skipped and reported
+  /// as diagnostics. Variables without any reported type are treated as strings with
+  /// [DEFAULT_RW_STRING_LEN].
+  pub async fn get_rw_detail<N, V>(
+    &mut self,
+    ups: N,
+    var: V,
+  ) -> Result<responses::Lenient<RwDetail>, Error>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+  {
+    let (ups, var) = (ups.borrow(), var.borrow());
+    let type_info = self.get_var_type(ups, var).await?;
+
+    for var_type in type_info.var_types {
+      let response = match var_type {
+        VarType::ReadWrite => continue,
+        VarType::Enum => {
+          let mut batch = Batch::new();
+          let enum_item = batch.list_enum_lenient(ups, var);
+          let enum_list = self.send_batch(&batch).await?.take(enum_item)?;
+
+          if enum_list.response.values.is_empty() {
+            warn!(message = "nut driver reports variable type as enum, but it does not provide any enum option", var_name = %var, device = %ups);
+          }
+
+          return Ok(responses::Lenient {
+            response: RwDetail::Enum {
+              options: enum_list.response.values,
+            },
+            diagnostics: enum_list.diagnostics,
+          });
+        }
+        VarType::Range => {
+          let range_list = self.list_range(ups, var).await?;
+
+          // Drivers may report range type without any range, any value is accepted in that case.
+          let ranges = if range_list.ranges.is_empty() {
+            warn!(message = "nut driver reports variable type as range, but it does not provide any range information", var_name = %var, device = %ups);
+            vec![(Value::from(i64::MIN), Value::from(i64::MAX))]
+          } else {
+            range_list.ranges
+          };
+
+          RwDetail::Range {
+            ranges: ValueRanges::new(ranges),
+          }
+        }
+        VarType::String { max_len } => RwDetail::String { max_len },
+        VarType::Number => RwDetail::Number,
+      };
+
+      return Ok(responses::Lenient {
+        response,
+        diagnostics: Vec::new(),
+      });
+    }
+
+    Ok(responses::Lenient {
+      response: RwDetail::String {
+        max_len: DEFAULT_RW_STRING_LEN,
+      },
+      diagnostics: Vec::new(),
+    })
+  }
+
+  /// Sends `LIST VAR` and passes each variable to `on_var` as soon as its line is read. Lines are
+  /// tokenised in place from the read buffer, and the buffer is reused across requests. Returns
+  /// the number of received variables.
+  pub async fn list_var_streamed<N, F>(&mut self, ups: N, on_var: F) -> Result<usize, Error>
+  where
+    N: Borrow<UpsName>,
+    F: FnMut(ListVar<'_>),
+  {
+    self
+      .send_streamed_strict(ups.borrow(), list_var_command, on_var)
+      .await
+  }
+
+  /// Same as [NutClient::list_var_streamed] for `LIST RW`.
+  pub async fn list_rw_streamed<N, F>(&mut self, ups: N, on_var: F) -> Result<usize, Error>
+  where
+    N: Borrow<UpsName>,
+    F: FnMut(ListVar<'_>),
+  {
+    self
+      .send_streamed_strict(ups.borrow(), list_rw_command, on_var)
+      .await
+  }
+
+  /// Writes `LIST VAR` for all devices at once, then streams the responses in order. Each
+  /// variable is passed to `on_item` along with the index of its device as soon as its line is
+  /// read. Malformed lines are passed as [ParseDiagnostic]s in the same pass, without failing the
+  /// response.
+  ///
+  /// Returns the number of received variables per device. Protocol errors are kept per device, I/O
+  /// errors and timeouts fail the whole request.
+  pub async fn list_vars_streamed<F>(
+    &mut self,
+    devices: &[UpsName],
+    on_item: F,
+  ) -> Result<Vec<Result<usize, Error>>, Error>
+  where
+    F: FnMut(usize, Result<ListVar<'_>, ParseDiagnostic>),
+  {
+    self.send_streamed(devices, list_var_command, on_item).await
+  }
+
+  /// Streams a single list response, the first malformed item fails the request after `END
+  /// LIST`, so remaining lines of the response do not leak into the next request.
+  async fn send_streamed_strict<F>(
+    &mut self,
+    ups: &UpsName,
+    command: fn(&UpsName) -> String,
+    mut on_var: F,
+  ) -> Result<usize, Error>
+  where
+    F: FnMut(ListVar<'_>),
+  {
+    let mut item_error: Option<ParseDiagnostic> = None;
+
+    let mut results = self
+      .send_streamed(core::slice::from_ref(&ups), command, |_, item| match item {
+        Ok(var) if item_error.is_none() => on_var(var),
+        Ok(_) => {}
+        Err(diagnostic) => _ = item_error.get_or_insert(diagnostic),
+      })
+      .await?;
+
+    match item_error {
+      Some(diagnostic) => Err(
+        ErrorKind::ParseError {
+          inner: diagnostic.error,
+          position: diagnostic.position,
+        }
+        .into(),
+      ),
+      None => results
+        .pop()
+        .unwrap_or(Err(ErrorKind::EmptyResponse.into())),
+    }
+  }
 
-      Ok(response_buf)
+  async fn send_streamed<D, F>(
+    &mut self,
+    devices: &[D],
+    command: fn(&UpsName) -> String,
+    on_item: F,
+  ) -> Result<Vec<Result<usize, Error>>, Error>
+  where
+    D: Borrow<UpsName>,
+    F: FnMut(usize, Result<ListVar<'_>, ParseDiagnostic>),
+  {
+    match timeout(
+      self.timeout,
+      self.inner_send_streamed(devices, command, on_item),
+    )
+    .await
+    {
+      Ok(r) => r,
+      Err(_) => {
+        self.protocol.reset();
+        Err(ErrorKind::RequestTimeout.into())
+      }
     }
   }
 
+  async fn inner_send_streamed<D, F>(
+    &mut self,
+    devices: &[D],
+    command: fn(&UpsName) -> String,
+    mut on_item: F,
+  ) -> Result<Vec<Result<usize, Error>>, Error>
+  where
+    D: Borrow<UpsName>,
+    F: FnMut(usize, Result<ListVar<'_>, ParseDiagnostic>),
+  {
+    for ups in devices {
+      let command = command(ups.borrow());
+
+      trace!(message = "tcp streamed list message", send = command);
+      self.protocol.send(&command);
+    }
+
+    self.flush_outgoing().await?;
+
+    let mut results: Vec<Result<usize, Error>> = Vec::with_capacity(devices.len());
+    let mut count: usize = 0;
+    let mut line_number: usize = 0;
+
+    while results.len() < devices.len() {
+      let received = self.reader.fill_buf().await?;
+      let len = received.len();
+
+      if len == 0 {
+        self.protocol.reset();
+        return Err(ErrorKind::EmptyResponse.into());
+      }
+
+      // Lines are parsed straight from the reader's buffer. Only bytes up to the end of the last
+      // response are consumed, so the buffer is not copied and nothing is read ahead of it.
+      let mut input = received;
+
+      while results.len() < devices.len() {
+        let index = results.len();
+
+        let event = match self.protocol.poll_list_event_from(&mut input) {
+          Ok(Some(event)) => event,
+          Ok(None) => break,
+          Err(err) if matches!(err.kind(), ErrorKind::IOError { .. }) => {
+            self.protocol.reset();
+            return Err(err);
+          }
+          Err(err) => {
+            results.push(Err(err));
+            (count, line_number) = (0, 0);
+            continue;
+          }
+        };
+
+        line_number += 1;
+
+        match event {
+          ListEvent::Begin(_) => {}
+          ListEvent::Item(line) => match line.var() {
+            Ok(var) if devices[index].borrow().eq(var.ups) => {
+              count += 1;
+              on_item(index, Ok(var));
+            }
+            result => {
+              let (inner, col) = match result {
+                Err(err) => match err.kind() {
+                  ErrorKind::ParseError { inner, position } => (inner.clone(), position.col),
+                  _ => (ParseError::InvalidToken, 0),
+                },
+                Ok(_) => (ParseError::InvalidToken, 0),
+              };
+
+              trace!(message = "skipping malformed list item", line = line.as_str(), reason = %inner);
+
+              on_item(
+                index,
+                Err(ParseDiagnostic {
+                  line: Box::from(line.as_str()),
+                  position: Position {
+                    line: line_number,
+                    col,
+                  },
+                  error: inner,
+                }),
+              );
+            }
+          },
+          ListEvent::End(_) => {
+            results.push(Ok(count));
+            (count, line_number) = (0, 0);
+          }
+        }
+      }
+
+      let consumed = len - input.len();
+      self.reader.consume(consumed);
+    }
+
+    Ok(results)
+  }
+
   pub(crate) async fn send<C, R>(&mut self, command: C) -> Result<R, Error>
   where
     R: Deserialize<Error = Error>,
@@ -192,6 +583,29 @@ where
     }
   }
 
+  async fn get_tracking<I>(self, id: I) -> Result<responses::TrackingStatus, Error>
+  where
+    I: AsRef<str>,
+  {
+    let command = commands::GetTracking { id: id.as_ref() }.serialize();
+
+    match self.send::<_, responses::TrackingStatus>(command).await {
+      Ok(status) => Ok(status),
+      Err(err) => match err.kind() {
+        ErrorKind::ProtocolError {
+          inner:
+            reason @ (ProtocolError::InvalidArgument
+            | ProtocolError::InstcmdFailed
+            | ProtocolError::SetFailed
+            | ProtocolError::Unknown(_)),
+        } => Ok(responses::TrackingStatus::Failed {
+          reason: reason.clone(),
+        }),
+        _ => Err(err),
+      },
+    }
+  }
+
   fn get_ups_desc<N>(self, ups: N) -> impl Future<Output = Result<responses::UpsDesc, Error>>
   where
     N: Borrow<UpsName>,
@@ -344,3 +758,13 @@ where
     self.send::<_, responses::UpsVarList>(command)
   }
 }
+
+#[inline]
+fn list_var_command(ups: &UpsName) -> String {
+  commands::ListVar { ups }.serialize()
+}
+
+#[inline]
+fn list_rw_command(ups: &UpsName) -> String {
+  commands::ListRw { ups }.serialize()
+}
@@ -0,0 +1,302 @@
+use crate::{
+  CmdName, UpsName, VarName, commands,
+  errors::{Error, ErrorKind},
+  internal::{Deserialize, Serialize, lexer::Lexer},
+  responses,
+};
+use core::{borrow::Borrow, marker::PhantomData};
+
+/// Queue of commands sent with a single write. upsd answers pipelined commands in order, so
+/// responses are matched back to their commands by position.
+#[derive(Debug, Clone, Default)]
+pub struct Batch {
+  buffer: String,
+  offsets: Vec<usize>,
+}
+
+/// Handle of a queued command, used to take its parsed response from [BatchResponses].
+#[derive(Debug)]
+pub struct BatchItem<R> {
+  index: usize,
+  parse: fn(&str) -> Result<R, Error>,
+  _response: PhantomData<fn() -> R>,
+}
+
+/// Responses of a [Batch], in the same order as the queued commands.
+#[derive(Debug)]
+pub struct BatchResponses {
+  responses: Vec<Option<Result<String, Error>>>,
+}
+
+fn parse_response<R>(response: &str) -> Result<R, Error>
+where
+  R: Deserialize<Error = Error>,
+{
+  let mut lexer = Lexer::new(response);
+  R::deserialize(&mut lexer)
+}
+
+impl Batch {
+  #[inline]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Number of queued commands.
+  #[inline]
+  pub fn len(&self) -> usize {
+    self.offsets.len()
+  }
+
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.offsets.is_empty()
+  }
+
+  /// Returns all queued commands as they are written to the connection.
+  #[inline]
+  pub fn as_str(&self) -> &str {
+    &self.buffer
+  }
+
+  /// Iterates over serialized commands one by one.
+  pub fn commands(&self) -> impl Iterator<Item = &str> + '_ {
+    let starts = core::iter::once(0).chain(self.offsets.iter().copied());
+
+    starts
+      .zip(self.offsets.iter().copied())
+      .map(|(start, end)| &self.buffer[start..end])
+  }
+
+  fn push<C, R>(&mut self, command: C, parse: fn(&str) -> Result<R, Error>) -> BatchItem<R>
+  where
+    C: AsRef<str>,
+  {
+    let index = self.offsets.len();
+
+    self.buffer.push_str(command.as_ref());
+    self.offsets.push(self.buffer.len());
+
+    BatchItem {
+      index,
+      parse,
+      _response: PhantomData,
+    }
+  }
+
+  pub fn get_cmd_desc<N, C>(&mut self, ups: N, cmd: C) -> BatchItem<responses::CmdDesc>
+  where
+    N: Borrow<UpsName>,
+    C: Borrow<CmdName>,
+  {
+    let command = commands::GetCmdDesc {
+      ups: ups.borrow(),
+      cmd: cmd.borrow(),
+    }
+    .serialize();
+
+    self.push(command, parse_response)
+  }
+
+  pub fn get_ups_desc<N>(&mut self, ups: N) -> BatchItem<responses::UpsDesc>
+  where
+    N: Borrow<UpsName>,
+  {
+    let command = commands::GetUpsDesc { ups: ups.borrow() }.serialize();
+    self.push(command, parse_response)
+  }
+
+  pub fn get_var<N, V>(&mut self, ups: N, var: V) -> BatchItem<responses::UpsVar>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+  {
+    let command = commands::GetVar {
+      ups: ups.borrow(),
+      var: var.borrow(),
+    }
+    .serialize();
+
+    self.push(command, parse_response)
+  }
+
+  pub fn get_var_type<N, V>(&mut self, ups: N, var: V) -> BatchItem<responses::UpsVarType>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+  {
+    let command = commands::GetVarType {
+      ups: ups.borrow(),
+      var: var.borrow(),
+    }
+    .serialize();
+
+    self.push(command, parse_response)
+  }
+
+  pub fn get_var_desc<N, V>(&mut self, ups: N, var: V) -> BatchItem<responses::UpsVarDesc>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+  {
+    let command = commands::GetVarDesc {
+      ups: ups.borrow(),
+      var: var.borrow(),
+    }
+    .serialize();
+
+    self.push(command, parse_response)
+  }
+
+  pub fn list_client<N>(&mut self, ups: N) -> BatchItem<responses::ClientList>
+  where
+    N: Borrow<UpsName>,
+  {
+    let command = commands::ListClient { ups: ups.borrow() }.serialize();
+    self.push(command, parse_response)
+  }
+
+  pub fn list_cmd<N>(&mut self, ups: N) -> BatchItem<Vec<String>>
+  where
+    N: Borrow<UpsName>,
+  {
+    let command = commands::ListCmd { ups: ups.borrow() }.serialize();
+
+    self.push(command, |response| {
+      let result: responses::CmdList = parse_response(response)?;
+
+      Ok(
+        result
+          .cmds
+          .into_iter()
+          .map(|c| c.into_boxed_str().into())
+          .collect(),
+      )
+    })
+  }
+
+  /// Same as [Batch::list_cmd], but skips malformed `CMD` lines and reports them as diagnostics.
+  pub fn list_cmd_lenient<N>(&mut self, ups: N) -> BatchItem<responses::Lenient<responses::CmdList>>
+  where
+    N: Borrow<UpsName>,
+  {
+    let command = commands::ListCmd { ups: ups.borrow() }.serialize();
+    self.push(command, parse_response)
+  }
+
+  pub fn list_enum<N, V>(&mut self, ups: N, var: V) -> BatchItem<responses::EnumList>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+  {
+    let command = commands::ListEnum {
+      ups: ups.borrow(),
+      var: var.borrow(),
+    }
+    .serialize();
+
+    self.push(command, parse_response)
+  }
+
+  /// Same as [Batch::list_enum], but skips malformed `ENUM` lines and reports them as diagnostics.
+  pub fn list_enum_lenient<N, V>(
+    &mut self,
+    ups: N,
+    var: V,
+  ) -> BatchItem<responses::Lenient<responses::EnumList>>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+  {
+    let command = commands::ListEnum {
+      ups: ups.borrow(),
+      var: var.borrow(),
+    }
+    .serialize();
+
+    self.push(command, parse_response)
+  }
+
+  pub fn list_range<N, V>(&mut self, ups: N, var: V) -> BatchItem<responses::RangeList>
+  where
+    N: Borrow<UpsName>,
+    V: Borrow<VarName>,
+  {
+    let command = commands::ListRange {
+      ups: ups.borrow(),
+      var: var.borrow(),
+    }
+    .serialize();
+
+    self.push(command, parse_response)
+  }
+
+  pub fn list_rw<N>(&mut self, ups: N) -> BatchItem<responses::RwList>
+  where
+    N: Borrow<UpsName>,
+  {
+    let command = commands::ListRw { ups: ups.borrow() }.serialize();
+    self.push(command, parse_response)
+  }
+
+  /// Same as [Batch::list_rw], but skips malformed `RW` lines and reports them as diagnostics.
+  pub fn list_rw_lenient<N>(&mut self, ups: N) -> BatchItem<responses::Lenient<responses::RwList>>
+  where
+    N: Borrow<UpsName>,
+  {
+    let command = commands::ListRw { ups: ups.borrow() }.serialize();
+    self.push(command, parse_response)
+  }
+
+  pub fn list_ups(&mut self) -> BatchItem<responses::UpsList> {
+    self.push(commands::ListUps.serialize(), parse_response)
+  }
+
+  pub fn list_var<N>(&mut self, ups: N) -> BatchItem<responses::UpsVarList>
+  where
+    N: Borrow<UpsName>,
+  {
+    let command = commands::ListVar { ups: ups.borrow() }.serialize();
+    self.push(command, parse_response)
+  }
+
+  /// Same as [Batch::list_var], but skips malformed `VAR` lines and reports them as diagnostics.
+  pub fn list_var_lenient<N>(
+    &mut self,
+    ups: N,
+  ) -> BatchItem<responses::Lenient<responses::UpsVarList>>
+  where
+    N: Borrow<UpsName>,
+  {
+    let command = commands::ListVar { ups: ups.borrow() }.serialize();
+    self.push(command, parse_response)
+  }
+}
+
+impl BatchResponses {
+  pub(super) fn new(responses: Vec<Result<String, Error>>) -> Self {
+    Self {
+      responses: responses.into_iter().map(Some).collect(),
+    }
+  }
+
+  #[inline]
+  pub fn len(&self) -> usize {
+    self.responses.len()
+  }
+
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.responses.is_empty()
+  }
+
+  /// Takes and parses the response of a queued command. Returns the upsd protocol error when the
+  /// command failed, or [ErrorKind::EmptyResponse] when the item does not belong to this batch.
+  pub fn take<R>(&mut self, item: BatchItem<R>) -> Result<R, Error> {
+    match self.responses.get_mut(item.index).and_then(Option::take) {
+      Some(Ok(response)) => (item.parse)(&response),
+      Some(Err(err)) => Err(err),
+      None => Err(ErrorKind::EmptyResponse.into()),
+    }
+  }
+}
@@ -0,0 +1,178 @@
+use super::NutClient;
+use crate::{
+  CmdName, UpsName, commands,
+  errors::{Error, ErrorKind, ProtocolError},
+  internal::Serialize,
+};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tracing::debug;
+
+/// Placeholder device used by probe commands. upsd validates the command before the device, so
+/// known commands fail with `UNKNOWN-UPS` instead of `UNKNOWN-COMMAND` or `INVALID-ARGUMENT`.
+const PROBE_UPS: &str = "upsmc-capability-probe";
+
+/// Support state of an optional upsd command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Support {
+  /// Not probed yet, or probe failed with an unexpected error.
+  #[default]
+  Unknown,
+  Supported,
+  /// Command is recognized but disabled by upsd configuration, e.g. `STARTTLS` without a
+  /// certificate.
+  NotConfigured,
+  Unsupported,
+}
+
+/// Optional commands supported by a upsd instance, see [NutClient::probe_capabilities].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Capabilities {
+  pub list_client: Support,
+  pub get_tracking: Support,
+  pub cmd_desc: Support,
+  /// `PRIMARY` upsmon login, older upsd versions only accept `MASTER`.
+  pub primary: Support,
+  pub start_tls: Support,
+}
+
+impl Support {
+  /// Returns `false` only when the command is known to be unsupported, unknown state is treated as
+  /// supported so callers keep trying.
+  #[inline]
+  pub const fn is_available(&self) -> bool {
+    matches!(self, Self::Supported | Self::Unknown)
+  }
+
+  pub const fn as_str(&self) -> &'static str {
+    match self {
+      Support::Unknown => "unknown",
+      Support::Supported => "supported",
+      Support::NotConfigured => "not_configured",
+      Support::Unsupported => "unsupported",
+    }
+  }
+
+  /// Classifies probe response. Any upsd answer other than an unrecognized command means the
+  /// command exists.
+  fn from_probe(result: &Result<String, Error>) -> Self {
+    match result {
+      Ok(_) => Support::Supported,
+      Err(err) => match err.kind() {
+        ErrorKind::ProtocolError { inner } => match inner {
+          ProtocolError::UnknownCommand | ProtocolError::InvalidArgument => Support::Unsupported,
+          ProtocolError::FeatureNotConfigured => Support::NotConfigured,
+          ProtocolError::FeatureNotSupported => Support::Unsupported,
+          // UNKNOWN-UPS, ACCESS-DENIED, TLS-ALREADY-ENABLED etc.
+          _ => Support::Supported,
+        },
+        _ => Support::Unknown,
+      },
+    }
+  }
+}
+
+impl Capabilities {
+  /// upsmon login command accepted by upsd.
+  #[inline]
+  pub const fn login_command(&self) -> &'static str {
+    match self.primary {
+      Support::Unsupported => "MASTER",
+      _ => "PRIMARY",
+    }
+  }
+}
+
+impl std::fmt::Display for Support {
+  #[inline]
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+impl<S> NutClient<S>
+where
+  S: AsyncRead + AsyncWrite + Unpin,
+{
+  /// Detects optional commands supported by upsd. Probe commands target a placeholder device, so
+  /// they have no side effects, except `STARTTLS` which is sent last. The connection may expect
+  /// a TLS handshake afterwards, so it's closed once the probe completes.
+  ///
+  /// Connection errors and timeouts fail the probe, since remaining results would be unreliable.
+  pub async fn probe_capabilities(mut self) -> Result<Capabilities, Error> {
+    let ups = UpsName::new_unchecked(PROBE_UPS);
+    let cmd = CmdName::new_unchecked("probe");
+
+    let list_client = self
+      .probe(commands::ListClient { ups: &ups }.serialize())
+      .await?;
+    let get_tracking = self
+      .probe(commands::GetTracking { id: PROBE_UPS }.serialize())
+      .await?;
+    let cmd_desc = self
+      .probe(
+        commands::GetCmdDesc {
+          ups: &ups,
+          cmd: &cmd,
+        }
+        .serialize(),
+      )
+      .await?;
+    let primary = self
+      .probe(commands::Primary { ups: &ups }.serialize())
+      .await?;
+    let start_tls = self.probe(commands::StartTls.serialize()).await?;
+
+    _ = self.close().await;
+
+    let capabilities = Capabilities {
+      list_client,
+      get_tracking,
+      cmd_desc,
+      primary,
+      start_tls,
+    };
+
+    debug!(message = "upsd capabilities detected", capabilities = ?capabilities);
+
+    Ok(capabilities)
+  }
+
+  async fn probe<C>(&mut self, command: C) -> Result<Support, Error>
+  where
+    C: AsRef<str>,
+  {
+    match self.send_raw(command.as_ref()).await {
+      Err(err) if !matches!(err.kind(), ErrorKind::ProtocolError { .. }) => Err(err),
+      result => Ok(Support::from_probe(&result)),
+    }
+  }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Support {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer,
+  {
+    serializer.serialize_str(self.as_str())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Capabilities {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer,
+  {
+    use serde::ser::SerializeStruct;
+
+    let mut s = serializer.serialize_struct("Capabilities", 6)?;
+    s.serialize_field("list_client", &self.list_client)?;
+    s.serialize_field("get_tracking", &self.get_tracking)?;
+    s.serialize_field("cmd_desc", &self.cmd_desc)?;
+    s.serialize_field("primary", &self.primary)?;
+    s.serialize_field("start_tls", &self.start_tls)?;
+    s.serialize_field("login_command", self.login_command())?;
+    s.end()
+  }
+}
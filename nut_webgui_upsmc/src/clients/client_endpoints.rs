@@ -0,0 +1,217 @@
+use crate::errors::Error;
+use core::{
+  sync::atomic::{AtomicUsize, Ordering},
+  time::Duration,
+};
+use std::{
+  sync::Mutex,
+  time::{Instant, SystemTime},
+};
+use tracing::{info, warn};
+
+/// Controls when [super::NutPoolClient] returns to a higher priority endpoint after a failover.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailbackPolicy {
+  /// Keeps using the active endpoint until it fails.
+  Never,
+
+  /// Probes higher priority endpoints at most once per given duration, and switches back to the
+  /// first one answering requests. Probing runs on [super::NutPoolClient::try_failback] calls.
+  After(Duration),
+}
+
+impl Default for FailbackPolicy {
+  #[inline]
+  fn default() -> Self {
+    Self::After(Duration::from_secs(30))
+  }
+}
+
+/// Health snapshot of a pool endpoint.
+#[derive(Debug, Clone)]
+pub struct EndpointHealth<A> {
+  pub addr: A,
+
+  /// Endpoint is used for new connections.
+  pub is_active: bool,
+
+  /// Number of failed connection attempts or requests since the last successful connection.
+  pub consecutive_failures: u32,
+
+  pub last_error: Option<Box<str>>,
+  pub last_failure: Option<SystemTime>,
+}
+
+impl<A> EndpointHealth<A> {
+  #[inline]
+  pub fn is_healthy(&self) -> bool {
+    self.consecutive_failures == 0
+  }
+}
+
+#[derive(Debug, Default)]
+struct HealthState {
+  consecutive_failures: u32,
+  last_error: Option<Box<str>>,
+  last_failure: Option<SystemTime>,
+}
+
+#[derive(Debug)]
+struct Endpoint<A> {
+  addr: A,
+  health: Mutex<HealthState>,
+}
+
+/// Ordered upsd endpoint list, first endpoint has the highest priority.
+#[derive(Debug)]
+pub(super) struct Endpoints<A> {
+  entries: Box<[Endpoint<A>]>,
+  active: AtomicUsize,
+  failback: FailbackPolicy,
+  last_failback_probe: Mutex<Option<Instant>>,
+}
+
+impl<A> Endpoints<A> {
+  pub fn new(addrs: Vec<A>, failback: FailbackPolicy) -> Self {
+    assert!(!addrs.is_empty(), "endpoint list cannot be empty");
+
+    let entries = addrs
+      .into_iter()
+      .map(|addr| Endpoint {
+        addr,
+        health: Mutex::new(HealthState::default()),
+      })
+      .collect();
+
+    Self {
+      entries,
+      active: AtomicUsize::new(0),
+      failback,
+      last_failback_probe: Mutex::new(None),
+    }
+  }
+
+  #[inline]
+  pub fn active(&self) -> usize {
+    self.active.load(Ordering::Acquire)
+  }
+
+  #[inline]
+  pub fn addr(&self, idx: usize) -> &A {
+    &self.entries[idx].addr
+  }
+
+  /// Connection order for new connections, starting from the active endpoint.
+  pub fn connect_order(&self) -> impl Iterator<Item = usize> {
+    let active = self.active();
+    let len = self.entries.len();
+
+    (0..len).map(move |offset| (active + offset) % len)
+  }
+
+  /// Returns the higher priority endpoints that should be probed for failback. Each call resets
+  /// the probe timer, so the list is only returned once per failback duration.
+  pub fn failback_candidates(&self) -> core::ops::Range<usize> {
+    let active = self.active();
+
+    let delay = match self.failback {
+      FailbackPolicy::After(delay) if active > 0 => delay,
+      _ => return 0..0,
+    };
+
+    let Ok(mut last_probe) = self.last_failback_probe.lock() else {
+      return 0..0;
+    };
+
+    match *last_probe {
+      Some(probe) if probe.elapsed() < delay => 0..0,
+      _ => {
+        *last_probe = Some(Instant::now());
+        0..active
+      }
+    }
+  }
+
+  pub fn report_success(&self, idx: usize)
+  where
+    A: core::fmt::Debug,
+  {
+    if let Ok(mut health) = self.entries[idx].health.lock() {
+      health.consecutive_failures = 0;
+    }
+
+    let previous = self.active.swap(idx, Ordering::AcqRel);
+
+    if previous != idx {
+      info!(
+        message = "upsd endpoint switched",
+        from = ?self.entries[previous].addr,
+        to = ?self.entries[idx].addr
+      );
+
+      if let Ok(mut last_probe) = self.last_failback_probe.lock() {
+        *last_probe = Some(Instant::now());
+      }
+    }
+  }
+
+  /// Records a failure, and moves the active endpoint to the next one if the failed endpoint was
+  /// active.
+  pub fn report_failure(&self, idx: usize, err: &Error)
+  where
+    A: core::fmt::Debug,
+  {
+    if let Ok(mut health) = self.entries[idx].health.lock() {
+      health.consecutive_failures = health.consecutive_failures.saturating_add(1);
+      health.last_error = Some(err.to_string().into_boxed_str());
+      health.last_failure = Some(SystemTime::now());
+    }
+
+    let next = (idx + 1) % self.entries.len();
+
+    if next != idx
+      && self
+        .active
+        .compare_exchange(idx, next, Ordering::AcqRel, Ordering::Acquire)
+        .is_ok()
+    {
+      warn!(
+        message = "upsd endpoint failed, switching to next endpoint",
+        failed = ?self.entries[idx].addr,
+        next = ?self.entries[next].addr,
+        reason = %err
+      );
+    }
+  }
+
+  pub fn health(&self) -> Vec<EndpointHealth<A>>
+  where
+    A: Clone,
+  {
+    let active = self.active();
+
+    self
+      .entries
+      .iter()
+      .enumerate()
+      .map(|(idx, endpoint)| {
+        let (consecutive_failures, last_error, last_failure) = match endpoint.health.lock() {
+          Ok(health) => (
+            health.consecutive_failures,
+            health.last_error.clone(),
+            health.last_failure,
+          ),
+          Err(_) => (0, None, None),
+        };
+
+        EndpointHealth {
+          addr: endpoint.addr.clone(),
+          is_active: idx == active,
+          consecutive_failures,
+          last_error,
+          last_failure,
+        }
+      })
+      .collect()
+  }
+}
@@ -1,33 +1,133 @@
-use super::{AsyncNutClient, NutClient};
+use super::{
+  AsyncNutClient, Capabilities, NutAddr, NutClient, NutStream, ToNutAddr,
+  client_auth_pool::{AuthClientAllocator, NutAuthPoolClient},
+  client_batch::{Batch, BatchResponses},
+  client_endpoints::{EndpointHealth, Endpoints, FailbackPolicy},
+  client_stats::{CommandMetrics, PoolStats},
+};
 use crate::{
-  CmdName, UpsName, VarName,
-  errors::{Error, ErrorKind},
-  internal::item_pool::{ItemAllocator, ItemPool, ItemPoolError},
+  CmdName, RwDetail, UpsName, VarName,
+  errors::{Error, ErrorKind, ParseDiagnostic},
+  internal::item_pool::{ItemAllocator, ItemPool, ItemPoolConfig, ItemPoolError, PoolGuard},
+  protocol::ListVar,
   responses,
 };
-use core::{num::NonZeroUsize, time::Duration};
-use std::net::ToSocketAddrs;
-use tokio::net::TcpStream;
+use core::{
+  fmt::Debug,
+  num::NonZeroUsize,
+  ops::{Deref, DerefMut},
+  time::Duration,
+};
+use std::{sync::Arc, time::Instant};
 use tracing::warn;
 
-pub struct ClientAllocator<A>
+#[cfg(feature = "tls")]
+use crate::{errors::TlsError, tls::TlsConfig};
+
+/// Connection settings and endpoint list, shared by the pools created from the same builder so
+/// they fail over together.
+pub(super) struct Connector<A>
 where
-  A: ToSocketAddrs + Send + Sync + 'static,
+  A: ToNutAddr + Debug + Send + Sync + 'static,
 {
-  addr: A,
+  pub endpoints: Endpoints<A>,
+  connect_timeout: Option<Duration>,
   timeout: Option<Duration>,
+  #[cfg(feature = "tls")]
+  tls: Option<TlsConfig>,
+  /// Endpoint specific TLS settings, same order as endpoints. Overrides `tls` when it's set.
+  #[cfg(feature = "tls")]
+  endpoint_tls: Box<[Option<TlsConfig>]>,
 }
 
-impl<A> ItemAllocator for ClientAllocator<A>
+pub struct ClientAllocator<A>
 where
-  A: ToSocketAddrs + Send + Sync + 'static,
+  A: ToNutAddr + Debug + Send + Sync + 'static,
 {
-  type Output = NutClient<TcpStream>;
-  type Error = Error;
+  connector: Arc<Connector<A>>,
+  metrics: CommandMetrics,
+  /// Probed capabilities with the endpoint index they belong to.
+  capabilities: std::sync::Mutex<Option<(usize, Capabilities)>>,
+}
 
-  async fn init(&self) -> Result<Self::Output, Self::Error> {
-    let addr: Vec<_> = self.addr.to_socket_addrs()?.collect();
-    let mut client = NutClient::connect(addr.as_slice()).await?;
+/// Pooled connection with the index of the endpoint it's connected to.
+pub struct PooledClient {
+  client: NutClient<NutStream>,
+  endpoint: usize,
+}
+
+impl Deref for PooledClient {
+  type Target = NutClient<NutStream>;
+
+  #[inline]
+  fn deref(&self) -> &Self::Target {
+    &self.client
+  }
+}
+
+impl DerefMut for PooledClient {
+  #[inline]
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    &mut self.client
+  }
+}
+
+/// Errors caused by a broken or unresponsive connection rather than the request itself.
+#[inline]
+pub(super) fn is_connection_error(err: &Error) -> bool {
+  matches!(
+    err.kind(),
+    ErrorKind::IOError { .. } | ErrorKind::EmptyResponse | ErrorKind::RequestTimeout
+  )
+}
+
+impl<A> Connector<A>
+where
+  A: ToNutAddr + Debug + Send + Sync + 'static,
+{
+  /// Connects to the endpoint, connection attempt is bounded by the connect timeout, or by the
+  /// request timeout when not set, so an unresponsive endpoint does not block failover.
+  async fn connect(&self, idx: usize) -> Result<NutClient<NutStream>, Error> {
+    let addr = self.endpoints.addr(idx).to_nut_addr()?;
+
+    #[cfg(feature = "tls")]
+    let tls = self.endpoint_tls[idx].as_ref().or(self.tls.as_ref());
+
+    let connect = async {
+      let stream = match &addr {
+        #[cfg(feature = "tls")]
+        NutAddr::Tcp(addr) => match tls {
+          Some(tls) => NutStream::connect_tls(addr.as_slice(), tls).await?,
+          None => NutStream::connect(addr.as_slice()).await?,
+        },
+        #[cfg(not(feature = "tls"))]
+        NutAddr::Tcp(addr) => NutStream::connect(addr.as_slice()).await?,
+        #[cfg(unix)]
+        NutAddr::Unix(path) => {
+          // STARTTLS is only supported on TCP, refuse instead of silently falling back to plaintext.
+          #[cfg(feature = "tls")]
+          if tls.is_some() {
+            return Err(
+              TlsError::Config("STARTTLS is not supported on unix domain sockets".into()).into(),
+            );
+          }
+
+          NutStream::connect_unix(path).await?
+        }
+      };
+
+      Ok::<_, Error>(stream)
+    };
+
+    let stream = match self.connect_timeout.or(self.timeout) {
+      Some(timeout) => match tokio::time::timeout(timeout, connect).await {
+        Ok(stream) => stream?,
+        Err(_) => return Err(ErrorKind::RequestTimeout.into()),
+      },
+      None => connect.await?,
+    };
+
+    let mut client = NutClient::new(stream);
 
     if let Some(timeout) = self.timeout {
       client.set_timeout(timeout);
@@ -36,15 +136,90 @@ where
     Ok(client)
   }
 
+  /// Connects to the first reachable endpoint starting from the active one, and runs `setup` on
+  /// the new connection. Connection errors move to the next endpoint, other `setup` errors are
+  /// returned immediately.
+  pub async fn connect_active<T, F, Fut>(&self, setup: F) -> Result<(T, usize), Error>
+  where
+    F: Fn(NutClient<NutStream>) -> Fut,
+    Fut: Future<Output = Result<T, Error>>,
+  {
+    let mut last_error = None;
+
+    for idx in self.endpoints.connect_order() {
+      match self.connect(idx).await {
+        Ok(client) => match setup(client).await {
+          Ok(item) => {
+            self.endpoints.report_success(idx);
+            return Ok((item, idx));
+          }
+          Err(err) if is_connection_error(&err) => {
+            self.endpoints.report_failure(idx, &err);
+            last_error = Some(err);
+          }
+          Err(err) => return Err(err),
+        },
+        Err(err) => {
+          self.endpoints.report_failure(idx, &err);
+          last_error = Some(err);
+        }
+      }
+    }
+
+    Err(last_error.unwrap_or_else(|| std::io::ErrorKind::NotConnected.into()))
+  }
+
+  /// Probes higher priority endpoints when failback is due, and returns true when the active
+  /// endpoint is switched. An endpoint is only considered healthy after it answers `PROTVER`, an
+  /// accepted connection alone may belong to a hung upsd.
+  pub async fn try_failback(&self) -> bool {
+    for idx in self.endpoints.failback_candidates() {
+      let probe = async {
+        let mut client = self.connect(idx).await?;
+        let result = client.get_protver().await;
+        _ = client.close().await;
+
+        result
+      };
+
+      match probe.await {
+        Ok(_) => {
+          self.endpoints.report_success(idx);
+          return true;
+        }
+        Err(err) => self.endpoints.report_failure(idx, &err),
+      }
+    }
+
+    false
+  }
+}
+
+impl<A> ItemAllocator for ClientAllocator<A>
+where
+  A: ToNutAddr + Debug + Send + Sync + 'static,
+{
+  type Output = PooledClient;
+  type Error = Error;
+
+  async fn init(&self) -> Result<Self::Output, Self::Error> {
+    let (client, endpoint) = self
+      .connector
+      .connect_active(|client| async { Ok(client) })
+      .await?;
+
+    Ok(PooledClient { client, endpoint })
+  }
+
   async fn dealloc(&self, item: Self::Output) {
-    if let Err(err) = item.close().await {
+    if let Err(err) = item.client.close().await {
       warn!(message = "unable to close a connection in pool", error = %err);
     }
   }
 
-  #[inline]
-  fn is_valid_state(&self, item: &mut Self::Output) -> impl Future<Output = bool> {
-    item.is_open()
+  /// Connections to an inactive endpoint are discarded after a failover or failback.
+  async fn is_valid_state(&self, item: &mut Self::Output) -> bool {
+    item.endpoint == self.connector.endpoints.active() && item.client.is_open().await
   }
 }
 
@@ -60,14 +235,31 @@ impl From<ItemPoolError<Error>> for Error {
 
 pub struct NutPoolClient<A>
 where
-  A: ToSocketAddrs + Send + Sync + 'static,
+  A: ToNutAddr + Debug + Send + Sync + 'static,
 {
-  pool: ItemPool<NutClient<TcpStream>, ClientAllocator<A>>,
+  pool: ItemPool<PooledClient, ClientAllocator<A>>,
+}
+
+/// Builder for [NutPoolClient] with optional connection settings.
+pub struct NutPoolClientBuilder<A>
+where
+  A: ToNutAddr + Debug + Send + Sync + 'static,
+{
+  endpoints: Vec<A>,
+  failback: FailbackPolicy,
+  limit: NonZeroUsize,
+  pool_config: ItemPoolConfig,
+  connect_timeout: Option<Duration>,
+  timeout: Option<Duration>,
+  #[cfg(feature = "tls")]
+  tls: Option<TlsConfig>,
+  #[cfg(feature = "tls")]
+  endpoint_tls: Vec<Option<TlsConfig>>,
 }
 
 impl<A> Clone for NutPoolClient<A>
 where
-  A: ToSocketAddrs + Send + Sync + 'static,
+  A: ToNutAddr + Debug + Send + Sync + 'static,
 {
   #[inline]
   fn clone(&self) -> Self {
@@ -77,28 +269,43 @@ where
   }
 }
 
-unsafe impl<A> Send for NutPoolClient<A> where A: ToSocketAddrs + Send + Sync + 'static {}
-unsafe impl<A> Sync for NutPoolClient<A> where A: ToSocketAddrs + Send + Sync + 'static {}
+unsafe impl<A> Send for NutPoolClient<A> where A: ToNutAddr + Debug + Send + Sync + 'static {}
+unsafe impl<A> Sync for NutPoolClient<A> where A: ToNutAddr + Debug + Send + Sync + 'static {}
 
 macro_rules! impl_pooled_call {
-  ($pool:expr, $fn:ident $( , $($args:expr),+ )?) => {{
-    let mut client = $pool.get().await?;
+  ($client_pool:expr, $fn:ident $( , $($args:expr),+ )?) => {{
+    let started = Instant::now();
+    let result = impl_pooled_call!(@call $client_pool, $fn $(, $($args),+)?);
 
-    match impl_pooled_call!(@action client, $fn $(, $($args),+)?) {
-      Ok(res) => Ok(res),
-      Err(err) => {
-        match err.kind() {
-          ErrorKind::IOError { .. } | ErrorKind::EmptyResponse | ErrorKind::RequestTimeout => {
-            let mut client = $pool.get_checked().await?;
-            impl_pooled_call!(@action client, $fn $(, $($args),+)?)
+    $client_pool
+      .pool
+      .allocator()
+      .metrics
+      .record(stringify!($fn), started.elapsed(), result.is_err());
+
+    result
+  }};
+
+  (@call $client_pool:expr, $fn:ident $( , $($args:expr),+ )?) => {
+    async {
+      let mut client = $client_pool.get_client().await?;
+
+      match impl_pooled_call!(@action $client_pool, client, false, $fn $(, $($args),+)?) {
+        Ok(res) => Ok(res),
+        Err(err) => {
+          match err.kind() {
+            ErrorKind::IOError { .. } | ErrorKind::EmptyResponse | ErrorKind::RequestTimeout => {
+              let mut client = $client_pool.pool.get_checked().await?;
+              impl_pooled_call!(@action $client_pool, client, true, $fn $(, $($args),+)?)
+            }
+            _ => Err(err)
           }
-          _ => Err(err)
         }
       }
-    }
-  }};
+    }.await
+  };
 
-  (@action $client:expr, $fn:ident $( , $($args:expr),+ )?) => {{
+  (@action $client_pool:expr, $client:expr, $report_failure:expr, $fn:ident $( , $($args:expr),+ )?) => {{
     match $client.$fn($($($args),+)?).await {
       Ok(result) => {
         _ = $client.release().await;
@@ -107,6 +314,10 @@ macro_rules! impl_pooled_call {
       Err(err) => {
         match err.kind() {
           ErrorKind::IOError { .. } | ErrorKind::ConnectionPoolClosed | ErrorKind::EmptyResponse | ErrorKind::RequestTimeout => {
+          if $report_failure {
+            $client_pool.pool.allocator().connector.endpoints.report_failure($client.endpoint, &err);
+          }
+
           drop($client);
         }
           _ => {
@@ -123,32 +334,176 @@ macro_rules! impl_pooled_call {
 
 impl<A> NutPoolClient<A>
 where
-  A: ToSocketAddrs + Send + Sync + 'static,
+  A: ToNutAddr + Debug + Send + Sync + 'static,
 {
+  #[inline]
   pub fn new(addr: A, limit: NonZeroUsize) -> Self {
-    Self {
-      pool: ItemPool::new(
-        limit,
-        ClientAllocator {
-          addr,
-          timeout: None,
-        },
-      ),
-    }
+    Self::builder(addr, limit).build()
   }
 
+  #[inline]
   pub fn new_with_timeout(addr: A, limit: NonZeroUsize, timeout: Duration) -> Self {
-    Self {
-      pool: ItemPool::new(
-        limit,
-        ClientAllocator {
-          addr,
-          timeout: Some(timeout),
-        },
-      ),
+    Self::builder(addr, limit).with_timeout(timeout).build()
+  }
+
+  pub fn builder(addr: A, limit: NonZeroUsize) -> NutPoolClientBuilder<A> {
+    NutPoolClientBuilder {
+      endpoints: vec![addr],
+      failback: FailbackPolicy::default(),
+      limit,
+      pool_config: ItemPoolConfig::default(),
+      connect_timeout: None,
+      timeout: None,
+      #[cfg(feature = "tls")]
+      tls: None,
+      #[cfg(feature = "tls")]
+      endpoint_tls: vec![None],
     }
   }
 
+  /// Returns the endpoint used for new connections.
+  #[inline]
+  pub fn active_endpoint(&self) -> &A {
+    let endpoints = &self.pool.allocator().connector.endpoints;
+    endpoints.addr(endpoints.active())
+  }
+
+  /// Returns health snapshots of all endpoints, in priority order.
+  #[inline]
+  pub fn endpoints(&self) -> Vec<EndpointHealth<A>>
+  where
+    A: Clone,
+  {
+    self.pool.allocator().connector.endpoints.health()
+  }
+
+  /// Sends all commands in the batch with a single write on one pooled connection. See
+  /// [NutClient::send_batch].
+  pub async fn send_batch(&self, batch: &Batch) -> Result<BatchResponses, Error> {
+    impl_pooled_call!(self, send_batch, batch)
+  }
+
+  /// Loads type details of a writable variable on one pooled connection. See
+  /// [NutClient::get_rw_detail].
+  pub async fn get_rw_detail<N, V>(
+    &self,
+    ups: N,
+    var: V,
+  ) -> Result<responses::Lenient<RwDetail>, Error>
+  where
+    N: std::borrow::Borrow<UpsName>,
+    V: std::borrow::Borrow<VarName>,
+  {
+    impl_pooled_call!(self, get_rw_detail, ups.borrow(), var.borrow())
+  }
+
+  /// Sends `LIST VAR` on one pooled connection, see [NutClient::list_var_streamed]. When the
+  /// first attempt fails with an I/O error, the request is retried on a validated connection and
+  /// `on_var` may receive the same variables again.
+  pub async fn list_var_streamed<N, F>(&self, ups: N, mut on_var: F) -> Result<usize, Error>
+  where
+    N: std::borrow::Borrow<UpsName>,
+    F: FnMut(ListVar<'_>),
+  {
+    impl_pooled_call!(self, list_var_streamed, ups.borrow(), &mut on_var)
+  }
+
+  /// Same as [NutPoolClient::list_var_streamed] for `LIST RW`.
+  pub async fn list_rw_streamed<N, F>(&self, ups: N, mut on_var: F) -> Result<usize, Error>
+  where
+    N: std::borrow::Borrow<UpsName>,
+    F: FnMut(ListVar<'_>),
+  {
+    impl_pooled_call!(self, list_rw_streamed, ups.borrow(), &mut on_var)
+  }
+
+  /// Streams `LIST VAR` of all devices with a single write on one pooled connection, see
+  /// [NutClient::list_vars_streamed]. When the first attempt fails with an I/O error, the request
+  /// is retried on a validated connection and `on_item` may receive the same items again.
+  pub async fn list_vars_streamed<F>(
+    &self,
+    devices: &[UpsName],
+    mut on_item: F,
+  ) -> Result<Vec<Result<usize, Error>>, Error>
+  where
+    F: FnMut(usize, Result<ListVar<'_>, ParseDiagnostic>),
+  {
+    impl_pooled_call!(self, list_vars_streamed, devices, &mut on_item)
+  }
+
+  /// Returns capabilities of the active endpoint. Capabilities are probed once on a dedicated
+  /// connection and reused until the active endpoint changes. See [NutClient::probe_capabilities].
+  pub async fn capabilities(&self) -> Result<Capabilities, Error> {
+    let allocator = self.pool.allocator();
+
+    if let Some(capabilities) = self.cached_capabilities() {
+      return Ok(capabilities);
+    }
+
+    let (capabilities, endpoint) = allocator
+      .connector
+      .connect_active(|client| client.probe_capabilities())
+      .await?;
+
+    if let Ok(mut cache) = allocator.capabilities.lock() {
+      *cache = Some((endpoint, capabilities));
+    }
+
+    Ok(capabilities)
+  }
+
+  /// Returns cached capabilities of the active endpoint without probing.
+  pub fn cached_capabilities(&self) -> Option<Capabilities> {
+    let allocator = self.pool.allocator();
+    let active = allocator.connector.endpoints.active();
+
+    match allocator.capabilities.lock().as_deref() {
+      Ok(Some((endpoint, capabilities))) if *endpoint == active => Some(*capabilities),
+      _ => None,
+    }
+  }
+
+  /// Creates a pool of connections authenticated with the given credentials, for `INSTCMD`,
+  /// `SET VAR` and `FSD` requests. Both pools share the endpoint list, so a failover detected by
+  /// either one moves the other to the same upsd.
+  pub fn auth_pool<U, P>(
+    &self,
+    username: U,
+    password: P,
+    limit: NonZeroUsize,
+  ) -> NutAuthPoolClient<A>
+  where
+    U: Into<Box<str>>,
+    P: Into<Box<str>>,
+  {
+    let connector = self.pool.allocator().connector.clone();
+    let allocator = AuthClientAllocator::new(connector, username, password);
+
+    NutAuthPoolClient::new(ItemPool::new(limit, allocator, self.pool.config()))
+  }
+
+  /// Drops cached capabilities, next [NutPoolClient::capabilities] call probes the endpoint again.
+  /// Useful when upsd is restarted, possibly with a different version.
+  pub fn reset_capabilities(&self) {
+    if let Ok(mut cache) = self.pool.allocator().capabilities.lock() {
+      *cache = None;
+    }
+  }
+
+  /// Returns a snapshot of connection pool and per-command statistics.
+  #[inline]
+  pub fn stats(&self) -> PoolStats {
+    PoolStats::new(self.pool.stats(), &self.pool.allocator().metrics)
+  }
+
+  /// Closes idle connections exceeding the configured max idle time or max lifetime, and returns
+  /// the number of closed connections. Expired connections are also closed on checkout, calling
+  /// this periodically only releases them earlier.
+  #[inline]
+  pub fn evict_expired(&self) -> impl Future<Output = usize> {
+    self.pool.evict_expired()
+  }
+
   #[inline]
   pub fn close(self) -> impl Future<Output = ()> {
     self.pool.close()
@@ -158,29 +513,204 @@ where
   pub fn clear(&mut self) -> impl Future<Output = ()> {
     self.pool.clear()
   }
+
+  /// Probes higher priority endpoints when failback is due, and returns true when the active
+  /// endpoint is switched back. Requests never probe endpoints themselves, call this periodically
+  /// from a background task to return to the primary upsd after a failover.
+  #[inline]
+  pub fn try_failback(&self) -> impl Future<Output = bool> {
+    self.pool.allocator().connector.try_failback()
+  }
+
+  /// Returns a pooled connection for the active endpoint. Connections left from a previous
+  /// endpoint are replaced with a checked one.
+  async fn get_client(&self) -> Result<PoolGuard<'_, PooledClient, ClientAllocator<A>>, Error> {
+    let connector = &self.pool.allocator().connector;
+    let client = self.pool.get().await?;
+
+    if client.endpoint == connector.endpoints.active() {
+      Ok(client)
+    } else {
+      client.discard().await;
+      Ok(self.pool.get_checked().await?)
+    }
+  }
+}
+
+impl<A> NutPoolClientBuilder<A>
+where
+  A: ToNutAddr + Debug + Send + Sync + 'static,
+{
+  /// Adds a fallback endpoint. Endpoints are tried in insertion order, and the pool switches to the
+  /// next one when the active endpoint stops answering.
+  #[inline]
+  pub fn with_endpoint(mut self, addr: A) -> Self {
+    self.endpoints.push(addr);
+    #[cfg(feature = "tls")]
+    self.endpoint_tls.push(None);
+    self
+  }
+
+  /// Adds a fallback endpoint with its own TLS settings, which are used instead of
+  /// [NutPoolClientBuilder::with_tls] for this endpoint. Typically the same config with the
+  /// endpoint's hostname, see [TlsConfig::with_server_name].
+  #[cfg(feature = "tls")]
+  #[inline]
+  pub fn with_endpoint_tls(mut self, addr: A, config: TlsConfig) -> Self {
+    self.endpoints.push(addr);
+    self.endpoint_tls.push(Some(config));
+    self
+  }
+
+  /// Sets when the pool returns to a higher priority endpoint after a failover. Default is
+  /// [FailbackPolicy::After] 30 seconds.
+  #[inline]
+  pub fn with_failback(mut self, policy: FailbackPolicy) -> Self {
+    self.failback = policy;
+    self
+  }
+
+  /// Overrides the maximum number of pooled connections.
+  #[inline]
+  pub fn with_max_connections(mut self, limit: NonZeroUsize) -> Self {
+    self.limit = limit;
+    self
+  }
+
+  /// Sets request timeout for pooled connections.
+  #[inline]
+  pub fn with_timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = Some(timeout);
+    self
+  }
+
+  /// Sets connection timeout, including the TLS upgrade. Defaults to the request timeout.
+  #[inline]
+  pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+    self.connect_timeout = Some(timeout);
+    self
+  }
+
+  /// Closes connections that stayed idle in the pool longer than the given duration.
+  #[inline]
+  pub fn with_max_idle(mut self, max_idle: Duration) -> Self {
+    self.pool_config.max_idle = Some(max_idle);
+    self
+  }
+
+  /// Closes connections older than the given duration once they are returned to the pool.
+  #[inline]
+  pub fn with_max_lifetime(mut self, max_lifetime: Duration) -> Self {
+    self.pool_config.max_lifetime = Some(max_lifetime);
+    self
+  }
+
+  /// Checks pooled connections with a `NETVER` round-trip before use, when they were idle longer
+  /// than the given duration. By default, pooled connections are used without checks and broken
+  /// ones are only replaced after a failed request.
+  #[inline]
+  pub fn with_validate_after(mut self, idle: Duration) -> Self {
+    self.pool_config.validate_after = Some(idle);
+    self
+  }
+
+  /// Upgrades every new pooled connection to TLS with `STARTTLS`. Connections failing the upgrade
+  /// are never used in plaintext.
+  #[cfg(feature = "tls")]
+  #[inline]
+  pub fn with_tls(mut self, config: TlsConfig) -> Self {
+    self.tls = Some(config);
+    self
+  }
+
+  pub fn build(self) -> NutPoolClient<A> {
+    let (limit, pool_config) = (self.limit, self.pool_config);
+    let allocator = ClientAllocator {
+      connector: self.into_connector(),
+      metrics: CommandMetrics::default(),
+      capabilities: std::sync::Mutex::new(None),
+    };
+
+    NutPoolClient {
+      pool: ItemPool::new(limit, allocator, pool_config),
+    }
+  }
+
+  /// Builds a standalone pool of connections authenticated with the given credentials, for
+  /// `INSTCMD`, `SET VAR` and `FSD` requests. Use [NutPoolClient::auth_pool] to share endpoint
+  /// state with an existing pool.
+  pub fn build_auth<U, P>(self, username: U, password: P) -> NutAuthPoolClient<A>
+  where
+    U: Into<Box<str>>,
+    P: Into<Box<str>>,
+  {
+    let (limit, pool_config) = (self.limit, self.pool_config);
+    let allocator = AuthClientAllocator::new(self.into_connector(), username, password);
+
+    NutAuthPoolClient::new(ItemPool::new(limit, allocator, pool_config))
+  }
+
+  fn into_connector(self) -> Arc<Connector<A>> {
+    Arc::new(Connector {
+      endpoints: Endpoints::new(self.endpoints, self.failback),
+      connect_timeout: self.connect_timeout,
+      timeout: self.timeout,
+      #[cfg(feature = "tls")]
+      tls: self.tls,
+      #[cfg(feature = "tls")]
+      endpoint_tls: self.endpoint_tls.into_boxed_slice(),
+    })
+  }
+}
+
+impl<A> Clone for NutPoolClientBuilder<A>
+where
+  A: ToNutAddr + Debug + Clone + Send + Sync + 'static,
+{
+  fn clone(&self) -> Self {
+    Self {
+      endpoints: self.endpoints.clone(),
+      failback: self.failback,
+      limit: self.limit,
+      pool_config: self.pool_config,
+      connect_timeout: self.connect_timeout,
+      timeout: self.timeout,
+      #[cfg(feature = "tls")]
+      tls: self.tls.clone(),
+      #[cfg(feature = "tls")]
+      endpoint_tls: self.endpoint_tls.clone(),
+    }
+  }
 }
 
 impl<A> AsyncNutClient for &NutPoolClient<A>
 where
-  A: ToSocketAddrs + Send + Sync + 'static,
+  A: ToNutAddr + Debug + Send + Sync + 'static,
 {
   async fn get_cmd_desc<N, C>(self, ups: N, cmd: C) -> Result<responses::CmdDesc, Error>
   where
     N: std::borrow::Borrow<UpsName>,
     C: std::borrow::Borrow<CmdName>,
   {
-    impl_pooled_call!(&self.pool, get_cmd_desc, ups.borrow(), cmd.borrow())
+    impl_pooled_call!(self, get_cmd_desc, ups.borrow(), cmd.borrow())
   }
 
   async fn get_protver(self) -> Result<responses::ProtVer, Error> {
-    impl_pooled_call!(&self.pool, get_protver)
+    impl_pooled_call!(self, get_protver)
+  }
+
+  async fn get_tracking<I>(self, id: I) -> Result<responses::TrackingStatus, Error>
+  where
+    I: AsRef<str>,
+  {
+    impl_pooled_call!(self, get_tracking, id.as_ref())
   }
 
   async fn get_ups_desc<N>(self, ups: N) -> Result<responses::UpsDesc, Error>
   where
     N: std::borrow::Borrow<UpsName>,
   {
-    impl_pooled_call!(&self.pool, get_ups_desc, ups.borrow())
+    impl_pooled_call!(self, get_ups_desc, ups.borrow())
   }
 
   async fn get_var<N, V>(self, ups: N, var: V) -> Result<responses::UpsVar, Error>
@@ -188,7 +718,7 @@ where
     N: std::borrow::Borrow<UpsName>,
     V: std::borrow::Borrow<VarName>,
   {
-    impl_pooled_call!(&self.pool, get_var, ups.borrow(), var.borrow())
+    impl_pooled_call!(self, get_var, ups.borrow(), var.borrow())
   }
 
   async fn get_var_type<N, V>(self, ups: N, var: V) -> Result<responses::UpsVarType, Error>
@@ -196,7 +726,7 @@ where
     N: std::borrow::Borrow<UpsName>,
     V: std::borrow::Borrow<VarName>,
   {
-    impl_pooled_call!(&self.pool, get_var_type, ups.borrow(), var.borrow())
+    impl_pooled_call!(self, get_var_type, ups.borrow(), var.borrow())
   }
 
   async fn get_var_desc<N, V>(self, ups: N, var: V) -> Result<responses::UpsVarDesc, Error>
@@ -204,25 +734,25 @@ where
     N: std::borrow::Borrow<UpsName>,
     V: std::borrow::Borrow<VarName>,
   {
-    impl_pooled_call!(&self.pool, get_var_desc, ups.borrow(), var.borrow())
+    impl_pooled_call!(self, get_var_desc, ups.borrow(), var.borrow())
   }
 
   async fn get_ver(self) -> Result<responses::DaemonVer, Error> {
-    impl_pooled_call!(&self.pool, get_ver)
+    impl_pooled_call!(self, get_ver)
   }
 
   async fn list_client<N>(self, ups: N) -> Result<responses::ClientList, Error>
   where
     N: std::borrow::Borrow<UpsName>,
   {
-    impl_pooled_call!(&self.pool, list_client, ups.borrow())
+    impl_pooled_call!(self, list_client, ups.borrow())
   }
 
   async fn list_cmd<N>(self, ups: N) -> Result<Vec<String>, Error>
   where
     N: std::borrow::Borrow<UpsName>,
   {
-    impl_pooled_call!(&self.pool, list_cmd, ups.borrow())
+    impl_pooled_call!(self, list_cmd, ups.borrow())
   }
 
   async fn list_enum<N, V>(self, ups: N, var: V) -> Result<responses::EnumList, Error>
@@ -230,7 +760,7 @@ where
     N: std::borrow::Borrow<UpsName>,
     V: std::borrow::Borrow<VarName>,
   {
-    impl_pooled_call!(&self.pool, list_enum, ups.borrow(), var.borrow())
+    impl_pooled_call!(self, list_enum, ups.borrow(), var.borrow())
   }
 
   async fn list_range<N, V>(self, ups: N, var: V) -> Result<responses::RangeList, Error>
@@ -238,24 +768,24 @@ where
     N: std::borrow::Borrow<UpsName>,
     V: std::borrow::Borrow<VarName>,
   {
-    impl_pooled_call!(&self.pool, list_range, ups.borrow(), var.borrow())
+    impl_pooled_call!(self, list_range, ups.borrow(), var.borrow())
   }
 
   async fn list_rw<N>(self, ups: N) -> Result<responses::RwList, Error>
   where
     N: std::borrow::Borrow<UpsName>,
   {
-    impl_pooled_call!(&self.pool, list_rw, ups.borrow())
+    impl_pooled_call!(self, list_rw, ups.borrow())
   }
 
   async fn list_ups(self) -> Result<responses::UpsList, Error> {
-    impl_pooled_call!(&self.pool, list_ups)
+    impl_pooled_call!(self, list_ups)
   }
 
   async fn list_var<N>(self, ups: N) -> Result<responses::UpsVarList, Error>
   where
     N: std::borrow::Borrow<UpsName>,
   {
-    impl_pooled_call!(&self.pool, list_var, ups.borrow())
+    impl_pooled_call!(self, list_var, ups.borrow())
   }
 }
@@ -0,0 +1,191 @@
+use crate::internal::item_pool::ItemPoolStats;
+use core::time::Duration;
+use std::{collections::BTreeMap, sync::Mutex};
+
+/// Upper bounds of [LatencyHistogram] buckets in milliseconds. Last bucket collects everything
+/// above the final bound.
+pub const LATENCY_BUCKETS_MS: [u64; 11] = [1, 5, 10, 25, 50, 100, 250, 500, 1000, 2500, 5000];
+
+/// Fixed bucket latency histogram.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LatencyHistogram {
+  buckets: [u64; LATENCY_BUCKETS_MS.len() + 1],
+  count: u64,
+  sum: Duration,
+  max: Duration,
+}
+
+impl LatencyHistogram {
+  pub fn record(&mut self, latency: Duration) {
+    let millis = latency.as_millis();
+    let idx = LATENCY_BUCKETS_MS
+      .iter()
+      .position(|bound| millis <= u128::from(*bound))
+      .unwrap_or(LATENCY_BUCKETS_MS.len());
+
+    self.buckets[idx] += 1;
+    self.count += 1;
+    self.sum = self.sum.saturating_add(latency);
+    self.max = self.max.max(latency);
+  }
+
+  #[inline]
+  pub fn count(&self) -> u64 {
+    self.count
+  }
+
+  #[inline]
+  pub fn sum(&self) -> Duration {
+    self.sum
+  }
+
+  #[inline]
+  pub fn max(&self) -> Duration {
+    self.max
+  }
+
+  pub fn mean(&self) -> Duration {
+    match u32::try_from(self.count) {
+      Ok(0) => Duration::ZERO,
+      Ok(count) => self.sum / count,
+      Err(_) => Duration::from_secs_f64(self.sum.as_secs_f64() / self.count as f64),
+    }
+  }
+
+  /// Returns `(upper_bound, count)` pairs for each bucket. Upper bound of the last bucket is
+  /// [None].
+  pub fn buckets(&self) -> impl Iterator<Item = (Option<Duration>, u64)> + '_ {
+    self.buckets.iter().enumerate().map(|(idx, count)| {
+      let bound = LATENCY_BUCKETS_MS
+        .get(idx)
+        .map(|ms| Duration::from_millis(*ms));
+
+      (bound, *count)
+    })
+  }
+
+  /// Estimates the quantile as the upper bound of the bucket containing it. Values in the last
+  /// bucket are estimated with the observed maximum.
+  pub fn quantile(&self, q: f64) -> Option<Duration> {
+    if self.count == 0 {
+      return None;
+    }
+
+    let rank = ((self.count as f64) * q.clamp(0.0, 1.0)).ceil().max(1.0) as u64;
+    let mut seen = 0;
+
+    for (bound, count) in self.buckets() {
+      seen += count;
+
+      if seen >= rank {
+        return Some(bound.map_or(self.max, |bound| bound.min(self.max)));
+      }
+    }
+
+    Some(self.max)
+  }
+}
+
+/// Call statistics of a single client command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandStats {
+  pub command: &'static str,
+  pub calls: u64,
+  pub errors: u64,
+  pub latency: LatencyHistogram,
+}
+
+/// Snapshot of [super::NutPoolClient] connection pool.
+#[derive(Debug, Clone)]
+pub struct PoolStats {
+  pub max_connections: usize,
+
+  /// Open connections waiting in the pool.
+  pub idle: usize,
+
+  /// Connections currently lent to callers.
+  pub in_use: usize,
+
+  /// Callers waiting for a free connection.
+  pub waiting: usize,
+
+  /// Connection attempts in progress.
+  pub connecting: usize,
+
+  pub connections_opened: u64,
+  pub connection_failures: u64,
+
+  /// Connections closed due to an invalid state, or a stale endpoint after failover.
+  pub connections_discarded: u64,
+
+  /// Connections closed after exceeding max idle time or max lifetime.
+  pub connections_evicted: u64,
+
+  /// Number of connection requests served by the pool.
+  pub permit_waits: u64,
+  pub permit_wait_avg: Duration,
+  pub permit_wait_max: Duration,
+
+  /// Per-command statistics, ordered by command name.
+  pub commands: Vec<CommandStats>,
+}
+
+impl PoolStats {
+  pub(super) fn new(pool: ItemPoolStats, metrics: &CommandMetrics) -> Self {
+    let permit_wait_avg = match u32::try_from(pool.wait_count) {
+      Ok(0) => Duration::ZERO,
+      Ok(count) => pool.wait_total / count,
+      Err(_) => Duration::from_secs_f64(pool.wait_total.as_secs_f64() / pool.wait_count as f64),
+    };
+
+    Self {
+      max_connections: pool.limit,
+      idle: pool.idle,
+      in_use: pool.in_use,
+      waiting: pool.waiting,
+      connecting: pool.allocating,
+      connections_opened: pool.allocations,
+      connection_failures: pool.allocation_failures,
+      connections_discarded: pool.discarded,
+      connections_evicted: pool.evicted,
+      permit_waits: pool.wait_count,
+      permit_wait_avg,
+      permit_wait_max: pool.wait_max,
+      commands: metrics.snapshot(),
+    }
+  }
+}
+
+#[derive(Debug, Default)]
+pub(super) struct CommandMetrics {
+  commands: Mutex<BTreeMap<&'static str, CommandStats>>,
+}
+
+impl CommandMetrics {
+  pub fn record(&self, command: &'static str, latency: Duration, is_err: bool) {
+    let Ok(mut commands) = self.commands.lock() else {
+      return;
+    };
+
+    let stats = commands.entry(command).or_insert_with(|| CommandStats {
+      command,
+      calls: 0,
+      errors: 0,
+      latency: LatencyHistogram::default(),
+    });
+
+    stats.calls += 1;
+    stats.latency.record(latency);
+
+    if is_err {
+      stats.errors += 1;
+    }
+  }
+
+  pub fn snapshot(&self) -> Vec<CommandStats> {
+    match self.commands.lock() {
+      Ok(commands) => commands.values().cloned().collect(),
+      Err(_) => Vec::new(),
+    }
+  }
+}
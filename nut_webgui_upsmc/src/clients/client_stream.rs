@@ -0,0 +1,149 @@
+use super::client_base::connect_tcp;
+use crate::errors::Error;
+use core::{
+  pin::Pin,
+  task::{Context, Poll},
+};
+use tokio::{
+  io::{AsyncRead, AsyncWrite, ReadBuf},
+  net::{TcpStream, ToSocketAddrs},
+};
+
+#[cfg(unix)]
+use {std::path::Path, tokio::net::UnixStream};
+
+#[cfg(feature = "tls")]
+use crate::{
+  clients::NutClient,
+  tls::{TlsConfig, TlsStream},
+};
+
+/// Transport stream for upsd connections, either plain TCP, TCP upgraded to TLS via `STARTTLS`, or
+/// a unix domain socket.
+pub enum NutStream {
+  Tcp(TcpStream),
+  #[cfg(unix)]
+  Unix(UnixStream),
+  #[cfg(feature = "tls")]
+  Tls(Box<TlsStream<TcpStream>>),
+}
+
+impl NutStream {
+  /// Opens a plain TCP connection.
+  pub async fn connect<A>(addr: A) -> Result<Self, Error>
+  where
+    A: ToSocketAddrs,
+  {
+    let stream = connect_tcp(addr).await?;
+
+    Ok(Self::Tcp(stream))
+  }
+
+  /// Opens a unix domain socket connection.
+  #[cfg(unix)]
+  pub async fn connect_unix<P>(path: P) -> Result<Self, Error>
+  where
+    P: AsRef<Path>,
+  {
+    let stream = UnixStream::connect(path).await?;
+
+    Ok(Self::Unix(stream))
+  }
+
+  /// Opens a TCP connection and upgrades it to TLS with `STARTTLS`.
+  #[cfg(feature = "tls")]
+  pub async fn connect_tls<A>(addr: A, config: &TlsConfig) -> Result<Self, Error>
+  where
+    A: ToSocketAddrs,
+  {
+    let client = NutClient::connect(addr).await?.start_tls(config).await?;
+
+    Ok(Self::Tls(Box::new(client.into_inner())))
+  }
+
+  #[inline]
+  pub const fn is_tls(&self) -> bool {
+    match self {
+      NutStream::Tcp(_) => false,
+      #[cfg(unix)]
+      NutStream::Unix(_) => false,
+      #[cfg(feature = "tls")]
+      NutStream::Tls(_) => true,
+    }
+  }
+}
+
+impl From<TcpStream> for NutStream {
+  #[inline]
+  fn from(value: TcpStream) -> Self {
+    Self::Tcp(value)
+  }
+}
+
+#[cfg(unix)]
+impl From<UnixStream> for NutStream {
+  #[inline]
+  fn from(value: UnixStream) -> Self {
+    Self::Unix(value)
+  }
+}
+
+#[cfg(feature = "tls")]
+impl From<TlsStream<TcpStream>> for NutStream {
+  #[inline]
+  fn from(value: TlsStream<TcpStream>) -> Self {
+    Self::Tls(Box::new(value))
+  }
+}
+
+impl AsyncRead for NutStream {
+  fn poll_read(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    buf: &mut ReadBuf<'_>,
+  ) -> Poll<std::io::Result<()>> {
+    match self.get_mut() {
+      NutStream::Tcp(stream) => Pin::new(stream).poll_read(cx, buf),
+      #[cfg(unix)]
+      NutStream::Unix(stream) => Pin::new(stream).poll_read(cx, buf),
+      #[cfg(feature = "tls")]
+      NutStream::Tls(stream) => Pin::new(stream.as_mut()).poll_read(cx, buf),
+    }
+  }
+}
+
+impl AsyncWrite for NutStream {
+  fn poll_write(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    buf: &[u8],
+  ) -> Poll<Result<usize, std::io::Error>> {
+    match self.get_mut() {
+      NutStream::Tcp(stream) => Pin::new(stream).poll_write(cx, buf),
+      #[cfg(unix)]
+      NutStream::Unix(stream) => Pin::new(stream).poll_write(cx, buf),
+      #[cfg(feature = "tls")]
+      NutStream::Tls(stream) => Pin::new(stream.as_mut()).poll_write(cx, buf),
+    }
+  }
+
+  fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), std::io::Error>> {
+    match self.get_mut() {
+      NutStream::Tcp(stream) => Pin::new(stream).poll_flush(cx),
+      #[cfg(unix)]
+      NutStream::Unix(stream) => Pin::new(stream).poll_flush(cx),
+      #[cfg(feature = "tls")]
+      NutStream::Tls(stream) => Pin::new(stream.as_mut()).poll_flush(cx),
+    }
+  }
+
+  fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), std::io::Error>> {
+    match self.get_mut() {
+      NutStream::Tcp(stream) => Pin::new(stream).poll_shutdown(cx),
+      #[cfg(unix)]
+      NutStream::Unix(stream) => Pin::new(stream).poll_shutdown(cx),
+      #[cfg(feature = "tls")]
+      NutStream::Tls(stream) => Pin::new(stream.as_mut()).poll_shutdown(cx),
+    }
+  }
+}
@@ -0,0 +1,208 @@
+use crate::CmdName;
+
+macro_rules! impl_standard_cmds {
+  ($(
+    ($name:literal, $category:ident, $risk:ident, $accepts_param:literal);
+  )+) => {
+    /// Standard instant command names with their metadata. Per outlet commands use `n` in place of
+    /// the outlet number.
+    pub const STANDARD_CMDS: &[(&str, CmdMetadata)] = &[
+      $(
+        (
+          $name,
+          CmdMetadata {
+            category: CmdCategory::$category,
+            risk: CmdRisk::$risk,
+            accepts_param: $accepts_param,
+          },
+        ),
+      )+
+    ];
+
+    fn find_standard_cmd(name: &str) -> Option<CmdMetadata> {
+      match name {
+        $(
+          $name => Some(CmdMetadata {
+            category: CmdCategory::$category,
+            risk: CmdRisk::$risk,
+            accepts_param: $accepts_param,
+          }),
+        )+
+        _ => None,
+      }
+    }
+  };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CmdCategory {
+  Beeper,
+  Bypass,
+  Calibrate,
+  Driver,
+  Load,
+  Reset,
+  Shutdown,
+  Test,
+  Other,
+}
+
+/// Impact of an instant command on the connected load.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CmdRisk {
+  /// Does not affect the load, e.g. beeper control or stopping a test.
+  Safe,
+  /// Load stays powered, but the UPS may run on battery or bypass, e.g. battery tests.
+  Disruptive,
+  /// Turns off the load, either immediately or after a delay.
+  PowerCutting,
+}
+
+/// Metadata of standard instant commands, see [CmdName::metadata].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CmdMetadata {
+  pub category: CmdCategory,
+  pub risk: CmdRisk,
+  /// Command accepts an optional value, e.g. delay in seconds for `load.off.delay`.
+  pub accepts_param: bool,
+}
+
+impl CmdCategory {
+  pub const fn as_str(&self) -> &'static str {
+    match self {
+      CmdCategory::Beeper => "beeper",
+      CmdCategory::Bypass => "bypass",
+      CmdCategory::Calibrate => "calibrate",
+      CmdCategory::Driver => "driver",
+      CmdCategory::Load => "load",
+      CmdCategory::Reset => "reset",
+      CmdCategory::Shutdown => "shutdown",
+      CmdCategory::Test => "test",
+      CmdCategory::Other => "other",
+    }
+  }
+}
+
+impl CmdRisk {
+  pub const fn as_str(&self) -> &'static str {
+    match self {
+      CmdRisk::Safe => "safe",
+      CmdRisk::Disruptive => "disruptive",
+      CmdRisk::PowerCutting => "power_cutting",
+    }
+  }
+}
+
+impl std::fmt::Display for CmdCategory {
+  #[inline]
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+impl std::fmt::Display for CmdRisk {
+  #[inline]
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+impl CmdName {
+  /// Returns category, risk level and parameter support of standard commands. Per outlet commands
+  /// like `outlet.2.load.off` are matched regardless of the outlet number. Non-standard commands
+  /// return `None`.
+  pub fn metadata(&self) -> Option<CmdMetadata> {
+    let name = self.as_str();
+
+    if let Some(metadata) = find_standard_cmd(name) {
+      return Some(metadata);
+    }
+
+    let (outlet, cmd) = name.strip_prefix("outlet.")?.split_once('.')?;
+
+    if !outlet.is_empty() && outlet.bytes().all(|b| b.is_ascii_digit()) {
+      find_standard_cmd(&format!("outlet.n.{cmd}"))
+    } else {
+      None
+    }
+  }
+}
+
+// Generated with tools/gen_cmd_catalog.lua
+impl_standard_cmds!(
+  ("beeper.disable"                ,Beeper    ,Safe         ,false);
+  ("beeper.enable"                 ,Beeper    ,Safe         ,false);
+  ("beeper.mute"                   ,Beeper    ,Safe         ,false);
+  ("beeper.off"                    ,Beeper    ,Safe         ,false);
+  ("beeper.on"                     ,Beeper    ,Safe         ,false);
+  ("beeper.toggle"                 ,Beeper    ,Safe         ,false);
+  ("bypass.start"                  ,Bypass    ,Disruptive   ,false);
+  ("bypass.stop"                   ,Bypass    ,Safe         ,false);
+  ("calibrate.start"               ,Calibrate ,Disruptive   ,false);
+  ("calibrate.stop"                ,Calibrate ,Safe         ,false);
+  ("driver.killpower"              ,Driver    ,PowerCutting ,false);
+  ("driver.reload"                 ,Driver    ,Disruptive   ,false);
+  ("driver.reload-or-error"        ,Driver    ,Disruptive   ,false);
+  ("driver.reload-or-exit"         ,Driver    ,Disruptive   ,false);
+  ("load.off"                      ,Load      ,PowerCutting ,false);
+  ("load.off.delay"                ,Load      ,PowerCutting ,true);
+  ("load.on"                       ,Load      ,Safe         ,false);
+  ("load.on.delay"                 ,Load      ,Safe         ,true);
+  ("outlet.n.load.cycle"           ,Load      ,PowerCutting ,false);
+  ("outlet.n.load.off"             ,Load      ,PowerCutting ,false);
+  ("outlet.n.load.on"              ,Load      ,Safe         ,false);
+  ("outlet.n.shutdown.return"      ,Shutdown  ,PowerCutting ,false);
+  ("reset.input.minmax"            ,Reset     ,Safe         ,false);
+  ("reset.watchdog"                ,Reset     ,PowerCutting ,false);
+  ("shutdown.reboot"               ,Shutdown  ,PowerCutting ,false);
+  ("shutdown.reboot.graceful"      ,Shutdown  ,PowerCutting ,false);
+  ("shutdown.return"               ,Shutdown  ,PowerCutting ,false);
+  ("shutdown.stayoff"              ,Shutdown  ,PowerCutting ,false);
+  ("shutdown.stop"                 ,Shutdown  ,Safe         ,false);
+  ("test.battery.start"            ,Test      ,Disruptive   ,false);
+  ("test.battery.start.deep"       ,Test      ,Disruptive   ,false);
+  ("test.battery.start.quick"      ,Test      ,Disruptive   ,false);
+  ("test.battery.stop"             ,Test      ,Safe         ,false);
+  ("test.failure.start"            ,Test      ,Disruptive   ,false);
+  ("test.failure.stop"             ,Test      ,Safe         ,false);
+  ("test.panel.start"              ,Test      ,Safe         ,false);
+  ("test.panel.stop"               ,Test      ,Safe         ,false);
+  ("test.system.start"             ,Test      ,Disruptive   ,false);
+);
+
+#[cfg(feature = "serde")]
+mod serde {
+  use super::{CmdCategory, CmdMetadata, CmdRisk};
+  use serde::ser::SerializeStruct;
+
+  impl serde::Serialize for CmdCategory {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+      S: serde::Serializer,
+    {
+      serializer.serialize_str(self.as_str())
+    }
+  }
+
+  impl serde::Serialize for CmdRisk {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+      S: serde::Serializer,
+    {
+      serializer.serialize_str(self.as_str())
+    }
+  }
+
+  impl serde::Serialize for CmdMetadata {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+      S: serde::Serializer,
+    {
+      let mut s = serializer.serialize_struct("CmdMetadata", 3)?;
+      s.serialize_field("category", &self.category)?;
+      s.serialize_field("risk", &self.risk)?;
+      s.serialize_field("accepts_param", &self.accepts_param)?;
+      s.end()
+    }
+  }
+}
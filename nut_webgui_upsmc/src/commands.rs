@@ -1,5 +1,32 @@
 use crate::internal::Serialize;
 use crate::{CmdName, UpsName, Value, VarName};
+use std::borrow::Cow;
+
+const PASSWORD_PREFIX: &str = "PASSWORD ";
+
+/// Replaces arguments of `PASSWORD` lines, so serialized commands can be logged or recorded.
+pub fn redact(commands: &str) -> Cow<'_, str> {
+  if !commands.contains(PASSWORD_PREFIX) {
+    return Cow::Borrowed(commands);
+  }
+
+  let mut redacted = String::with_capacity(commands.len());
+
+  for line in commands.split_inclusive('\n') {
+    if line.starts_with(PASSWORD_PREFIX) {
+      redacted.push_str(PASSWORD_PREFIX);
+      redacted.push_str("***");
+
+      if line.ends_with('\n') {
+        redacted.push('\n');
+      }
+    } else {
+      redacted.push_str(line);
+    }
+  }
+
+  Cow::Owned(redacted)
+}
 
 pub struct AttachCommand<'a> {
   pub ups: &'a UpsName,
@@ -132,20 +159,43 @@ impl Serialize for GetType<'_, '_> {
   }
 }
 
-pub struct InstCmd<'a, 'b> {
+pub struct InstCmd<'a, 'b, 'c> {
   pub ups: &'a UpsName,
   pub cmd: &'b CmdName,
+  /// Optional command argument, e.g. delay in seconds for `load.off.delay`.
+  pub value: Option<&'c Value>,
 }
 
-impl Serialize for InstCmd<'_, '_> {
+impl Serialize for InstCmd<'_, '_, '_> {
   type Output = String;
 
   fn serialize(self) -> Self::Output {
-    format!(
-      "INSTCMD {ups_name} {cmd_name}\n",
-      ups_name = self.ups.as_escaped_str(),
-      cmd_name = self.cmd
-    )
+    match self.value {
+      Some(value) => format!(
+        "INSTCMD {ups_name} {cmd_name} \"{value}\"\n",
+        ups_name = self.ups.as_escaped_str(),
+        cmd_name = self.cmd,
+        value = value.as_escaped_str()
+      ),
+      None => format!(
+        "INSTCMD {ups_name} {cmd_name}\n",
+        ups_name = self.ups.as_escaped_str(),
+        cmd_name = self.cmd
+      ),
+    }
+  }
+}
+
+/// upsmon login command, replaced legacy `MASTER` in NUT 2.8.0.
+pub struct Primary<'a> {
+  pub ups: &'a UpsName,
+}
+
+impl Serialize for Primary<'_> {
+  type Output = String;
+
+  fn serialize(self) -> Self::Output {
+    format!("PRIMARY {}\n", self.ups.as_escaped_str())
   }
 }
 
@@ -294,6 +344,34 @@ impl Serialize for GetDaemonVer {
   }
 }
 
+pub struct GetTracking<'a> {
+  pub id: &'a str,
+}
+
+impl Serialize for GetTracking<'_> {
+  type Output = String;
+
+  fn serialize(self) -> Self::Output {
+    format!("GET TRACKING {id}\n", id = self.id)
+  }
+}
+
+pub struct SetTracking {
+  pub enabled: bool,
+}
+
+impl Serialize for SetTracking {
+  type Output = &'static str;
+
+  fn serialize(self) -> Self::Output {
+    if self.enabled {
+      "SET TRACKING ON\n"
+    } else {
+      "SET TRACKING OFF\n"
+    }
+  }
+}
+
 pub struct GetProtVer;
 
 impl Serialize for GetProtVer {
@@ -303,3 +381,13 @@ impl Serialize for GetProtVer {
     "NETVER\n"
   }
 }
+
+pub struct StartTls;
+
+impl Serialize for StartTls {
+  type Output = &'static str;
+
+  fn serialize(self) -> Self::Output {
+    "STARTTLS\n"
+  }
+}
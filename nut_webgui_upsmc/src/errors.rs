@@ -1,4 +1,4 @@
-use crate::internal::lexer::Position;
+pub use crate::internal::lexer::Position;
 
 #[derive(Debug)]
 pub struct Error {
@@ -23,6 +23,12 @@ pub enum ErrorKind {
   ProtocolError {
     inner: ProtocolError,
   },
+  TlsError {
+    inner: TlsError,
+  },
+  ArgumentError {
+    inner: ArgumentError,
+  },
   ConnectionPoolClosed,
   EmptyResponse,
   RequestTimeout,
@@ -45,6 +51,15 @@ pub enum ParseError {
   VarType(VarTypeParseError),
 }
 
+/// A response line skipped by a lenient parser.
+#[derive(Debug, Clone)]
+pub struct ParseDiagnostic {
+  /// Raw text of the skipped line.
+  pub line: Box<str>,
+  pub position: Position,
+  pub error: ParseError,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum VarTypeParseError {
   Empty,
@@ -72,6 +87,44 @@ pub enum CmdParseError {
 #[derive(Debug, Clone, Copy)]
 pub struct NumberParseError;
 
+/// Reason of a rejected writable variable value, see [crate::RwDetail].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RwValueError {
+  NotNumber,
+  NotText,
+  /// String value is empty or consists of only whitespaces.
+  Empty,
+  TooLong {
+    max_len: usize,
+  },
+  InvalidOption,
+  OutOfRange,
+  /// Driver reported range bounds are not numeric.
+  MalformedRange,
+}
+
+/// Reason of a command or variable value rejected before it's sent to upsd.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgumentError {
+  /// Value contains a control character, e.g. a line break which would terminate the request line.
+  ControlCharacter,
+  TooLong {
+    max_len: usize,
+  },
+}
+
+#[derive(Debug, Clone)]
+pub enum TlsError {
+  /// Neither a CA certificate nor a pinned certificate fingerprint is configured.
+  NoTrustAnchor,
+  InvalidServerName,
+  InvalidFingerprint,
+  InvalidCertificate,
+  InvalidPrivateKey,
+  Config(Box<str>),
+  Handshake(Box<str>),
+}
+
 impl std::fmt::Display for CmdParseError {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     match self {
@@ -108,6 +161,52 @@ impl std::fmt::Display for VarTypeParseError {
   }
 }
 
+impl std::fmt::Display for RwValueError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      RwValueError::NotNumber => f.write_str("value is not a number"),
+      RwValueError::NotText => f.write_str("value is not a string"),
+      RwValueError::Empty => f.write_str("value cannot be empty or consist of only whitespaces"),
+      RwValueError::TooLong { max_len } => f.write_fmt(format_args!(
+        "value exceeds the maximum allowed length of {}",
+        max_len
+      )),
+      RwValueError::InvalidOption => f.write_str("value is not one of the enum options"),
+      RwValueError::OutOfRange => f.write_str("value is not within the acceptable ranges"),
+      RwValueError::MalformedRange => {
+        f.write_str("driver reported min-max values are not numeric values")
+      }
+    }
+  }
+}
+
+impl std::fmt::Display for TlsError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      TlsError::NoTrustAnchor => {
+        f.write_str("no CA certificate or pinned certificate fingerprint is configured")
+      }
+      TlsError::InvalidServerName => f.write_str("invalid server name"),
+      TlsError::InvalidFingerprint => {
+        f.write_str("invalid certificate fingerprint, expected hex encoded SHA-256 digest")
+      }
+      TlsError::InvalidCertificate => f.write_str("no valid PEM encoded certificate found"),
+      TlsError::InvalidPrivateKey => f.write_str("no valid PEM encoded private key found"),
+      TlsError::Config(reason) => f.write_fmt(format_args!("invalid tls config, {}", reason)),
+      TlsError::Handshake(reason) => f.write_fmt(format_args!("tls handshake failed, {}", reason)),
+    }
+  }
+}
+
+impl std::fmt::Display for ParseDiagnostic {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_fmt(format_args!(
+      "{} at {}:{}, line={:?}",
+      self.error, self.position.line, self.position.col, self.line
+    ))
+  }
+}
+
 impl std::fmt::Display for NumberParseError {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     f.write_str("parse failed not a valid number neither f64 nor i64")
@@ -137,6 +236,22 @@ impl std::fmt::Display for ErrorKind {
         "nut server responded with an error message. error={}",
         inner
       )),
+      ErrorKind::TlsError { inner } => inner.fmt(f),
+      ErrorKind::ArgumentError { inner } => {
+        f.write_fmt(format_args!("invalid command argument, {}", inner))
+      }
+    }
+  }
+}
+
+impl std::fmt::Display for ArgumentError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      ArgumentError::ControlCharacter => f.write_str("value contains a control character"),
+      ArgumentError::TooLong { max_len } => f.write_fmt(format_args!(
+        "value exceeds the maximum allowed length of {}",
+        max_len
+      )),
     }
   }
 }
@@ -261,6 +376,22 @@ impl From<ProtocolError> for Error {
   }
 }
 
+impl From<TlsError> for Error {
+  fn from(value: TlsError) -> Self {
+    Self {
+      inner: Box::from(ErrorKind::TlsError { inner: value }),
+    }
+  }
+}
+
+impl From<ArgumentError> for Error {
+  fn from(value: ArgumentError) -> Self {
+    Self {
+      inner: Box::from(ErrorKind::ArgumentError { inner: value }),
+    }
+  }
+}
+
 impl From<ErrorKind> for Error {
   fn from(value: ErrorKind) -> Self {
     Self {
@@ -288,7 +419,10 @@ impl From<std::io::Error> for Error {
 impl std::error::Error for CmdParseError {}
 impl std::error::Error for Error {}
 impl std::error::Error for ParseError {}
+impl std::error::Error for RwValueError {}
+impl std::error::Error for ArgumentError {}
 impl std::error::Error for UpsNameParseError {}
 impl std::error::Error for VarNameParseError {}
 impl std::error::Error for NumberParseError {}
+impl std::error::Error for TlsError {}
 impl std::error::Error for VarTypeParseError {}
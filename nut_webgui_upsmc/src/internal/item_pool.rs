@@ -2,9 +2,11 @@ use core::{
   mem::MaybeUninit,
   num::NonZeroUsize,
   ops::{Deref, DerefMut},
+  sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+  time::Duration,
 };
-use std::{collections::VecDeque, sync::Arc};
-use tokio::sync::{AcquireError, Mutex, Semaphore, SemaphorePermit};
+use std::{collections::VecDeque, sync::Arc, time::Instant};
+use tokio::sync::{AcquireError, Mutex, MutexGuard, Semaphore, SemaphorePermit};
 
 pub trait ItemAllocator {
   type Output;
@@ -39,14 +41,88 @@ where
   }
 }
 
+/// Item expiration and validation settings.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ItemPoolConfig {
+  /// Idle items older than this are deallocated instead of reused.
+  pub max_idle: Option<Duration>,
+
+  /// Items older than this are deallocated on release or checkout, regardless of their usage.
+  pub max_lifetime: Option<Duration>,
+
+  /// [ItemPool::get] validates items with [ItemAllocator::is_valid_state] only when they were idle
+  /// longer than this. [None] disables validation.
+  pub validate_after: Option<Duration>,
+}
+
+#[derive(Debug)]
+struct PoolSlot<T> {
+  item: T,
+  created: Instant,
+  last_used: Instant,
+}
+
 #[derive(Debug)]
 struct InnerPool<T, A>
 where
   A: ItemAllocator<Output = T>,
 {
-  items: Mutex<VecDeque<T>>,
+  items: Mutex<VecDeque<PoolSlot<T>>>,
   permits: Semaphore,
   allocator: A,
+  config: ItemPoolConfig,
+  limit: usize,
+  counters: PoolCounters,
+}
+
+#[derive(Debug, Default)]
+struct PoolCounters {
+  idle: AtomicUsize,
+  waiting: AtomicUsize,
+  allocating: AtomicUsize,
+  allocations: AtomicU64,
+  allocation_failures: AtomicU64,
+  discarded: AtomicU64,
+  evicted: AtomicU64,
+  wait_count: AtomicU64,
+  wait_total_us: AtomicU64,
+  wait_max_us: AtomicU64,
+}
+
+/// Increments a gauge counter, and decrements it on drop. Keeps the counter accurate when the
+/// future holding it is cancelled, e.g. by a timeout.
+struct GaugeGuard<'a>(&'a AtomicUsize);
+
+impl<'a> GaugeGuard<'a> {
+  #[inline]
+  fn new(gauge: &'a AtomicUsize) -> Self {
+    gauge.fetch_add(1, Ordering::Relaxed);
+    Self(gauge)
+  }
+}
+
+impl Drop for GaugeGuard<'_> {
+  #[inline]
+  fn drop(&mut self) {
+    self.0.fetch_sub(1, Ordering::Relaxed);
+  }
+}
+
+/// Point-in-time pool counters.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ItemPoolStats {
+  pub limit: usize,
+  pub idle: usize,
+  pub in_use: usize,
+  pub waiting: usize,
+  pub allocating: usize,
+  pub allocations: u64,
+  pub allocation_failures: u64,
+  pub discarded: u64,
+  pub evicted: u64,
+  pub wait_count: u64,
+  pub wait_total: Duration,
+  pub wait_max: Duration,
 }
 
 pub struct ItemPool<T, A>
@@ -63,6 +139,7 @@ where
   pool: Arc<InnerPool<T, A>>,
   _permit: SemaphorePermit<'a>,
   item: MaybeUninit<T>,
+  created: Instant,
 }
 
 impl<T, A> Clone for ItemPool<T, A>
@@ -86,7 +163,15 @@ where
   pub async fn release(mut self) {
     let item: T = unsafe { self.item.assume_init_read() };
     self.item = MaybeUninit::zeroed();
-    self.pool.release(item).await;
+    self.pool.release(item, self.created).await;
+  }
+
+  /// Deallocates the item instead of returning it back to the pool.
+  pub async fn discard(mut self) {
+    let item: T = unsafe { self.item.assume_init_read() };
+    self.item = MaybeUninit::zeroed();
+    self.pool.counters.discarded.fetch_add(1, Ordering::Relaxed);
+    self.pool.allocator.dealloc(item).await;
   }
 }
 
@@ -114,7 +199,7 @@ impl<T, A> ItemPool<T, A>
 where
   A: ItemAllocator<Output = T>,
 {
-  pub fn new(limit: NonZeroUsize, allocator: A) -> Self {
+  pub fn new(limit: NonZeroUsize, allocator: A, config: ItemPoolConfig) -> Self {
     let limit: usize = limit.into();
 
     Self {
@@ -122,35 +207,73 @@ where
         items: Mutex::new(VecDeque::with_capacity(limit)),
         permits: Semaphore::new(limit),
         allocator,
+        config,
+        limit,
+        counters: PoolCounters::default(),
       }),
     }
   }
 
-  /// Returns an item from pool with provided [ItemAllocator::try_check()] function until a valid
-  /// item got found or allocator returns new one.
+  /// Returns an item from pool with provided [ItemAllocator::is_valid_state()] function until a
+  /// valid item got found or allocator returns new one.
   pub async fn get_checked(&self) -> Result<PoolGuard<T, A>, ItemPoolError<A::Error>> {
-    let permit = self.inner.permits.acquire().await?;
-    let mut items_lock = self.inner.items.lock().await;
+    self.checkout(|_| true).await
+  }
+
+  /// Returns an item from pool. Items are validated only when they were idle longer than
+  /// [ItemPoolConfig::validate_after], so returned item might be in an invalid state.
+  pub async fn get(&self) -> Result<PoolGuard<T, A>, ItemPoolError<A::Error>> {
+    let validate_after = self.inner.config.validate_after;
+
+    self
+      .checkout(|idle| validate_after.is_some_and(|threshold| idle >= threshold))
+      .await
+  }
+
+  async fn checkout<F>(
+    &self,
+    needs_validation: F,
+  ) -> Result<PoolGuard<'_, T, A>, ItemPoolError<A::Error>>
+  where
+    F: Fn(Duration) -> bool,
+  {
+    let (permit, mut items_lock) = self.acquire().await?;
+    let now = Instant::now();
 
     loop {
-      match items_lock.pop_front() {
-        Some(mut item) => {
-          if self.inner.allocator.is_valid_state(&mut item).await {
-            return Ok(PoolGuard {
-              _permit: permit,
-              item: MaybeUninit::new(item),
-              pool: self.inner.clone(),
-            });
-          } else {
-            _ = self.inner.allocator.dealloc(item).await;
+      match self.inner.pop_item(&mut items_lock) {
+        Some(mut slot) => {
+          if self.inner.is_expired(&slot, now) {
+            self.inner.counters.evicted.fetch_add(1, Ordering::Relaxed);
+            self.inner.allocator.dealloc(slot.item).await;
             continue;
           }
+
+          if needs_validation(now.saturating_duration_since(slot.last_used))
+            && !self.inner.allocator.is_valid_state(&mut slot.item).await
+          {
+            self
+              .inner
+              .counters
+              .discarded
+              .fetch_add(1, Ordering::Relaxed);
+            self.inner.allocator.dealloc(slot.item).await;
+            continue;
+          }
+
+          return Ok(PoolGuard {
+            _permit: permit,
+            item: MaybeUninit::new(slot.item),
+            created: slot.created,
+            pool: self.inner.clone(),
+          });
         }
         None => {
-          return match self.inner.allocator.init().await {
+          return match self.inner.allocate().await {
             Ok(item) => Ok(PoolGuard {
               _permit: permit,
               item: MaybeUninit::new(item),
+              created: Instant::now(),
               pool: self.inner.clone(),
             }),
             Err(inner) => Err(ItemPoolError::AllocatorError { inner }),
@@ -160,34 +283,114 @@ where
     }
   }
 
-  /// Returns an item from pool without any check. Returned item might be in an invalid state.
-  pub async fn get(&self) -> Result<PoolGuard<T, A>, ItemPoolError<A::Error>> {
-    let permit = self.inner.permits.acquire().await?;
-    let mut items_lock = self.inner.items.lock().await;
-
-    match items_lock.pop_front() {
-      Some(item) => Ok(PoolGuard {
-        _permit: permit,
-        item: MaybeUninit::new(item),
-        pool: self.inner.clone(),
-      }),
-      None => match self.inner.allocator.init().await {
-        Ok(item) => Ok(PoolGuard {
-          _permit: permit,
-          item: MaybeUninit::new(item),
-          pool: self.inner.clone(),
-        }),
-        Err(inner) => Err(ItemPoolError::AllocatorError { inner }),
-      },
+  #[inline]
+  pub fn allocator(&self) -> &A {
+    &self.inner.allocator
+  }
+
+  #[inline]
+  pub fn config(&self) -> ItemPoolConfig {
+    self.inner.config
+  }
+
+  pub fn stats(&self) -> ItemPoolStats {
+    let counters = &self.inner.counters;
+    let in_use = if self.inner.permits.is_closed() {
+      0
+    } else {
+      self
+        .inner
+        .limit
+        .saturating_sub(self.inner.permits.available_permits())
+    };
+
+    ItemPoolStats {
+      limit: self.inner.limit,
+      idle: counters.idle.load(Ordering::Relaxed),
+      in_use,
+      waiting: counters.waiting.load(Ordering::Relaxed),
+      allocating: counters.allocating.load(Ordering::Relaxed),
+      allocations: counters.allocations.load(Ordering::Relaxed),
+      allocation_failures: counters.allocation_failures.load(Ordering::Relaxed),
+      discarded: counters.discarded.load(Ordering::Relaxed),
+      evicted: counters.evicted.load(Ordering::Relaxed),
+      wait_count: counters.wait_count.load(Ordering::Relaxed),
+      wait_total: Duration::from_micros(counters.wait_total_us.load(Ordering::Relaxed)),
+      wait_max: Duration::from_micros(counters.wait_max_us.load(Ordering::Relaxed)),
     }
   }
 
   pub async fn clear(&mut self) {
     let mut items = self.inner.items.lock().await;
 
-    while let Some(item) = items.pop_back() {
-      self.inner.allocator.dealloc(item).await;
+    while let Some(slot) = self.inner.pop_item(&mut items) {
+      self.inner.allocator.dealloc(slot.item).await;
+    }
+  }
+
+  /// Deallocates idle items exceeding [ItemPoolConfig::max_idle] or [ItemPoolConfig::max_lifetime],
+  /// and returns the number of evicted items.
+  pub async fn evict_expired(&self) -> usize {
+    let now = Instant::now();
+    let expired: Vec<PoolSlot<T>> = {
+      let mut items = self.inner.items.lock().await;
+      let mut expired = Vec::new();
+      let mut idx = 0;
+
+      while idx < items.len() {
+        if self.inner.is_expired(&items[idx], now) {
+          if let Some(slot) = items.remove(idx) {
+            expired.push(slot);
+          }
+        } else {
+          idx += 1;
+        }
+      }
+
+      self
+        .inner
+        .counters
+        .idle
+        .fetch_sub(expired.len(), Ordering::Relaxed);
+
+      expired
+    };
+
+    let count = expired.len();
+    self
+      .inner
+      .counters
+      .evicted
+      .fetch_add(count as u64, Ordering::Relaxed);
+
+    for slot in expired {
+      self.inner.allocator.dealloc(slot.item).await;
     }
+
+    count
+  }
+
+  /// Waits for a permit and the item queue lock, wait time is recorded in pool stats.
+  async fn acquire(
+    &self,
+  ) -> Result<(SemaphorePermit<'_>, MutexGuard<'_, VecDeque<PoolSlot<T>>>), ItemPoolError<A::Error>>
+  {
+    let counters = &self.inner.counters;
+    let started = Instant::now();
+
+    let waiting = GaugeGuard::new(&counters.waiting);
+    let permit = self.inner.permits.acquire().await;
+    drop(waiting);
+
+    let permit = permit?;
+    let items_lock = self.inner.items.lock().await;
+    let elapsed = u64::try_from(started.elapsed().as_micros()).unwrap_or(u64::MAX);
+
+    counters.wait_count.fetch_add(1, Ordering::Relaxed);
+    counters.wait_total_us.fetch_add(elapsed, Ordering::Relaxed);
+    counters.wait_max_us.fetch_max(elapsed, Ordering::Relaxed);
+
+    Ok((permit, items_lock))
   }
 
   pub async fn close(mut self) {
@@ -200,12 +403,65 @@ impl<T, A> InnerPool<T, A>
 where
   A: ItemAllocator<Output = T>,
 {
-  async fn release(&self, item: T) {
+  async fn release(&self, item: T, created: Instant) {
+    let now = Instant::now();
+    let slot = PoolSlot {
+      item,
+      created,
+      last_used: now,
+    };
+
     if self.permits.is_closed() {
-      self.allocator.dealloc(item).await;
+      self.allocator.dealloc(slot.item).await;
+    } else if self.is_expired(&slot, now) {
+      self.counters.evicted.fetch_add(1, Ordering::Relaxed);
+      self.allocator.dealloc(slot.item).await;
     } else {
       let mut items = self.items.lock().await;
-      items.push_back(item);
+      items.push_back(slot);
+      self.counters.idle.fetch_add(1, Ordering::Relaxed);
+    }
+  }
+
+  /// Pops the most recently used item, so rarely used items stay at the front and expire.
+  fn pop_item(&self, items: &mut VecDeque<PoolSlot<T>>) -> Option<PoolSlot<T>> {
+    let slot = items.pop_back()?;
+    self.counters.idle.fetch_sub(1, Ordering::Relaxed);
+
+    Some(slot)
+  }
+
+  fn is_expired(&self, slot: &PoolSlot<T>, now: Instant) -> bool {
+    let idle_expired = self
+      .config
+      .max_idle
+      .is_some_and(|max_idle| now.saturating_duration_since(slot.last_used) >= max_idle);
+
+    let lifetime_expired = self
+      .config
+      .max_lifetime
+      .is_some_and(|max_lifetime| now.saturating_duration_since(slot.created) >= max_lifetime);
+
+    idle_expired || lifetime_expired
+  }
+
+  async fn allocate(&self) -> Result<T, A::Error> {
+    let allocating = GaugeGuard::new(&self.counters.allocating);
+    let result = self.allocator.init().await;
+    drop(allocating);
+
+    match result {
+      Ok(item) => {
+        self.counters.allocations.fetch_add(1, Ordering::Relaxed);
+        Ok(item)
+      }
+      Err(err) => {
+        self
+          .counters
+          .allocation_failures
+          .fetch_add(1, Ordering::Relaxed);
+        Err(err)
+      }
     }
   }
 }
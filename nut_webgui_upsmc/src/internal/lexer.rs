@@ -89,7 +89,7 @@ impl<'a> Lexer<'a> {
     }
   }
 
-  pub fn extract_from_token(&self, token: &Token) -> Cow<'_, str> {
+  pub fn extract_from_token(&self, token: &Token) -> Cow<'a, str> {
     let buffer_str = &self.buffer;
 
     match token {
@@ -236,6 +236,24 @@ impl<'a> Lexer<'a> {
     }
   }
 
+  /// Returns the remaining text of the current line, excluding the line feed.
+  pub fn current_line(&self) -> &'a str {
+    let rest = &self.buffer[self.state.read_head..];
+
+    match rest.find('\n') {
+      Some(end) => &rest[..end],
+      None => rest,
+    }
+  }
+
+  /// Moves the read head past the next line feed and skips the following whitespaces.
+  pub fn skip_line(&mut self) {
+    let line_len = self.current_line().len();
+
+    self.move_read_head(line_len as isize);
+    self.skip_whitespaces();
+  }
+
   #[inline]
   pub fn is_finished(&self) -> bool {
     self.peek().is_none()
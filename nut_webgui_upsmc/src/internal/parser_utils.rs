@@ -1,7 +1,7 @@
 use crate::{
   CmdName, InferValueFrom, UpsName, Value, VarName,
-  errors::{Error, ErrorKind, ParseError},
-  internal::lexer::{Lexer, Token},
+  errors::{Error, ErrorKind, ParseDiagnostic, ParseError},
+  internal::lexer::{Lexer, Position, Token},
 };
 use std::borrow::Cow;
 use tracing::trace;
@@ -286,6 +286,71 @@ pub fn end_parser(lexer: &mut Lexer) -> Result<(), Error> {
   }
 }
 
+/// Parses item lines of a `BEGIN LIST` response until its `END` line.
+///
+/// Without diagnostics, items are parsed while lines start with `keyword`, and the first malformed
+/// item fails the whole response. With diagnostics, each line is parsed in isolation and malformed
+/// lines are skipped and recorded instead.
+pub fn parse_list_items<F>(
+  lexer: &mut Lexer,
+  keyword: &str,
+  diagnostics: Option<&mut Vec<ParseDiagnostic>>,
+  mut parse_item: F,
+) -> Result<(), Error>
+where
+  F: FnMut(&mut Lexer) -> Result<(), Error>,
+{
+  let Some(diagnostics) = diagnostics else {
+    while lexer.peek_as_str() == Some(keyword) {
+      parse_item(lexer)?;
+    }
+
+    return Ok(());
+  };
+
+  loop {
+    let line = lexer.current_line();
+
+    if line.is_empty() || line.split_ascii_whitespace().next() == Some("END") {
+      return Ok(());
+    }
+
+    let line_number = lexer.get_positon().line;
+    let mut line_lexer = Lexer::new(line);
+
+    let result = if line_lexer.peek_as_str() == Some(keyword) {
+      parse_item(&mut line_lexer)
+    } else {
+      Err(
+        ErrorKind::ParseError {
+          inner: ParseError::InvalidToken,
+          position: Position::default(),
+        }
+        .into(),
+      )
+    };
+
+    if let Err(err) = result {
+      let ErrorKind::ParseError { inner, position } = err.kind() else {
+        return Err(err);
+      };
+
+      trace!(message = "skipping malformed list item", line = line, reason = %err);
+
+      diagnostics.push(ParseDiagnostic {
+        line: Box::from(line),
+        position: Position {
+          line: line_number,
+          col: position.col,
+        },
+        error: inner.clone(),
+      });
+    }
+
+    lexer.skip_line();
+  }
+}
+
 /// Iterates over lexer and tries to parse a line based on given syntax definition.
 ///
 /// ## Example usage:
@@ -1,21 +1,33 @@
 pub(crate) mod internal;
 
+mod cmd_catalog;
 mod cmd_name;
 mod commands;
+mod rw_detail;
 mod ups_name;
 mod value;
+mod var_metadata;
 mod var_name;
 mod var_type;
 
 pub mod clients;
 pub mod errors;
+pub mod protocol;
+pub mod recording;
 pub mod responses;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "tls")]
+pub mod tls;
 pub mod ups_event;
 pub mod ups_status;
 pub mod variables;
 
+pub use cmd_catalog::*;
 pub use cmd_name::*;
+pub use rw_detail::*;
 pub use ups_name::*;
 pub use value::*;
+pub use var_metadata::*;
 pub use var_name::*;
 pub use var_type::*;
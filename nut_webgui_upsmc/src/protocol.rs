@@ -0,0 +1,523 @@
+//! Sans-IO core of the client protocol. [ClientProtocol] does no I/O by itself: commands are
+//! queued as outgoing bytes, received bytes are fed back in, and framed responses are polled out.
+//! Async and blocking clients in [crate::clients] are thin adapters on top of it, the same state
+//! machine can be driven from any runtime or event loop.
+
+use crate::{
+  errors::{Error, ErrorKind, ParseError, ProtocolError},
+  internal::{
+    Deserialize,
+    lexer::{Lexer, Position, Token},
+  },
+  var_name::is_var_name,
+};
+use core::fmt::Display;
+use std::borrow::Cow;
+
+const LIST_START: &[u8] = b"BEGIN LIST";
+const LIST_END: &[u8] = b"END LIST";
+const PROT_ERR: &[u8] = b"ERR";
+
+/// Single framed upsd response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Frame {
+  /// Single line response, including its line terminator.
+  Line(String),
+
+  /// Complete `BEGIN LIST` .. `END LIST` block.
+  List(String),
+
+  /// `ERR` response.
+  Error(ProtocolError),
+}
+
+impl Frame {
+  /// Returns raw response text, `ERR` frames are returned as protocol errors.
+  pub fn into_raw(self) -> Result<String, Error> {
+    match self {
+      Frame::Line(response) | Frame::List(response) => Ok(response),
+      Frame::Error(inner) => Err(inner.into()),
+    }
+  }
+
+  /// Parses frame into a response type, e.g. [crate::responses::UpsVar].
+  pub fn parse<R>(self) -> Result<R, Error>
+  where
+    R: ParseResponse,
+  {
+    R::parse(&self.into_raw()?)
+  }
+
+  #[inline]
+  pub fn is_error(&self) -> bool {
+    matches!(self, Frame::Error(_))
+  }
+}
+
+impl Display for Frame {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      Frame::Line(response) | Frame::List(response) => f.write_str(response),
+      Frame::Error(inner) => write!(f, "ERR {}", inner),
+    }
+  }
+}
+
+/// Event of a `BEGIN LIST` response read in place with [ResponseFramer::next_list_event].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListEvent<'a> {
+  /// `BEGIN LIST` line.
+  Begin(ListLine<'a>),
+
+  /// Item line between `BEGIN LIST` and `END LIST`.
+  Item(ListLine<'a>),
+
+  /// `END LIST` line, the response is complete.
+  End(ListLine<'a>),
+}
+
+/// Single line of a list response, borrowed from the framer buffer without its line terminator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListLine<'a> {
+  line: &'a str,
+}
+
+/// `VAR` or `RW` item tokenised in place. Only quoted values containing escape sequences are
+/// allocated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListVar<'a> {
+  pub ups: &'a str,
+  pub name: &'a str,
+  pub value: Cow<'a, str>,
+}
+
+impl<'a> ListLine<'a> {
+  #[inline]
+  pub fn as_str(&self) -> &'a str {
+    self.line
+  }
+
+  /// First word of the line, e.g. `VAR`, `RW` or `CMD`.
+  #[inline]
+  pub fn keyword(&self) -> &'a str {
+    self
+      .line
+      .split_ascii_whitespace()
+      .next()
+      .unwrap_or_default()
+  }
+
+  /// Parses `<KEYWORD> <ups> <var> "<value>"` lines, which are used by `LIST VAR` and `LIST RW`.
+  pub fn var(&self) -> Result<ListVar<'a>, Error> {
+    let mut lexer = Lexer::new(self.line);
+
+    _ = next_text(&mut lexer)?;
+    let ups = next_text(&mut lexer)?;
+    let name = next_text(&mut lexer)?;
+
+    if let Err(err) = is_var_name(name) {
+      return Err(
+        ErrorKind::ParseError {
+          inner: ParseError::VarName(err),
+          position: lexer.get_positon(),
+        }
+        .into(),
+      );
+    }
+
+    let value = match lexer.next_token()? {
+      Some(token @ Token::QuotedText { .. }) => lexer.extract_from_token(&token),
+      _ => {
+        return Err(invalid_token(
+          &lexer,
+          ParseError::ExpectedDoubleQuotedTextToken,
+        ));
+      }
+    };
+
+    if lexer.is_finished() {
+      Ok(ListVar { ups, name, value })
+    } else {
+      Err(invalid_token(&lexer, ParseError::InvalidToken))
+    }
+  }
+}
+
+/// Returns next text token as a slice of the lexer buffer.
+fn next_text<'a>(lexer: &mut Lexer<'a>) -> Result<&'a str, Error> {
+  match lexer.next_token()? {
+    Some(token @ Token::Text { .. }) => match lexer.extract_from_token(&token) {
+      Cow::Borrowed(text) => Ok(text),
+      Cow::Owned(_) => Err(invalid_token(lexer, ParseError::InvalidToken)),
+    },
+    _ => Err(invalid_token(lexer, ParseError::ExpectedTextToken)),
+  }
+}
+
+#[inline]
+fn invalid_token(lexer: &Lexer, inner: ParseError) -> Error {
+  ErrorKind::ParseError {
+    inner,
+    position: lexer.get_positon(),
+  }
+  .into()
+}
+
+/// Classifies a single list response line, `raw` excludes the `\n` terminator.
+fn list_event<'a>(in_list: &mut bool, raw: &'a [u8]) -> Result<ListEvent<'a>, Error> {
+  let line = core::str::from_utf8(raw)
+    .map_err(|_| Error::from(std::io::Error::from(std::io::ErrorKind::InvalidData)))?;
+  let line = ListLine {
+    line: line.strip_suffix('\r').unwrap_or(line),
+  };
+
+  if *in_list {
+    if line.line.as_bytes().starts_with(LIST_END) {
+      *in_list = false;
+      Ok(ListEvent::End(line))
+    } else {
+      Ok(ListEvent::Item(line))
+    }
+  } else if line.line.as_bytes().starts_with(LIST_START) {
+    *in_list = true;
+    Ok(ListEvent::Begin(line))
+  } else if let Some(prot_err) = line.line.strip_prefix("ERR") {
+    Err(ProtocolError::from(prot_err.trim()).into())
+  } else {
+    Err(
+      ErrorKind::ParseError {
+        inner: ParseError::InvalidToken,
+        position: Position::default(),
+      }
+      .into(),
+    )
+  }
+}
+
+/// Response types which can be parsed from raw response text.
+pub trait ParseResponse: Sized {
+  fn parse(response: &str) -> Result<Self, Error>;
+}
+
+impl<T> ParseResponse for T
+where
+  T: Deserialize<Error = Error>,
+{
+  fn parse(response: &str) -> Result<Self, Error> {
+    let mut lexer = Lexer::new(response);
+    T::deserialize(&mut lexer)
+  }
+}
+
+/// Splits received bytes into [Frame]s. Partial responses are buffered until they are complete.
+#[derive(Debug, Default)]
+pub struct ResponseFramer {
+  buffer: Vec<u8>,
+
+  /// Start of the first line which is not checked yet.
+  scan_pos: usize,
+
+  /// Bytes already returned by [ResponseFramer::next_list_event]. They are dropped on the next
+  /// [ResponseFramer::push_bytes] call, so the buffer is not shifted for each line.
+  consumed: usize,
+
+  /// Set while inside of a `BEGIN LIST` block.
+  in_list: bool,
+}
+
+impl ResponseFramer {
+  #[inline]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Appends received bytes.
+  #[inline]
+  pub fn push_bytes(&mut self, data: &[u8]) {
+    self.compact();
+    self.buffer.extend_from_slice(data);
+  }
+
+  /// Number of buffered bytes which are not framed yet.
+  #[inline]
+  pub fn buffered(&self) -> usize {
+    self.buffer.len() - self.consumed
+  }
+
+  /// Returns the next complete frame, or `None` when more bytes are needed.
+  pub fn next_frame(&mut self) -> Result<Option<Frame>, Error> {
+    self.compact();
+
+    while let Some(offset) = self.buffer[self.scan_pos..]
+      .iter()
+      .position(|b| *b == b'\n')
+    {
+      let line_start = self.scan_pos;
+      let line_end = line_start + offset + 1;
+      let line = &self.buffer[line_start..line_end];
+
+      self.scan_pos = line_end;
+
+      if self.in_list {
+        if line.starts_with(LIST_END) {
+          self.in_list = false;
+          return self.take_frame(line_end).map(|v| Some(Frame::List(v)));
+        }
+      } else if line.starts_with(LIST_START) {
+        self.in_list = true;
+      } else if line.starts_with(PROT_ERR) {
+        let response = self.take_frame(line_end)?;
+        let prot_err = ProtocolError::from(response[PROT_ERR.len()..].trim());
+
+        return Ok(Some(Frame::Error(prot_err)));
+      } else {
+        return self.take_frame(line_end).map(|v| Some(Frame::Line(v)));
+      }
+    }
+
+    Ok(None)
+  }
+
+  /// Returns the next line of a list response in place, or `None` when more bytes are needed.
+  /// Unlike [ResponseFramer::next_frame], the block is not collected into a [Frame::List], so large
+  /// lists are processed line by line as they arrive. `ERR` responses are returned as protocol
+  /// errors, and single line responses as [ParseError::InvalidToken].
+  pub fn next_list_event(&mut self) -> Result<Option<ListEvent<'_>>, Error> {
+    let Some(offset) = self.buffer[self.scan_pos..]
+      .iter()
+      .position(|b| *b == b'\n')
+    else {
+      return Ok(None);
+    };
+
+    let line_start = self.scan_pos;
+    let line_end = line_start + offset + 1;
+
+    self.scan_pos = line_end;
+    self.consumed = line_end;
+
+    list_event(&mut self.in_list, &self.buffer[line_start..line_end - 1]).map(Some)
+  }
+
+  /// Same as [ResponseFramer::next_list_event], but reads the line straight from `data`, which is
+  /// usually the read buffer of the connection. `data` is advanced past the returned line. Complete
+  /// lines are tokenised in place, only a line split between two reads is copied into the framer
+  /// until its remainder arrives.
+  pub fn next_list_event_from<'s, 'd, 'r>(
+    &'s mut self,
+    data: &mut &'d [u8],
+  ) -> Result<Option<ListEvent<'r>>, Error>
+  where
+    's: 'r,
+    'd: 'r,
+  {
+    let input: &'d [u8] = data;
+    let newline = input.iter().position(|b| *b == b'\n');
+
+    if self.buffered() > 0 {
+      if !self.buffer[self.scan_pos..].contains(&b'\n') {
+        let taken = newline.map_or(input.len(), |offset| offset + 1);
+
+        self.push_bytes(&input[..taken]);
+        *data = &input[taken..];
+      }
+
+      return self.next_list_event();
+    }
+
+    match newline {
+      Some(offset) => {
+        *data = &input[offset + 1..];
+        list_event(&mut self.in_list, &input[..offset]).map(Some)
+      }
+      None => {
+        self.push_bytes(input);
+        *data = &[];
+        Ok(None)
+      }
+    }
+  }
+
+  /// Frames remaining bytes after the connection is closed by the peer. Unterminated single line
+  /// responses are still returned, unfinished lists and empty buffers are
+  /// [ErrorKind::EmptyResponse].
+  pub fn finish(&mut self) -> Result<Frame, Error> {
+    if let Some(frame) = self.next_frame()? {
+      return Ok(frame);
+    }
+
+    if self.in_list || self.buffer.is_empty() {
+      self.clear();
+      return Err(ErrorKind::EmptyResponse.into());
+    }
+
+    let response = self.take_frame(self.buffer.len())?;
+
+    match response.strip_prefix("ERR") {
+      Some(prot_err) => Ok(Frame::Error(ProtocolError::from(prot_err.trim()))),
+      None => Ok(Frame::Line(response)),
+    }
+  }
+
+  /// Discards all buffered bytes. Allocated buffer capacity is kept for following responses.
+  pub fn clear(&mut self) {
+    self.buffer.clear();
+    self.scan_pos = 0;
+    self.consumed = 0;
+    self.in_list = false;
+  }
+
+  fn compact(&mut self) {
+    if self.consumed > 0 {
+      self.buffer.drain(..self.consumed);
+      self.scan_pos -= self.consumed;
+      self.consumed = 0;
+    }
+  }
+
+  fn take_frame(&mut self, end: usize) -> Result<String, Error> {
+    let frame: Vec<u8> = self.buffer.drain(..end).collect();
+    self.scan_pos = 0;
+
+    String::from_utf8(frame)
+      .map_err(|_| std::io::Error::from(std::io::ErrorKind::InvalidData).into())
+  }
+}
+
+/// Client side protocol state. Tracks queued commands, outgoing bytes and received responses.
+///
+/// ```
+/// use nut_webgui_upsmc::protocol::{ClientProtocol, Frame};
+///
+/// let mut protocol = ClientProtocol::new();
+/// protocol.send("GET VAR ups ups.status\n");
+///
+/// // Write `protocol.outgoing()` to the connection, then mark it as written.
+/// let written = protocol.outgoing().len();
+/// protocol.consume_outgoing(written);
+///
+/// protocol.receive(b"VAR ups ups.status \"OL\"\n");
+///
+/// let frame = protocol.poll_response().unwrap();
+/// assert_eq!(frame, Some(Frame::Line("VAR ups ups.status \"OL\"\n".into())));
+/// assert_eq!(protocol.pending(), 0);
+/// ```
+#[derive(Debug, Default)]
+pub struct ClientProtocol {
+  framer: ResponseFramer,
+  outgoing: Vec<u8>,
+  pending: usize,
+}
+
+impl ClientProtocol {
+  #[inline]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Queues a serialized command, its response is expected after all previously queued
+  /// commands, since upsd answers pipelined commands in order.
+  pub fn send(&mut self, command: &str) {
+    self.outgoing.extend_from_slice(command.as_bytes());
+    self.pending += 1;
+  }
+
+  /// Bytes waiting to be written to the connection.
+  #[inline]
+  pub fn outgoing(&self) -> &[u8] {
+    &self.outgoing
+  }
+
+  /// Marks `amt` bytes of [ClientProtocol::outgoing] as written.
+  pub fn consume_outgoing(&mut self, amt: usize) {
+    let amt = amt.min(self.outgoing.len());
+    self.outgoing.drain(..amt);
+  }
+
+  /// Feeds bytes read from the connection.
+  #[inline]
+  pub fn receive(&mut self, data: &[u8]) {
+    self.framer.push_bytes(data);
+  }
+
+  /// Returns next complete response, or `None` when more bytes are needed.
+  pub fn poll_response(&mut self) -> Result<Option<Frame>, Error> {
+    let frame = self.framer.next_frame()?;
+
+    if frame.is_some() {
+      self.pending = self.pending.saturating_sub(1);
+    }
+
+    Ok(frame)
+  }
+
+  /// Returns the next line of a list response in place, see [ResponseFramer::next_list_event].
+  pub fn poll_list_event(&mut self) -> Result<Option<ListEvent<'_>>, Error> {
+    let event = self.framer.next_list_event();
+
+    if completes_response(&event) {
+      self.pending = self.pending.saturating_sub(1);
+    }
+
+    event
+  }
+
+  /// Returns the next line of a list response read from the caller's buffer, see
+  /// [ResponseFramer::next_list_event_from].
+  pub fn poll_list_event_from<'s, 'd, 'r>(
+    &'s mut self,
+    data: &mut &'d [u8],
+  ) -> Result<Option<ListEvent<'r>>, Error>
+  where
+    's: 'r,
+    'd: 'r,
+  {
+    let event = self.framer.next_list_event_from(data);
+
+    if completes_response(&event) {
+      self.pending = self.pending.saturating_sub(1);
+    }
+
+    event
+  }
+
+  /// Handles connection close. Returns the last buffered response if there is one, otherwise
+  /// [ErrorKind::EmptyResponse]. All pending responses are dropped.
+  pub fn receive_eof(&mut self) -> Result<Frame, Error> {
+    let result = self.framer.finish();
+    self.pending = 0;
+    self.framer.clear();
+
+    result
+  }
+
+  /// Number of queued commands waiting for a response.
+  #[inline]
+  pub fn pending(&self) -> usize {
+    self.pending
+  }
+
+  /// Returns true when there are no queued commands or unsent bytes.
+  #[inline]
+  pub fn is_idle(&self) -> bool {
+    self.pending == 0 && self.outgoing.is_empty()
+  }
+
+  /// Drops queued commands and buffered bytes, e.g. after a request timeout where remaining
+  /// responses can no longer be matched.
+  pub fn reset(&mut self) {
+    self.framer.clear();
+    self.outgoing.clear();
+    self.pending = 0;
+  }
+}
+
+/// Returns true when a polled list event completes its response. I/O errors leave the response
+/// incomplete, since the connection can no longer be read.
+fn completes_response(event: &Result<Option<ListEvent<'_>>, Error>) -> bool {
+  match event {
+    Ok(Some(ListEvent::End(_))) => true,
+    Ok(_) => false,
+    Err(err) => !matches!(err.kind(), ErrorKind::IOError { .. }),
+  }
+}
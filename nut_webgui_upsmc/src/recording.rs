@@ -0,0 +1,522 @@
+//! Session recording and replay for reproducing protocol issues without a packet capture.
+//!
+//! [RecordingStream] wraps any client transport and writes every sent and received line to a
+//! [SessionRecorder]. `PASSWORD` arguments are redacted before they reach the recorder. Recordings
+//! are plain text, sent lines start with `> ` and received lines start with `< `:
+//!
+//! ```text
+//! # nut_webgui_upsmc session recording
+//! > GET VAR ups ups.status
+//! < VAR ups ups.status "OL"
+//! ```
+//!
+//! [ReplayStream] feeds a [Recording] back into [crate::clients::NutClient], so parser issues can
+//! be reproduced in tests.
+
+use crate::commands::redact;
+use core::{
+  pin::Pin,
+  str::FromStr,
+  task::{Context, Poll},
+};
+use std::{
+  collections::VecDeque,
+  fs::File,
+  io::{BufWriter, Write},
+  path::Path,
+  sync::{Arc, Mutex},
+};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tracing::warn;
+
+const RECORDING_HEADER: &str = "# nut_webgui_upsmc session recording";
+const SENT_PREFIX: &str = ">";
+const RECEIVED_PREFIX: &str = "<";
+
+/// Single line of a recorded session, without its line terminator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordedLine {
+  Sent(Box<str>),
+  Received(Box<str>),
+}
+
+impl std::fmt::Display for RecordedLine {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      RecordedLine::Sent(line) => write!(f, "{SENT_PREFIX} {line}"),
+      RecordedLine::Received(line) => write!(f, "{RECEIVED_PREFIX} {line}"),
+    }
+  }
+}
+
+/// Parsed session recording.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Recording {
+  lines: Vec<RecordedLine>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordingParseError {
+  /// One-based line number of the malformed line.
+  pub line: usize,
+}
+
+impl std::fmt::Display for RecordingParseError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "recording line {} does not start with '> ' or '< '",
+      self.line
+    )
+  }
+}
+
+impl std::error::Error for RecordingParseError {}
+
+impl Recording {
+  #[inline]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Reads and parses a recording file.
+  pub fn load<P>(path: P) -> Result<Self, std::io::Error>
+  where
+    P: AsRef<Path>,
+  {
+    let text = std::fs::read_to_string(path)?;
+
+    text
+      .parse()
+      .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+  }
+
+  #[inline]
+  pub fn lines(&self) -> &[RecordedLine] {
+    &self.lines
+  }
+
+  #[inline]
+  pub fn push(&mut self, line: RecordedLine) {
+    self.lines.push(line);
+  }
+}
+
+impl FromStr for Recording {
+  type Err = RecordingParseError;
+
+  /// Empty lines and lines starting with `#` are skipped.
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut lines = Vec::new();
+
+    for (idx, line) in s.lines().enumerate() {
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+
+      let recorded = if let Some(content) = line.strip_prefix(SENT_PREFIX) {
+        RecordedLine::Sent(Box::from(content.strip_prefix(' ').unwrap_or(content)))
+      } else if let Some(content) = line.strip_prefix(RECEIVED_PREFIX) {
+        RecordedLine::Received(Box::from(content.strip_prefix(' ').unwrap_or(content)))
+      } else {
+        return Err(RecordingParseError { line: idx + 1 });
+      };
+
+      lines.push(recorded);
+    }
+
+    Ok(Self { lines })
+  }
+}
+
+impl std::fmt::Display for Recording {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    writeln!(f, "{RECORDING_HEADER}")?;
+
+    for line in self.lines.iter() {
+      writeln!(f, "{line}")?;
+    }
+
+    Ok(())
+  }
+}
+
+enum RecorderSink {
+  Writer(Box<dyn Write + Send>),
+  Memory(Recording),
+}
+
+/// Shared destination of recorded lines. Lines are written as soon as they are complete.
+///
+/// A recorder can be shared between connections, but lines of concurrent connections are
+/// interleaved in that case, which makes the recording unusable for [ReplayStream].
+#[derive(Clone)]
+pub struct SessionRecorder {
+  sink: Arc<Mutex<RecorderSink>>,
+}
+
+impl std::fmt::Debug for SessionRecorder {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("SessionRecorder").finish_non_exhaustive()
+  }
+}
+
+impl SessionRecorder {
+  /// Records into the given writer. Write errors are logged and otherwise ignored, so a broken
+  /// recording never fails the connection itself.
+  pub fn new<W>(writer: W) -> Self
+  where
+    W: Write + Send + 'static,
+  {
+    let mut writer: Box<dyn Write + Send> = Box::new(writer);
+
+    if let Err(err) = writeln!(writer, "{RECORDING_HEADER}") {
+      warn!(message = "unable to write session recording", reason = %err);
+    }
+
+    Self {
+      sink: Arc::new(Mutex::new(RecorderSink::Writer(writer))),
+    }
+  }
+
+  /// Creates or truncates a recording file.
+  pub fn create<P>(path: P) -> Result<Self, std::io::Error>
+  where
+    P: AsRef<Path>,
+  {
+    let file = File::create(path)?;
+
+    Ok(Self::new(BufWriter::new(file)))
+  }
+
+  /// Keeps recorded lines in memory, see [SessionRecorder::recording].
+  pub fn in_memory() -> Self {
+    Self {
+      sink: Arc::new(Mutex::new(RecorderSink::Memory(Recording::new()))),
+    }
+  }
+
+  /// Returns recorded lines of an in-memory recorder, [None] for writer based recorders.
+  pub fn recording(&self) -> Option<Recording> {
+    match &*self.lock() {
+      RecorderSink::Memory(recording) => Some(recording.clone()),
+      RecorderSink::Writer(_) => None,
+    }
+  }
+
+  /// Flushes buffered lines of writer based recorders.
+  pub fn flush(&self) -> Result<(), std::io::Error> {
+    match &mut *self.lock() {
+      RecorderSink::Writer(writer) => writer.flush(),
+      RecorderSink::Memory(_) => Ok(()),
+    }
+  }
+
+  fn record(&self, line: RecordedLine) {
+    match &mut *self.lock() {
+      RecorderSink::Writer(writer) => {
+        if let Err(err) = writeln!(writer, "{line}").and_then(|_| writer.flush()) {
+          warn!(message = "unable to write session recording", reason = %err);
+        }
+      }
+      RecorderSink::Memory(recording) => recording.push(line),
+    }
+  }
+
+  fn lock(&self) -> std::sync::MutexGuard<'_, RecorderSink> {
+    self
+      .sink
+      .lock()
+      .unwrap_or_else(|poisoned| poisoned.into_inner())
+  }
+}
+
+/// Splits a byte stream into lines.
+#[derive(Debug, Default)]
+struct LineBuffer {
+  buffer: Vec<u8>,
+}
+
+impl LineBuffer {
+  fn push<F>(&mut self, data: &[u8], mut on_line: F)
+  where
+    F: FnMut(&str),
+  {
+    self.buffer.extend_from_slice(data);
+
+    while let Some(pos) = self.buffer.iter().position(|b| *b == b'\n') {
+      let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+      on_line(&String::from_utf8_lossy(&line[..pos]));
+    }
+  }
+
+  fn take_remaining(&mut self) -> Option<String> {
+    if self.buffer.is_empty() {
+      None
+    } else {
+      let line = String::from_utf8_lossy(&self.buffer).into_owned();
+      self.buffer.clear();
+
+      Some(line)
+    }
+  }
+}
+
+/// Transport wrapper which records all traffic to a [SessionRecorder].
+///
+/// ```no_run
+/// use nut_webgui_upsmc::{
+///   clients::NutClient,
+///   recording::{RecordingStream, SessionRecorder},
+/// };
+/// use tokio::net::TcpStream;
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let recorder = SessionRecorder::create("session.txt")?;
+/// let stream = TcpStream::connect("localhost:3493").await?;
+/// let client = NutClient::new(RecordingStream::new(stream, recorder));
+/// # Ok(())
+/// # }
+/// ```
+pub struct RecordingStream<S> {
+  inner: S,
+  recorder: SessionRecorder,
+  sent: LineBuffer,
+  received: LineBuffer,
+}
+
+impl<S> RecordingStream<S> {
+  pub fn new(inner: S, recorder: SessionRecorder) -> Self {
+    Self {
+      inner,
+      recorder,
+      sent: LineBuffer::default(),
+      received: LineBuffer::default(),
+    }
+  }
+
+  #[inline]
+  pub fn recorder(&self) -> &SessionRecorder {
+    &self.recorder
+  }
+
+  #[inline]
+  pub fn into_inner(self) -> S {
+    self.inner
+  }
+
+  fn record_sent(&mut self, data: &[u8]) {
+    let recorder = &self.recorder;
+
+    self.sent.push(data, |line| {
+      recorder.record(RecordedLine::Sent(Box::from(redact(line))))
+    });
+  }
+
+  fn record_received(&mut self, data: &[u8]) {
+    let recorder = &self.recorder;
+
+    self.received.push(data, |line| {
+      recorder.record(RecordedLine::Received(Box::from(line)))
+    });
+  }
+
+  /// Records unterminated lines, called when the stream is shut down.
+  fn record_remaining(&mut self) {
+    if let Some(line) = self.sent.take_remaining() {
+      self
+        .recorder
+        .record(RecordedLine::Sent(Box::from(redact(&line))));
+    }
+
+    if let Some(line) = self.received.take_remaining() {
+      self.recorder.record(RecordedLine::Received(line.into()));
+    }
+  }
+}
+
+impl<S> AsyncRead for RecordingStream<S>
+where
+  S: AsyncRead + Unpin,
+{
+  fn poll_read(
+    mut self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    buf: &mut ReadBuf<'_>,
+  ) -> Poll<std::io::Result<()>> {
+    let filled_before = buf.filled().len();
+    let result = Pin::new(&mut self.inner).poll_read(cx, buf);
+
+    if let Poll::Ready(Ok(())) = result {
+      let received = &buf.filled()[filled_before..];
+
+      if received.is_empty() {
+        self.record_remaining();
+      } else {
+        self.record_received(received);
+      }
+    }
+
+    result
+  }
+}
+
+impl<S> AsyncWrite for RecordingStream<S>
+where
+  S: AsyncWrite + Unpin,
+{
+  fn poll_write(
+    mut self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    buf: &[u8],
+  ) -> Poll<Result<usize, std::io::Error>> {
+    let result = Pin::new(&mut self.inner).poll_write(cx, buf);
+
+    if let Poll::Ready(Ok(written)) = result {
+      self.record_sent(&buf[..written]);
+    }
+
+    result
+  }
+
+  #[inline]
+  fn poll_flush(
+    mut self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+  ) -> Poll<Result<(), std::io::Error>> {
+    Pin::new(&mut self.inner).poll_flush(cx)
+  }
+
+  fn poll_shutdown(
+    mut self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+  ) -> Poll<Result<(), std::io::Error>> {
+    self.record_remaining();
+    _ = self.recorder.flush();
+
+    Pin::new(&mut self.inner).poll_shutdown(cx)
+  }
+}
+
+/// In-memory transport which answers commands from a [Recording].
+///
+/// Each written line is matched against the next recorded command, then the responses recorded
+/// after that command become readable. Reads return end of stream when no recorded response is
+/// left. In strict mode, which is the default, a mismatching command fails the write with
+/// [std::io::ErrorKind::InvalidData]. Written `PASSWORD` lines are redacted before comparison.
+#[derive(Debug)]
+pub struct ReplayStream {
+  lines: VecDeque<RecordedLine>,
+  readable: VecDeque<u8>,
+  written: LineBuffer,
+  strict: bool,
+}
+
+impl ReplayStream {
+  pub fn new(recording: Recording) -> Self {
+    let mut stream = Self {
+      lines: recording.lines.into(),
+      readable: VecDeque::new(),
+      written: LineBuffer::default(),
+      strict: true,
+    };
+
+    stream.queue_responses();
+    stream
+  }
+
+  /// Accepts any command in place of the recorded ones, only their order is kept.
+  pub fn lenient(mut self) -> Self {
+    self.strict = false;
+    self
+  }
+
+  /// Returns true when all recorded commands are sent and all responses are read.
+  pub fn is_finished(&self) -> bool {
+    self.lines.is_empty() && self.readable.is_empty()
+  }
+
+  fn queue_responses(&mut self) {
+    while let Some(RecordedLine::Received(_)) = self.lines.front() {
+      if let Some(RecordedLine::Received(line)) = self.lines.pop_front() {
+        self.readable.extend(line.as_bytes());
+        self.readable.push_back(b'\n');
+      }
+    }
+  }
+
+  fn handle_written(&mut self, data: &[u8]) -> Result<(), std::io::Error> {
+    let mut commands = Vec::new();
+    self
+      .written
+      .push(data, |line| commands.push(redact(line).into_owned()));
+
+    for command in commands {
+      match self.lines.pop_front() {
+        Some(RecordedLine::Sent(expected)) if !self.strict || *expected == *command => {
+          self.queue_responses();
+        }
+        Some(RecordedLine::Sent(expected)) => {
+          return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("replay expected command '{expected}', but received '{command}'"),
+          ));
+        }
+        Some(RecordedLine::Received(_)) | None if !self.strict => {}
+        _ => {
+          return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("replay has no recorded command left for '{command}'"),
+          ));
+        }
+      }
+    }
+
+    Ok(())
+  }
+}
+
+impl AsyncRead for ReplayStream {
+  fn poll_read(
+    mut self: Pin<&mut Self>,
+    _cx: &mut Context<'_>,
+    buf: &mut ReadBuf<'_>,
+  ) -> Poll<std::io::Result<()>> {
+    let len = buf.remaining().min(self.readable.len());
+    let (front, back) = self.readable.as_slices();
+
+    if len <= front.len() {
+      buf.put_slice(&front[..len]);
+    } else {
+      buf.put_slice(front);
+      buf.put_slice(&back[..len - front.len()]);
+    }
+
+    self.readable.drain(..len);
+
+    Poll::Ready(Ok(()))
+  }
+}
+
+impl AsyncWrite for ReplayStream {
+  fn poll_write(
+    mut self: Pin<&mut Self>,
+    _cx: &mut Context<'_>,
+    buf: &[u8],
+  ) -> Poll<Result<usize, std::io::Error>> {
+    Poll::Ready(self.handle_written(buf).map(|_| buf.len()))
+  }
+
+  #[inline]
+  fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), std::io::Error>> {
+    Poll::Ready(Ok(()))
+  }
+
+  #[inline]
+  fn poll_shutdown(
+    self: Pin<&mut Self>,
+    _cx: &mut Context<'_>,
+  ) -> Poll<Result<(), std::io::Error>> {
+    Poll::Ready(Ok(()))
+  }
+}
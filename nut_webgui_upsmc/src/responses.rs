@@ -4,13 +4,15 @@ mod get_var;
 mod get_var_desc;
 mod get_var_type;
 mod list_client;
-pub(crate) mod list_cmd;
+mod lenient;
+mod list_cmd;
 mod list_enum;
 mod list_range;
 mod list_rw;
 mod list_ups;
 mod list_var;
 mod misc;
+mod tracking;
 
 pub use get_cmd_desc::*;
 pub use get_ups_desc::*;
@@ -18,10 +20,12 @@ pub use get_var::*;
 pub use get_var_desc::*;
 pub use get_var_type::*;
 pub use list_client::*;
-pub(crate) use list_cmd::CmdList;
+pub use lenient::*;
+pub use list_cmd::*;
 pub use list_enum::*;
 pub use list_range::*;
 pub use list_rw::*;
 pub use list_ups::*;
 pub use list_var::*;
 pub use misc::*;
+pub use tracking::*;
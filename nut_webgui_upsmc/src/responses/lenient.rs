@@ -0,0 +1,22 @@
+use crate::errors::ParseDiagnostic;
+
+/// Response parsed in lenient mode. Malformed list items are skipped and reported as diagnostics
+/// instead of failing the whole response.
+#[derive(Debug)]
+pub struct Lenient<T> {
+  pub response: T,
+  pub diagnostics: Vec<ParseDiagnostic>,
+}
+
+impl<T> Lenient<T> {
+  /// Returns `true` when no line is skipped.
+  #[inline]
+  pub fn is_clean(&self) -> bool {
+    self.diagnostics.is_empty()
+  }
+
+  #[inline]
+  pub fn into_inner(self) -> T {
+    self.response
+  }
+}
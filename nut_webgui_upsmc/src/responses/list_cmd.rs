@@ -1,7 +1,12 @@
 use crate::{
   CmdName, UpsName,
-  errors::{Error, ErrorKind, ParseError},
-  internal::{Deserialize, lexer::Lexer, parser_utils::parse_line},
+  errors::{Error, ErrorKind, ParseDiagnostic, ParseError},
+  internal::{
+    Deserialize,
+    lexer::Lexer,
+    parser_utils::{parse_line, parse_list_items},
+  },
+  responses::Lenient,
 };
 
 #[derive(Debug)]
@@ -10,18 +15,20 @@ pub struct CmdList {
   pub cmds: Vec<CmdName>,
 }
 
-impl Deserialize for CmdList {
-  type Error = Error;
-
-  fn deserialize(lexer: &mut Lexer) -> Result<Self, Self::Error> {
+impl CmdList {
+  fn parse(
+    lexer: &mut Lexer,
+    diagnostics: Option<&mut Vec<ParseDiagnostic>>,
+  ) -> Result<Self, Error> {
     let mut cmds: Vec<CmdName> = Vec::new();
     let ups_name = parse_line!(lexer, "BEGIN" "LIST" "CMD" {UPS, name = ups_name})?;
 
-    while let Some("CMD") = lexer.peek_as_str() {
+    parse_list_items(lexer, "CMD", diagnostics, |lexer| {
       let cmd_name = parse_line!(lexer, "CMD" {TEXT, cmp_to = &ups_name} {CMD, name = cmd_name})?;
 
       cmds.push(cmd_name);
-    }
+      Ok(())
+    })?;
 
     parse_line!(lexer, "END" "LIST" "CMD" {TEXT, cmp_to = &ups_name})?;
 
@@ -38,3 +45,26 @@ impl Deserialize for CmdList {
     }
   }
 }
+
+impl Deserialize for CmdList {
+  type Error = Error;
+
+  #[inline]
+  fn deserialize(lexer: &mut Lexer) -> Result<Self, Self::Error> {
+    Self::parse(lexer, None)
+  }
+}
+
+impl Deserialize for Lenient<CmdList> {
+  type Error = Error;
+
+  fn deserialize(lexer: &mut Lexer) -> Result<Self, Self::Error> {
+    let mut diagnostics = Vec::new();
+    let response = CmdList::parse(lexer, Some(&mut diagnostics))?;
+
+    Ok(Self {
+      response,
+      diagnostics,
+    })
+  }
+}
@@ -1,7 +1,12 @@
 use crate::{
   UpsName, Value, VarName,
-  errors::{Error, ErrorKind, ParseError},
-  internal::{Deserialize, lexer::Lexer, parser_utils::parse_line},
+  errors::{Error, ErrorKind, ParseDiagnostic, ParseError},
+  internal::{
+    Deserialize,
+    lexer::Lexer,
+    parser_utils::{parse_line, parse_list_items},
+  },
+  responses::Lenient,
 };
 
 #[derive(Debug)]
@@ -11,10 +16,11 @@ pub struct EnumList {
   pub values: Vec<Value>,
 }
 
-impl Deserialize for EnumList {
-  type Error = Error;
-
-  fn deserialize(lexer: &mut Lexer) -> Result<Self, Self::Error> {
+impl EnumList {
+  fn parse(
+    lexer: &mut Lexer,
+    diagnostics: Option<&mut Vec<ParseDiagnostic>>,
+  ) -> Result<Self, Error> {
     let mut values: Vec<Value> = Vec::new();
     let (ups_name, var_name) =
       parse_line!(lexer, "BEGIN" "LIST" "ENUM" {UPS, name = ups_name} {TEXT, name = var_name})?;
@@ -24,10 +30,11 @@ impl Deserialize for EnumList {
       position: lexer.get_positon(),
     })?;
 
-    while let Some("ENUM") = lexer.peek_as_str() {
+    parse_list_items(lexer, "ENUM", diagnostics, |lexer| {
       let value = parse_line!(lexer, "ENUM" {TEXT, cmp_to = &ups_name} {TEXT, cmp_to = name.as_str()} {VALUE, name = value})?;
       values.push(value);
-    }
+      Ok(())
+    })?;
 
     parse_line!(lexer, "END" "LIST" "ENUM" {TEXT, cmp_to = &ups_name} {TEXT, cmp_to = name.as_str()})?;
 
@@ -48,3 +55,26 @@ impl Deserialize for EnumList {
     }
   }
 }
+
+impl Deserialize for EnumList {
+  type Error = Error;
+
+  #[inline]
+  fn deserialize(lexer: &mut Lexer) -> Result<Self, Self::Error> {
+    Self::parse(lexer, None)
+  }
+}
+
+impl Deserialize for Lenient<EnumList> {
+  type Error = Error;
+
+  fn deserialize(lexer: &mut Lexer) -> Result<Self, Self::Error> {
+    let mut diagnostics = Vec::new();
+    let response = EnumList::parse(lexer, Some(&mut diagnostics))?;
+
+    Ok(Self {
+      response,
+      diagnostics,
+    })
+  }
+}
@@ -1,7 +1,12 @@
 use crate::{
   UpsName,
-  errors::{Error, ErrorKind, ParseError},
-  internal::{Deserialize, lexer::Lexer, parser_utils::parse_line},
+  errors::{Error, ErrorKind, ParseDiagnostic, ParseError},
+  internal::{
+    Deserialize,
+    lexer::Lexer,
+    parser_utils::{parse_line, parse_list_items},
+  },
+  responses::Lenient,
   variables::UpsVariables,
 };
 use tracing::warn;
@@ -12,14 +17,15 @@ pub struct RwList {
   pub ups_name: UpsName,
 }
 
-impl Deserialize for RwList {
-  type Error = Error;
-
-  fn deserialize(lexer: &mut Lexer) -> Result<Self, Self::Error> {
+impl RwList {
+  fn parse(
+    lexer: &mut Lexer,
+    diagnostics: Option<&mut Vec<ParseDiagnostic>>,
+  ) -> Result<Self, Error> {
     let mut variables = UpsVariables::new();
     let ups_name = parse_line!(lexer, "BEGIN" "LIST" "RW" {UPS, name = ups_name})?;
 
-    while let Some("RW") = lexer.peek_as_str() {
+    parse_list_items(lexer, "RW", diagnostics, |lexer| {
       let (name, value) = parse_line!(lexer, "RW" {TEXT, cmp_to = &ups_name} {VAR, name = var_name} {VALUE, name = value})?;
 
       if let Some(previous) = variables.insert(name, value) {
@@ -28,7 +34,9 @@ impl Deserialize for RwList {
           previous = previous.to_string(),
         )
       }
-    }
+
+      Ok(())
+    })?;
 
     parse_line!(lexer, "END" "LIST" "RW" {TEXT, cmp_to = &ups_name})?;
 
@@ -48,3 +56,26 @@ impl Deserialize for RwList {
     }
   }
 }
+
+impl Deserialize for RwList {
+  type Error = Error;
+
+  #[inline]
+  fn deserialize(lexer: &mut Lexer) -> Result<Self, Self::Error> {
+    Self::parse(lexer, None)
+  }
+}
+
+impl Deserialize for Lenient<RwList> {
+  type Error = Error;
+
+  fn deserialize(lexer: &mut Lexer) -> Result<Self, Self::Error> {
+    let mut diagnostics = Vec::new();
+    let response = RwList::parse(lexer, Some(&mut diagnostics))?;
+
+    Ok(Self {
+      response,
+      diagnostics,
+    })
+  }
+}
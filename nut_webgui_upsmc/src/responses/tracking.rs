@@ -0,0 +1,112 @@
+use crate::{
+  errors::{Error, ErrorKind, ParseError, ProtocolError},
+  internal::{Deserialize, lexer::Lexer, parser_utils::parse_line},
+};
+
+/// Response of `INSTCMD` and `SET VAR` when tracking is enabled on the connection.
+#[derive(Debug)]
+pub struct ProtOkTracking {
+  pub id: Box<str>,
+}
+
+/// Execution state of a tracked `INSTCMD` or `SET VAR` request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrackingStatus {
+  Pending,
+  Success,
+  /// Driver reported failure, or upsd no longer knows the tracking id.
+  Failed {
+    reason: ProtocolError,
+  },
+}
+
+impl TrackingStatus {
+  #[inline]
+  pub const fn is_pending(&self) -> bool {
+    matches!(self, Self::Pending)
+  }
+}
+
+impl Deserialize for ProtOkTracking {
+  type Error = Error;
+
+  fn deserialize(lexer: &mut Lexer) -> Result<Self, Self::Error> {
+    let id = parse_line!(lexer, "OK" "TRACKING" {TEXT, name = id})?;
+
+    if lexer.is_finished() {
+      Ok(Self { id })
+    } else {
+      Err(
+        ErrorKind::ParseError {
+          inner: ParseError::InvalidToken,
+          position: lexer.get_positon(),
+        }
+        .into(),
+      )
+    }
+  }
+}
+
+impl Deserialize for TrackingStatus {
+  type Error = Error;
+
+  fn deserialize(lexer: &mut Lexer) -> Result<Self, Self::Error> {
+    let position = lexer.get_positon();
+    let status = parse_line!(lexer, {TEXT, name = status})?;
+
+    let status = match status.as_ref() {
+      "PENDING" => Self::Pending,
+      "SUCCESS" => Self::Success,
+      _ => {
+        return Err(
+          ErrorKind::ParseError {
+            inner: ParseError::InvalidToken,
+            position,
+          }
+          .into(),
+        );
+      }
+    };
+
+    if lexer.is_finished() {
+      Ok(status)
+    } else {
+      Err(
+        ErrorKind::ParseError {
+          inner: ParseError::InvalidToken,
+          position: lexer.get_positon(),
+        }
+        .into(),
+      )
+    }
+  }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for TrackingStatus {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer,
+  {
+    use serde::ser::SerializeStruct;
+
+    match self {
+      TrackingStatus::Pending => {
+        let mut s = serializer.serialize_struct("TrackingStatus", 1)?;
+        s.serialize_field("status", "pending")?;
+        s.end()
+      }
+      TrackingStatus::Success => {
+        let mut s = serializer.serialize_struct("TrackingStatus", 1)?;
+        s.serialize_field("status", "success")?;
+        s.end()
+      }
+      TrackingStatus::Failed { reason } => {
+        let mut s = serializer.serialize_struct("TrackingStatus", 2)?;
+        s.serialize_field("status", "failed")?;
+        s.serialize_field("reason", reason)?;
+        s.end()
+      }
+    }
+  }
+}
@@ -0,0 +1,239 @@
+use crate::{InferValueFrom, Value, errors::RwValueError};
+
+/// Fallback string length for writable variables without any reported type.
+pub const DEFAULT_RW_STRING_LEN: usize = 64;
+
+/// Writable variable type and its allowed values, loaded with `GET TYPE` followed by `LIST ENUM`
+/// or `LIST RANGE`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RwDetail {
+  String { max_len: usize },
+  Number,
+  Enum { options: Vec<Value> },
+  Range { ranges: ValueRanges },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueRange {
+  pub min: Value,
+  pub max: Value,
+}
+
+/// All intervals reported by `LIST RANGE`, ordered by their lower bound. Drivers may report
+/// disjoint intervals, a value is valid when it is within any of them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueRanges(Vec<ValueRange>);
+
+impl RwDetail {
+  /// Checks an already typed value, e.g. a JSON request body.
+  pub fn validate(&self, value: &Value) -> Result<(), RwValueError> {
+    match self {
+      RwDetail::String { max_len } => {
+        if !value.is_text() {
+          Err(RwValueError::NotText)
+        } else {
+          validate_text(value.as_str().as_ref(), *max_len)
+        }
+      }
+      RwDetail::Number => {
+        if value.is_numeric() {
+          Ok(())
+        } else {
+          Err(RwValueError::NotNumber)
+        }
+      }
+      RwDetail::Enum { options } => {
+        if options.contains(value) {
+          Ok(())
+        } else {
+          Err(RwValueError::InvalidOption)
+        }
+      }
+      RwDetail::Range { ranges } => {
+        if !value.is_numeric() {
+          return Err(RwValueError::NotNumber);
+        }
+
+        match ranges.contains(value.as_lossly_f64().unwrap_or(0.0)) {
+          Some(true) => Ok(()),
+          Some(false) => Err(RwValueError::OutOfRange),
+          None => Err(RwValueError::MalformedRange),
+        }
+      }
+    }
+  }
+
+  /// Converts text input, e.g. a form field or command-line argument, into a [Value] of the
+  /// variable type and checks it.
+  pub fn parse(&self, input: &str) -> Result<Value, RwValueError> {
+    let value = match self {
+      RwDetail::String { max_len } => {
+        validate_text(input, *max_len)?;
+        return Ok(Value::from(input));
+      }
+      RwDetail::Enum { options } => {
+        let value = Value::infer_from(input);
+
+        return match options.iter().find(|v| **v == value || **v == input) {
+          Some(option) => Ok(option.clone()),
+          None => Err(RwValueError::InvalidOption),
+        };
+      }
+      RwDetail::Number | RwDetail::Range { .. } => {
+        Value::infer_number_from(input).map_err(|_| RwValueError::NotNumber)?
+      }
+    };
+
+    self.validate(&value)?;
+    Ok(value)
+  }
+}
+
+fn validate_text(text: &str, max_len: usize) -> Result<(), RwValueError> {
+  if text.trim().is_empty() {
+    Err(RwValueError::Empty)
+  } else if text.len() > max_len {
+    Err(RwValueError::TooLong { max_len })
+  } else {
+    Ok(())
+  }
+}
+
+impl ValueRange {
+  /// Returns [None] when the driver reported bounds are not numeric.
+  pub fn contains(&self, value: f64) -> Option<bool> {
+    let min = self.min.as_lossly_f64()?;
+    let max = self.max.as_lossly_f64()?;
+
+    Some(min <= value && value <= max)
+  }
+}
+
+impl ValueRanges {
+  pub fn new(ranges: Vec<(Value, Value)>) -> Self {
+    let mut ranges: Vec<ValueRange> = ranges
+      .into_iter()
+      .map(|(min, max)| ValueRange { min, max })
+      .collect();
+
+    ranges.sort_by(|a, b| {
+      let a = a.min.as_lossly_f64().unwrap_or(f64::MIN);
+      let b = b.min.as_lossly_f64().unwrap_or(f64::MIN);
+      a.total_cmp(&b)
+    });
+
+    Self(ranges)
+  }
+
+  /// Returns [None] when any of the driver reported bounds is not numeric.
+  pub fn contains(&self, value: f64) -> Option<bool> {
+    let mut found = false;
+
+    for range in self.0.iter() {
+      found |= range.contains(value)?;
+    }
+
+    Some(found)
+  }
+
+  /// Lowest bound of all intervals.
+  pub fn min(&self) -> Option<&Value> {
+    self.0.first().map(|v| &v.min)
+  }
+
+  /// Highest bound of all intervals.
+  pub fn max(&self) -> Option<&Value> {
+    self.0.iter().map(|v| &v.max).max_by(|a, b| {
+      let a = a.as_lossly_f64().unwrap_or(f64::MIN);
+      let b = b.as_lossly_f64().unwrap_or(f64::MIN);
+      a.total_cmp(&b)
+    })
+  }
+
+  #[inline]
+  pub fn iter(&self) -> core::slice::Iter<'_, ValueRange> {
+    self.0.iter()
+  }
+}
+
+impl core::fmt::Display for ValueRanges {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    for (idx, range) in self.0.iter().enumerate() {
+      if idx > 0 {
+        f.write_str(", ")?;
+      }
+
+      write!(f, "[{}, {}]", range.min, range.max)?;
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+  use super::{RwDetail, ValueRange, ValueRanges};
+  use serde::ser::{SerializeSeq, SerializeStruct};
+
+  impl serde::Serialize for RwDetail {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+      S: serde::Serializer,
+    {
+      match self {
+        RwDetail::String { max_len } => {
+          let mut ser = serializer.serialize_struct("RwDetail", 2)?;
+          ser.serialize_field("type", "string")?;
+          ser.serialize_field("max_len", max_len)?;
+          ser.end()
+        }
+        RwDetail::Number => {
+          let mut ser = serializer.serialize_struct("RwDetail", 1)?;
+          ser.serialize_field("type", "number")?;
+          ser.end()
+        }
+        RwDetail::Enum { options } => {
+          let mut ser = serializer.serialize_struct("RwDetail", 2)?;
+          ser.serialize_field("type", "enum")?;
+          ser.serialize_field("options", options)?;
+          ser.end()
+        }
+        RwDetail::Range { ranges } => {
+          let mut ser = serializer.serialize_struct("RwDetail", 4)?;
+          ser.serialize_field("type", "range")?;
+          ser.serialize_field("min", &ranges.min())?;
+          ser.serialize_field("max", &ranges.max())?;
+          ser.serialize_field("ranges", ranges)?;
+          ser.end()
+        }
+      }
+    }
+  }
+
+  impl serde::Serialize for ValueRange {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+      S: serde::Serializer,
+    {
+      let mut ser = serializer.serialize_struct("ValueRange", 2)?;
+      ser.serialize_field("min", &self.min)?;
+      ser.serialize_field("max", &self.max)?;
+      ser.end()
+    }
+  }
+
+  impl serde::Serialize for ValueRanges {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+      S: serde::Serializer,
+    {
+      let mut ser = serializer.serialize_seq(Some(self.0.len()))?;
+
+      for range in self.0.iter() {
+        ser.serialize_element(range)?;
+      }
+
+      ser.end()
+    }
+  }
+}
@@ -0,0 +1,565 @@
+//! Minimal upsd compatible server (RFC 9271), mainly intended for testing clients without a real
+//! NUT installation.
+//!
+//! ```no_run
+//! use nut_webgui_upsmc::{UpsName, VarName, server::{MemoryBackend, MemoryDevice, NutServer}};
+//! use tokio::net::TcpListener;
+//!
+//! # async fn example() -> Result<(), nut_webgui_upsmc::errors::Error> {
+//! let backend = MemoryBackend::new().with_device(
+//!   UpsName::new_unchecked("ups"),
+//!   MemoryDevice::new("Test UPS").with_var(VarName::UPS_STATUS, "OL"),
+//! );
+//!
+//! let listener = TcpListener::bind("127.0.0.1:0").await?;
+//! tokio::spawn(NutServer::new(backend).listen(listener));
+//! # Ok(())
+//! # }
+//! ```
+
+mod backend;
+mod memory;
+
+pub use backend::DeviceBackend;
+pub use memory::{MemoryBackend, MemoryDevice, RwVarKind};
+
+use crate::{
+  CmdName, InferValueFrom, UpsName, Value, VarName,
+  errors::{Error, ProtocolError},
+  internal::{
+    escape::escape_nut_str,
+    lexer::{Lexer, Token},
+  },
+};
+use core::{
+  fmt::Write,
+  net::{IpAddr, Ipv4Addr},
+  sync::atomic::{AtomicU64, Ordering},
+};
+use std::{
+  collections::HashMap,
+  sync::{Arc, Mutex},
+};
+use tokio::{
+  io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader, split},
+  net::TcpListener,
+};
+use tracing::{trace, warn};
+
+const SERVER_VERSION: &str = concat!(
+  "Network UPS Tools upsd ",
+  env!("CARGO_PKG_VERSION"),
+  " (nut_webgui_upsmc)"
+);
+const PROTOCOL_VERSION: &str = "1.3";
+
+/// upsd compatible server, serves devices provided by a [DeviceBackend].
+///
+/// Cloning is cheap, all clones share the same backend, attached clients and tracking table.
+pub struct NutServer<B>
+where
+  B: DeviceBackend,
+{
+  inner: Arc<ServerShared<B>>,
+}
+
+struct ServerShared<B> {
+  backend: B,
+  attached: Mutex<HashMap<UpsName, Vec<IpAddr>>>,
+  tracking: Mutex<HashMap<Box<str>, Result<(), ProtocolError>>>,
+  tracking_seq: AtomicU64,
+}
+
+/// Per connection state.
+#[derive(Debug)]
+struct Session {
+  peer: IpAddr,
+  username: Option<Box<str>>,
+  password: Option<Box<str>>,
+  attached: Vec<UpsName>,
+  tracking: bool,
+  closed: bool,
+}
+
+impl<B> Clone for NutServer<B>
+where
+  B: DeviceBackend,
+{
+  fn clone(&self) -> Self {
+    Self {
+      inner: self.inner.clone(),
+    }
+  }
+}
+
+impl<B> NutServer<B>
+where
+  B: DeviceBackend,
+{
+  pub fn new(backend: B) -> Self {
+    Self {
+      inner: Arc::new(ServerShared {
+        backend,
+        attached: Mutex::new(HashMap::new()),
+        tracking: Mutex::new(HashMap::new()),
+        tracking_seq: AtomicU64::new(1),
+      }),
+    }
+  }
+
+  #[inline]
+  pub fn backend(&self) -> &B {
+    &self.inner.backend
+  }
+
+  /// Accepts connections until the listener fails. Each connection is served on its own task.
+  pub async fn listen(self, listener: TcpListener) -> Result<(), Error> {
+    loop {
+      let (stream, peer) = listener.accept().await?;
+      let server = self.clone();
+
+      tokio::spawn(async move {
+        if let Err(err) = server.serve_connection(stream, peer.ip()).await {
+          warn!(message = "client connection failed", peer = %peer, reason = %err);
+        }
+      });
+    }
+  }
+
+  /// Serves a single client connection until it sends `LOGOUT` or closes the stream.
+  pub async fn serve_connection<S>(&self, stream: S, peer: IpAddr) -> Result<(), Error>
+  where
+    S: AsyncRead + AsyncWrite + Unpin,
+  {
+    let (reader, mut writer) = split(stream);
+    let mut reader = BufReader::new(reader);
+    let mut line = String::new();
+    let mut session = Session {
+      peer,
+      username: None,
+      password: None,
+      attached: Vec::new(),
+      tracking: false,
+      closed: false,
+    };
+
+    let result = async {
+      while !session.closed {
+        line.clear();
+
+        if reader.read_line(&mut line).await? == 0 {
+          break;
+        }
+
+        let response = self.handle_line(&mut session, &line);
+        trace!(
+          message = "server response",
+          request = %crate::commands::redact(&line),
+          response = &response
+        );
+
+        writer.write_all(response.as_bytes()).await?;
+        writer.flush().await?;
+      }
+
+      Ok(())
+    }
+    .await;
+
+    self.detach_all(&session);
+    _ = writer.shutdown().await;
+
+    result
+  }
+
+  /// Serves a client over an in-memory stream, peer address is reported as `127.0.0.1`.
+  #[inline]
+  pub fn serve_stream<S>(&self, stream: S) -> impl Future<Output = Result<(), Error>>
+  where
+    S: AsyncRead + AsyncWrite + Unpin,
+  {
+    self.serve_connection(stream, IpAddr::V4(Ipv4Addr::LOCALHOST))
+  }
+
+  fn handle_line(&self, session: &mut Session, line: &str) -> String {
+    let args = match tokenize(line) {
+      Some(args) if !args.is_empty() => args,
+      _ => return err_response(ProtocolError::InvalidArgument),
+    };
+
+    let args: Vec<&str> = args.iter().map(|v| v.as_str()).collect();
+
+    match self.handle_command(session, args.as_slice()) {
+      Ok(response) => response,
+      Err(err) => err_response(err),
+    }
+  }
+
+  fn handle_command(&self, session: &mut Session, args: &[&str]) -> Result<String, ProtocolError> {
+    let backend = &self.inner.backend;
+
+    match args {
+      ["VER"] => Ok(format!("{SERVER_VERSION}\n")),
+      ["NETVER"] | ["PROTVER"] => Ok(format!("{PROTOCOL_VERSION}\n")),
+      ["HELP"] => Ok(
+        "Commands: HELP VER GET LIST SET INSTCMD LOGIN LOGOUT USERNAME PASSWORD STARTTLS\n"
+          .to_owned(),
+      ),
+      ["STARTTLS"] => Err(ProtocolError::FeatureNotConfigured),
+      ["USERNAME", username] => {
+        if session.username.is_some() {
+          Err(ProtocolError::AlreadySetUsername)
+        } else {
+          session.username = Some(Box::from(*username));
+          Ok(ok_response())
+        }
+      }
+      ["PASSWORD", password] => {
+        if session.password.is_some() {
+          Err(ProtocolError::AlreadySetPassword)
+        } else {
+          session.password = Some(Box::from(*password));
+          Ok(ok_response())
+        }
+      }
+      ["LOGIN", ups] => {
+        let ups = parse_ups(ups)?;
+        self.authorize(session)?;
+        _ = backend.get_ups_desc(&ups)?;
+
+        if session.attached.contains(&ups) {
+          return Err(ProtocolError::AlreadyAttached);
+        }
+
+        if let Ok(mut attached) = self.inner.attached.lock() {
+          attached.entry(ups.clone()).or_default().push(session.peer);
+        }
+
+        session.attached.push(ups);
+        Ok(ok_response())
+      }
+      ["LOGOUT"] => {
+        self.detach_all(session);
+        session.attached.clear();
+        session.closed = true;
+        Ok("OK Goodbye\n".to_owned())
+      }
+      ["FSD", ups] => {
+        let ups = parse_ups(ups)?;
+        self.authorize(session)?;
+        backend.fsd(&ups)?;
+
+        Ok("OK FSD-SET\n".to_owned())
+      }
+      ["INSTCMD", ups, cmd] => {
+        let ups = parse_ups(ups)?;
+        let cmd = parse_cmd(cmd)?;
+        self.authorize(session)?;
+
+        let result = backend.instcmd(&ups, &cmd);
+        self.complete(session, result)
+      }
+      ["INSTCMD", ups, cmd, value] => {
+        let ups = parse_ups(ups)?;
+        let cmd = parse_cmd(cmd)?;
+        self.authorize(session)?;
+
+        let result = backend.instcmd_with_value(&ups, &cmd, Value::from(*value));
+        self.complete(session, result)
+      }
+      ["SET", "VAR", ups, var, value] => {
+        let ups = parse_ups(ups)?;
+        let var = parse_var(var)?;
+        self.authorize(session)?;
+
+        let result = backend.set_var(&ups, &var, Value::infer_from(*value));
+        self.complete(session, result)
+      }
+      ["SET", "TRACKING", "ON"] => {
+        session.tracking = true;
+        Ok(ok_response())
+      }
+      ["SET", "TRACKING", "OFF"] => {
+        session.tracking = false;
+        Ok(ok_response())
+      }
+      ["GET", "VAR", ups, var] => {
+        let ups = parse_ups(ups)?;
+        let var = parse_var(var)?;
+        let value = backend.get_var(&ups, &var)?;
+
+        Ok(format!(
+          "VAR {ups} {var} \"{value}\"\n",
+          ups = ups.as_escaped_str(),
+          value = value.as_escaped_str()
+        ))
+      }
+      ["GET", "TYPE", ups, var] => {
+        let ups = parse_ups(ups)?;
+        let var = parse_var(var)?;
+        let types = backend.get_var_type(&ups, &var)?;
+        let mut response = format!("TYPE {ups} {var}", ups = ups.as_escaped_str());
+
+        for var_type in types {
+          _ = write!(&mut response, " {var_type}");
+        }
+
+        response.push('\n');
+        Ok(response)
+      }
+      ["GET", "DESC", ups, var] => {
+        let ups = parse_ups(ups)?;
+        let var = parse_var(var)?;
+        let desc = backend.get_var_desc(&ups, &var)?;
+
+        Ok(format!(
+          "DESC {ups} {var} \"{desc}\"\n",
+          ups = ups.as_escaped_str(),
+          desc = escape_nut_str(&desc)
+        ))
+      }
+      ["GET", "CMDDESC", ups, cmd] => {
+        let ups = parse_ups(ups)?;
+        let cmd = parse_cmd(cmd)?;
+        let desc = backend.get_cmd_desc(&ups, &cmd)?;
+
+        Ok(format!(
+          "CMDDESC {ups} {cmd} \"{desc}\"\n",
+          ups = ups.as_escaped_str(),
+          desc = escape_nut_str(&desc)
+        ))
+      }
+      ["GET", "UPSDESC", ups] => {
+        let ups = parse_ups(ups)?;
+        let desc = backend.get_ups_desc(&ups)?;
+
+        Ok(format!(
+          "UPSDESC {ups} \"{desc}\"\n",
+          ups = ups.as_escaped_str(),
+          desc = escape_nut_str(&desc)
+        ))
+      }
+      ["GET", "NUMLOGINS", ups] => {
+        let ups = parse_ups(ups)?;
+        _ = backend.get_ups_desc(&ups)?;
+        let count = self.attached_clients(&ups).len();
+
+        Ok(format!(
+          "NUMLOGINS {ups} {count}\n",
+          ups = ups.as_escaped_str()
+        ))
+      }
+      ["GET", "TRACKING", id] => {
+        let tracking = self
+          .inner
+          .tracking
+          .lock()
+          .map_err(|_| ProtocolError::Unknown(Box::from("UNKNOWN")))?;
+
+        match tracking.get(*id) {
+          Some(Ok(())) => Ok("SUCCESS\n".to_owned()),
+          Some(Err(ProtocolError::InvalidArgument)) => Err(ProtocolError::InvalidArgument),
+          Some(Err(_)) => Err(ProtocolError::Unknown(Box::from("FAILED"))),
+          None => Err(ProtocolError::Unknown(Box::from("UNKNOWN"))),
+        }
+      }
+      ["LIST", "UPS"] => {
+        let mut response = String::from("BEGIN LIST UPS\n");
+
+        for (ups, desc) in backend.list_ups() {
+          _ = writeln!(
+            &mut response,
+            "UPS {ups} \"{desc}\"",
+            ups = ups.as_escaped_str(),
+            desc = escape_nut_str(&desc)
+          );
+        }
+
+        response.push_str("END LIST UPS\n");
+        Ok(response)
+      }
+      ["LIST", list @ ("VAR" | "RW"), ups] => {
+        let ups = parse_ups(ups)?;
+        let variables = if *list == "VAR" {
+          backend.list_var(&ups)?
+        } else {
+          backend.list_rw(&ups)?
+        };
+
+        let ups = ups.as_escaped_str();
+        let mut response = format!("BEGIN LIST {list} {ups}\n");
+
+        for (var, value) in variables {
+          _ = writeln!(
+            &mut response,
+            "{list} {ups} {var} \"{value}\"",
+            value = value.as_escaped_str()
+          );
+        }
+
+        _ = writeln!(&mut response, "END LIST {list} {ups}");
+        Ok(response)
+      }
+      ["LIST", "CMD", ups] => {
+        let ups = parse_ups(ups)?;
+        let commands = backend.list_cmd(&ups)?;
+        let ups = ups.as_escaped_str();
+        let mut response = format!("BEGIN LIST CMD {ups}\n");
+
+        for cmd in commands {
+          _ = writeln!(&mut response, "CMD {ups} {cmd}");
+        }
+
+        _ = writeln!(&mut response, "END LIST CMD {ups}");
+        Ok(response)
+      }
+      ["LIST", "ENUM", ups, var] => {
+        let ups = parse_ups(ups)?;
+        let var = parse_var(var)?;
+        let options = backend.list_enum(&ups, &var)?;
+        let ups = ups.as_escaped_str();
+        let mut response = format!("BEGIN LIST ENUM {ups} {var}\n");
+
+        for value in options {
+          _ = writeln!(
+            &mut response,
+            "ENUM {ups} {var} \"{value}\"",
+            value = value.as_escaped_str()
+          );
+        }
+
+        _ = writeln!(&mut response, "END LIST ENUM {ups} {var}");
+        Ok(response)
+      }
+      ["LIST", "RANGE", ups, var] => {
+        let ups = parse_ups(ups)?;
+        let var = parse_var(var)?;
+        let ranges = backend.list_range(&ups, &var)?;
+        let ups = ups.as_escaped_str();
+        let mut response = format!("BEGIN LIST RANGE {ups} {var}\n");
+
+        for (min, max) in ranges {
+          _ = writeln!(
+            &mut response,
+            "RANGE {ups} {var} \"{min}\" \"{max}\"",
+            min = min.as_escaped_str(),
+            max = max.as_escaped_str()
+          );
+        }
+
+        _ = writeln!(&mut response, "END LIST RANGE {ups} {var}");
+        Ok(response)
+      }
+      ["LIST", "CLIENT", ups] => {
+        let ups = parse_ups(ups)?;
+        _ = backend.get_ups_desc(&ups)?;
+        let clients = self.attached_clients(&ups);
+        let ups = ups.as_escaped_str();
+        let mut response = format!("BEGIN LIST CLIENT {ups}\n");
+
+        for ip in clients {
+          _ = writeln!(&mut response, "CLIENT {ups} {ip}");
+        }
+
+        _ = writeln!(&mut response, "END LIST CLIENT {ups}");
+        Ok(response)
+      }
+      [
+        "VER" | "NETVER" | "PROTVER" | "HELP" | "STARTTLS" | "USERNAME" | "PASSWORD" | "LOGIN"
+        | "LOGOUT" | "FSD" | "INSTCMD" | "SET" | "GET" | "LIST",
+        ..,
+      ] => Err(ProtocolError::InvalidArgument),
+      _ => Err(ProtocolError::UnknownCommand),
+    }
+  }
+
+  fn authorize(&self, session: &Session) -> Result<(), ProtocolError> {
+    match (&session.username, &session.password) {
+      (None, _) => Err(ProtocolError::UsernameRequired),
+      (Some(_), None) => Err(ProtocolError::PasswordRequired),
+      (Some(username), Some(password)) => self.inner.backend.authenticate(username, password),
+    }
+  }
+
+  /// Creates response for `INSTCMD` and `SET VAR`. When tracking is enabled, result is stored and
+  /// the request is always acknowledged with a tracking id.
+  fn complete(
+    &self,
+    session: &Session,
+    result: Result<(), ProtocolError>,
+  ) -> Result<String, ProtocolError> {
+    if !session.tracking {
+      return result.map(|_| ok_response());
+    }
+
+    let seq = self.inner.tracking_seq.fetch_add(1, Ordering::Relaxed);
+    let id = format!("00000000-0000-4000-8000-{seq:012x}");
+
+    if let Ok(mut tracking) = self.inner.tracking.lock() {
+      tracking.insert(Box::from(id.as_str()), result);
+    }
+
+    Ok(format!("OK TRACKING {id}\n"))
+  }
+
+  fn attached_clients(&self, ups: &UpsName) -> Vec<IpAddr> {
+    match self.inner.attached.lock() {
+      Ok(attached) => attached.get(ups).cloned().unwrap_or_default(),
+      Err(_) => Vec::new(),
+    }
+  }
+
+  fn detach_all(&self, session: &Session) {
+    if let Ok(mut attached) = self.inner.attached.lock() {
+      for ups in session.attached.iter() {
+        let Some(clients) = attached.get_mut(ups) else {
+          continue;
+        };
+
+        if let Some(idx) = clients.iter().position(|ip| *ip == session.peer) {
+          clients.swap_remove(idx);
+        }
+      }
+    }
+  }
+}
+
+/// Splits request line into arguments, double-quoted arguments are unescaped.
+fn tokenize(line: &str) -> Option<Vec<String>> {
+  let mut lexer = Lexer::new(line);
+  let mut args = Vec::new();
+
+  while let Some(token) = lexer.next_token().ok()? {
+    match token {
+      Token::LF => break,
+      token => args.push(lexer.extract_from_token(&token).into_owned()),
+    }
+  }
+
+  Some(args)
+}
+
+#[inline]
+fn ok_response() -> String {
+  "OK\n".to_owned()
+}
+
+#[inline]
+fn err_response(err: ProtocolError) -> String {
+  format!("ERR {err}\n")
+}
+
+#[inline]
+fn parse_ups(name: &str) -> Result<UpsName, ProtocolError> {
+  UpsName::new(name).map_err(|_| ProtocolError::UnknownUps)
+}
+
+#[inline]
+fn parse_var(name: &str) -> Result<VarName, ProtocolError> {
+  VarName::new(name).map_err(|_| ProtocolError::VarNotSupported)
+}
+
+#[inline]
+fn parse_cmd(name: &str) -> Result<CmdName, ProtocolError> {
+  CmdName::new(name).map_err(|_| ProtocolError::CmdNotSupported)
+}
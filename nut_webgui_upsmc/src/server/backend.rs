@@ -0,0 +1,63 @@
+use crate::{CmdName, UpsName, Value, VarName, VarType, errors::ProtocolError};
+
+/// Device data source used by [super::NutServer].
+///
+/// Methods are called synchronously while a client request is being processed. Returned
+/// [ProtocolError]s are sent to the client as `ERR <code>` responses.
+pub trait DeviceBackend: Send + Sync + 'static {
+  /// Lists all UPS devices with their descriptions.
+  fn list_ups(&self) -> Vec<(UpsName, Box<str>)>;
+
+  fn get_ups_desc(&self, ups: &UpsName) -> Result<Box<str>, ProtocolError>;
+
+  fn list_var(&self, ups: &UpsName) -> Result<Vec<(VarName, Value)>, ProtocolError>;
+
+  fn get_var(&self, ups: &UpsName, var: &VarName) -> Result<Value, ProtocolError>;
+
+  fn get_var_type(&self, ups: &UpsName, var: &VarName) -> Result<Vec<VarType>, ProtocolError>;
+
+  fn get_var_desc(&self, ups: &UpsName, var: &VarName) -> Result<Box<str>, ProtocolError> {
+    _ = self.get_var(ups, var)?;
+    Ok(Box::from("Description unavailable"))
+  }
+
+  fn list_rw(&self, ups: &UpsName) -> Result<Vec<(VarName, Value)>, ProtocolError>;
+
+  fn list_enum(&self, ups: &UpsName, var: &VarName) -> Result<Vec<Value>, ProtocolError>;
+
+  fn list_range(&self, ups: &UpsName, var: &VarName) -> Result<Vec<(Value, Value)>, ProtocolError>;
+
+  fn list_cmd(&self, ups: &UpsName) -> Result<Vec<CmdName>, ProtocolError>;
+
+  fn get_cmd_desc(&self, ups: &UpsName, cmd: &CmdName) -> Result<Box<str>, ProtocolError> {
+    if self.list_cmd(ups)?.contains(cmd) {
+      Ok(Box::from("Description unavailable"))
+    } else {
+      Err(ProtocolError::CmdNotSupported)
+    }
+  }
+
+  fn set_var(&self, ups: &UpsName, var: &VarName, value: Value) -> Result<(), ProtocolError>;
+
+  fn instcmd(&self, ups: &UpsName, cmd: &CmdName) -> Result<(), ProtocolError>;
+
+  /// Handles `INSTCMD` with a command argument. Rejects arguments by default.
+  fn instcmd_with_value(
+    &self,
+    ups: &UpsName,
+    cmd: &CmdName,
+    value: Value,
+  ) -> Result<(), ProtocolError> {
+    _ = (ups, cmd, value);
+    Err(ProtocolError::InvalidArgument)
+  }
+
+  fn fsd(&self, ups: &UpsName) -> Result<(), ProtocolError>;
+
+  /// Validates credentials before `LOGIN`, `FSD`, `INSTCMD` and `SET VAR` requests. Accepts all
+  /// users by default.
+  fn authenticate(&self, username: &str, password: &str) -> Result<(), ProtocolError> {
+    _ = (username, password);
+    Ok(())
+  }
+}
@@ -0,0 +1,389 @@
+use super::DeviceBackend;
+use crate::{CmdName, UpsName, Value, VarName, VarType, errors::ProtocolError};
+use std::{
+  collections::{BTreeMap, HashMap},
+  sync::RwLock,
+};
+
+/// Write rules of a RW variable.
+#[derive(Debug, Clone)]
+pub enum RwVarKind {
+  String { max_len: usize },
+  Number,
+  Enum { options: Vec<Value> },
+  Range { ranges: Vec<(Value, Value)> },
+}
+
+/// Simulated UPS device for [MemoryBackend].
+#[derive(Debug, Clone, Default)]
+pub struct MemoryDevice {
+  desc: Box<str>,
+  variables: BTreeMap<VarName, Value>,
+  var_desc: HashMap<VarName, Box<str>>,
+  rw_variables: HashMap<VarName, RwVarKind>,
+  commands: BTreeMap<CmdName, Box<str>>,
+  executed: Vec<CmdName>,
+  executed_values: Vec<(CmdName, Value)>,
+}
+
+impl MemoryDevice {
+  pub fn new<D>(desc: D) -> Self
+  where
+    D: Into<Box<str>>,
+  {
+    Self {
+      desc: desc.into(),
+      ..Default::default()
+    }
+  }
+
+  pub fn with_var<V>(mut self, var: VarName, value: V) -> Self
+  where
+    V: Into<Value>,
+  {
+    self.variables.insert(var, value.into());
+    self
+  }
+
+  pub fn with_rw_var<V>(mut self, var: VarName, value: V, kind: RwVarKind) -> Self
+  where
+    V: Into<Value>,
+  {
+    self.variables.insert(var.clone(), value.into());
+    self.rw_variables.insert(var, kind);
+    self
+  }
+
+  pub fn with_var_desc<D>(mut self, var: VarName, desc: D) -> Self
+  where
+    D: Into<Box<str>>,
+  {
+    self.var_desc.insert(var, desc.into());
+    self
+  }
+
+  pub fn with_cmd<D>(mut self, cmd: CmdName, desc: D) -> Self
+  where
+    D: Into<Box<str>>,
+  {
+    self.commands.insert(cmd, desc.into());
+    self
+  }
+
+  #[inline]
+  pub fn get_var(&self, var: &VarName) -> Option<&Value> {
+    self.variables.get(var)
+  }
+
+  /// Commands executed with `INSTCMD`, in call order.
+  #[inline]
+  pub fn executed_cmds(&self) -> &[CmdName] {
+    &self.executed
+  }
+
+  /// Commands executed with `INSTCMD` and a command argument, in call order.
+  #[inline]
+  pub fn executed_cmd_values(&self) -> &[(CmdName, Value)] {
+    &self.executed_values
+  }
+
+  fn validate(&self, var: &VarName, value: &Value) -> Result<(), ProtocolError> {
+    let kind = match self.rw_variables.get(var) {
+      Some(kind) => kind,
+      None if self.variables.contains_key(var) => return Err(ProtocolError::Readonly),
+      None => return Err(ProtocolError::VarNotSupported),
+    };
+
+    let is_valid = match kind {
+      RwVarKind::String { max_len } => {
+        if value.as_str().len() > *max_len {
+          return Err(ProtocolError::TooLong);
+        }
+
+        true
+      }
+      RwVarKind::Number => value.is_numeric(),
+      RwVarKind::Enum { options } => options.iter().any(|v| v.as_str() == value.as_str()),
+      RwVarKind::Range { ranges } => match value.as_lossly_f64() {
+        Some(value) => {
+          ranges.iter().any(
+            |(min, max)| match (min.as_lossly_f64(), max.as_lossly_f64()) {
+              (Some(min), Some(max)) => min <= value && value <= max,
+              _ => false,
+            },
+          )
+        }
+        None => false,
+      },
+    };
+
+    if is_valid {
+      Ok(())
+    } else {
+      Err(ProtocolError::InvalidValue)
+    }
+  }
+}
+
+/// In-memory [DeviceBackend] implementation.
+#[derive(Debug, Default)]
+pub struct MemoryBackend {
+  devices: RwLock<BTreeMap<UpsName, MemoryDevice>>,
+  users: HashMap<Box<str>, Box<str>>,
+}
+
+impl MemoryBackend {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn with_device(self, ups: UpsName, device: MemoryDevice) -> Self {
+    self.insert_device(ups, device);
+    self
+  }
+
+  /// Restricts privileged commands to the given users. When no user is configured, any
+  /// credential is accepted.
+  pub fn with_user<U, P>(mut self, username: U, password: P) -> Self
+  where
+    U: Into<Box<str>>,
+    P: Into<Box<str>>,
+  {
+    self.users.insert(username.into(), password.into());
+    self
+  }
+
+  /// Adds or replaces a device.
+  pub fn insert_device(&self, ups: UpsName, device: MemoryDevice) {
+    if let Ok(mut devices) = self.devices.write() {
+      devices.insert(ups, device);
+    }
+  }
+
+  pub fn remove_device(&self, ups: &UpsName) -> Option<MemoryDevice> {
+    self.devices.write().ok()?.remove(ups)
+  }
+
+  /// Returns a snapshot of the device.
+  pub fn device(&self, ups: &UpsName) -> Option<MemoryDevice> {
+    self.devices.read().ok()?.get(ups).cloned()
+  }
+
+  /// Updates a variable directly, bypassing RW checks.
+  pub fn update_var<V>(&self, ups: &UpsName, var: VarName, value: V) -> bool
+  where
+    V: Into<Value>,
+  {
+    self
+      .write_device(ups, |device| {
+        device.variables.insert(var, value.into());
+      })
+      .is_ok()
+  }
+
+  fn read_device<F, R>(&self, ups: &UpsName, f: F) -> Result<R, ProtocolError>
+  where
+    F: FnOnce(&MemoryDevice) -> Result<R, ProtocolError>,
+  {
+    let devices = self
+      .devices
+      .read()
+      .map_err(|_| ProtocolError::DriverNotConnected)?;
+
+    match devices.get(ups) {
+      Some(device) => f(device),
+      None => Err(ProtocolError::UnknownUps),
+    }
+  }
+
+  fn write_device<F, R>(&self, ups: &UpsName, f: F) -> Result<R, ProtocolError>
+  where
+    F: FnOnce(&mut MemoryDevice) -> R,
+  {
+    let mut devices = self
+      .devices
+      .write()
+      .map_err(|_| ProtocolError::DriverNotConnected)?;
+
+    match devices.get_mut(ups) {
+      Some(device) => Ok(f(device)),
+      None => Err(ProtocolError::UnknownUps),
+    }
+  }
+}
+
+impl DeviceBackend for MemoryBackend {
+  fn list_ups(&self) -> Vec<(UpsName, Box<str>)> {
+    match self.devices.read() {
+      Ok(devices) => devices
+        .iter()
+        .map(|(name, device)| (name.clone(), device.desc.clone()))
+        .collect(),
+      Err(_) => Vec::new(),
+    }
+  }
+
+  fn get_ups_desc(&self, ups: &UpsName) -> Result<Box<str>, ProtocolError> {
+    self.read_device(ups, |device| Ok(device.desc.clone()))
+  }
+
+  fn list_var(&self, ups: &UpsName) -> Result<Vec<(VarName, Value)>, ProtocolError> {
+    self.read_device(ups, |device| {
+      Ok(
+        device
+          .variables
+          .iter()
+          .map(|(k, v)| (k.clone(), v.clone()))
+          .collect(),
+      )
+    })
+  }
+
+  fn get_var(&self, ups: &UpsName, var: &VarName) -> Result<Value, ProtocolError> {
+    self.read_device(ups, |device| {
+      device
+        .variables
+        .get(var)
+        .cloned()
+        .ok_or(ProtocolError::VarNotSupported)
+    })
+  }
+
+  fn get_var_type(&self, ups: &UpsName, var: &VarName) -> Result<Vec<VarType>, ProtocolError> {
+    self.read_device(ups, |device| {
+      let value = device
+        .variables
+        .get(var)
+        .ok_or(ProtocolError::VarNotSupported)?;
+
+      let types = match device.rw_variables.get(var) {
+        Some(RwVarKind::String { max_len }) => {
+          vec![VarType::ReadWrite, VarType::String { max_len: *max_len }]
+        }
+        Some(RwVarKind::Number) => vec![VarType::ReadWrite, VarType::Number],
+        Some(RwVarKind::Enum { .. }) => vec![VarType::ReadWrite, VarType::Enum],
+        Some(RwVarKind::Range { .. }) => vec![VarType::ReadWrite, VarType::Range],
+        None if value.is_numeric() => vec![VarType::Number],
+        None => vec![VarType::String {
+          max_len: value.as_str().len(),
+        }],
+      };
+
+      Ok(types)
+    })
+  }
+
+  fn get_var_desc(&self, ups: &UpsName, var: &VarName) -> Result<Box<str>, ProtocolError> {
+    self.read_device(ups, |device| {
+      match (device.var_desc.get(var), device.variables.contains_key(var)) {
+        (Some(desc), _) => Ok(desc.clone()),
+        (None, true) => Ok(Box::from("Description unavailable")),
+        (None, false) => Err(ProtocolError::VarNotSupported),
+      }
+    })
+  }
+
+  fn list_rw(&self, ups: &UpsName) -> Result<Vec<(VarName, Value)>, ProtocolError> {
+    self.read_device(ups, |device| {
+      Ok(
+        device
+          .variables
+          .iter()
+          .filter(|(k, _)| device.rw_variables.contains_key(*k))
+          .map(|(k, v)| (k.clone(), v.clone()))
+          .collect(),
+      )
+    })
+  }
+
+  fn list_enum(&self, ups: &UpsName, var: &VarName) -> Result<Vec<Value>, ProtocolError> {
+    self.read_device(ups, |device| match device.rw_variables.get(var) {
+      Some(RwVarKind::Enum { options }) => Ok(options.clone()),
+      Some(_) => Ok(Vec::new()),
+      None => Err(ProtocolError::VarNotSupported),
+    })
+  }
+
+  fn list_range(&self, ups: &UpsName, var: &VarName) -> Result<Vec<(Value, Value)>, ProtocolError> {
+    self.read_device(ups, |device| match device.rw_variables.get(var) {
+      Some(RwVarKind::Range { ranges }) => Ok(ranges.clone()),
+      Some(_) => Ok(Vec::new()),
+      None => Err(ProtocolError::VarNotSupported),
+    })
+  }
+
+  fn list_cmd(&self, ups: &UpsName) -> Result<Vec<CmdName>, ProtocolError> {
+    self.read_device(ups, |device| Ok(device.commands.keys().cloned().collect()))
+  }
+
+  fn get_cmd_desc(&self, ups: &UpsName, cmd: &CmdName) -> Result<Box<str>, ProtocolError> {
+    self.read_device(ups, |device| {
+      device
+        .commands
+        .get(cmd)
+        .cloned()
+        .ok_or(ProtocolError::CmdNotSupported)
+    })
+  }
+
+  fn set_var(&self, ups: &UpsName, var: &VarName, value: Value) -> Result<(), ProtocolError> {
+    self.write_device(ups, |device| {
+      device.validate(var, &value)?;
+      device.variables.insert(var.clone(), value);
+
+      Ok(())
+    })?
+  }
+
+  fn instcmd(&self, ups: &UpsName, cmd: &CmdName) -> Result<(), ProtocolError> {
+    self.write_device(ups, |device| {
+      if device.commands.contains_key(cmd) {
+        device.executed.push(cmd.clone());
+        Ok(())
+      } else {
+        Err(ProtocolError::CmdNotSupported)
+      }
+    })?
+  }
+
+  fn instcmd_with_value(
+    &self,
+    ups: &UpsName,
+    cmd: &CmdName,
+    value: Value,
+  ) -> Result<(), ProtocolError> {
+    self.write_device(ups, |device| {
+      if device.commands.contains_key(cmd) {
+        device.executed_values.push((cmd.clone(), value));
+        Ok(())
+      } else {
+        Err(ProtocolError::CmdNotSupported)
+      }
+    })?
+  }
+
+  fn fsd(&self, ups: &UpsName) -> Result<(), ProtocolError> {
+    self.write_device(ups, |device| {
+      let status = match device.variables.get(&VarName::UPS_STATUS) {
+        Some(status) if !status.as_str().split(' ').any(|v| v == "FSD") => {
+          Value::from(format!("FSD {}", status))
+        }
+        Some(status) => status.clone(),
+        None => Value::from("FSD"),
+      };
+
+      device.variables.insert(VarName::UPS_STATUS, status);
+    })
+  }
+
+  fn authenticate(&self, username: &str, password: &str) -> Result<(), ProtocolError> {
+    if self.users.is_empty() {
+      return Ok(());
+    }
+
+    match self.users.get(username) {
+      Some(expected) if expected.as_ref() == password => Ok(()),
+      _ => Err(ProtocolError::AccessDenied),
+    }
+  }
+}
@@ -0,0 +1,316 @@
+//! TLS configuration for upsd `STARTTLS` upgrades.
+
+use crate::errors::{Error, TlsError};
+use ring::digest::{SHA256, SHA256_OUTPUT_LEN, digest};
+use std::{
+  fs::File,
+  io::BufReader,
+  path::{Path, PathBuf},
+  sync::Arc,
+};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_rustls::{
+  TlsConnector,
+  rustls::{
+    CertificateError, ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme,
+    client::{
+      WebPkiServerVerifier,
+      danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+    },
+    crypto::{
+      WebPkiSupportedAlgorithms, ring::default_provider, verify_tls12_signature,
+      verify_tls13_signature,
+    },
+    pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime},
+  },
+};
+
+pub use tokio_rustls::client::TlsStream;
+
+/// Client side TLS settings used by [crate::clients::NutClient::start_tls].
+///
+/// Cloning is cheap, underlying rustls config is reference counted.
+#[derive(Clone)]
+pub struct TlsConfig {
+  connector: TlsConnector,
+  server_name: ServerName<'static>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TlsConfigBuilder {
+  server_name: Box<str>,
+  ca_files: Vec<PathBuf>,
+  pinned_certs: Vec<Box<str>>,
+  client_cert: Option<(PathBuf, PathBuf)>,
+}
+
+impl TlsConfig {
+  /// Creates a new builder. `server_name` is used for SNI and certificate hostname verification,
+  /// it can be a DNS name or an IP address.
+  pub fn builder<N>(server_name: N) -> TlsConfigBuilder
+  where
+    N: Into<Box<str>>,
+  {
+    TlsConfigBuilder {
+      server_name: server_name.into(),
+      ca_files: Vec::new(),
+      pinned_certs: Vec::new(),
+      client_cert: None,
+    }
+  }
+
+  #[inline]
+  pub fn server_name(&self) -> &ServerName<'static> {
+    &self.server_name
+  }
+
+  /// Returns a copy of the config with a different server name, trust anchors and client
+  /// certificate are shared. Useful for fallback upsd endpoints with their own hostnames.
+  pub fn with_server_name<N>(&self, server_name: N) -> Result<Self, Error>
+  where
+    N: Into<Box<str>>,
+  {
+    let server_name = ServerName::try_from(server_name.into().into_string())
+      .map_err(|_| TlsError::InvalidServerName)?;
+
+    Ok(Self {
+      connector: self.connector.clone(),
+      server_name,
+    })
+  }
+
+  pub(crate) async fn handshake<S>(&self, stream: S) -> Result<TlsStream<S>, Error>
+  where
+    S: AsyncRead + AsyncWrite + Unpin,
+  {
+    self
+      .connector
+      .connect(self.server_name.clone(), stream)
+      .await
+      .map_err(|err| TlsError::Handshake(err.to_string().into_boxed_str()).into())
+  }
+}
+
+impl std::fmt::Debug for TlsConfig {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("TlsConfig")
+      .field("server_name", &self.server_name)
+      .finish_non_exhaustive()
+  }
+}
+
+impl TlsConfigBuilder {
+  /// Adds PEM encoded CA certificate(s) used for verifying upsd certificate chain.
+  pub fn with_ca_file<P>(mut self, path: P) -> Self
+  where
+    P: Into<PathBuf>,
+  {
+    self.ca_files.push(path.into());
+    self
+  }
+
+  /// Pins upsd certificate with its hex encoded SHA-256 fingerprint. Colon separated format
+  /// (`openssl x509 -fingerprint -sha256`) is also accepted.
+  ///
+  /// When no CA file is configured, a matching fingerprint is the only requirement for trust.
+  /// Otherwise, certificate must pass both chain verification and pinning.
+  pub fn with_pinned_cert<F>(mut self, fingerprint: F) -> Self
+  where
+    F: Into<Box<str>>,
+  {
+    self.pinned_certs.push(fingerprint.into());
+    self
+  }
+
+  /// Sets PEM encoded client certificate chain and private key for mutual TLS.
+  pub fn with_client_cert<C, K>(mut self, cert_path: C, key_path: K) -> Self
+  where
+    C: Into<PathBuf>,
+    K: Into<PathBuf>,
+  {
+    self.client_cert = Some((cert_path.into(), key_path.into()));
+    self
+  }
+
+  /// Loads all certificates and keys, and creates the final [TlsConfig].
+  pub fn build(self) -> Result<TlsConfig, Error> {
+    let server_name = ServerName::try_from(self.server_name.into_string())
+      .map_err(|_| TlsError::InvalidServerName)?;
+
+    let provider = Arc::new(default_provider());
+    let pins = self
+      .pinned_certs
+      .iter()
+      .map(|v| parse_fingerprint(v))
+      .collect::<Result<Vec<_>, _>>()?;
+
+    let mut roots = RootCertStore::empty();
+
+    for path in self.ca_files.iter() {
+      for cert in load_certs(path)? {
+        roots.add(cert).map_err(config_error)?;
+      }
+    }
+
+    let webpki_verifier = if roots.is_empty() {
+      None
+    } else {
+      let verifier = WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider.clone())
+        .build()
+        .map_err(config_error)?;
+
+      Some(verifier)
+    };
+
+    let builder = ClientConfig::builder_with_provider(provider.clone())
+      .with_safe_default_protocol_versions()
+      .map_err(config_error)?;
+
+    let builder = match (webpki_verifier, pins.is_empty()) {
+      (None, true) => return Err(TlsError::NoTrustAnchor.into()),
+      (Some(verifier), true) => builder.with_webpki_verifier(verifier),
+      (verifier, false) => builder
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(PinnedCertVerifier {
+          pins,
+          inner: verifier,
+          algorithms: provider.signature_verification_algorithms,
+        })),
+    };
+
+    let client_config = match self.client_cert {
+      Some((cert_path, key_path)) => {
+        let certs = load_certs(&cert_path)?;
+        let key = load_private_key(&key_path)?;
+
+        builder
+          .with_client_auth_cert(certs, key)
+          .map_err(config_error)?
+      }
+      None => builder.with_no_client_auth(),
+    };
+
+    Ok(TlsConfig {
+      connector: TlsConnector::from(Arc::new(client_config)),
+      server_name,
+    })
+  }
+}
+
+/// Certificate verifier for fingerprint pinning. Chain verification is delegated to `inner` when
+/// it's available.
+#[derive(Debug)]
+struct PinnedCertVerifier {
+  pins: Vec<[u8; SHA256_OUTPUT_LEN]>,
+  inner: Option<Arc<WebPkiServerVerifier>>,
+  algorithms: WebPkiSupportedAlgorithms,
+}
+
+impl ServerCertVerifier for PinnedCertVerifier {
+  fn verify_server_cert(
+    &self,
+    end_entity: &CertificateDer<'_>,
+    intermediates: &[CertificateDer<'_>],
+    server_name: &ServerName<'_>,
+    ocsp_response: &[u8],
+    now: UnixTime,
+  ) -> Result<ServerCertVerified, tokio_rustls::rustls::Error> {
+    let fingerprint = digest(&SHA256, end_entity.as_ref());
+
+    if !self.pins.iter().any(|pin| pin == fingerprint.as_ref()) {
+      return Err(tokio_rustls::rustls::Error::InvalidCertificate(
+        CertificateError::ApplicationVerificationFailure,
+      ));
+    }
+
+    match &self.inner {
+      Some(verifier) => {
+        verifier.verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)
+      }
+      None => Ok(ServerCertVerified::assertion()),
+    }
+  }
+
+  fn verify_tls12_signature(
+    &self,
+    message: &[u8],
+    cert: &CertificateDer<'_>,
+    dss: &DigitallySignedStruct,
+  ) -> Result<HandshakeSignatureValid, tokio_rustls::rustls::Error> {
+    verify_tls12_signature(message, cert, dss, &self.algorithms)
+  }
+
+  fn verify_tls13_signature(
+    &self,
+    message: &[u8],
+    cert: &CertificateDer<'_>,
+    dss: &DigitallySignedStruct,
+  ) -> Result<HandshakeSignatureValid, tokio_rustls::rustls::Error> {
+    verify_tls13_signature(message, cert, dss, &self.algorithms)
+  }
+
+  fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+    self.algorithms.supported_schemes()
+  }
+}
+
+fn config_error<E>(err: E) -> TlsError
+where
+  E: std::fmt::Display,
+{
+  TlsError::Config(err.to_string().into_boxed_str())
+}
+
+fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>, Error> {
+  let mut reader = BufReader::new(File::open(path)?);
+  let certs = rustls_pemfile::certs(&mut reader).collect::<Result<Vec<_>, _>>()?;
+
+  if certs.is_empty() {
+    Err(TlsError::InvalidCertificate.into())
+  } else {
+    Ok(certs)
+  }
+}
+
+fn load_private_key(path: &Path) -> Result<PrivateKeyDer<'static>, Error> {
+  let mut reader = BufReader::new(File::open(path)?);
+
+  match rustls_pemfile::private_key(&mut reader)? {
+    Some(key) => Ok(key),
+    None => Err(TlsError::InvalidPrivateKey.into()),
+  }
+}
+
+/// Parses hex encoded SHA-256 fingerprints, colons and whitespaces are ignored.
+fn parse_fingerprint(value: &str) -> Result<[u8; SHA256_OUTPUT_LEN], TlsError> {
+  let mut output = [0u8; SHA256_OUTPUT_LEN];
+  let mut digits = value
+    .bytes()
+    .filter(|b| *b != b':' && !b.is_ascii_whitespace());
+
+  for byte in output.iter_mut() {
+    let high = digits.next().and_then(hex_value);
+    let low = digits.next().and_then(hex_value);
+
+    match (high, low) {
+      (Some(high), Some(low)) => *byte = (high << 4) | low,
+      _ => return Err(TlsError::InvalidFingerprint),
+    }
+  }
+
+  if digits.next().is_some() {
+    Err(TlsError::InvalidFingerprint)
+  } else {
+    Ok(output)
+  }
+}
+
+#[inline]
+const fn hex_value(digit: u8) -> Option<u8> {
+  match digit {
+    b'0'..=b'9' => Some(digit - b'0'),
+    b'a'..=b'f' => Some(digit - b'a' + 10),
+    b'A'..=b'F' => Some(digit - b'A' + 10),
+    _ => None,
+  }
+}
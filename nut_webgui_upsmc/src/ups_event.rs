@@ -39,15 +39,45 @@ pub enum UpsEvent {
   /// Battery discharge stoped
   DischargeEnded,
 
+  /// Ups switched to ECO mode
+  EcoMode,
+
+  /// Ups left ECO mode
+  EcoModeEnded,
+
+  /// Fan failure detected
+  FanFailure,
+
+  /// Fan failure cleared
+  FanFailureEnded,
+
   /// Forced shutdown initiated (Execute Order 66)
   FSD,
 
+  /// Battery is overcharged or voltage is too high
+  HighBattery,
+
+  /// Battery level no longer high
+  HighBatteryEnded,
+
+  /// Ups switched to high efficiency mode
+  HighEfficiency,
+
+  /// Ups left high efficiency mode
+  HighEfficiencyEnded,
+
   /// Battery is low (LOWBATT)
   LowBattery,
 
   /// Battery level normal
   LowBatteryEnded,
 
+  /// Battery is missing or disconnected
+  NoBattery,
+
+  /// Battery is present again
+  NoBatteryEnded,
+
   /// Device is turned off
   DeviceOff,
 
@@ -60,6 +90,12 @@ pub enum UpsEvent {
   /// Device is on battery (ONBATT)
   OnBattery,
 
+  /// Ups is overheated
+  Overheat,
+
+  /// Ups temperature normal
+  OverheatEnded,
+
   /// Ups is overloaded
   Overloaded,
 
@@ -109,8 +145,14 @@ impl UpsEvents {
         UpsStatus::CALIBRATING => events.insert(UpsEvent::CalibrationEnded),
         UpsStatus::CHARGING => events.insert(UpsEvent::ChargeEnded),
         UpsStatus::DISCHARGE => events.insert(UpsEvent::DischargeEnded),
+        UpsStatus::ECO => events.insert(UpsEvent::EcoModeEnded),
+        UpsStatus::FAN_FAILURE => events.insert(UpsEvent::FanFailureEnded),
+        UpsStatus::HIGH_BATTERY => events.insert(UpsEvent::HighBatteryEnded),
+        UpsStatus::HIGH_EFFICIENCY => events.insert(UpsEvent::HighEfficiencyEnded),
         UpsStatus::LOW_BATTERY => events.insert(UpsEvent::LowBatteryEnded),
+        UpsStatus::NO_BATTERY => events.insert(UpsEvent::NoBatteryEnded),
         UpsStatus::OFFLINE => events.insert(UpsEvent::DeviceOn),
+        UpsStatus::OVERHEAT => events.insert(UpsEvent::OverheatEnded),
         UpsStatus::OVERLOADED => events.insert(UpsEvent::OverloadEnded),
         UpsStatus::REPLACE_BATTERY => events.insert(UpsEvent::ReplaceBatteryEnded),
         UpsStatus::TEST => events.insert(UpsEvent::TestEnded),
@@ -130,12 +172,18 @@ impl UpsEvents {
         UpsStatus::CHARGING => events.insert(UpsEvent::Charging),
         UpsStatus::COMM => events.insert(UpsEvent::COMM),
         UpsStatus::DISCHARGE => events.insert(UpsEvent::Discharging),
+        UpsStatus::ECO => events.insert(UpsEvent::EcoMode),
+        UpsStatus::FAN_FAILURE => events.insert(UpsEvent::FanFailure),
         UpsStatus::FORCED_SHUTDOWN => events.insert(UpsEvent::FSD),
+        UpsStatus::HIGH_BATTERY => events.insert(UpsEvent::HighBattery),
+        UpsStatus::HIGH_EFFICIENCY => events.insert(UpsEvent::HighEfficiency),
         UpsStatus::LOW_BATTERY => events.insert(UpsEvent::LowBattery),
         UpsStatus::NOCOMM => events.insert(UpsEvent::NoCOMM),
+        UpsStatus::NO_BATTERY => events.insert(UpsEvent::NoBattery),
         UpsStatus::OFFLINE => events.insert(UpsEvent::DeviceOff),
         UpsStatus::ONLINE => events.insert(UpsEvent::Online),
         UpsStatus::ON_BATTERY => events.insert(UpsEvent::OnBattery),
+        UpsStatus::OVERHEAT => events.insert(UpsEvent::Overheat),
         UpsStatus::OVERLOADED => events.insert(UpsEvent::Overloaded),
         UpsStatus::REPLACE_BATTERY => events.insert(UpsEvent::ReplaceBattery),
         UpsStatus::TEST => events.insert(UpsEvent::Testing),
@@ -205,13 +253,25 @@ impl std::fmt::Display for UpsEvent {
       UpsEvent::ChargeEnded => "Not charging",
       UpsEvent::Discharging => "Discharging",
       UpsEvent::DischargeEnded => "Not discharging",
+      UpsEvent::EcoMode => "ECO mode on",
+      UpsEvent::EcoModeEnded => "ECO mode off",
+      UpsEvent::FanFailure => "Fan failure",
+      UpsEvent::FanFailureEnded => "Fan failure cleared",
       UpsEvent::FSD => "System shutdown",
+      UpsEvent::HighBattery => "High battery",
+      UpsEvent::HighBatteryEnded => "Battery not high",
+      UpsEvent::HighEfficiency => "High efficiency mode on",
+      UpsEvent::HighEfficiencyEnded => "High efficiency mode off",
       UpsEvent::LowBattery => "Low battery",
       UpsEvent::LowBatteryEnded => "Battery not low",
+      UpsEvent::NoBattery => "Battery missing",
+      UpsEvent::NoBatteryEnded => "Battery present",
       UpsEvent::DeviceOff => "Ups turned off",
       UpsEvent::DeviceOn => "Ups not turned off",
       UpsEvent::Online => "Receiving power",
       UpsEvent::OnBattery => "Power lost",
+      UpsEvent::Overheat => "Ups overheated",
+      UpsEvent::OverheatEnded => "Ups not overheated",
       UpsEvent::Overloaded => "Ups overloaded",
       UpsEvent::OverloadEnded => "Ups not overloaded",
       UpsEvent::ReplaceBattery => "Replace battery",
@@ -13,16 +13,23 @@ macro_rules! impl_status {
         let mut result = $crate::ups_status::UpsStatus::default();
 
         for status in value.as_ref().split_whitespace() {
-          match status {
-            $(
-              $value => { result |= $crate::ups_status::UpsStatus::$name },
-            )+
-            _ => {}
-          };
+          if let Some(flag) = Self::from_token(status) {
+            result |= flag;
+          }
         }
 
         result
       }
+
+      /// Returns the flag of a single status token, or [None] for unrecognised tokens.
+      pub fn from_token(token: &str) -> Option<Self> {
+        match token {
+          $(
+            $value => Some($crate::ups_status::UpsStatus::$name),
+          )+
+          _ => None,
+        }
+      }
     }
 
     fn get_state_str(value: $crate::ups_status::UpsStatus) -> &'static str {
@@ -47,8 +54,8 @@ macro_rules! impl_status {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct UpsStatus(u32);
 
-// NOTE: networkupstools may have more options like ECO (deprecated), FANFAIL, OVERHEAT etc.
-// Current implementation follows RFC version and ignores any other status texts.
+// Flags follow RFC 9271 and the additional states reported by networkupstools 2.8 drivers.
+// Tokens outside of this list are preserved by [UpsStatusInfo].
 impl_status!(
 (ALARM,           "ALARM");
 (BOOST,           "BOOST");
@@ -57,12 +64,18 @@ impl_status!(
 (CHARGING,        "CHRG");
 (COMM,            "COMM");
 (DISCHARGE,       "DISCHRG");
+(ECO,             "ECO");
+(FAN_FAILURE,     "FANFAIL");
 (FORCED_SHUTDOWN, "FSD");
+(HIGH_BATTERY,    "HB");
+(HIGH_EFFICIENCY, "HE");
 (LOW_BATTERY,     "LB");
 (NOCOMM,          "NOCOMM");
+(NO_BATTERY,      "NOBATTERY");
 (OFFLINE,         "OFF");
 (ONLINE,          "OL");
 (ON_BATTERY,      "OB");
+(OVERHEAT,        "OVERHEAT");
 (OVERLOADED,      "OVER");
 (REPLACE_BATTERY, "RB");
 (TEST,            "TEST");
@@ -221,9 +234,106 @@ impl IntoIterator for UpsStatus {
   }
 }
 
+/// Parsed `ups.status` value. Recognised tokens are stored as [UpsStatus] flags, everything else
+/// is kept in its original order instead of being discarded.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct UpsStatusInfo {
+  flags: UpsStatus,
+  unknown: Vec<Box<str>>,
+}
+
+impl UpsStatusInfo {
+  pub fn new<T>(value: T) -> Self
+  where
+    T: AsRef<str>,
+  {
+    let mut flags = UpsStatus::default();
+    let mut unknown: Vec<Box<str>> = Vec::new();
+
+    for token in value.as_ref().split_whitespace() {
+      match UpsStatus::from_token(token) {
+        Some(flag) => flags |= flag,
+        None => {
+          if !unknown.iter().any(|v| v.as_ref() == token) {
+            unknown.push(Box::from(token));
+          }
+        }
+      }
+    }
+
+    Self { flags, unknown }
+  }
+
+  /// Recognised status flags.
+  #[inline]
+  pub const fn flags(&self) -> UpsStatus {
+    self.flags
+  }
+
+  /// Status tokens not covered by [UpsStatus], e.g. vendor specific states.
+  #[inline]
+  pub fn unknown(&self) -> &[Box<str>] {
+    &self.unknown
+  }
+
+  #[inline]
+  pub const fn has(&self, rhs: UpsStatus) -> bool {
+    self.flags.has(rhs)
+  }
+
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.flags.is_empty() && self.unknown.is_empty()
+  }
+}
+
+impl std::fmt::Display for UpsStatusInfo {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let known = self.flags.iter().map(|v| -> &str { get_state_str(v) });
+    let parts: Vec<&str> = known
+      .chain(self.unknown.iter().map(|v| v.as_ref()))
+      .collect();
+
+    f.write_str(&parts.join(" "))
+  }
+}
+
+impl From<Value> for UpsStatusInfo {
+  #[inline]
+  fn from(value: Value) -> Self {
+    Self::from(&value)
+  }
+}
+
+impl From<&Value> for UpsStatusInfo {
+  fn from(value: &Value) -> Self {
+    match value {
+      Value::String(text) => UpsStatusInfo::new(text),
+      _ => UpsStatusInfo::default(),
+    }
+  }
+}
+
+impl From<&str> for UpsStatusInfo {
+  #[inline]
+  fn from(value: &str) -> Self {
+    Self::new(value)
+  }
+}
+
+impl From<UpsStatus> for UpsStatusInfo {
+  #[inline]
+  fn from(flags: UpsStatus) -> Self {
+    Self {
+      flags,
+      unknown: Vec::new(),
+    }
+  }
+}
+
 #[cfg(feature = "serde")]
 mod serde {
-  use super::UpsStatus;
+  use super::{UpsStatus, UpsStatusInfo};
   use serde::de::Visitor;
 
   impl serde::Serialize for UpsStatus {
@@ -275,4 +385,24 @@ mod serde {
       Ok(UpsStatus::new(v))
     }
   }
+
+  impl serde::Serialize for UpsStatusInfo {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+      S: serde::Serializer,
+    {
+      serializer.serialize_str(&self.to_string())
+    }
+  }
+
+  impl<'de> serde::Deserialize<'de> for UpsStatusInfo {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+      D: serde::Deserializer<'de>,
+    {
+      let text = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+      Ok(UpsStatusInfo::new(text))
+    }
+  }
 }
@@ -1,4 +1,7 @@
-use crate::{errors::NumberParseError, internal::escape::escape_nut_str};
+use crate::{
+  errors::{ArgumentError, NumberParseError},
+  internal::escape::escape_nut_str,
+};
 use std::borrow::Cow;
 
 macro_rules! impl_value_from {
@@ -19,6 +22,9 @@ macro_rules! impl_value_from {
   };
 }
 
+/// Maximum length of a string value sent with `SET VAR` or `INSTCMD`.
+pub const MAX_ARGUMENT_LEN: usize = 256;
+
 /// Basic container type for variable values.
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub enum Value {
@@ -84,6 +90,25 @@ impl Value {
       _ => None,
     }
   }
+
+  /// Checks if the value can be sent as a single request line argument. Quotes and backslashes
+  /// are escaped, but control characters like line breaks cannot be represented in the protocol.
+  pub fn validate_argument(&self) -> Result<(), ArgumentError> {
+    match self {
+      Value::Float(_) | Value::Int(_) => Ok(()),
+      Value::String(text) => {
+        if text.chars().any(char::is_control) {
+          Err(ArgumentError::ControlCharacter)
+        } else if text.len() > MAX_ARGUMENT_LEN {
+          Err(ArgumentError::TooLong {
+            max_len: MAX_ARGUMENT_LEN,
+          })
+        } else {
+          Ok(())
+        }
+      }
+    }
+  }
 }
 
 fn infer_type(input: &str) -> InferredType {
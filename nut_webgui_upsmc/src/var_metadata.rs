@@ -0,0 +1,497 @@
+use crate::var_name::StandardName;
+
+macro_rules! impl_standard_metadata {
+  ($enum_name:ident,
+  $(
+    ($variant_name:ident, $category:ident, $kind:ident, $unit:expr);
+  )+
+  ) => {
+    impl $enum_name {
+      pub const fn metadata(&self) -> VarMetadata {
+        match self {
+          $(
+            Self::$variant_name => VarMetadata {
+              category: VarCategory::$category,
+              kind: VarKind::$kind,
+              unit: $unit,
+            },
+          )+
+        }
+      }
+    }
+  };
+}
+
+/// Device component a variable belongs to, based on the first segment of its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VarCategory {
+  Ambient,
+  Battery,
+  Device,
+  Driver,
+  Input,
+  Outlet,
+  Output,
+  Server,
+  Ups,
+  Other,
+}
+
+/// How a variable value should be interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VarKind {
+  Text,
+  Number,
+  /// Number between 0 and 100.
+  Percentage,
+  /// Number of [VarMetadata::unit], usually seconds.
+  Duration,
+  /// Date or time, format depends on the driver.
+  Timestamp,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VarUnit {
+  Ampere,
+  AmpereHour,
+  Celsius,
+  Hertz,
+  Minute,
+  Percent,
+  Second,
+  Volt,
+  VoltAmpere,
+  Watt,
+}
+
+/// Metadata of standard variable names, see [crate::VarName::metadata].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VarMetadata {
+  pub category: VarCategory,
+  pub kind: VarKind,
+  pub unit: Option<VarUnit>,
+}
+
+impl VarCategory {
+  pub const fn as_str(&self) -> &'static str {
+    match self {
+      VarCategory::Ambient => "ambient",
+      VarCategory::Battery => "battery",
+      VarCategory::Device => "device",
+      VarCategory::Driver => "driver",
+      VarCategory::Input => "input",
+      VarCategory::Outlet => "outlet",
+      VarCategory::Output => "output",
+      VarCategory::Server => "server",
+      VarCategory::Ups => "ups",
+      VarCategory::Other => "other",
+    }
+  }
+
+  /// Detects category from the first segment of a variable name.
+  pub fn from_name(name: &str) -> Self {
+    let prefix = name.split_once('.').map_or(name, |(prefix, _)| prefix);
+
+    match prefix {
+      "ambient" => VarCategory::Ambient,
+      "battery" => VarCategory::Battery,
+      "device" => VarCategory::Device,
+      "driver" => VarCategory::Driver,
+      "input" => VarCategory::Input,
+      "outlet" => VarCategory::Outlet,
+      "output" => VarCategory::Output,
+      "server" => VarCategory::Server,
+      "ups" => VarCategory::Ups,
+      _ => VarCategory::Other,
+    }
+  }
+}
+
+impl VarKind {
+  pub const fn as_str(&self) -> &'static str {
+    match self {
+      VarKind::Text => "text",
+      VarKind::Number => "number",
+      VarKind::Percentage => "percentage",
+      VarKind::Duration => "duration",
+      VarKind::Timestamp => "timestamp",
+    }
+  }
+
+  /// Returns `true` when values are expected to be numeric.
+  #[inline]
+  pub const fn is_numeric(&self) -> bool {
+    matches!(self, Self::Number | Self::Percentage | Self::Duration)
+  }
+}
+
+impl VarUnit {
+  pub const fn symbol(&self) -> &'static str {
+    match self {
+      VarUnit::Ampere => "A",
+      VarUnit::AmpereHour => "Ah",
+      VarUnit::Celsius => "℃",
+      VarUnit::Hertz => "Hz",
+      VarUnit::Minute => "min",
+      VarUnit::Percent => "%",
+      VarUnit::Second => "s",
+      VarUnit::Volt => "V",
+      VarUnit::VoltAmpere => "VA",
+      VarUnit::Watt => "W",
+    }
+  }
+}
+
+impl std::fmt::Display for VarCategory {
+  #[inline]
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+impl std::fmt::Display for VarKind {
+  #[inline]
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+impl std::fmt::Display for VarUnit {
+  #[inline]
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(self.symbol())
+  }
+}
+
+// Generated with tools/gen_var_metadata.lua
+impl_standard_metadata!(
+  StandardName,
+  (AmbientHumidity                                 ,Ambient ,Percentage,Some(VarUnit::Percent));
+  (AmbientHumidityAlarmMaximum                     ,Ambient ,Percentage,Some(VarUnit::Percent));
+  (AmbientHumidityAlarmMinimum                     ,Ambient ,Percentage,Some(VarUnit::Percent));
+  (AmbientHumidityHigh                             ,Ambient ,Percentage,Some(VarUnit::Percent));
+  (AmbientHumidityHighCritical                     ,Ambient ,Percentage,Some(VarUnit::Percent));
+  (AmbientHumidityHighWarning                      ,Ambient ,Percentage,Some(VarUnit::Percent));
+  (AmbientHumidityLow                              ,Ambient ,Percentage,Some(VarUnit::Percent));
+  (AmbientHumidityLowCritical                      ,Ambient ,Percentage,Some(VarUnit::Percent));
+  (AmbientHumidityLowWarning                       ,Ambient ,Percentage,Some(VarUnit::Percent));
+  (AmbientHumidityStatus                           ,Ambient ,Text      ,None);
+  (AmbientPresent                                  ,Ambient ,Text      ,None);
+  (AmbientTemperature                              ,Ambient ,Number    ,Some(VarUnit::Celsius));
+  (AmbientTemperatureAlarmMaximum                  ,Ambient ,Number    ,Some(VarUnit::Celsius));
+  (AmbientTemperatureAlarmMinimum                  ,Ambient ,Number    ,Some(VarUnit::Celsius));
+  (AmbientTemperatureHigh                          ,Ambient ,Number    ,Some(VarUnit::Celsius));
+  (AmbientTemperatureHighCritical                  ,Ambient ,Number    ,Some(VarUnit::Celsius));
+  (AmbientTemperatureHighWarning                   ,Ambient ,Number    ,Some(VarUnit::Celsius));
+  (AmbientTemperatureLow                           ,Ambient ,Number    ,Some(VarUnit::Celsius));
+  (AmbientTemperatureLowCritical                   ,Ambient ,Number    ,Some(VarUnit::Celsius));
+  (AmbientTemperatureLowWarning                    ,Ambient ,Number    ,Some(VarUnit::Celsius));
+  (AmbientTemperatureStatus                        ,Ambient ,Text      ,None);
+  (BatteryAlarmThreshold                           ,Battery ,Text      ,None);
+  (BatteryCapacity                                 ,Battery ,Number    ,Some(VarUnit::AmpereHour));
+  (BatteryCapacityNominal                          ,Battery ,Number    ,Some(VarUnit::AmpereHour));
+  (BatteryCharge                                   ,Battery ,Percentage,Some(VarUnit::Percent));
+  (BatteryChargeLow                                ,Battery ,Percentage,Some(VarUnit::Percent));
+  (BatteryChargeRestart                            ,Battery ,Percentage,Some(VarUnit::Percent));
+  (BatteryChargerStatus                            ,Battery ,Text      ,None);
+  (BatteryChargeWarning                            ,Battery ,Percentage,Some(VarUnit::Percent));
+  (BatteryChemistry                                ,Battery ,Text      ,None);
+  (BatteryCurrent                                  ,Battery ,Number    ,Some(VarUnit::Ampere));
+  (BatteryCurrentMaximum                           ,Battery ,Number    ,Some(VarUnit::Ampere));
+  (BatteryCurrentMinimum                           ,Battery ,Number    ,Some(VarUnit::Ampere));
+  (BatteryCurrentTotal                             ,Battery ,Number    ,Some(VarUnit::Ampere));
+  (BatteryDate                                     ,Battery ,Timestamp ,None);
+  (BatteryDateMaintenance                          ,Battery ,Timestamp ,None);
+  (BatteryEnergysave                               ,Battery ,Text      ,None);
+  (BatteryEnergysaveDelay                          ,Battery ,Duration  ,Some(VarUnit::Minute));
+  (BatteryEnergysaveLoad                           ,Battery ,Percentage,Some(VarUnit::Percent));
+  (BatteryLowbatt                                  ,Battery ,Percentage,Some(VarUnit::Percent));
+  (BatteryMfrDate                                  ,Battery ,Timestamp ,None);
+  (BatteryPacks                                    ,Battery ,Number    ,None);
+  (BatteryPacksBad                                 ,Battery ,Number    ,None);
+  (BatteryProtection                               ,Battery ,Text      ,None);
+  (BatteryRuntime                                  ,Battery ,Duration  ,Some(VarUnit::Second));
+  (BatteryRuntimeElapsed                           ,Battery ,Duration  ,Some(VarUnit::Second));
+  (BatteryRuntimeLow                               ,Battery ,Duration  ,Some(VarUnit::Second));
+  (BatteryTemperature                              ,Battery ,Number    ,Some(VarUnit::Celsius));
+  (BatteryTestStatus                               ,Battery ,Text      ,None);
+  (BatteryType                                     ,Battery ,Text      ,None);
+  (BatteryVoltage                                  ,Battery ,Number    ,Some(VarUnit::Volt));
+  (BatteryVoltageHigh                              ,Battery ,Number    ,Some(VarUnit::Volt));
+  (BatteryVoltageLow                               ,Battery ,Number    ,Some(VarUnit::Volt));
+  (BatteryVoltageMaximum                           ,Battery ,Number    ,Some(VarUnit::Volt));
+  (BatteryVoltageMinimum                           ,Battery ,Number    ,Some(VarUnit::Volt));
+  (BatteryVoltageNominal                           ,Battery ,Number    ,Some(VarUnit::Volt));
+  (DeviceContact                                   ,Device  ,Text      ,None);
+  (DeviceDescription                               ,Device  ,Text      ,None);
+  (DeviceLocation                                  ,Device  ,Text      ,None);
+  (DeviceMacaddr                                   ,Device  ,Text      ,None);
+  (DeviceMfr                                       ,Device  ,Text      ,None);
+  (DeviceModel                                     ,Device  ,Text      ,None);
+  (DevicePart                                      ,Device  ,Text      ,None);
+  (DeviceRevision                                  ,Device  ,Text      ,None);
+  (DeviceSerial                                    ,Device  ,Text      ,None);
+  (DeviceType                                      ,Device  ,Text      ,None);
+  (DriverFlagAllowKillpower                        ,Driver  ,Text      ,None);
+  (DriverFlagIgnorelb                              ,Driver  ,Text      ,None);
+  (DriverFlagIgnoreoff                             ,Driver  ,Text      ,None);
+  (DriverFlagMaxreport                             ,Driver  ,Text      ,None);
+  (DriverFlagNolock                                ,Driver  ,Text      ,None);
+  (DriverFlagNorating                              ,Driver  ,Text      ,None);
+  (DriverFlagNoscanlangid                          ,Driver  ,Text      ,None);
+  (DriverFlagNovendor                              ,Driver  ,Text      ,None);
+  (DriverFlagPollonly                              ,Driver  ,Text      ,None);
+  (DriverName                                      ,Driver  ,Text      ,None);
+  (DriverParameterAlarmControl                     ,Driver  ,Text      ,None);
+  (DriverParameterBattvoltmult                     ,Driver  ,Text      ,None);
+  (DriverParameterBattvolts                        ,Driver  ,Text      ,None);
+  (DriverParameterBaudRate                         ,Driver  ,Text      ,None);
+  (DriverParameterBaudrate                         ,Driver  ,Text      ,None);
+  (DriverParameterBus                              ,Driver  ,Text      ,None);
+  (DriverParameterBypassForbidding                 ,Driver  ,Text      ,None);
+  (DriverParameterCable                            ,Driver  ,Text      ,None);
+  (DriverParameterCablepower                       ,Driver  ,Text      ,None);
+  (DriverParameterChargetime                       ,Driver  ,Text      ,None);
+  (DriverParameterDefaultBatteryVoltage            ,Driver  ,Text      ,None);
+  (DriverParameterDefaultBatteryVoltageHigh        ,Driver  ,Text      ,None);
+  (DriverParameterDefaultBatteryVoltageLow         ,Driver  ,Text      ,None);
+  (DriverParameterDefaultBatteryVoltageNominal     ,Driver  ,Text      ,None);
+  (DriverParameterDefaultInputVoltage              ,Driver  ,Text      ,None);
+  (DriverParameterDefaultInputVoltageNominal       ,Driver  ,Text      ,None);
+  (DriverParameterDevice                           ,Driver  ,Text      ,None);
+  (DriverParameterFrequency                        ,Driver  ,Text      ,None);
+  (DriverParameterIdleload                         ,Driver  ,Text      ,None);
+  (DriverParameterLangidFix                        ,Driver  ,Text      ,None);
+  (DriverParameterLimitedRuntimeOnBattery          ,Driver  ,Text      ,None);
+  (DriverParameterLinevoltage                      ,Driver  ,Text      ,None);
+  (DriverParameterLowbatt                          ,Driver  ,Text      ,None);
+  (DriverParameterLowbattPct                       ,Driver  ,Text      ,None);
+  (DriverParameterLowbattvolt                      ,Driver  ,Text      ,None);
+  (DriverParameterManufacturer                     ,Driver  ,Text      ,None);
+  (DriverParameterMfr                              ,Driver  ,Text      ,None);
+  (DriverParameterMibs                             ,Driver  ,Text      ,None);
+  (DriverParameterModel                            ,Driver  ,Text      ,None);
+  (DriverParameterModelname                        ,Driver  ,Text      ,None);
+  (DriverParameterNotification                     ,Driver  ,Text      ,None);
+  (DriverParameterOffdelay                         ,Driver  ,Text      ,None);
+  (DriverParameterOndelay                          ,Driver  ,Text      ,None);
+  (DriverParameterOverrideBatteryChargeLow         ,Driver  ,Text      ,None);
+  (DriverParameterOverrideBatteryChargeWarning     ,Driver  ,Text      ,None);
+  (DriverParameterOverrideBatteryVoltageNominal    ,Driver  ,Text      ,None);
+  (DriverParameterPollfreq                         ,Driver  ,Text      ,None);
+  (DriverParameterPollinterval                     ,Driver  ,Duration  ,Some(VarUnit::Second));
+  (DriverParameterPort                             ,Driver  ,Text      ,None);
+  (DriverParameterProduct                          ,Driver  ,Text      ,None);
+  (DriverParameterProductid                        ,Driver  ,Text      ,None);
+  (DriverParameterProtocol                         ,Driver  ,Text      ,None);
+  (DriverParameterRuntimecal                       ,Driver  ,Text      ,None);
+  (DriverParameterSdtype                           ,Driver  ,Text      ,None);
+  (DriverParameterSerial                           ,Driver  ,Text      ,None);
+  (DriverParameterSerialnumber                     ,Driver  ,Text      ,None);
+  (DriverParameterShutdownDelay                    ,Driver  ,Text      ,None);
+  (DriverParameterSnmpRetries                      ,Driver  ,Text      ,None);
+  (DriverParameterSnmpTimeout                      ,Driver  ,Text      ,None);
+  (DriverParameterSnmpVersion                      ,Driver  ,Text      ,None);
+  (DriverParameterSubdriver                        ,Driver  ,Text      ,None);
+  (DriverParameterSynchronous                      ,Driver  ,Text      ,None);
+  (DriverParameterType                             ,Driver  ,Text      ,None);
+  (DriverParameterUpstype                          ,Driver  ,Text      ,None);
+  (DriverParameterVendor                           ,Driver  ,Text      ,None);
+  (DriverParameterVendorid                         ,Driver  ,Text      ,None);
+  (DriverParameterVoltage                          ,Driver  ,Text      ,None);
+  (DriverState                                     ,Driver  ,Text      ,None);
+  (DriverVersion                                   ,Driver  ,Text      ,None);
+  (DriverVersionData                               ,Driver  ,Text      ,None);
+  (DriverVersionInternal                           ,Driver  ,Text      ,None);
+  (DriverVersionUsb                                ,Driver  ,Text      ,None);
+  (InputBypassCurrent                              ,Input   ,Number    ,Some(VarUnit::Ampere));
+  (InputBypassFrequency                            ,Input   ,Number    ,Some(VarUnit::Hertz));
+  (InputBypassFrequencyNominal                     ,Input   ,Number    ,Some(VarUnit::Hertz));
+  (InputBypassPhases                               ,Input   ,Number    ,None);
+  (InputBypassVoltage                              ,Input   ,Number    ,Some(VarUnit::Volt));
+  (InputCount                                      ,Input   ,Number    ,None);
+  (InputCurrent                                    ,Input   ,Number    ,Some(VarUnit::Ampere));
+  (InputCurrentHighCritical                        ,Input   ,Number    ,Some(VarUnit::Ampere));
+  (InputCurrentHighWarning                         ,Input   ,Number    ,Some(VarUnit::Ampere));
+  (InputCurrentLowWarning                          ,Input   ,Number    ,Some(VarUnit::Ampere));
+  (InputCurrentNominal                             ,Input   ,Number    ,Some(VarUnit::Ampere));
+  (InputCurrentStatus                              ,Input   ,Text      ,None);
+  (InputFrequency                                  ,Input   ,Number    ,Some(VarUnit::Hertz));
+  (InputFrequencyExtended                          ,Input   ,Text      ,None);
+  (InputFrequencyHigh                              ,Input   ,Number    ,Some(VarUnit::Hertz));
+  (InputFrequencyLow                               ,Input   ,Number    ,Some(VarUnit::Hertz));
+  (InputFrequencyNominal                           ,Input   ,Number    ,Some(VarUnit::Hertz));
+  (InputFrequencyStatus                            ,Input   ,Text      ,None);
+  (InputLoad                                       ,Input   ,Percentage,Some(VarUnit::Percent));
+  (InputPhases                                     ,Input   ,Number    ,None);
+  (InputPower                                      ,Input   ,Number    ,Some(VarUnit::VoltAmpere));
+  (InputPowerfactor                                ,Input   ,Number    ,None);
+  (InputQuality                                    ,Input   ,Text      ,None);
+  (InputRealpower                                  ,Input   ,Number    ,Some(VarUnit::Watt));
+  (InputSensitivity                                ,Input   ,Text      ,None);
+  (InputSource                                     ,Input   ,Text      ,None);
+  (InputSourcePreferred                            ,Input   ,Text      ,None);
+  (InputTransferBoostHigh                          ,Input   ,Number    ,Some(VarUnit::Volt));
+  (InputTransferBoostLow                           ,Input   ,Number    ,Some(VarUnit::Volt));
+  (InputTransferDelay                              ,Input   ,Duration  ,Some(VarUnit::Second));
+  (InputTransferHigh                               ,Input   ,Number    ,Some(VarUnit::Volt));
+  (InputTransferHighMax                            ,Input   ,Number    ,Some(VarUnit::Volt));
+  (InputTransferHighMin                            ,Input   ,Number    ,Some(VarUnit::Volt));
+  (InputTransferLow                                ,Input   ,Number    ,Some(VarUnit::Volt));
+  (InputTransferLowMax                             ,Input   ,Number    ,Some(VarUnit::Volt));
+  (InputTransferLowMin                             ,Input   ,Number    ,Some(VarUnit::Volt));
+  (InputTransferReason                             ,Input   ,Text      ,None);
+  (InputTransferTrimHigh                           ,Input   ,Number    ,Some(VarUnit::Volt));
+  (InputTransferTrimLow                            ,Input   ,Number    ,Some(VarUnit::Volt));
+  (InputVoltage                                    ,Input   ,Number    ,Some(VarUnit::Volt));
+  (InputVoltageExtended                            ,Input   ,Text      ,None);
+  (InputVoltageFault                               ,Input   ,Number    ,Some(VarUnit::Volt));
+  (InputVoltageHighCritical                        ,Input   ,Number    ,Some(VarUnit::Volt));
+  (InputVoltageHighWarning                         ,Input   ,Number    ,Some(VarUnit::Volt));
+  (InputVoltageLowCritical                         ,Input   ,Number    ,Some(VarUnit::Volt));
+  (InputVoltageLowWarning                          ,Input   ,Number    ,Some(VarUnit::Volt));
+  (InputVoltageMaximum                             ,Input   ,Number    ,Some(VarUnit::Volt));
+  (InputVoltageMinimim                             ,Input   ,Number    ,Some(VarUnit::Volt));
+  (InputVoltageMinimum                             ,Input   ,Number    ,Some(VarUnit::Volt));
+  (InputVoltageNominal                             ,Input   ,Number    ,Some(VarUnit::Volt));
+  (InputVoltageStatus                              ,Input   ,Text      ,None);
+  (OutletCount                                     ,Outlet  ,Number    ,None);
+  (OutletCurrent                                   ,Outlet  ,Number    ,Some(VarUnit::Ampere));
+  (OutletDesc                                      ,Outlet  ,Text      ,None);
+  (OutletFrequency                                 ,Outlet  ,Number    ,Some(VarUnit::Hertz));
+  (OutletGroupCount                                ,Outlet  ,Number    ,None);
+  (OutletId                                        ,Outlet  ,Text      ,None);
+  (OutletPower                                     ,Outlet  ,Number    ,Some(VarUnit::VoltAmpere));
+  (OutletPowerfactor                               ,Outlet  ,Number    ,None);
+  (OutletRealpower                                 ,Outlet  ,Number    ,Some(VarUnit::Watt));
+  (OutletSwitchable                                ,Outlet  ,Text      ,None);
+  (OutletVoltage                                   ,Outlet  ,Number    ,Some(VarUnit::Volt));
+  (OutputCurrent                                   ,Output  ,Number    ,Some(VarUnit::Ampere));
+  (OutputCurrentMaximum                            ,Output  ,Number    ,Some(VarUnit::Ampere));
+  (OutputCurrentNominal                            ,Output  ,Number    ,Some(VarUnit::Ampere));
+  (OutputFrequency                                 ,Output  ,Number    ,Some(VarUnit::Hertz));
+  (OutputFrequencyMaximum                          ,Output  ,Number    ,Some(VarUnit::Hertz));
+  (OutputFrequencyMinimum                          ,Output  ,Number    ,Some(VarUnit::Hertz));
+  (OutputFrequencyNominal                          ,Output  ,Number    ,Some(VarUnit::Hertz));
+  (OutputPhases                                    ,Output  ,Number    ,None);
+  (OutputPower                                     ,Output  ,Number    ,Some(VarUnit::VoltAmpere));
+  (OutputPowerfactor                               ,Output  ,Number    ,None);
+  (OutputPowerMaximumPercent                       ,Output  ,Percentage,Some(VarUnit::Percent));
+  (OutputPowerMinimumPercent                       ,Output  ,Percentage,Some(VarUnit::Percent));
+  (OutputPowerNominal                              ,Output  ,Number    ,Some(VarUnit::VoltAmpere));
+  (OutputPowerPercent                              ,Output  ,Percentage,Some(VarUnit::Percent));
+  (OutputRealpower                                 ,Output  ,Number    ,Some(VarUnit::Watt));
+  (OutputRealpowerNominal                          ,Output  ,Number    ,Some(VarUnit::Watt));
+  (OutputVoltage                                   ,Output  ,Number    ,Some(VarUnit::Volt));
+  (OutputVoltageHigh                               ,Output  ,Number    ,Some(VarUnit::Volt));
+  (OutputVoltageLow                                ,Output  ,Number    ,Some(VarUnit::Volt));
+  (OutputVoltageMaximum                            ,Output  ,Number    ,Some(VarUnit::Volt));
+  (OutputVoltageMinimum                            ,Output  ,Number    ,Some(VarUnit::Volt));
+  (OutputVoltageNominal                            ,Output  ,Number    ,Some(VarUnit::Volt));
+  (OutputVoltageTargetBattery                      ,Output  ,Number    ,Some(VarUnit::Volt));
+  (OutputVoltageTargetLine                         ,Output  ,Number    ,Some(VarUnit::Volt));
+  (UpsAlarm                                        ,Ups     ,Text      ,None);
+  (UpsBeeperEnable                                 ,Ups     ,Text      ,None);
+  (UpsBeeperStatus                                 ,Ups     ,Text      ,None);
+  (UpsContacts                                     ,Ups     ,Text      ,None);
+  (UpsDate                                         ,Ups     ,Timestamp ,None);
+  (UpsDelayReboot                                  ,Ups     ,Duration  ,Some(VarUnit::Second));
+  (UpsDelayRestart                                 ,Ups     ,Duration  ,Some(VarUnit::Second));
+  (UpsDelayShutdown                                ,Ups     ,Duration  ,Some(VarUnit::Second));
+  (UpsDelayStart                                   ,Ups     ,Duration  ,Some(VarUnit::Second));
+  (UpsDescription                                  ,Ups     ,Text      ,None);
+  (UpsDevicechemistry                              ,Ups     ,Text      ,None);
+  (UpsEfficiency                                   ,Ups     ,Percentage,Some(VarUnit::Percent));
+  (UpsFirmware                                     ,Ups     ,Text      ,None);
+  (UpsFirmwareAux                                  ,Ups     ,Text      ,None);
+  (UpsId                                           ,Ups     ,Text      ,None);
+  (UpsInputFrequency                               ,Ups     ,Number    ,Some(VarUnit::Hertz));
+  (UpsInputVoltage                                 ,Ups     ,Number    ,Some(VarUnit::Volt));
+  (UpsLoad                                         ,Ups     ,Percentage,Some(VarUnit::Percent));
+  (UpsLoadHigh                                     ,Ups     ,Percentage,Some(VarUnit::Percent));
+  (UpsLoadNominal                                  ,Ups     ,Percentage,Some(VarUnit::Percent));
+  (UpsMacaddr                                      ,Ups     ,Text      ,None);
+  (UpsMfg                                          ,Ups     ,Text      ,None);
+  (UpsMfr                                          ,Ups     ,Text      ,None);
+  (UpsMfrDate                                      ,Ups     ,Timestamp ,None);
+  (UpsModel                                        ,Ups     ,Text      ,None);
+  (UpsModelAux                                     ,Ups     ,Text      ,None);
+  (UpsModelType                                    ,Ups     ,Text      ,None);
+  (UpsOutputPercentload                            ,Ups     ,Percentage,Some(VarUnit::Percent));
+  (UpsOutputVoltage                                ,Ups     ,Number    ,Some(VarUnit::Volt));
+  (UpsPower                                        ,Ups     ,Number    ,Some(VarUnit::VoltAmpere));
+  (UpsPowerNominal                                 ,Ups     ,Number    ,Some(VarUnit::VoltAmpere));
+  (UpsProduct                                      ,Ups     ,Text      ,None);
+  (UpsProductid                                    ,Ups     ,Text      ,None);
+  (UpsRealpower                                    ,Ups     ,Number    ,Some(VarUnit::Watt));
+  (UpsRealpowerNominal                             ,Ups     ,Number    ,Some(VarUnit::Watt));
+  (UpsRuntime                                      ,Ups     ,Duration  ,Some(VarUnit::Second));
+  (UpsSerial                                       ,Ups     ,Text      ,None);
+  (UpsSerialInternal                               ,Ups     ,Text      ,None);
+  (UpsShutdown                                     ,Ups     ,Text      ,None);
+  (UpsStartAuto                                    ,Ups     ,Text      ,None);
+  (UpsStartBattery                                 ,Ups     ,Text      ,None);
+  (UpsStartReboot                                  ,Ups     ,Text      ,None);
+  (UpsStatus                                       ,Ups     ,Text      ,None);
+  (UpsTemperature                                  ,Ups     ,Number    ,Some(VarUnit::Celsius));
+  (UpsTemperatureHigh                              ,Ups     ,Number    ,Some(VarUnit::Celsius));
+  (UpsTemperatureLow                               ,Ups     ,Number    ,Some(VarUnit::Celsius));
+  (UpsTestDate                                     ,Ups     ,Timestamp ,None);
+  (UpsTestInterval                                 ,Ups     ,Duration  ,Some(VarUnit::Second));
+  (UpsTestResult                                   ,Ups     ,Text      ,None);
+  (UpsTime                                         ,Ups     ,Timestamp ,None);
+  (UpsTimerReboot                                  ,Ups     ,Duration  ,Some(VarUnit::Second));
+  (UpsTimerRestart                                 ,Ups     ,Duration  ,Some(VarUnit::Second));
+  (UpsTimerShutdown                                ,Ups     ,Duration  ,Some(VarUnit::Second));
+  (UpsTimerStart                                   ,Ups     ,Duration  ,Some(VarUnit::Second));
+  (UpsType                                         ,Ups     ,Text      ,None);
+  (UpsVendor                                       ,Ups     ,Text      ,None);
+  (UpsVendorid                                     ,Ups     ,Text      ,None);
+  (UpsVoltageNominal                               ,Ups     ,Number    ,Some(VarUnit::Volt));
+);
+
+#[cfg(feature = "serde")]
+mod serde {
+  use super::{VarCategory, VarKind, VarMetadata, VarUnit};
+  use serde::ser::SerializeStruct;
+
+  impl serde::Serialize for VarCategory {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+      S: serde::Serializer,
+    {
+      serializer.serialize_str(self.as_str())
+    }
+  }
+
+  impl serde::Serialize for VarKind {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+      S: serde::Serializer,
+    {
+      serializer.serialize_str(self.as_str())
+    }
+  }
+
+  impl serde::Serialize for VarUnit {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+      S: serde::Serializer,
+    {
+      serializer.serialize_str(self.symbol())
+    }
+  }
+
+  impl serde::Serialize for VarMetadata {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+      S: serde::Serializer,
+    {
+      let mut s = serializer.serialize_struct("VarMetadata", 3)?;
+      s.serialize_field("category", &self.category)?;
+      s.serialize_field("kind", &self.kind)?;
+      s.serialize_field("unit", &self.unit)?;
+      s.end()
+    }
+  }
+}
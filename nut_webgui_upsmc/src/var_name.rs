@@ -1,6 +1,12 @@
 use super::internal::{Repr, ascii_rules::NutAsciiText};
-use crate::errors::VarNameParseError;
-use core::borrow::Borrow;
+use crate::{
+  errors::VarNameParseError,
+  var_metadata::{VarCategory, VarMetadata},
+};
+use core::{
+  borrow::Borrow,
+  hash::{Hash, Hasher},
+};
 
 macro_rules! impl_standard_names {
   ($enum_name:ident,
@@ -10,7 +16,7 @@ macro_rules! impl_standard_names {
   )+
   ) => {
     #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
-    enum $enum_name {
+    pub(crate) enum $enum_name {
       $( $variant_name,)+
     }
 
@@ -345,7 +351,7 @@ impl_standard_names!(
 );
 
 #[inline]
-fn is_var_name(name: &str) -> Result<(), VarNameParseError> {
+pub(crate) fn is_var_name(name: &str) -> Result<(), VarNameParseError> {
   let name = name.as_bytes();
 
   if name.is_empty() {
@@ -368,7 +374,7 @@ fn is_var_name(name: &str) -> Result<(), VarNameParseError> {
 }
 
 /// UPS variable name.
-#[derive(Debug, Clone, Eq, Hash)]
+#[derive(Debug, Clone, Eq)]
 pub struct VarName {
   name: Repr<StandardName, Box<str>>,
 }
@@ -429,6 +435,23 @@ impl VarName {
       Repr::Custom(boxed_name) => boxed_name,
     }
   }
+
+  /// Returns unit, kind and category of standard variable names. Custom names return `None`.
+  #[inline]
+  pub const fn metadata(&self) -> Option<VarMetadata> {
+    match &self.name {
+      Repr::Standard(name) => Some(name.metadata()),
+      Repr::Custom(_) => None,
+    }
+  }
+
+  /// Returns variable category, custom names are categorized by their first segment.
+  pub fn category(&self) -> VarCategory {
+    match &self.name {
+      Repr::Standard(name) => name.metadata().category,
+      Repr::Custom(name) => VarCategory::from_name(name),
+    }
+  }
 }
 
 impl AsRef<str> for VarName {
@@ -551,6 +574,15 @@ impl PartialEq<VarName> for VarName {
   }
 }
 
+// Hashed as text, so standard and custom representations of the same name, and `&str` lookups
+// through `Borrow<str>`, are hashed the same way.
+impl Hash for VarName {
+  #[inline]
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.as_str().hash(state)
+  }
+}
+
 impl Borrow<str> for VarName {
   #[inline]
   fn borrow(&self) -> &str {
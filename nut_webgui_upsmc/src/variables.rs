@@ -1,4 +1,7 @@
-use crate::{value::Value, var_name::VarName};
+use crate::{
+  value::{InferValueFrom, Value},
+  var_name::VarName,
+};
 use core::borrow;
 use std::collections::HashMap;
 
@@ -39,6 +42,40 @@ impl UpsVariables {
     self.inner.insert(name, value)
   }
 
+  /// Sets a variable from its raw value text, as read from a `VAR` line. Nothing is allocated
+  /// when the variable already holds the same value. Returns true when the variable is added or
+  /// its value is changed.
+  pub fn update_raw(&mut self, name: &str, value: &str) -> bool {
+    match self.inner.get_mut(name) {
+      Some(Value::String(current)) if current.as_ref() == value => false,
+      Some(current) => {
+        let value = Value::infer_from(value);
+
+        if *current == value {
+          false
+        } else {
+          *current = value;
+          true
+        }
+      }
+      None => {
+        self
+          .inner
+          .insert(VarName::new_unchecked(name), Value::infer_from(value));
+        true
+      }
+    }
+  }
+
+  /// Removes all variables for which `f` returns false.
+  #[inline]
+  pub fn retain<F>(&mut self, mut f: F)
+  where
+    F: FnMut(&VarName, &mut Value) -> bool,
+  {
+    self.inner.retain(|name, value| f(name, value))
+  }
+
   #[inline]
   pub fn remove<K>(&mut self, name: K) -> Option<(VarName, Value)>
   where
@@ -0,0 +1,275 @@
+#![cfg(feature = "server")]
+
+use core::num::NonZeroUsize;
+use nut_webgui_upsmc::{
+  CmdName, UpsName, Value, VarName,
+  clients::{AsyncNutClient, NutAuthPoolClient, NutPoolClient},
+  errors::{ErrorKind, ProtocolError},
+  responses::TrackingStatus,
+  server::{MemoryBackend, MemoryDevice, NutServer, RwVarKind},
+};
+use std::{
+  net::SocketAddr,
+  sync::{
+    Arc, Mutex,
+    atomic::{AtomicUsize, Ordering},
+  },
+};
+use tokio::{
+  io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+  net::TcpListener,
+  task::JoinSet,
+};
+
+fn test_server() -> NutServer<MemoryBackend> {
+  let device = MemoryDevice::new("Test UPS")
+    .with_var(VarName::UPS_STATUS, "OL")
+    .with_rw_var(
+      VarName::UPS_ID,
+      "server-room",
+      RwVarKind::String { max_len: 16 },
+    )
+    .with_cmd(
+      CmdName::new_unchecked("beeper.enable"),
+      "Enable the UPS beeper",
+    );
+
+  NutServer::new(
+    MemoryBackend::new()
+      .with_device(UpsName::new_unchecked("ups"), device)
+      .with_user("admin", "secret"),
+  )
+}
+
+/// Test server which can drop all client connections, similar to an upsd restart.
+struct RestartableServer {
+  server: NutServer<MemoryBackend>,
+  connections: Arc<Mutex<JoinSet<()>>>,
+  addr: SocketAddr,
+}
+
+impl RestartableServer {
+  async fn spawn() -> Self {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = test_server();
+    let connections = Arc::new(Mutex::new(JoinSet::new()));
+
+    tokio::spawn({
+      let server = server.clone();
+      let connections = connections.clone();
+
+      async move {
+        loop {
+          let (stream, peer) = listener.accept().await.unwrap();
+          let server = server.clone();
+
+          connections.lock().unwrap().spawn(async move {
+            _ = server.serve_connection(stream, peer.ip()).await;
+          });
+        }
+      }
+    });
+
+    Self {
+      server,
+      connections,
+      addr,
+    }
+  }
+
+  fn drop_connections(&self) {
+    self.connections.lock().unwrap().abort_all();
+  }
+}
+
+/// Authenticates every connection, executes `INSTCMD` requests and closes the connection without
+/// answering them, similar to a connection lost after upsd received the command. Returns the
+/// number of received commands.
+async fn spawn_lossy_server() -> (SocketAddr, Arc<AtomicUsize>) {
+  let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let addr = listener.local_addr().unwrap();
+  let received = Arc::new(AtomicUsize::new(0));
+
+  tokio::spawn({
+    let received = received.clone();
+
+    async move {
+      loop {
+        let (stream, _) = listener.accept().await.unwrap();
+        let received = received.clone();
+
+        tokio::spawn(async move {
+          let mut stream = BufReader::new(stream);
+          let mut line = String::new();
+
+          while stream.read_line(&mut line).await.is_ok_and(|n| n > 0) {
+            if line.starts_with("INSTCMD") {
+              received.fetch_add(1, Ordering::Relaxed);
+              return;
+            }
+
+            let response: &[u8] = if line.starts_with("NETVER") {
+              b"1.3\n"
+            } else {
+              b"OK\n"
+            };
+
+            stream.write_all(response).await.unwrap();
+            line.clear();
+          }
+        });
+      }
+    }
+  });
+
+  (addr, received)
+}
+
+fn auth_pool(addr: SocketAddr, password: &str) -> NutAuthPoolClient<SocketAddr> {
+  NutPoolClient::builder(addr, NonZeroUsize::new(2).unwrap()).build_auth("admin", password)
+}
+
+#[tokio::test]
+async fn reuse_authenticated_connection() {
+  let server = RestartableServer::spawn().await;
+  let pool = auth_pool(server.addr, "secret");
+  let ups = UpsName::new_unchecked("ups");
+  let cmd = CmdName::new_unchecked("beeper.enable");
+
+  pool.instcmd(&ups, &cmd).await.unwrap();
+  pool.instcmd(&ups, &cmd).await.unwrap();
+  pool
+    .set_var(&ups, VarName::UPS_ID, Value::from("rack-a"))
+    .await
+    .unwrap();
+  pool.fsd(&ups).await.unwrap();
+
+  let stats = pool.stats();
+  assert_eq!(stats.connections_opened, 1);
+  assert_eq!(stats.idle, 1);
+
+  let device = server.server.backend().device(&ups).unwrap();
+  assert_eq!(device.executed_cmds(), &[cmd.clone(), cmd]);
+  assert_eq!(
+    device.get_var(&VarName::UPS_ID),
+    Some(&Value::from("rack-a"))
+  );
+  assert_eq!(
+    device.get_var(&VarName::UPS_STATUS),
+    Some(&Value::from("FSD OL"))
+  );
+}
+
+#[tokio::test]
+async fn tracked_requests() {
+  let server = RestartableServer::spawn().await;
+  let pool = auth_pool(server.addr, "secret");
+  let read_pool = NutPoolClient::new(server.addr, NonZeroUsize::new(1).unwrap());
+  let ups = UpsName::new_unchecked("ups");
+
+  let id = pool
+    .instcmd_tracked(&ups, CmdName::new_unchecked("beeper.enable"))
+    .await
+    .unwrap()
+    .unwrap();
+  assert_eq!(
+    read_pool.get_tracking(&id).await.unwrap(),
+    TrackingStatus::Success
+  );
+
+  let id = pool
+    .set_var_tracked(
+      &ups,
+      VarName::UPS_ID,
+      Value::from("a very long ups identifier"),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+  assert!(matches!(
+    read_pool.get_tracking(&id).await.unwrap(),
+    TrackingStatus::Failed { .. }
+  ));
+
+  assert_eq!(pool.stats().connections_opened, 1);
+}
+
+#[tokio::test]
+async fn reauthenticate_after_connection_loss() {
+  let server = RestartableServer::spawn().await;
+  let pool = auth_pool(server.addr, "secret");
+  let ups = UpsName::new_unchecked("ups");
+  let cmd = CmdName::new_unchecked("beeper.enable");
+
+  pool.instcmd(&ups, &cmd).await.unwrap();
+
+  server.drop_connections();
+  tokio::task::yield_now().await;
+
+  pool.instcmd(&ups, &cmd).await.unwrap();
+
+  let stats = pool.stats();
+  assert_eq!(stats.connections_opened, 2);
+  assert_eq!(stats.connections_discarded, 1);
+
+  let device = server.server.backend().device(&ups).unwrap();
+  assert_eq!(device.executed_cmds().len(), 2);
+}
+
+#[tokio::test]
+async fn invalid_credentials() {
+  let server = RestartableServer::spawn().await;
+  let pool = auth_pool(server.addr, "wrong");
+
+  let err = pool.fsd(UpsName::new_unchecked("ups")).await.unwrap_err();
+
+  assert!(matches!(
+    err.kind(),
+    ErrorKind::ProtocolError {
+      inner: ProtocolError::AccessDenied
+    }
+  ));
+
+  // Access errors are not connection errors, endpoint stays healthy and no failover happens.
+  assert!(pool.endpoints().iter().all(|v| v.is_healthy()));
+  assert_eq!(pool.stats().commands[0].errors, 1);
+}
+
+#[tokio::test]
+async fn lost_response_is_not_resent() {
+  let (addr, received) = spawn_lossy_server().await;
+  let pool = auth_pool(addr, "secret");
+
+  let result = pool
+    .instcmd(
+      UpsName::new_unchecked("ups"),
+      CmdName::new_unchecked("load.off"),
+    )
+    .await;
+
+  assert!(result.is_err());
+  assert_eq!(received.load(Ordering::Relaxed), 1);
+  assert_eq!(pool.stats().idle, 0);
+}
+
+#[tokio::test]
+async fn auth_pool_shares_endpoints() {
+  let primary = {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    listener.local_addr().unwrap()
+  };
+
+  let secondary = RestartableServer::spawn().await;
+  let read_pool = NutPoolClient::builder(primary, NonZeroUsize::new(1).unwrap())
+    .with_endpoint(secondary.addr)
+    .build();
+
+  let pool = read_pool.auth_pool("admin", "secret", NonZeroUsize::new(1).unwrap());
+
+  read_pool.get_ver().await.unwrap();
+  assert_eq!(*pool.active_endpoint(), secondary.addr);
+
+  pool.fsd(UpsName::new_unchecked("ups")).await.unwrap();
+  assert_eq!(pool.stats().connection_failures, 0);
+}
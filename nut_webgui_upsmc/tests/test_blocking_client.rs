@@ -0,0 +1,161 @@
+#![cfg(all(feature = "blocking", feature = "server"))]
+
+use nut_webgui_upsmc::{
+  CmdName, UpsName, Value, VarName,
+  clients::blocking::{BlockingNutClient, NutAuthClient, NutClient},
+  errors::{ErrorKind, ProtocolError},
+  server::{MemoryBackend, MemoryDevice, NutServer, RwVarKind},
+};
+use std::net::SocketAddr;
+use tokio::{net::TcpListener, runtime::Runtime};
+
+/// Test server running on its own runtime, blocking clients are used from the test thread.
+struct TestServer {
+  server: NutServer<MemoryBackend>,
+  addr: SocketAddr,
+  _runtime: Runtime,
+}
+
+impl TestServer {
+  fn spawn() -> Self {
+    let runtime = Runtime::new().unwrap();
+    let device = MemoryDevice::new("Blocking UPS")
+      .with_var(VarName::UPS_STATUS, "OL")
+      .with_var(VarName::BATTERY_CHARGE, "100")
+      .with_rw_var(
+        VarName::UPS_ID,
+        "server-room",
+        RwVarKind::String { max_len: 16 },
+      )
+      .with_cmd(
+        CmdName::new_unchecked("beeper.enable"),
+        "Enable the UPS beeper",
+      );
+
+    let server = NutServer::new(
+      MemoryBackend::new()
+        .with_device(UpsName::new_unchecked("ups"), device)
+        .with_user("admin", "secret"),
+    );
+
+    let listener = runtime.block_on(TcpListener::bind("127.0.0.1:0")).unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    runtime.spawn({
+      let server = server.clone();
+
+      async move {
+        loop {
+          let (stream, peer) = listener.accept().await.unwrap();
+          let server = server.clone();
+
+          tokio::spawn(async move {
+            _ = server.serve_connection(stream, peer.ip()).await;
+          });
+        }
+      }
+    });
+
+    Self {
+      server,
+      addr,
+      _runtime: runtime,
+    }
+  }
+}
+
+#[test]
+fn get_var() {
+  let server = TestServer::spawn();
+  let mut client = NutClient::connect(server.addr).unwrap();
+
+  let charge = client
+    .get_var(UpsName::new_unchecked("ups"), VarName::BATTERY_CHARGE)
+    .unwrap();
+
+  assert_eq!(charge.value, Value::from(100));
+}
+
+#[test]
+fn list_commands() {
+  let server = TestServer::spawn();
+  let mut client = NutClient::connect(server.addr).unwrap();
+  let ups = UpsName::new_unchecked("ups");
+
+  let vars = client.list_var(&ups).unwrap();
+  assert_eq!(vars.variables.len(), 3);
+
+  let rw_vars = client.list_rw(&ups).unwrap();
+  assert_eq!(rw_vars.variables.len(), 1);
+
+  let cmds = client.list_cmd(&ups).unwrap();
+  assert_eq!(cmds, vec!["beeper.enable".to_owned()]);
+
+  let devices = client.list_ups().unwrap();
+  assert_eq!(devices.devices.len(), 1);
+
+  let desc = client.get_ups_desc(&ups).unwrap();
+  assert_eq!(desc.desc.as_ref(), "Blocking UPS");
+}
+
+#[test]
+fn protocol_error() {
+  let server = TestServer::spawn();
+  let mut client = NutClient::connect(server.addr).unwrap();
+
+  let err = client
+    .get_var(UpsName::new_unchecked("missing"), VarName::UPS_STATUS)
+    .unwrap_err();
+
+  assert!(matches!(
+    err.kind(),
+    ErrorKind::ProtocolError {
+      inner: ProtocolError::UnknownUps
+    }
+  ));
+
+  // Connection stays usable after a protocol error.
+  assert!(client.is_open());
+}
+
+#[test]
+fn auth_client_writes() {
+  let server = TestServer::spawn();
+  let ups = UpsName::new_unchecked("ups");
+  let mut client = NutAuthClient::connect(server.addr, "admin", "secret").unwrap();
+
+  client
+    .instcmd(&ups, CmdName::new_unchecked("beeper.enable"))
+    .unwrap();
+  client
+    .set_var(&ups, VarName::UPS_ID, Value::from("rack-2"))
+    .unwrap();
+
+  let id = client.get_var(&ups, VarName::UPS_ID).unwrap();
+  assert_eq!(id.value, Value::from("rack-2"));
+
+  client.close().unwrap();
+
+  let device = server.server.backend().device(&ups).unwrap();
+  assert_eq!(device.executed_cmds().len(), 1);
+}
+
+#[test]
+fn auth_invalid_password() {
+  let server = TestServer::spawn();
+  let mut client = NutAuthClient::connect(server.addr, "admin", "wrong").unwrap();
+
+  let err = client
+    .instcmd(
+      UpsName::new_unchecked("ups"),
+      CmdName::new_unchecked("beeper.enable"),
+    )
+    .unwrap_err();
+
+  assert!(matches!(
+    err.kind(),
+    ErrorKind::ProtocolError {
+      inner: ProtocolError::AccessDenied
+    }
+  ));
+}
@@ -1,5 +1,7 @@
 use nut_webgui_upsmc::clients::AsyncNutClient;
-use nut_webgui_upsmc::{CmdName, UpsName, Value, VarName, VarType};
+use nut_webgui_upsmc::errors::{ArgumentError, ErrorKind, ProtocolError};
+use nut_webgui_upsmc::responses::TrackingStatus;
+use nut_webgui_upsmc::{CmdName, MAX_ARGUMENT_LEN, UpsName, Value, VarName, VarType};
 
 #[tokio::test]
 async fn cmd_desc() {
@@ -471,6 +473,104 @@ async fn instcmd() {
     .unwrap();
 }
 
+#[tokio::test]
+async fn instcmd_with_value() {
+  let ups = UpsName::new_unchecked("bx1600mi");
+  let stream = tokio_test::io::Builder::new()
+    .write(b"USERNAME user\n")
+    .read(b"OK\n")
+    .write(b"PASSWORD password\n")
+    .read(b"OK\n")
+    .write(b"INSTCMD bx1600mi load.off.delay \"120\"\n")
+    .read(b"OK\n")
+    .write(b"INSTCMD bx1600mi load.off.delay \"say \\\"hi\\\"\"\n")
+    .read(b"OK\n")
+    .build();
+
+  let mut client = nut_webgui_upsmc::clients::NutClient::from(stream)
+    .authenticate("user", "password")
+    .await
+    .unwrap();
+
+  client
+    .instcmd_with_value(
+      &ups,
+      CmdName::new_unchecked("load.off.delay"),
+      Value::from(120),
+    )
+    .await
+    .unwrap();
+
+  client
+    .instcmd_with_value(
+      &ups,
+      CmdName::new_unchecked("load.off.delay"),
+      Value::from("say \"hi\""),
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn instcmd_with_value_rejects_control_characters() {
+  let ups = UpsName::new_unchecked("bx1600mi");
+  let stream = tokio_test::io::Builder::new()
+    .write(b"USERNAME user\n")
+    .read(b"OK\n")
+    .write(b"PASSWORD password\n")
+    .read(b"OK\n")
+    .write(b"INSTCMD bx1600mi load.off.delay \"120\"\n")
+    .read(b"OK\n")
+    .build();
+
+  let mut client = nut_webgui_upsmc::clients::NutClient::from(stream)
+    .authenticate("user", "password")
+    .await
+    .unwrap();
+
+  let err = client
+    .instcmd_with_value(
+      &ups,
+      CmdName::new_unchecked("load.off.delay"),
+      Value::from("120\nINSTCMD bx1600mi load.off"),
+    )
+    .await
+    .unwrap_err();
+
+  assert!(matches!(
+    err.kind(),
+    ErrorKind::ArgumentError {
+      inner: ArgumentError::ControlCharacter
+    }
+  ));
+
+  let err = client
+    .set_var(
+      &ups,
+      VarName::new_unchecked("ups.id"),
+      Value::from("a".repeat(MAX_ARGUMENT_LEN + 1)),
+    )
+    .await
+    .unwrap_err();
+
+  assert!(matches!(
+    err.kind(),
+    ErrorKind::ArgumentError {
+      inner: ArgumentError::TooLong { .. }
+    }
+  ));
+
+  // Rejected values are never written, the connection is still in sync.
+  client
+    .instcmd_with_value(
+      &ups,
+      CmdName::new_unchecked("load.off.delay"),
+      Value::from(120),
+    )
+    .await
+    .unwrap();
+}
+
 #[tokio::test]
 async fn fsd() {
   let ups = nut_webgui_upsmc::UpsName::new_unchecked("home:bx1600mi@localhost:4242");
@@ -544,3 +644,414 @@ async fn attach_ups_and_detach() {
   client.attach(&ups).await.unwrap();
   client.detach().await.unwrap();
 }
+
+#[tokio::test]
+async fn instcmd_tracked() {
+  let ups = nut_webgui_upsmc::UpsName::new_unchecked("bx1600mi");
+  let stream = tokio_test::io::Builder::new()
+    .write(b"USERNAME user\n")
+    .read(b"OK\n")
+    .write(b"PASSWORD password\n")
+    .read(b"OK\n")
+    .write(b"SET TRACKING ON\n")
+    .read(b"OK\n")
+    .write(b"INSTCMD bx1600mi beeper.on\n")
+    .read(b"OK TRACKING 1bd31808-cb49-4aec-9d75-d056e6f018d2\n")
+    .write(b"INSTCMD bx1600mi beeper.off\n")
+    .read(b"OK TRACKING 2e2b3b8f-5d3b-4c6e-9a0c-6a3f1b0c9d11\n")
+    .build();
+
+  let mut client = nut_webgui_upsmc::clients::NutClient::from(stream)
+    .authenticate("user", "password")
+    .await
+    .unwrap();
+
+  let id = client
+    .instcmd_tracked(&ups, CmdName::new_unchecked("beeper.on"))
+    .await
+    .unwrap();
+
+  assert_eq!(id.as_ref(), "1bd31808-cb49-4aec-9d75-d056e6f018d2");
+
+  let id = client
+    .instcmd_tracked(&ups, CmdName::new_unchecked("beeper.off"))
+    .await
+    .unwrap();
+
+  assert_eq!(id.as_ref(), "2e2b3b8f-5d3b-4c6e-9a0c-6a3f1b0c9d11");
+}
+
+#[tokio::test]
+async fn set_var_tracked() {
+  let ups = nut_webgui_upsmc::UpsName::new_unchecked("bx1600mi");
+  let stream = tokio_test::io::Builder::new()
+    .write(b"USERNAME user\n")
+    .read(b"OK\n")
+    .write(b"PASSWORD password\n")
+    .read(b"OK\n")
+    .write(b"SET TRACKING ON\n")
+    .read(b"OK\n")
+    .write(
+      format!(
+        "SET VAR {ups} {var} \"{value}\"\n",
+        ups = &ups,
+        var = VarName::BATTERY_RUNTIME_LOW,
+        value = 32
+      )
+      .as_bytes(),
+    )
+    .read(b"OK TRACKING 1bd31808-cb49-4aec-9d75-d056e6f018d2\n")
+    .build();
+
+  let mut client = nut_webgui_upsmc::clients::NutClient::from(stream)
+    .authenticate("user", "password")
+    .await
+    .unwrap();
+
+  let id = client
+    .set_var_tracked(ups, VarName::BATTERY_RUNTIME_LOW, Value::from(32))
+    .await
+    .unwrap();
+
+  assert_eq!(id.as_ref(), "1bd31808-cb49-4aec-9d75-d056e6f018d2");
+}
+
+#[tokio::test]
+async fn tracking_not_supported() {
+  let ups = nut_webgui_upsmc::UpsName::new_unchecked("bx1600mi");
+  let stream = tokio_test::io::Builder::new()
+    .write(b"USERNAME user\n")
+    .read(b"OK\n")
+    .write(b"PASSWORD password\n")
+    .read(b"OK\n")
+    .write(b"SET TRACKING ON\n")
+    .read(b"ERR INVALID-ARGUMENT\n")
+    .build();
+
+  let mut client = nut_webgui_upsmc::clients::NutClient::from(stream)
+    .authenticate("user", "password")
+    .await
+    .unwrap();
+
+  let result = client
+    .instcmd_tracked(&ups, CmdName::new_unchecked("beeper.on"))
+    .await;
+
+  match result {
+    Ok(_) => panic!("Expected protocol error, received tracking id"),
+    Err(err) => assert!(matches!(
+      err.kind(),
+      ErrorKind::ProtocolError {
+        inner: ProtocolError::InvalidArgument
+      }
+    )),
+  }
+}
+
+#[tokio::test]
+async fn get_tracking() {
+  const ID: &str = "1bd31808-cb49-4aec-9d75-d056e6f018d2";
+
+  let stream = tokio_test::io::Builder::new()
+    .write(format!("GET TRACKING {ID}\n").as_bytes())
+    .read(b"PENDING\n")
+    .write(format!("GET TRACKING {ID}\n").as_bytes())
+    .read(b"SUCCESS\n")
+    .write(format!("GET TRACKING {ID}\n").as_bytes())
+    .read(b"ERR INVALID-ARGUMENT\n")
+    .write(format!("GET TRACKING {ID}\n").as_bytes())
+    .read(b"ERR FAILED\n")
+    .write(format!("GET TRACKING {ID}\n").as_bytes())
+    .read(b"ERR ACCESS-DENIED\n")
+    .build();
+
+  let mut client = nut_webgui_upsmc::clients::NutClient::from(stream);
+
+  assert_eq!(
+    client.get_tracking(ID).await.unwrap(),
+    TrackingStatus::Pending
+  );
+  assert_eq!(
+    client.get_tracking(ID).await.unwrap(),
+    TrackingStatus::Success
+  );
+  assert_eq!(
+    client.get_tracking(ID).await.unwrap(),
+    TrackingStatus::Failed {
+      reason: ProtocolError::InvalidArgument
+    }
+  );
+  assert_eq!(
+    client.get_tracking(ID).await.unwrap(),
+    TrackingStatus::Failed {
+      reason: ProtocolError::Unknown("FAILED".into())
+    }
+  );
+  assert!(client.get_tracking(ID).await.is_err());
+}
+
+#[tokio::test]
+async fn send_batch() {
+  const RESPONSES: &[u8] = b"BEGIN LIST VAR bx1600mi
+VAR bx1600mi ups.status \"OL\"
+VAR bx1600mi battery.charge \"100\"
+END LIST VAR bx1600mi
+ERR UNKNOWN-UPS
+BEGIN LIST CLIENT bx1600mi
+CLIENT bx1600mi 127.0.0.1
+END LIST CLIENT bx1600mi
+VAR bx1600mi ups.status \"OL\"
+";
+
+  let ups = UpsName::new_unchecked("bx1600mi");
+  let missing = UpsName::new_unchecked("missing");
+
+  let mut batch = nut_webgui_upsmc::clients::Batch::new();
+  let vars = batch.list_var(&ups);
+  let missing_vars = batch.list_var(&missing);
+  let clients = batch.list_client(&ups);
+  let status = batch.get_var(&ups, VarName::UPS_STATUS);
+
+  assert_eq!(batch.len(), 4);
+
+  let stream = tokio_test::io::Builder::new()
+    .write(
+      b"LIST VAR bx1600mi\nLIST VAR missing\nLIST CLIENT bx1600mi\nGET VAR bx1600mi ups.status\n",
+    )
+    .read(RESPONSES)
+    .build();
+
+  let mut client = nut_webgui_upsmc::clients::NutClient::from(stream);
+  let mut responses = client.send_batch(&batch).await.unwrap();
+
+  assert_eq!(responses.len(), 4);
+
+  let status = responses.take(status).unwrap();
+  assert_eq!(status.value, Value::from("OL"));
+
+  let vars = responses.take(vars).unwrap();
+  assert_eq!(vars.variables.len(), 2);
+
+  let err = responses.take(missing_vars).unwrap_err();
+  assert!(matches!(
+    err.kind(),
+    ErrorKind::ProtocolError {
+      inner: ProtocolError::UnknownUps
+    }
+  ));
+
+  let clients = responses.take(clients).unwrap();
+  assert_eq!(clients.ips.len(), 1);
+}
+
+#[tokio::test]
+async fn send_batch_connection_closed() {
+  let ups = UpsName::new_unchecked("bx1600mi");
+
+  let mut batch = nut_webgui_upsmc::clients::Batch::new();
+  _ = batch.get_var(&ups, VarName::UPS_STATUS);
+  _ = batch.list_client(&ups);
+
+  let stream = tokio_test::io::Builder::new()
+    .write(b"GET VAR bx1600mi ups.status\nLIST CLIENT bx1600mi\n")
+    .read(b"VAR bx1600mi ups.status \"OL\"\nBEGIN LIST CLIENT bx1600mi\n")
+    .build();
+
+  let mut client = nut_webgui_upsmc::clients::NutClient::from(stream);
+  let err = client.send_batch(&batch).await.unwrap_err();
+
+  assert!(matches!(err.kind(), ErrorKind::EmptyResponse));
+}
+
+#[tokio::test]
+async fn list_var_streamed() {
+  let ups = UpsName::new_unchecked("bx1600mi");
+
+  let stream = tokio_test::io::Builder::new()
+    .write(b"LIST VAR bx1600mi\n")
+    .read(b"BEGIN LIST VAR bx1600mi\nVAR bx1600mi battery.charge \"100\"\nVAR bx16")
+    .read(b"00mi ups.status \"OL\"\nEND LIST VAR bx1600mi\n")
+    .write(b"LIST RW bx1600mi\n")
+    .read(b"BEGIN LIST RW bx1600mi\nRW bx1600mi ups.delay.shutdown \"20\"\nEND LIST RW bx1600mi\n")
+    .build();
+
+  let mut client = nut_webgui_upsmc::clients::NutClient::from(stream);
+  let mut vars = Vec::new();
+
+  let count = client
+    .list_var_streamed(&ups, |var| {
+      vars.push((
+        VarName::new(var.name).unwrap(),
+        Value::from(var.value.as_ref()),
+      ))
+    })
+    .await
+    .unwrap();
+
+  assert_eq!(count, 2);
+  assert_eq!(
+    vars,
+    vec![
+      (VarName::BATTERY_CHARGE, Value::from("100")),
+      (VarName::UPS_STATUS, Value::from("OL")),
+    ]
+  );
+
+  let mut rw_vars = Vec::new();
+  let count = client
+    .list_rw_streamed(&ups, |var| rw_vars.push(var.name.to_owned()))
+    .await
+    .unwrap();
+
+  assert_eq!(count, 1);
+  assert_eq!(rw_vars, vec!["ups.delay.shutdown"]);
+}
+
+#[tokio::test]
+async fn list_var_streamed_drains_invalid_response() {
+  let ups = UpsName::new_unchecked("bx1600mi");
+
+  let stream = tokio_test::io::Builder::new()
+    .write(b"LIST VAR bx1600mi\n")
+    .read(b"BEGIN LIST VAR bx1600mi\nVAR bx1600mi ups.status OL\nVAR bx1600mi ups.load \"12\"\nEND LIST VAR bx1600mi\n")
+    .write(b"GET VAR bx1600mi ups.status\n")
+    .read(b"VAR bx1600mi ups.status \"OL\"\n")
+    .build();
+
+  let mut client = nut_webgui_upsmc::clients::NutClient::from(stream);
+
+  let err = client.list_var_streamed(&ups, |_| {}).await.unwrap_err();
+  assert!(matches!(err.kind(), ErrorKind::ParseError { .. }));
+
+  let status = client.get_var(&ups, VarName::UPS_STATUS).await.unwrap();
+  assert_eq!(status.value, Value::from("OL"));
+}
+
+#[tokio::test]
+async fn list_vars_streamed_pipelined() {
+  let devices = [
+    UpsName::new_unchecked("bx1600mi"),
+    UpsName::new_unchecked("missing"),
+    UpsName::new_unchecked("ups"),
+  ];
+
+  let stream = tokio_test::io::Builder::new()
+    .write(b"LIST VAR bx1600mi\nLIST VAR missing\nLIST VAR ups\n")
+    .read(b"BEGIN LIST VAR bx1600mi\nVAR bx1600mi battery.charge \"100\"\nVAR bx16")
+    .read(b"00mi ups.status OL\nEND LIST VAR bx1600mi\nERR UNKNOWN-UPS\nBEGIN LIST VAR ups\n")
+    .read(b"VAR ups ups.status \"OB\"\nEND LIST VAR ups\nVAR ups ups.status \"OL\"\n")
+    .write(b"GET VAR ups ups.status\n")
+    .build();
+
+  let mut client = nut_webgui_upsmc::clients::NutClient::from(stream);
+  let mut vars = Vec::new();
+  let mut diagnostics = Vec::new();
+
+  let results = client
+    .list_vars_streamed(&devices, |index, item| match item {
+      Ok(var) => vars.push((index, var.name.to_owned(), var.value.into_owned())),
+      Err(diagnostic) => diagnostics.push((index, diagnostic)),
+    })
+    .await
+    .unwrap();
+
+  assert_eq!(results.len(), 3);
+  assert_eq!(*results[0].as_ref().unwrap(), 1);
+  assert!(matches!(
+    results[1].as_ref().unwrap_err().kind(),
+    ErrorKind::ProtocolError {
+      inner: ProtocolError::UnknownUps
+    }
+  ));
+  assert_eq!(*results[2].as_ref().unwrap(), 1);
+  assert_eq!(
+    vars,
+    vec![
+      (0, "battery.charge".to_owned(), "100".to_owned()),
+      (2, "ups.status".to_owned(), "OB".to_owned()),
+    ]
+  );
+
+  assert_eq!(diagnostics.len(), 1);
+  assert_eq!(diagnostics[0].0, 0);
+  assert_eq!(diagnostics[0].1.line.as_ref(), "VAR bx1600mi ups.status OL");
+  assert_eq!(diagnostics[0].1.position.line, 3);
+
+  // Bytes after the last response stay buffered for the next request.
+  let status = client
+    .get_var(UpsName::new_unchecked("ups"), VarName::UPS_STATUS)
+    .await
+    .unwrap();
+  assert_eq!(status.value, Value::from("OL"));
+}
+
+#[tokio::test]
+async fn probe_capabilities_modern() {
+  use nut_webgui_upsmc::clients::Support;
+
+  let stream = tokio_test::io::Builder::new()
+    .write(b"LIST CLIENT upsmc-capability-probe\n")
+    .read(b"ERR UNKNOWN-UPS\n")
+    .write(b"GET TRACKING upsmc-capability-probe\n")
+    .read(b"UNKNOWN\n")
+    .write(b"GET CMDDESC upsmc-capability-probe probe\n")
+    .read(b"ERR UNKNOWN-UPS\n")
+    .write(b"PRIMARY upsmc-capability-probe\n")
+    .read(b"ERR USERNAME-REQUIRED\n")
+    .write(b"STARTTLS\n")
+    .read(b"ERR FEATURE-NOT-CONFIGURED\n")
+    .build();
+
+  let client = nut_webgui_upsmc::clients::NutClient::from(stream);
+  let capabilities = client.probe_capabilities().await.unwrap();
+
+  assert_eq!(capabilities.list_client, Support::Supported);
+  assert_eq!(capabilities.get_tracking, Support::Supported);
+  assert_eq!(capabilities.cmd_desc, Support::Supported);
+  assert_eq!(capabilities.primary, Support::Supported);
+  assert_eq!(capabilities.start_tls, Support::NotConfigured);
+  assert_eq!(capabilities.login_command(), "PRIMARY");
+}
+
+#[tokio::test]
+async fn probe_capabilities_legacy() {
+  use nut_webgui_upsmc::clients::Support;
+
+  let stream = tokio_test::io::Builder::new()
+    .write(b"LIST CLIENT upsmc-capability-probe\n")
+    .read(b"ERR INVALID-ARGUMENT\n")
+    .write(b"GET TRACKING upsmc-capability-probe\n")
+    .read(b"ERR INVALID-ARGUMENT\n")
+    .write(b"GET CMDDESC upsmc-capability-probe probe\n")
+    .read(b"ERR UNKNOWN-UPS\n")
+    .write(b"PRIMARY upsmc-capability-probe\n")
+    .read(b"ERR UNKNOWN-COMMAND\n")
+    .write(b"STARTTLS\n")
+    .read(b"ERR UNKNOWN-COMMAND\n")
+    .build();
+
+  let client = nut_webgui_upsmc::clients::NutClient::from(stream);
+  let capabilities = client.probe_capabilities().await.unwrap();
+
+  assert_eq!(capabilities.list_client, Support::Unsupported);
+  assert_eq!(capabilities.get_tracking, Support::Unsupported);
+  assert_eq!(capabilities.cmd_desc, Support::Supported);
+  assert_eq!(capabilities.primary, Support::Unsupported);
+  assert_eq!(capabilities.start_tls, Support::Unsupported);
+  assert_eq!(capabilities.login_command(), "MASTER");
+  assert!(!capabilities.list_client.is_available());
+}
+
+#[tokio::test]
+async fn probe_capabilities_connection_error() {
+  let stream = tokio_test::io::Builder::new()
+    .write(b"LIST CLIENT upsmc-capability-probe\n")
+    .read(b"ERR UNKNOWN-UPS\n")
+    .write(b"GET TRACKING upsmc-capability-probe\n")
+    .build();
+
+  let client = nut_webgui_upsmc::clients::NutClient::from(stream);
+  let err = client.probe_capabilities().await.unwrap_err();
+
+  assert!(matches!(err.kind(), ErrorKind::EmptyResponse));
+}
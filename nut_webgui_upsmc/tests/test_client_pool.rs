@@ -0,0 +1,490 @@
+#![cfg(feature = "server")]
+
+use core::{num::NonZeroUsize, time::Duration};
+use nut_webgui_upsmc::{
+  UpsName, VarName,
+  clients::{AsyncNutClient, Batch, FailbackPolicy, LatencyHistogram, NutPoolClient},
+  server::{MemoryBackend, MemoryDevice, NutServer},
+};
+use std::net::SocketAddr;
+use tokio::{
+  io::{AsyncReadExt, AsyncWriteExt},
+  net::{TcpListener, TcpStream},
+};
+
+fn test_server(desc: &str) -> NutServer<MemoryBackend> {
+  NutServer::new(MemoryBackend::new().with_device(
+    UpsName::new_unchecked("ups"),
+    MemoryDevice::new(desc).with_var(VarName::UPS_STATUS, "OL"),
+  ))
+}
+
+async fn spawn_server(desc: &str) -> SocketAddr {
+  let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let addr = listener.local_addr().unwrap();
+
+  tokio::spawn(test_server(desc).listen(listener));
+
+  addr
+}
+
+/// Returns an address with nothing listening on it.
+async fn closed_addr() -> SocketAddr {
+  let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  listener.local_addr().unwrap()
+}
+
+/// Accepts connections, but closes them without answering any request.
+async fn spawn_unresponsive_server() -> SocketAddr {
+  let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let addr = listener.local_addr().unwrap();
+
+  tokio::spawn(async move {
+    loop {
+      let (mut stream, _): (TcpStream, _) = listener.accept().await.unwrap();
+      let mut buffer = [0u8; 64];
+      _ = stream.read(&mut buffer).await;
+    }
+  });
+
+  addr
+}
+
+/// Answers the first `VER` request, then closes the connection.
+async fn spawn_single_use_server() -> SocketAddr {
+  let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let addr = listener.local_addr().unwrap();
+
+  tokio::spawn(async move {
+    loop {
+      let (mut stream, _): (TcpStream, _) = listener.accept().await.unwrap();
+      let mut buffer = [0u8; 64];
+      _ = stream.read(&mut buffer).await;
+      _ = stream.write_all(b"Network UPS Tools upsd 2.8.2\n").await;
+    }
+  });
+
+  addr
+}
+
+/// Accepts connections and keeps them open without answering any request.
+async fn spawn_silent_server() -> SocketAddr {
+  let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let addr = listener.local_addr().unwrap();
+
+  tokio::spawn(async move {
+    let mut streams = Vec::new();
+
+    loop {
+      let (stream, _): (TcpStream, _) = listener.accept().await.unwrap();
+      streams.push(stream);
+    }
+  });
+
+  addr
+}
+
+async fn ups_desc(pool: &NutPoolClient<SocketAddr>) -> Box<str> {
+  pool
+    .get_ups_desc(UpsName::new_unchecked("ups"))
+    .await
+    .unwrap()
+    .desc
+}
+
+#[tokio::test]
+async fn failover_on_connect() {
+  let primary = closed_addr().await;
+  let secondary = spawn_server("secondary").await;
+
+  let pool = NutPoolClient::builder(primary, NonZeroUsize::new(2).unwrap())
+    .with_endpoint(secondary)
+    .with_failback(FailbackPolicy::Never)
+    .build();
+
+  assert_eq!(ups_desc(&pool).await.as_ref(), "secondary");
+  assert_eq!(*pool.active_endpoint(), secondary);
+
+  let endpoints = pool.endpoints();
+  assert_eq!(endpoints.len(), 2);
+  assert!(!endpoints[0].is_active);
+  assert!(!endpoints[0].is_healthy());
+  assert!(endpoints[0].last_error.is_some());
+  assert!(endpoints[1].is_active);
+  assert!(endpoints[1].is_healthy());
+}
+
+#[tokio::test]
+async fn failover_on_unresponsive_endpoint() {
+  let primary = spawn_unresponsive_server().await;
+  let secondary = spawn_server("secondary").await;
+
+  let pool = NutPoolClient::builder(primary, NonZeroUsize::new(2).unwrap())
+    .with_endpoint(secondary)
+    .with_failback(FailbackPolicy::Never)
+    .with_timeout(Duration::from_secs(1))
+    .build();
+
+  assert!(pool.get_ver().await.is_err());
+  assert_eq!(*pool.active_endpoint(), secondary);
+  assert_eq!(ups_desc(&pool).await.as_ref(), "secondary");
+}
+
+#[tokio::test]
+async fn failback_to_primary() {
+  let primary = closed_addr().await;
+  let secondary = spawn_server("secondary").await;
+
+  let pool = NutPoolClient::builder(primary, NonZeroUsize::new(2).unwrap())
+    .with_endpoint(secondary)
+    .with_failback(FailbackPolicy::After(Duration::ZERO))
+    .build();
+
+  assert_eq!(ups_desc(&pool).await.as_ref(), "secondary");
+
+  let listener = TcpListener::bind(primary).await.unwrap();
+  tokio::spawn(test_server("primary").listen(listener));
+
+  // Requests never probe endpoints, failback only happens on explicit probes.
+  assert_eq!(ups_desc(&pool).await.as_ref(), "secondary");
+  assert!(pool.try_failback().await);
+
+  assert_eq!(ups_desc(&pool).await.as_ref(), "primary");
+  assert_eq!(*pool.active_endpoint(), primary);
+  assert!(pool.endpoints().iter().all(|v| v.is_healthy()));
+}
+
+#[tokio::test]
+async fn failback_skips_hung_endpoint() {
+  let primary = closed_addr().await;
+  let secondary = spawn_server("secondary").await;
+
+  let pool = NutPoolClient::builder(primary, NonZeroUsize::new(2).unwrap())
+    .with_endpoint(secondary)
+    .with_failback(FailbackPolicy::After(Duration::ZERO))
+    .with_timeout(Duration::from_millis(200))
+    .build();
+
+  assert_eq!(ups_desc(&pool).await.as_ref(), "secondary");
+
+  // Accepts connections and keeps them open, but never answers.
+  let listener = TcpListener::bind(primary).await.unwrap();
+  tokio::spawn(async move {
+    let mut streams = Vec::new();
+
+    loop {
+      let (stream, _): (TcpStream, _) = listener.accept().await.unwrap();
+      streams.push(stream);
+    }
+  });
+
+  assert!(!pool.try_failback().await);
+  assert_eq!(*pool.active_endpoint(), secondary);
+  assert!(!pool.endpoints()[0].is_healthy());
+  assert_eq!(ups_desc(&pool).await.as_ref(), "secondary");
+}
+
+#[tokio::test]
+async fn failback_never() {
+  let primary = closed_addr().await;
+  let secondary = spawn_server("secondary").await;
+
+  let pool = NutPoolClient::builder(primary, NonZeroUsize::new(2).unwrap())
+    .with_endpoint(secondary)
+    .with_failback(FailbackPolicy::Never)
+    .build();
+
+  assert_eq!(ups_desc(&pool).await.as_ref(), "secondary");
+
+  let listener = TcpListener::bind(primary).await.unwrap();
+  tokio::spawn(test_server("primary").listen(listener));
+
+  assert!(!pool.try_failback().await);
+  assert_eq!(ups_desc(&pool).await.as_ref(), "secondary");
+  assert_eq!(*pool.active_endpoint(), secondary);
+}
+
+#[tokio::test]
+async fn all_endpoints_down() {
+  let pool = NutPoolClient::builder(closed_addr().await, NonZeroUsize::new(1).unwrap())
+    .with_endpoint(closed_addr().await)
+    .build();
+
+  assert!(pool.get_ver().await.is_err());
+  assert!(pool.endpoints().iter().all(|v| !v.is_healthy()));
+}
+
+#[tokio::test]
+async fn pool_stats() {
+  let addr = spawn_server("ups").await;
+  let pool = NutPoolClient::new(addr, NonZeroUsize::new(2).unwrap());
+
+  assert_eq!(ups_desc(&pool).await.as_ref(), "ups");
+  assert_eq!(ups_desc(&pool).await.as_ref(), "ups");
+  assert!(
+    pool
+      .get_var(UpsName::new_unchecked("missing"), VarName::UPS_STATUS)
+      .await
+      .is_err()
+  );
+
+  let stats = pool.stats();
+  assert_eq!(stats.max_connections, 2);
+  assert_eq!(stats.idle, 1);
+  assert_eq!(stats.in_use, 0);
+  assert_eq!(stats.waiting, 0);
+  assert_eq!(stats.connections_opened, 1);
+  assert_eq!(stats.connection_failures, 0);
+  assert_eq!(stats.permit_waits, 3);
+
+  assert_eq!(stats.commands.len(), 2);
+  assert_eq!(stats.commands[0].command, "get_ups_desc");
+  assert_eq!(stats.commands[0].calls, 2);
+  assert_eq!(stats.commands[0].errors, 0);
+  assert_eq!(stats.commands[0].latency.count(), 2);
+  assert_eq!(stats.commands[1].command, "get_var");
+  assert_eq!(stats.commands[1].errors, 1);
+}
+
+#[tokio::test]
+async fn pool_stats_failures() {
+  let primary = closed_addr().await;
+  let secondary = spawn_server("secondary").await;
+
+  let pool = NutPoolClient::builder(primary, NonZeroUsize::new(1).unwrap())
+    .with_endpoint(secondary)
+    .with_failback(FailbackPolicy::Never)
+    .build();
+
+  assert_eq!(ups_desc(&pool).await.as_ref(), "secondary");
+
+  let stats = pool.stats();
+  assert_eq!(stats.connections_opened, 1);
+  assert_eq!(stats.connection_failures, 0);
+  assert_eq!(stats.connections_discarded, 0);
+
+  let pool = NutPoolClient::new(closed_addr().await, NonZeroUsize::new(1).unwrap());
+  assert!(pool.get_ver().await.is_err());
+
+  let stats = pool.stats();
+  assert_eq!(stats.connection_failures, 1);
+  assert_eq!(stats.idle, 0);
+  assert_eq!(stats.in_use, 0);
+  assert_eq!(stats.commands[0].errors, 1);
+}
+
+#[tokio::test]
+async fn pool_stats_cancelled_requests() {
+  let addr = spawn_silent_server().await;
+  let pool = NutPoolClient::new(addr, NonZeroUsize::new(1).unwrap());
+
+  // First request holds the only connection, second one waits for it, both are cancelled.
+  let result = tokio::time::timeout(Duration::from_millis(100), async {
+    tokio::join!(pool.get_ver(), pool.get_ver())
+  })
+  .await;
+
+  assert!(result.is_err());
+
+  let stats = pool.stats();
+  assert_eq!(stats.waiting, 0);
+  assert_eq!(stats.connecting, 0);
+  assert_eq!(stats.in_use, 0);
+}
+
+#[test]
+fn latency_histogram() {
+  let mut histogram = LatencyHistogram::default();
+  assert_eq!(histogram.quantile(0.5), None);
+
+  for ms in [0, 3, 3, 44, 7000] {
+    histogram.record(Duration::from_millis(ms));
+  }
+
+  assert_eq!(histogram.count(), 5);
+  assert_eq!(histogram.max(), Duration::from_millis(7000));
+  assert_eq!(histogram.mean(), Duration::from_millis(1410));
+  assert_eq!(histogram.quantile(0.5), Some(Duration::from_millis(5)));
+  assert_eq!(histogram.quantile(0.8), Some(Duration::from_millis(50)));
+  assert_eq!(histogram.quantile(1.0), Some(Duration::from_millis(7000)));
+
+  let buckets: Vec<_> = histogram.buckets().map(|(_, count)| count).collect();
+  assert_eq!(buckets, vec![1, 2, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1]);
+}
+
+#[tokio::test]
+async fn evict_idle_connections() {
+  let addr = spawn_server("ups").await;
+  let pool = NutPoolClient::builder(addr, NonZeroUsize::new(2).unwrap())
+    .with_max_idle(Duration::from_millis(50))
+    .build();
+
+  assert_eq!(ups_desc(&pool).await.as_ref(), "ups");
+  assert_eq!(pool.evict_expired().await, 0);
+  assert_eq!(pool.stats().idle, 1);
+
+  tokio::time::sleep(Duration::from_millis(100)).await;
+
+  assert_eq!(pool.evict_expired().await, 1);
+
+  let stats = pool.stats();
+  assert_eq!(stats.idle, 0);
+  assert_eq!(stats.connections_evicted, 1);
+
+  assert_eq!(ups_desc(&pool).await.as_ref(), "ups");
+  assert_eq!(pool.stats().connections_opened, 2);
+}
+
+#[tokio::test]
+async fn max_lifetime() {
+  let addr = spawn_server("ups").await;
+  let pool = NutPoolClient::builder(addr, NonZeroUsize::new(1).unwrap())
+    .with_max_lifetime(Duration::from_millis(50))
+    .build();
+
+  assert_eq!(ups_desc(&pool).await.as_ref(), "ups");
+  assert_eq!(ups_desc(&pool).await.as_ref(), "ups");
+  assert_eq!(pool.stats().connections_opened, 1);
+
+  tokio::time::sleep(Duration::from_millis(100)).await;
+
+  assert_eq!(ups_desc(&pool).await.as_ref(), "ups");
+
+  let stats = pool.stats();
+  assert_eq!(stats.connections_opened, 2);
+  assert_eq!(stats.connections_evicted, 1);
+}
+
+#[tokio::test]
+async fn validate_idle_connections() {
+  let addr = spawn_single_use_server().await;
+  let pool = NutPoolClient::builder(addr, NonZeroUsize::new(1).unwrap())
+    .with_validate_after(Duration::ZERO)
+    .with_timeout(Duration::from_secs(1))
+    .build();
+
+  assert!(pool.get_ver().await.is_ok());
+  assert!(pool.get_ver().await.is_ok());
+
+  let stats = pool.stats();
+  assert_eq!(stats.connections_opened, 2);
+  assert_eq!(stats.connections_discarded, 1);
+  assert_eq!(stats.commands[0].errors, 0);
+}
+
+#[tokio::test]
+async fn skip_validation_for_recent_connections() {
+  let addr = spawn_single_use_server().await;
+  let pool = NutPoolClient::builder(addr, NonZeroUsize::new(1).unwrap())
+    .with_validate_after(Duration::from_secs(60))
+    .with_timeout(Duration::from_secs(1))
+    .build();
+
+  assert!(pool.get_ver().await.is_ok());
+
+  // Broken connection is reused without a check, the request is retried with a new connection.
+  assert!(pool.get_ver().await.is_ok());
+
+  let stats = pool.stats();
+  assert_eq!(stats.connections_opened, 2);
+  assert_eq!(stats.connections_discarded, 0);
+}
+
+#[tokio::test]
+async fn pooled_batch() {
+  let addr = spawn_server("ups").await;
+  let pool = NutPoolClient::new(addr, NonZeroUsize::new(2).unwrap());
+  let ups = UpsName::new_unchecked("ups");
+
+  let mut batch = Batch::new();
+  let desc = batch.get_ups_desc(&ups);
+  let vars = batch.list_var(&ups);
+  let clients = batch.list_client(&ups);
+  let missing = batch.list_cmd(UpsName::new_unchecked("missing"));
+
+  let mut responses = pool.send_batch(&batch).await.unwrap();
+
+  assert_eq!(responses.take(desc).unwrap().desc.as_ref(), "ups");
+  assert_eq!(responses.take(vars).unwrap().variables.len(), 1);
+  assert!(responses.take(clients).unwrap().ips.is_empty());
+  assert!(responses.take(missing).is_err());
+
+  let stats = pool.stats();
+  assert_eq!(stats.connections_opened, 1);
+  assert_eq!(stats.commands[0].command, "send_batch");
+  assert_eq!(stats.commands[0].calls, 1);
+  assert_eq!(stats.commands[0].errors, 0);
+}
+
+#[tokio::test]
+async fn pooled_streamed_list() {
+  let addr = spawn_server("ups").await;
+  let pool = NutPoolClient::new(addr, NonZeroUsize::new(2).unwrap());
+  let ups = UpsName::new_unchecked("ups");
+  let mut vars = Vec::new();
+
+  let count = pool
+    .list_var_streamed(&ups, |var| {
+      vars.push((var.name.to_owned(), var.value.into_owned()))
+    })
+    .await
+    .unwrap();
+
+  assert_eq!(count, 1);
+  assert_eq!(vars, vec![("ups.status".to_owned(), "OL".to_owned())]);
+
+  let count = pool.list_rw_streamed(&ups, |_| {}).await.unwrap();
+  assert_eq!(count, 0);
+
+  let missing = pool
+    .list_var_streamed(UpsName::new_unchecked("missing"), |_| {})
+    .await;
+  assert!(missing.is_err());
+
+  let stats = pool.stats();
+  assert_eq!(stats.connections_opened, 1);
+  assert!(
+    stats
+      .commands
+      .iter()
+      .any(|c| c.command == "list_var_streamed" && c.calls == 2 && c.errors == 1)
+  );
+}
+
+#[tokio::test]
+async fn pooled_pipelined_streamed_lists() {
+  let addr = spawn_server("ups").await;
+  let pool = NutPoolClient::new(addr, NonZeroUsize::new(2).unwrap());
+  let devices = [
+    UpsName::new_unchecked("ups"),
+    UpsName::new_unchecked("missing"),
+    UpsName::new_unchecked("ups"),
+  ];
+  let mut vars = Vec::new();
+
+  let results = pool
+    .list_vars_streamed(&devices, |index, item| {
+      let var = item.unwrap();
+      vars.push((index, var.name.to_owned()))
+    })
+    .await
+    .unwrap();
+
+  assert_eq!(results.len(), 3);
+  assert_eq!(*results[0].as_ref().unwrap(), 1);
+  assert!(results[1].is_err());
+  assert_eq!(*results[2].as_ref().unwrap(), 1);
+  assert_eq!(
+    vars,
+    vec![(0, "ups.status".to_owned()), (2, "ups.status".to_owned())]
+  );
+
+  let stats = pool.stats();
+  assert_eq!(stats.connections_opened, 1);
+  assert!(
+    stats
+      .commands
+      .iter()
+      .any(|c| c.command == "list_vars_streamed" && c.calls == 1 && c.errors == 0)
+  );
+}
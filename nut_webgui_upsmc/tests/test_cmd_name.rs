@@ -1,6 +1,6 @@
 use core::str::FromStr;
-use nut_webgui_upsmc::CmdName;
 use nut_webgui_upsmc::errors::CmdParseError;
+use nut_webgui_upsmc::{CmdCategory, CmdName, CmdRisk};
 
 macro_rules! cmd_name_test {
   ($test_name:ident, $name:literal) => {
@@ -96,3 +96,45 @@ fn to_string() {
     String::from("custom.cmd")
   );
 }
+
+#[test]
+fn standard_metadata() {
+  let load_off = CmdName::new("load.off").unwrap().metadata().unwrap();
+  assert_eq!(load_off.category, CmdCategory::Load);
+  assert_eq!(load_off.risk, CmdRisk::PowerCutting);
+  assert!(!load_off.accepts_param);
+
+  let delayed = CmdName::new("load.off.delay").unwrap().metadata().unwrap();
+  assert!(delayed.accepts_param);
+
+  let deep_test = CmdName::new("test.battery.start.deep")
+    .unwrap()
+    .metadata()
+    .unwrap();
+  assert_eq!(deep_test.category, CmdCategory::Test);
+  assert_eq!(deep_test.risk, CmdRisk::Disruptive);
+
+  let beeper = CmdName::new("beeper.mute").unwrap().metadata().unwrap();
+  assert_eq!(beeper.risk, CmdRisk::Safe);
+}
+
+#[test]
+fn outlet_metadata() {
+  let outlet = CmdName::new("outlet.12.shutdown.return")
+    .unwrap()
+    .metadata()
+    .unwrap();
+  assert_eq!(outlet.category, CmdCategory::Shutdown);
+  assert_eq!(outlet.risk, CmdRisk::PowerCutting);
+
+  assert_eq!(
+    CmdName::new("outlet.1.load.on")
+      .unwrap()
+      .metadata()
+      .unwrap()
+      .risk,
+    CmdRisk::Safe
+  );
+  assert_eq!(CmdName::new("outlet.x1.load.off").unwrap().metadata(), None);
+  assert_eq!(CmdName::new("experimental.cmd").unwrap().metadata(), None);
+}
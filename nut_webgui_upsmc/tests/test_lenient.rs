@@ -0,0 +1,132 @@
+use nut_webgui_upsmc::{
+  CmdName, Value, VarName,
+  errors::{ErrorKind, ParseError},
+  protocol::Frame,
+  responses::{CmdList, EnumList, Lenient, RwList, UpsVarList},
+};
+
+const MALFORMED_LIST_VAR: &str = "BEGIN LIST VAR bx1600mi\n\
+VAR bx1600mi battery.charge \"100\"\n\
+VAR bx1600mi ups.status OL\n\
+VAR other ups.load \"12\"\n\
+garbage from driver\n\
+VAR bx1600mi battery.runtime \"1860\"\n\
+END LIST VAR bx1600mi\n";
+
+fn list(text: &str) -> Frame {
+  Frame::List(text.into())
+}
+
+#[test]
+fn strict_list_var_fails() {
+  let err = list(MALFORMED_LIST_VAR).parse::<UpsVarList>().unwrap_err();
+
+  assert!(matches!(err.kind(), ErrorKind::ParseError { .. }));
+}
+
+#[test]
+fn lenient_list_var() {
+  let result = list(MALFORMED_LIST_VAR)
+    .parse::<Lenient<UpsVarList>>()
+    .unwrap();
+
+  assert!(!result.is_clean());
+
+  let vars = &result.response.variables;
+  assert_eq!(vars.len(), 2);
+  assert_eq!(vars.get(VarName::BATTERY_CHARGE), Some(&Value::from(100)));
+  assert_eq!(vars.get(VarName::BATTERY_RUNTIME), Some(&Value::from(1860)));
+
+  let lines: Vec<(usize, &str)> = result
+    .diagnostics
+    .iter()
+    .map(|diagnostic| (diagnostic.position.line, diagnostic.line.as_ref()))
+    .collect();
+
+  assert_eq!(
+    lines,
+    vec![
+      (3, "VAR bx1600mi ups.status OL"),
+      (4, "VAR other ups.load \"12\""),
+      (5, "garbage from driver"),
+    ]
+  );
+
+  assert!(matches!(
+    result.diagnostics[0].error,
+    ParseError::InvalidToken
+  ));
+}
+
+#[test]
+fn lenient_clean_response() {
+  let text = "BEGIN LIST VAR bx1600mi\n\
+VAR bx1600mi ups.status \"OL\"\n\
+END LIST VAR bx1600mi\n";
+
+  let result = list(text).parse::<Lenient<UpsVarList>>().unwrap();
+
+  assert!(result.is_clean());
+  assert_eq!(result.into_inner().variables.len(), 1);
+}
+
+#[test]
+fn lenient_unterminated_quote() {
+  let text = "BEGIN LIST RW bx1600mi\n\
+RW bx1600mi input.transfer.high \"280\n\
+RW bx1600mi input.transfer.low \"160\"\n\
+END LIST RW bx1600mi\n";
+
+  assert!(list(text).parse::<RwList>().is_err());
+
+  let result = list(text).parse::<Lenient<RwList>>().unwrap();
+
+  assert_eq!(result.response.variables.len(), 1);
+  assert_eq!(result.diagnostics.len(), 1);
+  assert!(matches!(
+    result.diagnostics[0].error,
+    ParseError::ExpectedDoubleQuote
+  ));
+}
+
+#[test]
+fn lenient_list_cmd() {
+  let text = "BEGIN LIST CMD bx1600mi\n\
+CMD bx1600mi beeper.enable\n\
+CMD bx1600mi\n\
+CMD bx1600mi test.battery.start\n\
+END LIST CMD bx1600mi\n";
+
+  let result = list(text).parse::<Lenient<CmdList>>().unwrap();
+
+  assert_eq!(
+    result.response.cmds,
+    vec![
+      CmdName::new_unchecked("beeper.enable"),
+      CmdName::new_unchecked("test.battery.start"),
+    ]
+  );
+  assert_eq!(result.diagnostics.len(), 1);
+  assert_eq!(result.diagnostics[0].position.line, 3);
+}
+
+#[test]
+fn lenient_list_enum() {
+  let text = "BEGIN LIST ENUM bx1600mi input.transfer.low\n\
+ENUM bx1600mi input.transfer.low \"160\"\n\
+ENUM bx1600mi input.transfer.high \"280\"\n\
+END LIST ENUM bx1600mi input.transfer.low\n";
+
+  let result = list(text).parse::<Lenient<EnumList>>().unwrap();
+
+  assert_eq!(result.response.values, vec![Value::from(160)]);
+  assert_eq!(result.diagnostics.len(), 1);
+}
+
+#[test]
+fn lenient_keeps_header_strict() {
+  let text = "BEGIN LIST VAR bx1600mi\n\
+VAR bx1600mi ups.status \"OL\"\n";
+
+  assert!(list(text).parse::<Lenient<UpsVarList>>().is_err());
+}
@@ -0,0 +1,380 @@
+use nut_webgui_upsmc::{
+  UpsName, Value,
+  errors::{ErrorKind, ProtocolError},
+  protocol::{ClientProtocol, Frame, ListEvent, ResponseFramer},
+  responses::{UpsVar, UpsVarList},
+};
+
+const LIST_VAR: &[u8] = b"BEGIN LIST VAR bx1600mi\n\
+VAR bx1600mi battery.charge \"100\"\n\
+VAR bx1600mi ups.status \"OL\"\n\
+END LIST VAR bx1600mi\n";
+
+#[test]
+fn frame_single_line() {
+  let mut framer = ResponseFramer::new();
+  framer.push_bytes(b"VAR bx1600mi ups.status \"OL\"\n");
+
+  let frame = framer.next_frame().unwrap();
+
+  assert_eq!(
+    frame,
+    Some(Frame::Line("VAR bx1600mi ups.status \"OL\"\n".into()))
+  );
+  assert_eq!(framer.buffered(), 0);
+  assert_eq!(framer.next_frame().unwrap(), None);
+}
+
+#[test]
+fn frame_list_byte_by_byte() {
+  let mut framer = ResponseFramer::new();
+
+  for (idx, byte) in LIST_VAR.iter().enumerate() {
+    framer.push_bytes(&[*byte]);
+    let frame = framer.next_frame().unwrap();
+
+    if idx + 1 < LIST_VAR.len() {
+      assert_eq!(frame, None);
+    } else {
+      assert_eq!(
+        frame,
+        Some(Frame::List(String::from_utf8(LIST_VAR.to_vec()).unwrap()))
+      );
+    }
+  }
+}
+
+#[test]
+fn frame_protocol_error() {
+  let mut framer = ResponseFramer::new();
+  framer.push_bytes(b"ERR UNKNOWN-UPS\nOK\n");
+
+  assert_eq!(
+    framer.next_frame().unwrap(),
+    Some(Frame::Error(ProtocolError::UnknownUps))
+  );
+  assert_eq!(
+    framer.next_frame().unwrap(),
+    Some(Frame::Line("OK\n".into()))
+  );
+}
+
+#[test]
+fn frame_invalid_utf8() {
+  let mut framer = ResponseFramer::new();
+  framer.push_bytes(b"VAR bx1600mi ups.status \"\xFF\"\n");
+
+  let err = framer.next_frame().unwrap_err();
+
+  assert!(matches!(
+    err.kind(),
+    ErrorKind::IOError {
+      kind: std::io::ErrorKind::InvalidData
+    }
+  ));
+}
+
+#[test]
+fn finish_unterminated_line() {
+  let mut framer = ResponseFramer::new();
+  framer.push_bytes(b"OK");
+
+  assert_eq!(framer.next_frame().unwrap(), None);
+  assert_eq!(framer.finish().unwrap(), Frame::Line("OK".into()));
+}
+
+#[test]
+fn finish_unterminated_list() {
+  let mut framer = ResponseFramer::new();
+  framer.push_bytes(&LIST_VAR[..40]);
+
+  let err = framer.finish().unwrap_err();
+
+  assert!(matches!(err.kind(), ErrorKind::EmptyResponse));
+  assert_eq!(framer.buffered(), 0);
+}
+
+#[test]
+fn client_protocol_pipelined() {
+  let mut protocol = ClientProtocol::new();
+  protocol.send("GET VAR bx1600mi ups.status\n");
+  protocol.send("LIST VAR bx1600mi\n");
+  protocol.send("GET VAR unknown ups.status\n");
+
+  assert_eq!(
+    protocol.outgoing(),
+    b"GET VAR bx1600mi ups.status\nLIST VAR bx1600mi\nGET VAR unknown ups.status\n"
+  );
+
+  protocol.consume_outgoing(10);
+  assert_eq!(protocol.outgoing().len(), 63);
+  protocol.consume_outgoing(usize::MAX);
+  assert_eq!(protocol.pending(), 3);
+
+  protocol.receive(b"VAR bx1600mi ups.status \"OL\"\n");
+  protocol.receive(&LIST_VAR[..20]);
+
+  let status: UpsVar = protocol.poll_response().unwrap().unwrap().parse().unwrap();
+  assert_eq!(status.ups_name, UpsName::new_unchecked("bx1600mi"));
+  assert_eq!(status.value, Value::from("OL"));
+  assert_eq!(protocol.poll_response().unwrap(), None);
+
+  protocol.receive(&LIST_VAR[20..]);
+  protocol.receive(b"ERR UNKNOWN-UPS\n");
+
+  let vars: UpsVarList = protocol.poll_response().unwrap().unwrap().parse().unwrap();
+  assert_eq!(vars.variables.len(), 2);
+
+  let err = protocol
+    .poll_response()
+    .unwrap()
+    .unwrap()
+    .parse::<UpsVar>()
+    .unwrap_err();
+
+  assert!(matches!(
+    err.kind(),
+    ErrorKind::ProtocolError {
+      inner: ProtocolError::UnknownUps
+    }
+  ));
+  assert!(protocol.is_idle());
+}
+
+#[test]
+fn client_protocol_eof() {
+  let mut protocol = ClientProtocol::new();
+  protocol.send("LIST VAR bx1600mi\n");
+  protocol.consume_outgoing(usize::MAX);
+  protocol.receive(&LIST_VAR[..40]);
+
+  assert_eq!(protocol.poll_response().unwrap(), None);
+
+  let err = protocol.receive_eof().unwrap_err();
+
+  assert!(matches!(err.kind(), ErrorKind::EmptyResponse));
+  assert!(protocol.is_idle());
+}
+
+#[test]
+fn list_events_byte_by_byte() {
+  let mut framer = ResponseFramer::new();
+  let mut items: Vec<(String, String)> = Vec::new();
+  let mut finished = false;
+
+  for byte in LIST_VAR.iter() {
+    framer.push_bytes(&[*byte]);
+
+    while let Some(event) = framer.next_list_event().unwrap() {
+      match event {
+        ListEvent::Begin(line) => assert_eq!(line.as_str(), "BEGIN LIST VAR bx1600mi"),
+        ListEvent::Item(line) => {
+          let var = line.var().unwrap();
+          assert_eq!(var.ups, "bx1600mi");
+          items.push((var.name.to_owned(), var.value.into_owned()));
+        }
+        ListEvent::End(_) => finished = true,
+      }
+    }
+  }
+
+  assert!(finished);
+  assert_eq!(
+    items,
+    vec![
+      ("battery.charge".to_owned(), "100".to_owned()),
+      ("ups.status".to_owned(), "OL".to_owned()),
+    ]
+  );
+  assert_eq!(framer.buffered(), 0);
+}
+
+#[test]
+fn list_events_borrow_values() {
+  let mut framer = ResponseFramer::new();
+  framer.push_bytes(b"BEGIN LIST RW ups\nRW ups ups.id \"plain\"\nRW ups ups.delay \"say \\\"hi\\\"\"\nEND LIST RW ups\n");
+
+  assert!(matches!(
+    framer.next_list_event().unwrap(),
+    Some(ListEvent::Begin(_))
+  ));
+
+  let Some(ListEvent::Item(line)) = framer.next_list_event().unwrap() else {
+    panic!("expected list item");
+  };
+  let var = line.var().unwrap();
+  assert_eq!(line.keyword(), "RW");
+  assert!(matches!(var.value, std::borrow::Cow::Borrowed("plain")));
+
+  let Some(ListEvent::Item(line)) = framer.next_list_event().unwrap() else {
+    panic!("expected list item");
+  };
+  let var = line.var().unwrap();
+  assert_eq!(var.value, "say \"hi\"");
+
+  assert!(matches!(
+    framer.next_list_event().unwrap(),
+    Some(ListEvent::End(_))
+  ));
+}
+
+#[test]
+fn list_events_invalid_item() {
+  let mut framer = ResponseFramer::new();
+  framer.push_bytes(b"BEGIN LIST VAR ups\nVAR ups 1invalid \"1\"\nVAR ups ups.load\n");
+
+  _ = framer.next_list_event().unwrap();
+
+  for _ in 0..2 {
+    let Some(ListEvent::Item(line)) = framer.next_list_event().unwrap() else {
+      panic!("expected list item");
+    };
+
+    assert!(matches!(
+      line.var().unwrap_err().kind(),
+      ErrorKind::ParseError { .. }
+    ));
+  }
+}
+
+#[test]
+fn list_events_from_read_buffer() {
+  let mut framer = ResponseFramer::new();
+  let mut items: Vec<(String, String)> = Vec::new();
+  let mut finished = false;
+
+  for chunk in LIST_VAR.chunks(7) {
+    let mut input = chunk;
+
+    while let Some(event) = framer.next_list_event_from(&mut input).unwrap() {
+      match event {
+        ListEvent::Begin(line) => assert_eq!(line.as_str(), "BEGIN LIST VAR bx1600mi"),
+        ListEvent::Item(line) => {
+          let var = line.var().unwrap();
+          items.push((var.name.to_owned(), var.value.into_owned()));
+        }
+        ListEvent::End(_) => finished = true,
+      }
+    }
+
+    assert!(input.is_empty());
+  }
+
+  assert!(finished);
+  assert_eq!(
+    items,
+    vec![
+      ("battery.charge".to_owned(), "100".to_owned()),
+      ("ups.status".to_owned(), "OL".to_owned()),
+    ]
+  );
+  assert_eq!(framer.buffered(), 0);
+}
+
+#[test]
+fn list_events_from_read_buffer_in_place() {
+  let mut framer = ResponseFramer::new();
+  let mut input = LIST_VAR;
+
+  while let Some(event) = framer.next_list_event_from(&mut input).unwrap() {
+    if let ListEvent::Item(line) = event {
+      let line = line.as_str().as_bytes().as_ptr_range();
+      assert!(LIST_VAR.as_ptr_range().contains(&line.start));
+    }
+  }
+
+  // Complete lines are never copied into the framer.
+  assert_eq!(framer.buffered(), 0);
+}
+
+#[test]
+fn list_events_from_pipelined_responses() {
+  let mut protocol = ClientProtocol::new();
+  protocol.send("LIST VAR bx1600mi\n");
+  protocol.send("LIST VAR unknown\n");
+  protocol.send("LIST VAR bx1600mi\n");
+  protocol.consume_outgoing(usize::MAX);
+
+  let mut received = LIST_VAR.to_vec();
+  received.extend_from_slice(b"ERR UNKNOWN-UPS\n");
+  received.extend_from_slice(LIST_VAR);
+
+  let mut input = received.as_slice();
+  let mut ends = 0;
+
+  while let Some(event) = protocol.poll_list_event_from(&mut input).unwrap() {
+    if let ListEvent::End(_) = event {
+      ends += 1;
+      break;
+    }
+  }
+
+  assert_eq!(ends, 1);
+  assert_eq!(protocol.pending(), 2);
+  assert_eq!(input.len(), received.len() - LIST_VAR.len());
+
+  let err = protocol.poll_list_event_from(&mut input).unwrap_err();
+  assert!(matches!(
+    err.kind(),
+    ErrorKind::ProtocolError {
+      inner: ProtocolError::UnknownUps
+    }
+  ));
+  assert_eq!(protocol.pending(), 1);
+
+  while let Some(event) = protocol.poll_list_event_from(&mut input).unwrap() {
+    if let ListEvent::End(_) = event {
+      ends += 1;
+    }
+  }
+
+  assert_eq!(ends, 2);
+  assert!(input.is_empty());
+  assert!(protocol.is_idle());
+}
+
+#[test]
+fn list_events_then_frames() {
+  let mut protocol = ClientProtocol::new();
+  protocol.send("LIST VAR bx1600mi\n");
+  protocol.send("GET VAR bx1600mi ups.status\n");
+  protocol.consume_outgoing(usize::MAX);
+
+  protocol.receive(LIST_VAR);
+  protocol.receive(b"VAR bx1600mi ups.status \"OL\"\n");
+
+  let mut count = 0;
+
+  while let Some(event) = protocol.poll_list_event().unwrap() {
+    match event {
+      ListEvent::Item(_) => count += 1,
+      ListEvent::End(_) => break,
+      ListEvent::Begin(_) => {}
+    }
+  }
+
+  assert_eq!(count, 2);
+  assert_eq!(protocol.pending(), 1);
+
+  let status: UpsVar = protocol.poll_response().unwrap().unwrap().parse().unwrap();
+  assert_eq!(status.value, Value::from("OL"));
+  assert!(protocol.is_idle());
+}
+
+#[test]
+fn list_events_protocol_error() {
+  let mut protocol = ClientProtocol::new();
+  protocol.send("LIST VAR unknown\n");
+  protocol.consume_outgoing(usize::MAX);
+  protocol.receive(b"ERR UNKNOWN-UPS\n");
+
+  let err = protocol.poll_list_event().unwrap_err();
+
+  assert!(matches!(
+    err.kind(),
+    ErrorKind::ProtocolError {
+      inner: ProtocolError::UnknownUps
+    }
+  ));
+  assert!(protocol.is_idle());
+}
@@ -0,0 +1,159 @@
+use nut_webgui_upsmc::{
+  CmdName, UpsName, Value, VarName,
+  clients::{AsyncNutClient, NutClient},
+  errors::{ErrorKind, ProtocolError},
+  recording::{RecordedLine, Recording, RecordingStream, ReplayStream, SessionRecorder},
+};
+use std::path::Path;
+
+fn fixture(name: &str) -> Recording {
+  let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+    .join("tests/fixtures/recordings")
+    .join(name);
+
+  Recording::load(path).unwrap()
+}
+
+#[test]
+fn parse_recording() {
+  let recording: Recording = "# comment\n> GET VAR ups ups.status\n\n< VAR ups ups.status \"OL\"\n"
+    .parse()
+    .unwrap();
+
+  assert_eq!(
+    recording.lines(),
+    &[
+      RecordedLine::Sent("GET VAR ups ups.status".into()),
+      RecordedLine::Received("VAR ups ups.status \"OL\"".into()),
+    ]
+  );
+
+  let text = recording.to_string();
+  assert_eq!(text.parse::<Recording>().unwrap(), recording);
+}
+
+#[test]
+fn parse_recording_invalid_line() {
+  let err = "> LIST UPS\nBEGIN LIST UPS\n"
+    .parse::<Recording>()
+    .unwrap_err();
+
+  assert_eq!(err.line, 2);
+}
+
+#[tokio::test]
+async fn replay_fixture() {
+  let ups = UpsName::new_unchecked("bx1600mi");
+  let stream = ReplayStream::new(fixture("list_var.txt"));
+
+  let mut client = NutClient::new(stream)
+    .authenticate("admin", "any-password")
+    .await
+    .unwrap();
+
+  let vars = client.list_var(&ups).await.unwrap();
+  assert_eq!(vars.variables.len(), 4);
+  assert_eq!(
+    vars.variables.get(VarName::BATTERY_RUNTIME),
+    Some(&Value::from(1860))
+  );
+
+  let err = client
+    .instcmd(&ups, CmdName::new_unchecked("beeper.enable"))
+    .await
+    .unwrap_err();
+
+  assert!(matches!(
+    err.kind(),
+    ErrorKind::ProtocolError {
+      inner: ProtocolError::AccessDenied
+    }
+  ));
+}
+
+#[tokio::test]
+async fn replay_strict_mismatch() {
+  let stream = ReplayStream::new(fixture("list_var.txt"));
+  let mut client = NutClient::new(stream);
+
+  let err = client.list_ups().await.unwrap_err();
+
+  assert!(matches!(
+    err.kind(),
+    ErrorKind::IOError {
+      kind: std::io::ErrorKind::InvalidData
+    }
+  ));
+}
+
+#[tokio::test]
+async fn replay_lenient() {
+  let recording: Recording = "> LIST VAR other\n< BEGIN LIST VAR bx1600mi\n< VAR bx1600mi ups.status \"OL\"\n< END LIST VAR bx1600mi\n"
+    .parse()
+    .unwrap();
+
+  let mut client = NutClient::new(ReplayStream::new(recording).lenient());
+  let vars = client
+    .list_var(UpsName::new_unchecked("bx1600mi"))
+    .await
+    .unwrap();
+
+  assert_eq!(vars.variables.len(), 1);
+}
+
+#[tokio::test]
+async fn record_and_replay() {
+  let ups = UpsName::new_unchecked("bx1600mi");
+  let recorder = SessionRecorder::in_memory();
+  let upstream = ReplayStream::new(fixture("list_var.txt"));
+
+  let mut client = NutClient::new(RecordingStream::new(upstream, recorder.clone()))
+    .authenticate("admin", "secret")
+    .await
+    .unwrap();
+
+  let recorded_vars = client.list_var(&ups).await.unwrap();
+  _ = client
+    .instcmd(&ups, CmdName::new_unchecked("beeper.enable"))
+    .await;
+
+  let recording = recorder.recording().unwrap();
+  let text = recording.to_string();
+
+  assert!(text.contains("> PASSWORD ***\n"));
+  assert!(!text.contains("secret"));
+  assert_eq!(recording, fixture("list_var.txt"));
+
+  let mut client = NutClient::new(ReplayStream::new(recording))
+    .authenticate("admin", "secret")
+    .await
+    .unwrap();
+
+  let replayed_vars = client.list_var(&ups).await.unwrap();
+
+  assert_eq!(replayed_vars.variables.len(), recorded_vars.variables.len());
+}
+
+#[tokio::test]
+async fn record_to_writer() {
+  let path = std::env::temp_dir().join(format!("nut-recording-{}.txt", std::process::id()));
+  let recorder = SessionRecorder::create(&path).unwrap();
+  let upstream = ReplayStream::new(fixture("list_var.txt"));
+
+  let client = NutClient::new(RecordingStream::new(upstream, recorder))
+    .authenticate("admin", "secret")
+    .await
+    .unwrap();
+
+  drop(client);
+
+  let recording = Recording::load(&path).unwrap();
+
+  assert_eq!(recording.lines().len(), 4);
+  assert_eq!(
+    recording.lines()[2],
+    RecordedLine::Sent("PASSWORD ***".into())
+  );
+
+  _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,234 @@
+use nut_webgui_upsmc::clients::NutClient;
+use nut_webgui_upsmc::errors::RwValueError;
+use nut_webgui_upsmc::{DEFAULT_RW_STRING_LEN, RwDetail, UpsName, Value, ValueRanges, VarName};
+
+fn ranges(bounds: &[(i64, i64)]) -> ValueRanges {
+  ValueRanges::new(
+    bounds
+      .iter()
+      .map(|(min, max)| (Value::from(*min), Value::from(*max)))
+      .collect(),
+  )
+}
+
+#[test]
+fn ranges_are_sorted_by_lower_bound() {
+  let ranges = ranges(&[(200, 240), (100, 120)]);
+  let lower_bounds: Vec<&Value> = ranges.iter().map(|v| &v.min).collect();
+
+  assert_eq!(lower_bounds, [&Value::from(100), &Value::from(200)]);
+  assert_eq!(ranges.to_string(), "[100, 120], [200, 240]");
+}
+
+#[test]
+fn ranges_contains_any_interval() {
+  let ranges = ranges(&[(100, 120), (200, 240)]);
+
+  assert_eq!(ranges.contains(100.0), Some(true));
+  assert_eq!(ranges.contains(120.0), Some(true));
+  assert_eq!(ranges.contains(230.5), Some(true));
+  assert_eq!(ranges.contains(150.0), Some(false));
+  assert_eq!(ranges.contains(99.9), Some(false));
+  assert_eq!(ranges.contains(241.0), Some(false));
+}
+
+#[test]
+fn ranges_contains_non_numeric_bounds() {
+  let ranges = ValueRanges::new(vec![
+    (Value::from(1), Value::from(10)),
+    (Value::from("low"), Value::from("high")),
+  ]);
+
+  assert_eq!(ranges.contains(5.0), None);
+}
+
+#[test]
+fn ranges_min_max_across_intervals() {
+  let ranges = ranges(&[(200, 240), (100, 250), (90, 95)]);
+
+  assert_eq!(ranges.min(), Some(&Value::from(90)));
+  assert_eq!(ranges.max(), Some(&Value::from(250)));
+
+  let empty = ValueRanges::new(Vec::new());
+
+  assert_eq!(empty.min(), None);
+  assert_eq!(empty.max(), None);
+}
+
+#[test]
+fn validate_string() {
+  let detail = RwDetail::String { max_len: 4 };
+
+  assert_eq!(detail.validate(&Value::from("ab c")), Ok(()));
+  assert_eq!(
+    detail.validate(&Value::from("   ")),
+    Err(RwValueError::Empty)
+  );
+  assert_eq!(
+    detail.validate(&Value::from("abcde")),
+    Err(RwValueError::TooLong { max_len: 4 })
+  );
+  assert_eq!(
+    detail.validate(&Value::from(12)),
+    Err(RwValueError::NotText)
+  );
+}
+
+#[test]
+fn validate_number() {
+  let detail = RwDetail::Number;
+
+  assert_eq!(detail.validate(&Value::from(12)), Ok(()));
+  assert_eq!(detail.validate(&Value::from(1.5)), Ok(()));
+  assert_eq!(
+    detail.validate(&Value::from("12")),
+    Err(RwValueError::NotNumber)
+  );
+}
+
+#[test]
+fn validate_enum() {
+  let detail = RwDetail::Enum {
+    options: vec![Value::from(100), Value::from("high")],
+  };
+
+  assert_eq!(detail.validate(&Value::from(100)), Ok(()));
+  assert_eq!(detail.validate(&Value::from("high")), Ok(()));
+  assert_eq!(
+    detail.validate(&Value::from(101)),
+    Err(RwValueError::InvalidOption)
+  );
+}
+
+#[test]
+fn validate_range() {
+  let detail = RwDetail::Range {
+    ranges: ranges(&[(100, 120), (200, 240)]),
+  };
+
+  assert_eq!(detail.validate(&Value::from(110)), Ok(()));
+  assert_eq!(detail.validate(&Value::from(230.5)), Ok(()));
+  assert_eq!(
+    detail.validate(&Value::from(150)),
+    Err(RwValueError::OutOfRange)
+  );
+  assert_eq!(
+    detail.validate(&Value::from("110")),
+    Err(RwValueError::NotNumber)
+  );
+
+  let malformed = RwDetail::Range {
+    ranges: ValueRanges::new(vec![(Value::from("low"), Value::from("high"))]),
+  };
+
+  assert_eq!(
+    malformed.validate(&Value::from(110)),
+    Err(RwValueError::MalformedRange)
+  );
+}
+
+#[test]
+fn parse_text_input() {
+  let range = RwDetail::Range {
+    ranges: ranges(&[(100, 120)]),
+  };
+  let options = RwDetail::Enum {
+    options: vec![Value::from(100), Value::from("high")],
+  };
+
+  assert_eq!(range.parse("110"), Ok(Value::from(110)));
+  assert_eq!(range.parse("130"), Err(RwValueError::OutOfRange));
+  assert_eq!(range.parse("abc"), Err(RwValueError::NotNumber));
+  assert_eq!(RwDetail::Number.parse("1.5"), Ok(Value::from(1.5)));
+  assert_eq!(options.parse("100"), Ok(Value::from(100)));
+  assert_eq!(options.parse("high"), Ok(Value::from("high")));
+  assert_eq!(options.parse("low"), Err(RwValueError::InvalidOption));
+  assert_eq!(
+    RwDetail::String { max_len: 8 }.parse("42"),
+    Ok(Value::from("42"))
+  );
+}
+
+#[tokio::test]
+async fn get_rw_detail_enum() {
+  let ups = UpsName::new_unchecked("bx1600mi");
+  let var = VarName::new_unchecked("input.transfer.low");
+
+  let stream = tokio_test::io::Builder::new()
+    .write(format!("GET TYPE {ups} {var}\n").as_bytes())
+    .read(format!("TYPE {ups} {var} RW ENUM\n").as_bytes())
+    .write(format!("LIST ENUM {ups} {var}\n").as_bytes())
+    .read(
+      format!(
+        "BEGIN LIST ENUM {ups} {var}\nENUM {ups} {var} \"103\"\nENUM {ups} {var} \"100\"\nEND LIST ENUM {ups} {var}\n"
+      )
+      .as_bytes(),
+    )
+    .build();
+
+  let mut client = NutClient::from(stream);
+  let detail = client.get_rw_detail(&ups, &var).await.unwrap();
+
+  assert!(detail.is_clean());
+  assert_eq!(
+    detail.response,
+    RwDetail::Enum {
+      options: vec![Value::from(103), Value::from(100)]
+    }
+  );
+}
+
+#[tokio::test]
+async fn get_rw_detail_range() {
+  let ups = UpsName::new_unchecked("bx1600mi");
+  let var = VarName::new_unchecked("input.transfer.low");
+
+  let stream = tokio_test::io::Builder::new()
+    .write(format!("GET TYPE {ups} {var}\n").as_bytes())
+    .read(format!("TYPE {ups} {var} RW RANGE\n").as_bytes())
+    .write(format!("LIST RANGE {ups} {var}\n").as_bytes())
+    .read(
+      format!(
+        "BEGIN LIST RANGE {ups} {var}\nRANGE {ups} {var} \"90\" \"105\"\nEND LIST RANGE {ups} {var}\n"
+      )
+      .as_bytes(),
+    )
+    .build();
+
+  let mut client = NutClient::from(stream);
+  let detail = client.get_rw_detail(&ups, &var).await.unwrap();
+
+  assert_eq!(
+    detail.response,
+    RwDetail::Range {
+      ranges: ranges(&[(90, 105)])
+    }
+  );
+}
+
+#[tokio::test]
+async fn get_rw_detail_string_and_fallback() {
+  let ups = UpsName::new_unchecked("bx1600mi");
+  let id = VarName::new_unchecked("ups.id");
+  let custom = VarName::new_unchecked("ups.custom");
+
+  let stream = tokio_test::io::Builder::new()
+    .write(format!("GET TYPE {ups} {id}\n").as_bytes())
+    .read(format!("TYPE {ups} {id} RW STRING:16\n").as_bytes())
+    .write(format!("GET TYPE {ups} {custom}\n").as_bytes())
+    .read(format!("TYPE {ups} {custom} RW\n").as_bytes())
+    .build();
+
+  let mut client = NutClient::from(stream);
+
+  assert_eq!(
+    client.get_rw_detail(&ups, &id).await.unwrap().response,
+    RwDetail::String { max_len: 16 }
+  );
+  assert_eq!(
+    client.get_rw_detail(&ups, &custom).await.unwrap().response,
+    RwDetail::String {
+      max_len: DEFAULT_RW_STRING_LEN
+    }
+  );
+}
@@ -1,7 +1,12 @@
 #![cfg(feature = "serde")]
 
 use nut_webgui_upsmc::{
-  CmdName, UpsName, Value, VarName, ups_status::UpsStatus, variables::UpsVariables,
+  CmdName, UpsName, Value, VarName,
+  clients::{Capabilities, Support},
+  errors::ProtocolError,
+  responses::TrackingStatus,
+  ups_status::UpsStatus,
+  variables::UpsVariables,
 };
 
 #[test]
@@ -85,3 +90,64 @@ fn ups_status() {
     assert_eq!(r, l);
   }
 }
+
+#[test]
+fn tracking_status() {
+  let input = vec![
+    TrackingStatus::Pending,
+    TrackingStatus::Success,
+    TrackingStatus::Failed {
+      reason: ProtocolError::InvalidArgument,
+    },
+  ];
+
+  let json_str = serde_json::to_string(&input).unwrap();
+
+  assert_eq!(
+    json_str,
+    r#"[{"status":"pending"},{"status":"success"},{"status":"failed","reason":"INVALID-ARGUMENT"}]"#
+  );
+}
+
+#[test]
+fn capabilities() {
+  let input = Capabilities {
+    list_client: Support::Supported,
+    get_tracking: Support::Unsupported,
+    cmd_desc: Support::Supported,
+    primary: Support::Unsupported,
+    start_tls: Support::NotConfigured,
+  };
+
+  let json_str = serde_json::to_string(&input).unwrap();
+
+  assert_eq!(
+    json_str,
+    r#"{"list_client":"supported","get_tracking":"unsupported","cmd_desc":"supported","primary":"unsupported","start_tls":"not_configured","login_command":"MASTER"}"#
+  );
+}
+
+#[test]
+fn var_metadata() {
+  let json_str = serde_json::to_string(&VarName::BATTERY_RUNTIME.metadata()).unwrap();
+
+  assert_eq!(
+    json_str,
+    r#"{"category":"battery","kind":"duration","unit":"s"}"#
+  );
+
+  let json_str = serde_json::to_string(&VarName::UPS_STATUS.metadata()).unwrap();
+
+  assert_eq!(json_str, r#"{"category":"ups","kind":"text","unit":null}"#);
+}
+
+#[test]
+fn cmd_metadata() {
+  let metadata = CmdName::new_unchecked("shutdown.return").metadata();
+  let json_str = serde_json::to_string(&metadata).unwrap();
+
+  assert_eq!(
+    json_str,
+    r#"{"category":"shutdown","risk":"power_cutting","accepts_param":false}"#
+  );
+}
@@ -0,0 +1,379 @@
+#![cfg(feature = "server")]
+
+use core::num::NonZeroUsize;
+use nut_webgui_upsmc::{
+  CmdName, UpsName, Value, VarName, VarType,
+  clients::{AsyncNutClient, NutAuthClient, NutClient, NutPoolClient},
+  errors::{Error, ErrorKind, ProtocolError},
+  responses::TrackingStatus,
+  server::{MemoryBackend, MemoryDevice, NutServer, RwVarKind},
+};
+use std::net::SocketAddr;
+use tokio::net::TcpListener;
+
+fn test_backend() -> MemoryBackend {
+  let device = MemoryDevice::new("Test UPS")
+    .with_var(VarName::UPS_STATUS, "OL")
+    .with_var(VarName::BATTERY_CHARGE, 100)
+    .with_var_desc(VarName::BATTERY_CHARGE, "Battery charge (percent)")
+    .with_rw_var(
+      VarName::UPS_ID,
+      "server-room",
+      RwVarKind::String { max_len: 16 },
+    )
+    .with_rw_var(
+      VarName::UPS_BEEPER_STATUS,
+      "enabled",
+      RwVarKind::Enum {
+        options: vec![Value::from("enabled"), Value::from("disabled")],
+      },
+    )
+    .with_rw_var(
+      VarName::INPUT_TRANSFER_HIGH,
+      264,
+      RwVarKind::Range {
+        ranges: vec![(Value::from(240), Value::from(280))],
+      },
+    )
+    .with_cmd(
+      CmdName::new_unchecked("beeper.enable"),
+      "Enable the UPS beeper",
+    )
+    .with_cmd(
+      CmdName::new_unchecked("test.battery.start"),
+      "Start a battery test",
+    );
+
+  MemoryBackend::new()
+    .with_device(UpsName::new_unchecked("ups"), device)
+    .with_user("admin", "secret")
+}
+
+async fn spawn_server() -> (NutServer<MemoryBackend>, SocketAddr) {
+  let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let addr = listener.local_addr().unwrap();
+  let server = NutServer::new(test_backend());
+
+  tokio::spawn(server.clone().listen(listener));
+
+  (server, addr)
+}
+
+fn protocol_error(err: Error) -> ProtocolError {
+  match err.kind() {
+    ErrorKind::ProtocolError { inner } => inner.clone(),
+    _ => panic!("Expected protocol error, received {err}"),
+  }
+}
+
+#[tokio::test]
+async fn list_and_get() {
+  let (_, addr) = spawn_server().await;
+  let ups = UpsName::new_unchecked("ups");
+  let mut client = NutClient::connect(addr).await.unwrap();
+
+  let ups_list = client.list_ups().await.unwrap();
+  assert_eq!(ups_list.devices.len(), 1);
+  assert_eq!(ups_list.devices[0].ups_name, ups);
+  assert_eq!(ups_list.devices[0].desc.as_ref(), "Test UPS");
+
+  let var_list = client.list_var(&ups).await.unwrap();
+  assert_eq!(var_list.variables.len(), 5);
+  assert_eq!(
+    var_list.variables.get(VarName::UPS_STATUS),
+    Some(&Value::from("OL"))
+  );
+
+  let rw_list = client.list_rw(&ups).await.unwrap();
+  assert_eq!(rw_list.variables.len(), 3);
+  assert!(!rw_list.variables.contains_key(VarName::UPS_STATUS));
+
+  let commands = client.list_cmd(&ups).await.unwrap();
+  assert_eq!(commands, vec!["beeper.enable", "test.battery.start"]);
+
+  let var = client.get_var(&ups, VarName::BATTERY_CHARGE).await.unwrap();
+  assert_eq!(var.value, Value::from(100));
+
+  let desc = client
+    .get_var_desc(&ups, VarName::BATTERY_CHARGE)
+    .await
+    .unwrap();
+  assert_eq!(desc.desc.as_ref(), "Battery charge (percent)");
+
+  let cmd_desc = client
+    .get_cmd_desc(&ups, CmdName::new_unchecked("beeper.enable"))
+    .await
+    .unwrap();
+  assert_eq!(cmd_desc.desc.as_ref(), "Enable the UPS beeper");
+
+  let var_type = client
+    .get_var_type(&ups, VarName::UPS_BEEPER_STATUS)
+    .await
+    .unwrap();
+  assert_eq!(var_type.var_types, vec![VarType::ReadWrite, VarType::Enum]);
+
+  let enum_list = client
+    .list_enum(&ups, VarName::UPS_BEEPER_STATUS)
+    .await
+    .unwrap();
+  assert_eq!(
+    enum_list.values,
+    vec![Value::from("enabled"), Value::from("disabled")]
+  );
+
+  let range_list = client
+    .list_range(&ups, VarName::INPUT_TRANSFER_HIGH)
+    .await
+    .unwrap();
+  assert_eq!(
+    range_list.ranges,
+    vec![(Value::from(240), Value::from(280))]
+  );
+}
+
+#[tokio::test]
+async fn unknown_names() {
+  let (_, addr) = spawn_server().await;
+  let mut client = NutClient::connect(addr).await.unwrap();
+
+  let err = client
+    .get_var(UpsName::new_unchecked("missing"), VarName::UPS_STATUS)
+    .await
+    .unwrap_err();
+  assert_eq!(protocol_error(err), ProtocolError::UnknownUps);
+
+  let err = client
+    .get_var(UpsName::new_unchecked("ups"), VarName::INPUT_VOLTAGE)
+    .await
+    .unwrap_err();
+  assert_eq!(protocol_error(err), ProtocolError::VarNotSupported);
+
+  let err = client
+    .get_cmd_desc(
+      UpsName::new_unchecked("ups"),
+      CmdName::new_unchecked("shutdown.return"),
+    )
+    .await
+    .unwrap_err();
+  assert_eq!(protocol_error(err), ProtocolError::CmdNotSupported);
+}
+
+#[tokio::test]
+async fn raw_commands() {
+  let (_, addr) = spawn_server().await;
+  let mut client = NutClient::connect(addr).await.unwrap();
+
+  assert_eq!(client.send_raw("NETVER\n").await.unwrap(), "1.3\n");
+
+  let cases = [
+    ("REBOOT\n", ProtocolError::UnknownCommand),
+    ("GET VAR ups\n", ProtocolError::InvalidArgument),
+    ("STARTTLS\n", ProtocolError::FeatureNotConfigured),
+    (
+      "INSTCMD ups beeper.enable\n",
+      ProtocolError::UsernameRequired,
+    ),
+  ];
+
+  for (request, expected) in cases {
+    let err = client.send_raw(request).await.unwrap_err();
+    assert_eq!(protocol_error(err), expected);
+  }
+}
+
+#[tokio::test]
+async fn set_var() {
+  let (server, addr) = spawn_server().await;
+  let ups = UpsName::new_unchecked("ups");
+  let mut client = NutAuthClient::connect(addr, "admin", "secret")
+    .await
+    .unwrap();
+
+  client
+    .set_var(&ups, VarName::UPS_ID, Value::from("rack \"A\""))
+    .await
+    .unwrap();
+
+  client
+    .set_var(&ups, VarName::INPUT_TRANSFER_HIGH, Value::from(270))
+    .await
+    .unwrap();
+
+  let device = server.backend().device(&ups).unwrap();
+  assert_eq!(
+    device.get_var(&VarName::UPS_ID),
+    Some(&Value::from("rack \"A\""))
+  );
+  assert_eq!(
+    device.get_var(&VarName::INPUT_TRANSFER_HIGH),
+    Some(&Value::from(270))
+  );
+
+  let cases = [
+    (
+      VarName::UPS_STATUS,
+      Value::from("OB"),
+      ProtocolError::Readonly,
+    ),
+    (
+      VarName::INPUT_VOLTAGE,
+      Value::from(230),
+      ProtocolError::VarNotSupported,
+    ),
+    (
+      VarName::UPS_ID,
+      Value::from("a very long ups identifier"),
+      ProtocolError::TooLong,
+    ),
+    (
+      VarName::UPS_BEEPER_STATUS,
+      Value::from("muted"),
+      ProtocolError::InvalidValue,
+    ),
+    (
+      VarName::INPUT_TRANSFER_HIGH,
+      Value::from(300),
+      ProtocolError::InvalidValue,
+    ),
+  ];
+
+  for (var, value, expected) in cases {
+    let err = client.set_var(&ups, var, value).await.unwrap_err();
+    assert_eq!(protocol_error(err), expected);
+  }
+}
+
+#[tokio::test]
+async fn instcmd_and_tracking() {
+  let (server, addr) = spawn_server().await;
+  let ups = UpsName::new_unchecked("ups");
+  let cmd = CmdName::new_unchecked("beeper.enable");
+  let mut client = NutAuthClient::connect(addr, "admin", "secret")
+    .await
+    .unwrap();
+
+  client.instcmd(&ups, &cmd).await.unwrap();
+
+  let id = client.instcmd_tracked(&ups, &cmd).await.unwrap();
+  let status = client.get_tracking(&id).await.unwrap();
+  assert_eq!(status, TrackingStatus::Success);
+
+  let id = client
+    .instcmd_tracked(&ups, CmdName::new_unchecked("shutdown.return"))
+    .await
+    .unwrap();
+  let status = client.get_tracking(&id).await.unwrap();
+  assert!(matches!(status, TrackingStatus::Failed { .. }));
+
+  let id = client
+    .set_var_tracked(&ups, VarName::UPS_BEEPER_STATUS, Value::from("disabled"))
+    .await
+    .unwrap();
+  let status = client.get_tracking(&id).await.unwrap();
+  assert_eq!(status, TrackingStatus::Success);
+
+  let device = server.backend().device(&ups).unwrap();
+  assert_eq!(device.executed_cmds(), &[cmd.clone(), cmd]);
+  assert_eq!(
+    device.get_var(&VarName::UPS_BEEPER_STATUS),
+    Some(&Value::from("disabled"))
+  );
+}
+
+#[tokio::test]
+async fn instcmd_with_value() {
+  let (server, addr) = spawn_server().await;
+  let ups = UpsName::new_unchecked("ups");
+  let cmd = CmdName::new_unchecked("test.battery.start");
+  let mut client = NutAuthClient::connect(addr, "admin", "secret")
+    .await
+    .unwrap();
+
+  client
+    .instcmd_with_value(&ups, &cmd, Value::from("say \"hi\""))
+    .await
+    .unwrap();
+
+  let id = client
+    .instcmd_with_value_tracked(&ups, &cmd, Value::from(120))
+    .await
+    .unwrap();
+  let status = client.get_tracking(&id).await.unwrap();
+  assert_eq!(status, TrackingStatus::Success);
+
+  let device = server.backend().device(&ups).unwrap();
+  assert!(device.executed_cmds().is_empty());
+  assert_eq!(
+    device.executed_cmd_values(),
+    &[
+      (cmd.clone(), Value::from("say \"hi\"")),
+      (cmd, Value::from("120"))
+    ]
+  );
+}
+
+#[tokio::test]
+async fn authentication() {
+  let (_, addr) = spawn_server().await;
+  let ups = UpsName::new_unchecked("ups");
+  let mut client = NutAuthClient::connect(addr, "admin", "wrong")
+    .await
+    .unwrap();
+
+  let err = client
+    .instcmd(&ups, CmdName::new_unchecked("beeper.enable"))
+    .await
+    .unwrap_err();
+  assert_eq!(protocol_error(err), ProtocolError::AccessDenied);
+
+  let mut client = NutClient::connect(addr).await.unwrap();
+  assert_eq!(client.send_raw("USERNAME admin\n").await.unwrap(), "OK\n");
+
+  let err = client.send_raw("USERNAME admin\n").await.unwrap_err();
+  assert_eq!(protocol_error(err), ProtocolError::AlreadySetUsername);
+
+  let err = client.send_raw("FSD ups\n").await.unwrap_err();
+  assert_eq!(protocol_error(err), ProtocolError::PasswordRequired);
+}
+
+#[tokio::test]
+async fn fsd_and_clients() {
+  let (server, addr) = spawn_server().await;
+  let ups = UpsName::new_unchecked("ups");
+  let mut client = NutAuthClient::connect(addr, "admin", "secret")
+    .await
+    .unwrap();
+
+  client.attach(&ups).await.unwrap();
+
+  let err = client.attach(&ups).await.unwrap_err();
+  assert_eq!(protocol_error(err), ProtocolError::AlreadyAttached);
+
+  let pool = NutPoolClient::new(addr, NonZeroUsize::new(2).unwrap());
+  let clients = pool.list_client(&ups).await.unwrap();
+  assert_eq!(clients.ips, vec![addr.ip()]);
+
+  client.fsd(&ups).await.unwrap();
+
+  let status = pool.get_var(&ups, VarName::UPS_STATUS).await.unwrap();
+  assert_eq!(status.value, Value::from("FSD OL"));
+
+  client.close().await.unwrap();
+
+  let clients = pool.list_client(&ups).await.unwrap();
+  assert!(clients.ips.is_empty());
+  assert!(server.backend().device(&ups).is_some());
+}
+
+#[tokio::test]
+async fn in_memory_stream() {
+  let (client_stream, server_stream) = tokio::io::duplex(1024);
+  let server = NutServer::new(test_backend());
+
+  tokio::spawn(async move { server.serve_stream(server_stream).await });
+
+  let mut client = NutClient::from(client_stream);
+  let ver = client.get_protver().await.unwrap();
+
+  assert_eq!(ver.value, "1.3");
+}
@@ -0,0 +1,143 @@
+//! Counts heap allocations of a full `LIST VAR` sync, streamed versus buffered responses.
+
+use nut_webgui_upsmc::{
+  UpsName,
+  clients::{Batch, NutClient},
+};
+use std::{
+  alloc::{GlobalAlloc, Layout, System},
+  cell::Cell,
+};
+
+struct CountingAllocator;
+
+thread_local! {
+  static COUNTING: Cell<bool> = const { Cell::new(false) };
+  static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+  unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+    if COUNTING.with(|c| c.get()) {
+      ALLOCATIONS.with(|c| c.set(c.get() + 1));
+    }
+
+    unsafe { System.alloc(layout) }
+  }
+
+  unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+    unsafe { System.dealloc(ptr, layout) }
+  }
+
+  unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+    if COUNTING.with(|c| c.get()) {
+      ALLOCATIONS.with(|c| c.set(c.get() + 1));
+    }
+
+    unsafe { System.realloc(ptr, layout, new_size) }
+  }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+const DEVICE_COUNT: usize = 8;
+const VAR_COUNT: usize = 300;
+
+fn devices() -> Vec<UpsName> {
+  (0..DEVICE_COUNT)
+    .map(|i| UpsName::new_unchecked(format!("ups{i}")))
+    .collect()
+}
+
+fn list_var_response(devices: &[UpsName]) -> (Vec<u8>, Vec<u8>) {
+  let mut commands = String::new();
+  let mut response = String::new();
+
+  for ups in devices {
+    commands.push_str(&format!("LIST VAR {ups}\n"));
+    response.push_str(&format!("BEGIN LIST VAR {ups}\n"));
+
+    for i in 0..VAR_COUNT {
+      response.push_str(&format!("VAR {ups} custom.var.{i} \"value {i}\"\n"));
+    }
+
+    response.push_str(&format!("END LIST VAR {ups}\n"));
+  }
+
+  (commands.into_bytes(), response.into_bytes())
+}
+
+/// Runs `f` on the current thread and returns its result with the number of allocations it made.
+fn count_allocations<F, R>(f: F) -> (R, usize)
+where
+  F: FnOnce() -> R,
+{
+  ALLOCATIONS.with(|c| c.set(0));
+  COUNTING.with(|c| c.set(true));
+  let result = f();
+  COUNTING.with(|c| c.set(false));
+
+  (result, ALLOCATIONS.with(|c| c.get()))
+}
+
+#[test]
+fn streamed_list_allocations_do_not_grow_with_variables() {
+  let runtime = tokio::runtime::Builder::new_current_thread()
+    .enable_time()
+    .build()
+    .unwrap();
+  let devices = devices();
+  let (commands, response) = list_var_response(&devices);
+
+  let stream = tokio_test::io::Builder::new()
+    .write(&commands)
+    .read(&response)
+    .build();
+  let mut client = NutClient::from(stream);
+  let mut value_len = 0;
+
+  let (results, streamed) = count_allocations(|| {
+    runtime.block_on(client.list_vars_streamed(&devices, |_, item| {
+      value_len += item.unwrap().value.len();
+    }))
+  });
+
+  assert!(
+    results
+      .unwrap()
+      .iter()
+      .all(|r| *r.as_ref().unwrap() == VAR_COUNT)
+  );
+  assert!(value_len > 0);
+
+  let mut batch = Batch::new();
+  let handles: Vec<_> = devices.iter().map(|ups| batch.list_var(ups)).collect();
+  let stream = tokio_test::io::Builder::new()
+    .write(&commands)
+    .read(&response)
+    .build();
+  let mut client = NutClient::from(stream);
+
+  let (variables, buffered) = count_allocations(|| {
+    let mut responses = runtime.block_on(client.send_batch(&batch)).unwrap();
+
+    handles
+      .into_iter()
+      .map(|handle| responses.take(handle).unwrap().variables.len())
+      .sum::<usize>()
+  });
+
+  assert_eq!(variables, DEVICE_COUNT * VAR_COUNT);
+
+  // Streamed requests only allocate per device, e.g. serialized commands, and for lines split
+  // between reads. Buffered responses allocate names and values of every variable.
+  assert!(
+    streamed < DEVICE_COUNT * 8,
+    "streamed sync made {streamed} allocations"
+  );
+  assert!(
+    buffered > DEVICE_COUNT * VAR_COUNT,
+    "buffered sync made {buffered} allocations"
+  );
+}
@@ -0,0 +1,336 @@
+#![cfg(feature = "tls")]
+
+use core::num::NonZeroUsize;
+use nut_webgui_upsmc::{
+  clients::{AsyncNutClient, NutClient, NutPoolClient},
+  errors::{ErrorKind, ProtocolError, TlsError},
+  tls::TlsConfig,
+};
+use std::{fs::File, io::BufReader, net::SocketAddr, path::PathBuf, sync::Arc};
+use tokio::{
+  io::{AsyncBufReadExt, AsyncWriteExt, BufReader as AsyncBufReader},
+  net::TcpListener,
+};
+use tokio_rustls::{
+  TlsAcceptor,
+  rustls::{
+    RootCertStore, ServerConfig, crypto::ring::default_provider, server::WebPkiClientVerifier,
+  },
+};
+
+const SERVER_FINGERPRINT: &str =
+  "7F:3E:45:17:01:D1:FD:AF:8D:65:AD:80:DF:85:B4:F6:0A:E7:94:13:69:95:21:B0:6D:EB:42:7E:0C:8A:94:3E";
+
+fn fixture(name: &str) -> PathBuf {
+  PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    .join("tests/fixtures/tls")
+    .join(name)
+}
+
+fn tls_acceptor(require_client_cert: bool) -> TlsAcceptor {
+  let provider = Arc::new(default_provider());
+  let certs = rustls_pemfile::certs(&mut BufReader::new(
+    File::open(fixture("server.pem")).unwrap(),
+  ))
+  .collect::<Result<Vec<_>, _>>()
+  .unwrap();
+  let key = rustls_pemfile::private_key(&mut BufReader::new(
+    File::open(fixture("server.key")).unwrap(),
+  ))
+  .unwrap()
+  .unwrap();
+
+  let builder = ServerConfig::builder_with_provider(provider.clone())
+    .with_safe_default_protocol_versions()
+    .unwrap();
+
+  let builder = if require_client_cert {
+    let mut roots = RootCertStore::empty();
+
+    for cert in rustls_pemfile::certs(&mut BufReader::new(File::open(fixture("ca.pem")).unwrap())) {
+      roots.add(cert.unwrap()).unwrap();
+    }
+
+    let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider)
+      .build()
+      .unwrap();
+
+    builder.with_client_cert_verifier(verifier)
+  } else {
+    builder.with_no_client_auth()
+  };
+
+  TlsAcceptor::from(Arc::new(builder.with_single_cert(certs, key).unwrap()))
+}
+
+/// Minimal upsd mock, accepts single connection, answers `STARTTLS` and then `NETVER` over TLS.
+async fn spawn_tls_server(require_client_cert: bool) -> SocketAddr {
+  let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let addr = listener.local_addr().unwrap();
+  let acceptor = tls_acceptor(require_client_cert);
+
+  tokio::spawn(async move {
+    let (stream, _) = listener.accept().await.unwrap();
+    let mut stream = AsyncBufReader::new(stream);
+    let mut line = String::new();
+
+    stream.read_line(&mut line).await.unwrap();
+    assert_eq!(line, "STARTTLS\n");
+    stream.write_all(b"OK STARTTLS\n").await.unwrap();
+
+    let Ok(tls_stream) = acceptor.accept(stream.into_inner()).await else {
+      return;
+    };
+
+    let mut tls_stream = AsyncBufReader::new(tls_stream);
+    line.clear();
+
+    tls_stream.read_line(&mut line).await.unwrap();
+    assert_eq!(line, "NETVER\n");
+    tls_stream.write_all(b"1.3\n").await.unwrap();
+    tls_stream.flush().await.unwrap();
+  });
+
+  addr
+}
+
+#[tokio::test]
+async fn start_tls_with_ca() {
+  let addr = spawn_tls_server(false).await;
+  let config = TlsConfig::builder("localhost")
+    .with_ca_file(fixture("ca.pem"))
+    .build()
+    .unwrap();
+
+  let mut client = NutClient::connect(addr)
+    .await
+    .unwrap()
+    .start_tls(&config)
+    .await
+    .unwrap();
+
+  let prot_ver = client.get_protver().await.unwrap();
+  assert_eq!(prot_ver.value, "1.3");
+}
+
+#[tokio::test]
+async fn start_tls_with_pinned_cert() {
+  let addr = spawn_tls_server(false).await;
+  let config = TlsConfig::builder("127.0.0.1")
+    .with_pinned_cert(SERVER_FINGERPRINT)
+    .build()
+    .unwrap();
+
+  let mut client = NutClient::connect(addr)
+    .await
+    .unwrap()
+    .start_tls(&config)
+    .await
+    .unwrap();
+
+  let prot_ver = client.get_protver().await.unwrap();
+  assert_eq!(prot_ver.value, "1.3");
+}
+
+#[tokio::test]
+async fn start_tls_pin_mismatch() {
+  let addr = spawn_tls_server(false).await;
+  let config = TlsConfig::builder("localhost")
+    .with_ca_file(fixture("ca.pem"))
+    .with_pinned_cert("00".repeat(32))
+    .build()
+    .unwrap();
+
+  let result = NutClient::connect(addr)
+    .await
+    .unwrap()
+    .start_tls(&config)
+    .await;
+
+  match result {
+    Ok(_) => panic!("Expected handshake failure, received TLS connection"),
+    Err(err) => assert!(matches!(
+      err.kind(),
+      ErrorKind::TlsError {
+        inner: TlsError::Handshake(_)
+      }
+    )),
+  }
+}
+
+#[tokio::test]
+async fn start_tls_hostname_mismatch() {
+  let addr = spawn_tls_server(false).await;
+  let config = TlsConfig::builder("upsd.example.com")
+    .with_ca_file(fixture("ca.pem"))
+    .build()
+    .unwrap();
+
+  let result = NutClient::connect(addr)
+    .await
+    .unwrap()
+    .start_tls(&config)
+    .await;
+
+  assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn start_tls_with_client_cert() {
+  let addr = spawn_tls_server(true).await;
+  let config = TlsConfig::builder("localhost")
+    .with_ca_file(fixture("ca.pem"))
+    .with_client_cert(fixture("client.pem"), fixture("client.key"))
+    .build()
+    .unwrap();
+
+  let mut client = NutClient::connect(addr)
+    .await
+    .unwrap()
+    .start_tls(&config)
+    .await
+    .unwrap();
+
+  let prot_ver = client.get_protver().await.unwrap();
+  assert_eq!(prot_ver.value, "1.3");
+}
+
+#[tokio::test]
+async fn pool_with_tls() {
+  let addr = spawn_tls_server(false).await;
+  let config = TlsConfig::builder("localhost")
+    .with_ca_file(fixture("ca.pem"))
+    .build()
+    .unwrap();
+
+  let pool = NutPoolClient::builder(addr, NonZeroUsize::new(1).unwrap())
+    .with_tls(config)
+    .build();
+
+  let prot_ver = pool.get_protver().await.unwrap();
+  assert_eq!(prot_ver.value, "1.3");
+}
+
+#[tokio::test]
+async fn pool_fallback_endpoint_tls_server_name() {
+  let closed_addr = {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    listener.local_addr().unwrap()
+  };
+
+  let addr = spawn_tls_server(false).await;
+  let config = TlsConfig::builder("upsd.example.com")
+    .with_ca_file(fixture("ca.pem"))
+    .build()
+    .unwrap();
+
+  let fallback_config = config.with_server_name("localhost").unwrap();
+
+  let pool = NutPoolClient::builder(closed_addr, NonZeroUsize::new(1).unwrap())
+    .with_tls(config)
+    .with_endpoint_tls(addr, fallback_config)
+    .build();
+
+  let prot_ver = pool.get_protver().await.unwrap();
+  assert_eq!(prot_ver.value, "1.3");
+  assert_eq!(*pool.active_endpoint(), addr);
+}
+
+#[test]
+fn config_with_server_name() {
+  let config = TlsConfig::builder("localhost")
+    .with_pinned_cert(SERVER_FINGERPRINT)
+    .build()
+    .unwrap();
+
+  let renamed = config.with_server_name("127.0.0.1").unwrap();
+  assert_eq!(renamed.server_name().to_str(), "127.0.0.1");
+  assert_eq!(config.server_name().to_str(), "localhost");
+
+  let result = config.with_server_name("not a hostname!");
+  assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn start_tls_not_configured() {
+  let stream = tokio_test::io::Builder::new()
+    .write(b"STARTTLS\n")
+    .read(b"ERR FEATURE-NOT-CONFIGURED\n")
+    .build();
+
+  let config = TlsConfig::builder("localhost")
+    .with_pinned_cert(SERVER_FINGERPRINT)
+    .build()
+    .unwrap();
+
+  let result = NutClient::from(stream).start_tls(&config).await;
+
+  match result {
+    Ok(_) => panic!("Expected protocol error, received TLS connection"),
+    Err(err) => assert!(matches!(
+      err.kind(),
+      ErrorKind::ProtocolError {
+        inner: ProtocolError::FeatureNotConfigured
+      }
+    )),
+  }
+}
+
+#[test]
+fn config_requires_trust_anchor() {
+  let result = TlsConfig::builder("localhost").build();
+
+  assert!(matches!(
+    result.unwrap_err().kind(),
+    ErrorKind::TlsError {
+      inner: TlsError::NoTrustAnchor
+    }
+  ));
+}
+
+#[test]
+fn config_invalid_fingerprint() {
+  for fingerprint in [
+    "",
+    "7F:3E",
+    "zz".repeat(32).as_str(),
+    "00".repeat(33).as_str(),
+  ] {
+    let result = TlsConfig::builder("localhost")
+      .with_pinned_cert(fingerprint)
+      .build();
+
+    assert!(matches!(
+      result.unwrap_err().kind(),
+      ErrorKind::TlsError {
+        inner: TlsError::InvalidFingerprint
+      }
+    ));
+  }
+}
+
+#[test]
+fn config_fingerprint_formats() {
+  let lower_case = SERVER_FINGERPRINT.replace(':', "").to_ascii_lowercase();
+
+  assert!(
+    TlsConfig::builder("localhost")
+      .with_pinned_cert(lower_case)
+      .build()
+      .is_ok()
+  );
+}
+
+#[test]
+fn config_invalid_server_name() {
+  let result = TlsConfig::builder("not a hostname")
+    .with_ca_file(fixture("ca.pem"))
+    .build();
+
+  assert!(matches!(
+    result.unwrap_err().kind(),
+    ErrorKind::TlsError {
+      inner: TlsError::InvalidServerName
+    }
+  ));
+}
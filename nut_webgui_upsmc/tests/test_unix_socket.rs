@@ -0,0 +1,136 @@
+#![cfg(all(unix, feature = "server"))]
+
+use core::num::NonZeroUsize;
+use nut_webgui_upsmc::{
+  CmdName, UpsName, VarName,
+  clients::{AsyncNutClient, NutAddr, NutAuthClient, NutClient, NutPoolClient, ToNutAddr},
+  server::{MemoryBackend, MemoryDevice, NutServer},
+};
+use std::{
+  net::{IpAddr, Ipv4Addr},
+  path::PathBuf,
+};
+use tokio::net::UnixListener;
+
+fn socket_path(name: &str) -> PathBuf {
+  let path = std::env::temp_dir().join(format!("nut-{}-{}.sock", std::process::id(), name));
+  _ = std::fs::remove_file(&path);
+
+  path
+}
+
+fn spawn_server(path: &PathBuf) -> NutServer<MemoryBackend> {
+  let listener = UnixListener::bind(path).unwrap();
+  let server = NutServer::new(
+    MemoryBackend::new()
+      .with_device(
+        UpsName::new_unchecked("ups"),
+        MemoryDevice::new("Unix UPS")
+          .with_var(VarName::UPS_STATUS, "OL")
+          .with_cmd(
+            CmdName::new_unchecked("beeper.enable"),
+            "Enable the UPS beeper",
+          ),
+      )
+      .with_user("admin", "secret"),
+  );
+
+  tokio::spawn({
+    let server = server.clone();
+
+    async move {
+      loop {
+        let (stream, _) = listener.accept().await.unwrap();
+        let server = server.clone();
+
+        tokio::spawn(async move {
+          _ = server
+            .serve_connection(stream, IpAddr::V4(Ipv4Addr::LOCALHOST))
+            .await;
+        });
+      }
+    }
+  });
+
+  server
+}
+
+#[test]
+fn parse_nut_addr() {
+  assert_eq!(
+    "unix:/run/nut/upsd.sock".to_nut_addr().unwrap(),
+    NutAddr::Unix(PathBuf::from("/run/nut/upsd.sock"))
+  );
+  assert_eq!(
+    "127.0.0.1:3493".to_nut_addr().unwrap(),
+    NutAddr::Tcp(vec!["127.0.0.1:3493".parse().unwrap()])
+  );
+  assert_eq!(
+    String::from("unix:relative.sock").to_nut_addr().unwrap(),
+    NutAddr::Unix(PathBuf::from("relative.sock"))
+  );
+}
+
+#[tokio::test]
+async fn client_over_unix_socket() {
+  let path = socket_path("client");
+  spawn_server(&path);
+
+  let mut client = NutClient::connect_unix(&path).await.unwrap();
+  let desc = client
+    .get_ups_desc(UpsName::new_unchecked("ups"))
+    .await
+    .unwrap();
+
+  assert_eq!(desc.desc.as_ref(), "Unix UPS");
+
+  _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn auth_client_over_unix_socket() {
+  let path = socket_path("auth");
+  let server = spawn_server(&path);
+  let ups = UpsName::new_unchecked("ups");
+
+  let mut client = NutAuthClient::connect_unix(&path, "admin", "secret")
+    .await
+    .unwrap();
+  client
+    .instcmd(&ups, CmdName::new_unchecked("beeper.enable"))
+    .await
+    .unwrap();
+
+  assert_eq!(
+    server.backend().device(&ups).unwrap().executed_cmds().len(),
+    1
+  );
+
+  _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn pool_over_unix_socket() {
+  let path = socket_path("pool");
+  let server = spawn_server(&path);
+  let addr = format!("unix:{}", path.display());
+  let ups = UpsName::new_unchecked("ups");
+
+  let pool = NutPoolClient::new(addr.clone(), NonZeroUsize::new(2).unwrap());
+  let status = pool.get_var(&ups, VarName::UPS_STATUS).await.unwrap();
+  assert_eq!(status.value.as_str(), "OL");
+
+  let auth_pool =
+    NutPoolClient::builder(addr, NonZeroUsize::new(1).unwrap()).build_auth("admin", "secret");
+  auth_pool
+    .instcmd(&ups, CmdName::new_unchecked("beeper.enable"))
+    .await
+    .unwrap();
+
+  assert_eq!(
+    server.backend().device(&ups).unwrap().executed_cmds().len(),
+    1
+  );
+
+  _ = std::fs::remove_file(&path);
+}
@@ -48,6 +48,19 @@ fn base_events() {
   );
 }
 
+#[test]
+fn extended_events() {
+  test_event_stream!(
+    (UpsStatus::ONLINE | UpsStatus::ECO             => [UpsEvent::Online, UpsEvent::EcoMode]);
+    (UpsStatus::ONLINE | UpsStatus::HIGH_EFFICIENCY => [UpsEvent::EcoModeEnded, UpsEvent::HighEfficiency]);
+    (UpsStatus::ONLINE | UpsStatus::HIGH_BATTERY    => [UpsEvent::HighEfficiencyEnded, UpsEvent::HighBattery]);
+    (UpsStatus::ONLINE | UpsStatus::FAN_FAILURE     => [UpsEvent::HighBatteryEnded, UpsEvent::FanFailure]);
+    (UpsStatus::ONLINE | UpsStatus::OVERHEAT        => [UpsEvent::FanFailureEnded, UpsEvent::Overheat]);
+    (UpsStatus::ONLINE | UpsStatus::NO_BATTERY      => [UpsEvent::OverheatEnded, UpsEvent::NoBattery]);
+    (UpsStatus::ONLINE                              => [UpsEvent::NoBatteryEnded])
+  );
+}
+
 #[test]
 fn ups_status_simulation() {
   test_event_stream!(
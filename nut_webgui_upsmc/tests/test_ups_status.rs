@@ -1,4 +1,4 @@
-use nut_webgui_upsmc::ups_status::UpsStatus;
+use nut_webgui_upsmc::ups_status::{UpsStatus, UpsStatusInfo};
 
 macro_rules! assert_status_text {
   (validate_str = true, $(($name:literal, $target:expr);)+) => {
@@ -22,25 +22,31 @@ macro_rules! assert_status_text {
 #[test]
 fn single_status() {
   assert_status_text!(validate_str = true,
-    ("ALARM",   UpsStatus::ALARM);
-    ("BOOST",   UpsStatus::BOOST);
-    ("BYPASS",  UpsStatus::BYPASS);
-    ("CAL",     UpsStatus::CALIBRATING);
-    ("CHRG",    UpsStatus::CHARGING);
-    ("COMM",    UpsStatus::COMM);
-    ("DISCHRG", UpsStatus::DISCHARGE);
-    ("FSD",     UpsStatus::FORCED_SHUTDOWN);
-    ("LB",      UpsStatus::LOW_BATTERY);
-    ("NOCOMM",  UpsStatus::NOCOMM);
-    ("OFF",     UpsStatus::OFFLINE);
-    ("OL",      UpsStatus::ONLINE);
-    ("OB",      UpsStatus::ON_BATTERY);
-    ("OVER",    UpsStatus::OVERLOADED);
-    ("RB",      UpsStatus::REPLACE_BATTERY);
-    ("TEST",    UpsStatus::TEST);
-    ("TICK",    UpsStatus::TICK);
-    ("TOCK",    UpsStatus::TOCK);
-    ("TRIM",    UpsStatus::TRIM);
+    ("ALARM",     UpsStatus::ALARM);
+    ("BOOST",     UpsStatus::BOOST);
+    ("BYPASS",    UpsStatus::BYPASS);
+    ("CAL",       UpsStatus::CALIBRATING);
+    ("CHRG",      UpsStatus::CHARGING);
+    ("COMM",      UpsStatus::COMM);
+    ("DISCHRG",   UpsStatus::DISCHARGE);
+    ("ECO",       UpsStatus::ECO);
+    ("FANFAIL",   UpsStatus::FAN_FAILURE);
+    ("FSD",       UpsStatus::FORCED_SHUTDOWN);
+    ("HB",        UpsStatus::HIGH_BATTERY);
+    ("HE",        UpsStatus::HIGH_EFFICIENCY);
+    ("LB",        UpsStatus::LOW_BATTERY);
+    ("NOCOMM",    UpsStatus::NOCOMM);
+    ("NOBATTERY", UpsStatus::NO_BATTERY);
+    ("OFF",       UpsStatus::OFFLINE);
+    ("OL",        UpsStatus::ONLINE);
+    ("OB",        UpsStatus::ON_BATTERY);
+    ("OVERHEAT",  UpsStatus::OVERHEAT);
+    ("OVER",      UpsStatus::OVERLOADED);
+    ("RB",        UpsStatus::REPLACE_BATTERY);
+    ("TEST",      UpsStatus::TEST);
+    ("TICK",      UpsStatus::TICK);
+    ("TOCK",      UpsStatus::TOCK);
+    ("TRIM",      UpsStatus::TRIM);
   );
 }
 
@@ -48,18 +54,46 @@ fn single_status() {
 fn with_multip_unspecified_status() {
   let status = UpsStatus::new("OB TEST OVERHEAT ECO MEGUSTA OL");
   assert_eq!(
-    UpsStatus::ON_BATTERY | UpsStatus::TEST | UpsStatus::ONLINE,
+    UpsStatus::ON_BATTERY
+      | UpsStatus::TEST
+      | UpsStatus::OVERHEAT
+      | UpsStatus::ECO
+      | UpsStatus::ONLINE,
     status
   )
 }
 
 #[test]
 fn with_single_unspecified_status() {
-  let status = UpsStatus::new("FANFAIL");
+  let status = UpsStatus::new("MEGUSTA");
 
   assert_eq!(UpsStatus::default(), status)
 }
 
+#[test]
+fn status_info_keeps_unknown_tokens() {
+  let status = UpsStatusInfo::new("OB MEGUSTA FANFAIL VENDOR-X OL MEGUSTA");
+
+  assert_eq!(
+    status.flags(),
+    UpsStatus::ON_BATTERY | UpsStatus::FAN_FAILURE | UpsStatus::ONLINE
+  );
+  assert_eq!(status.unknown(), &["MEGUSTA".into(), "VENDOR-X".into()]);
+  assert!(status.has(UpsStatus::FAN_FAILURE));
+}
+
+#[test]
+fn status_info_to_string() {
+  let status = UpsStatusInfo::new("MEGUSTA OL");
+  assert_eq!(status.to_string(), "OL MEGUSTA");
+
+  let status = UpsStatusInfo::new("VENDOR-X");
+  assert_eq!(status.flags(), UpsStatus::default());
+  assert_eq!(status.to_string(), "VENDOR-X");
+
+  assert!(UpsStatusInfo::new("").is_empty());
+}
+
 #[test]
 fn multiple_status() {
   assert_status_text!(validate_str = false,
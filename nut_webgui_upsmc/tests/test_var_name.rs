@@ -1,6 +1,6 @@
 use core::str::FromStr;
-use nut_webgui_upsmc::VarName;
 use nut_webgui_upsmc::errors::VarNameParseError;
+use nut_webgui_upsmc::{VarCategory, VarKind, VarName, VarUnit};
 
 macro_rules! var_name_test {
   ($test_name:ident, $name:literal) => {
@@ -142,3 +142,37 @@ fn to_string() {
     String::from("ambient.temperature.low.warning")
   );
 }
+
+#[test]
+fn standard_metadata() {
+  let charge = VarName::BATTERY_CHARGE.metadata().unwrap();
+  assert_eq!(charge.category, VarCategory::Battery);
+  assert_eq!(charge.kind, VarKind::Percentage);
+  assert_eq!(charge.unit, Some(VarUnit::Percent));
+
+  let voltage = VarName::new("input.voltage").unwrap().metadata().unwrap();
+  assert_eq!(voltage.category, VarCategory::Input);
+  assert_eq!(voltage.kind, VarKind::Number);
+  assert_eq!(voltage.unit.map(|u| u.symbol()), Some("V"));
+
+  let runtime = VarName::BATTERY_RUNTIME.metadata().unwrap();
+  assert_eq!(runtime.kind, VarKind::Duration);
+  assert_eq!(runtime.unit, Some(VarUnit::Second));
+
+  let status = VarName::INPUT_VOLTAGE_STATUS.metadata().unwrap();
+  assert_eq!(status.kind, VarKind::Text);
+  assert_eq!(status.unit, None);
+
+  let date = VarName::BATTERY_MFR_DATE.metadata().unwrap();
+  assert_eq!(date.kind, VarKind::Timestamp);
+}
+
+#[test]
+fn custom_metadata() {
+  let name = VarName::new("outlet.1.status").unwrap();
+  assert_eq!(name.metadata(), None);
+  assert_eq!(name.category(), VarCategory::Outlet);
+
+  let name = VarName::new("experimental.value").unwrap();
+  assert_eq!(name.category(), VarCategory::Other);
+}
@@ -88,3 +88,29 @@ fn update() {
 
   assert_eq!(vars.len(), 3);
 }
+
+#[test]
+fn update_raw() {
+  let mut vars = UpsVariables::from([
+    (VarName::UPS_STATUS, Value::String("OL".into())),
+    (VarName::BATTERY_CHARGE, Value::Int(100)),
+  ]);
+
+  assert!(!vars.update_raw("ups.status", "OL"));
+  assert!(!vars.update_raw("battery.charge", "100"));
+  assert!(vars.update_raw("battery.charge", "95"));
+  assert!(vars.update_raw("ups.status", "OB"));
+  assert!(vars.update_raw("custom.var", "value"));
+  assert!(!vars.update_raw("custom.var", "value"));
+
+  assert_eq!(vars.get(VarName::BATTERY_CHARGE), Some(&Value::Int(95)));
+  assert_eq!(vars.get(VarName::UPS_STATUS), Some(&Value::from("OB")));
+  assert_eq!(
+    vars.get(VarName::new("custom.var").unwrap()),
+    Some(&Value::from("value"))
+  );
+  assert_eq!(vars.len(), 3);
+
+  vars.retain(|name, _| name.as_str() != "custom.var");
+  assert_eq!(vars.len(), 2);
+}
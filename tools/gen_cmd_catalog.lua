@@ -0,0 +1,58 @@
+#!/bin/lua
+require("io")
+
+-- Reads standard instant command list from stdin and generates macro inputs for the command
+-- catalog. Per outlet commands are listed with `n` in place of the outlet number, e.g.
+-- `outlet.n.load.off`.
+
+-- Risk rules are matched against the full command name in order, first match wins. Commands
+-- without any matching rule are classified as disruptive.
+local RISK_RULES = {
+	{ "^driver%.killpower$", "PowerCutting" },
+	{ "^reset%.watchdog$", "PowerCutting" },
+	{ "%.stop$", "Safe" },
+	{ "^beeper%.", "Safe" },
+	{ "^reset%.", "Safe" },
+	{ "^test%.panel%.", "Safe" },
+	{ "load%.on", "Safe" },
+	{ "load%.off", "PowerCutting" },
+	{ "load%.cycle", "PowerCutting" },
+	{ "shutdown%.", "PowerCutting" },
+}
+
+local CATEGORIES = {
+	beeper = "Beeper",
+	bypass = "Bypass",
+	calibrate = "Calibrate",
+	driver = "Driver",
+	load = "Load",
+	reset = "Reset",
+	shutdown = "Shutdown",
+	test = "Test",
+}
+
+---@param input string
+---@return string
+local function classify_risk(input)
+	for _, rule in ipairs(RISK_RULES) do
+		if input:find(rule[1]) then
+			return rule[2]
+		end
+	end
+
+	return "Disruptive"
+end
+
+for line in io.stdin:lines("l") do
+	-- Outlet commands are categorized by the command after the outlet number.
+	local base_name = line:gsub("^outlet%.n%.", "")
+	local category = CATEGORIES[base_name:match("^(%w+)")] or "Other"
+	local risk = classify_risk(line)
+	local accepts_param = "false"
+
+	if line:find("%.delay$") then
+		accepts_param = "true"
+	end
+
+	print(string.format("(%-32s,%-10s,%-13s,%s);", string.format("%q", line), category, risk, accepts_param))
+end
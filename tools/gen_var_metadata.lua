@@ -0,0 +1,100 @@
+#!/bin/lua
+require("io")
+
+-- Reads variable list from stdin and generates macro inputs for standard variable metadata.
+-- Uses the same input as gen_var_names.lua.
+
+-- Rules are matched against the full variable name in order, first match wins. Names without any
+-- matching rule are classified as text.
+local RULES = {
+	{ "%.status$", nil, "Text" },
+	{ "%.extended$", nil, "Text" },
+	{ "^driver%.parameter%.pollinterval$", "Second", "Duration" },
+	{ "^driver%.", nil, "Text" },
+	{ "^device%.", nil, "Text" },
+	{ "date", nil, "Timestamp" },
+	{ "^ups%.time$", nil, "Timestamp" },
+	{ "phases$", nil, "Number" },
+	{ "count$", nil, "Number" },
+	{ "packs", nil, "Number" },
+	{ "percent", "Percent", "Percentage" },
+	{ "powerfactor", nil, "Number" },
+	{ "realpower", "Watt", "Number" },
+	{ "power", "VoltAmpere", "Number" },
+	{ "^input%.transfer%.reason$", nil, "Text" },
+	{ "^input%.transfer%.delay$", "Second", "Duration" },
+	{ "^input%.transfer%.", "Volt", "Number" },
+	{ "voltage", "Volt", "Number" },
+	{ "current", "Ampere", "Number" },
+	{ "frequency", "Hertz", "Number" },
+	{ "temperature", "Celsius", "Number" },
+	{ "humidity", "Percent", "Percentage" },
+	{ "charge", "Percent", "Percentage" },
+	{ "load", "Percent", "Percentage" },
+	{ "lowbatt$", "Percent", "Percentage" },
+	{ "efficiency", "Percent", "Percentage" },
+	{ "capacity", "AmpereHour", "Number" },
+	{ "energysave%.delay", "Minute", "Duration" },
+	{ "runtime", "Second", "Duration" },
+	{ "delay", "Second", "Duration" },
+	{ "timer", "Second", "Duration" },
+	{ "interval", "Second", "Duration" },
+}
+
+local CATEGORIES = {
+	ambient = "Ambient",
+	battery = "Battery",
+	device = "Device",
+	driver = "Driver",
+	input = "Input",
+	outlet = "Outlet",
+	output = "Output",
+	server = "Server",
+	ups = "Ups",
+}
+
+---@param input string
+---@return string
+local function into_variant_name(input)
+	local words = {}
+	local i = 1
+
+	for part in input:gmatch("%w+") do
+		local first = part:sub(1, 1)
+		local slice = part:sub(2)
+		words[i] = string.format("%s%s", first:upper(), slice)
+		i = i + 1
+	end
+
+	local result = ""
+	for _, part in pairs(words) do
+		result = result .. part
+	end
+
+	return result
+end
+
+---@param input string
+---@return string?, string
+local function classify(input)
+	for _, rule in ipairs(RULES) do
+		if input:find(rule[1]) then
+			return rule[2], rule[3]
+		end
+	end
+
+	return nil, "Text"
+end
+
+for line in io.stdin:lines("l") do
+	local variant_name = into_variant_name(line)
+	local category = CATEGORIES[line:match("^(%w+)")] or "Other"
+	local unit, kind = classify(line)
+	local unit_expr = "None"
+
+	if unit then
+		unit_expr = string.format("Some(VarUnit::%s)", unit)
+	end
+
+	print(string.format("(%-48s,%-8s,%-10s,%s);", variant_name, category, kind, unit_expr))
+end